@@ -0,0 +1,306 @@
+//! `clawbot check-config`: validate everything startup depends on and
+//! probe the external services, then print a pass/fail report without
+//! trading. Nothing is written except creating an empty database file when
+//! `DATABASE_URL` allows it.
+
+use std::future::Future;
+use std::panic::UnwindSafe;
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
+
+use common::{Config, ExchangeKind, ReadinessProbe, TradingMode};
+use engine::BinanceClient;
+use risk::RiskStateStore;
+use strategy::{StrategyFileConfig, StrategyRegistry};
+use telegram_ctrl::TelegramProbe;
+
+/// Longest a connectivity probe may take.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    /// Works, but worth a look.
+    Warn,
+    Fail,
+    /// Not checked, e.g. because an earlier check failed.
+    Skip,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+            Status::Skip => "SKIP",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Report {
+    failed: usize,
+}
+
+impl Report {
+    fn add(&mut self, status: Status, check: &str, detail: impl AsRef<str>) {
+        if status == Status::Fail {
+            self.failed += 1;
+        }
+        println!("{:<5} {check:<11} {}", status.label(), detail.as_ref());
+    }
+}
+
+/// Run every check, print the report, and return the process exit code:
+/// `0` when nothing failed.
+pub async fn run() -> i32 {
+    let mut report = Report::default();
+    println!("clawbot check-config");
+
+    let cfg = match catch(Config::load) {
+        Ok(cfg) => {
+            let source = cfg.config_file.as_deref().unwrap_or("environment only");
+            report.add(
+                Status::Pass,
+                "config",
+                format!(
+                    "{source}; {} on {}, account {}",
+                    cfg.trading_mode, cfg.exchange, cfg.account
+                ),
+            );
+            if cfg.dashboard_jwt_secret == cfg.dashboard_token {
+                report.add(
+                    Status::Warn,
+                    "config",
+                    "session tokens are signed with DASHBOARD_TOKEN; set a separate DASHBOARD_JWT_SECRET",
+                );
+            }
+            cfg
+        }
+        Err(e) => {
+            report.add(Status::Fail, "config", e);
+            for check in ["strategies", "database", "risk", "exchange", "telegram"] {
+                report.add(Status::Skip, check, "needs a valid config");
+            }
+            return summary(&report);
+        }
+    };
+
+    let path = cfg.strategy_config_path.clone();
+    match catch(move || StrategyFileConfig::load(&path)) {
+        Ok(file) => {
+            let problems = StrategyRegistry::check(&file);
+            if problems.is_empty() {
+                let pairs = file.pair_streams().len();
+                let enabled = file.strategies.iter().filter(|s| s.enabled).count();
+                report.add(
+                    Status::Pass,
+                    "strategies",
+                    format!(
+                        "{}: {enabled} of {} enabled, {pairs} pairs",
+                        cfg.strategy_config_path,
+                        file.strategies.len()
+                    ),
+                );
+            } else {
+                report.add(Status::Fail, "strategies", problems.join("; "));
+            }
+        }
+        Err(e) => report.add(Status::Fail, "strategies", e),
+    }
+
+    match check_database(&cfg).await {
+        Ok((db, detail, migrated)) => {
+            report.add(Status::Pass, "database", detail);
+            check_risk(&mut report, &cfg, db, migrated).await;
+        }
+        Err(e) => {
+            report.add(Status::Fail, "database", e);
+            report.add(Status::Skip, "risk", "needs the database");
+        }
+    }
+
+    match cfg.exchange {
+        ExchangeKind::Binance => check_binance(&mut report, &cfg).await,
+        other => report.add(
+            Status::Skip,
+            "exchange",
+            format!("API key permissions are only checked on Binance, not {other}"),
+        ),
+    }
+
+    let probe = TelegramProbe::new(teloxide::Bot::new(cfg.telegram_token.clone()));
+    match probe_with_timeout(probe.check()).await {
+        Ok(()) => report.add(
+            Status::Pass,
+            "telegram",
+            format!(
+                "bot token works; {} allowed users",
+                cfg.telegram_allowed_user_ids.len()
+            ),
+        ),
+        Err(e) => report.add(Status::Fail, "telegram", e),
+    }
+
+    summary(&report)
+}
+
+fn summary(report: &Report) -> i32 {
+    if report.failed == 0 {
+        println!("Result: PASS");
+        0
+    } else {
+        println!("Result: FAIL ({} failed)", report.failed);
+        1
+    }
+}
+
+/// Connect and compare applied migrations with this build's, without
+/// running any. Also returns whether every migration is applied.
+async fn check_database(cfg: &Config) -> Result<(SqlitePool, String, bool), String> {
+    let options = SqliteConnectOptions::from_str(&cfg.database_url)
+        .map_err(|e| format!("invalid DATABASE_URL: {e}"))?;
+    let db = probe_with_timeout(async { Ok(SqlitePool::connect_with(options).await?) })
+        .await
+        .map_err(|e| format!("cannot connect: {e}"))?;
+
+    let has_table: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_optional(&db)
+    .await
+    .map_err(|e| e.to_string())?;
+    let applied: Vec<(i64, Vec<u8>)> = if has_table.is_some() {
+        sqlx::query_as("SELECT version, checksum FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(&db)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        Vec::new()
+    };
+
+    let migrator = sqlx::migrate!("../../migrations");
+    for (version, checksum) in &applied {
+        match migrator.iter().find(|m| m.version == *version) {
+            None => {
+                return Err(format!(
+                    "migration {version} is applied but unknown to this build (database from a newer version?)"
+                ))
+            }
+            Some(m) if m.checksum.as_ref() != checksum.as_slice() => {
+                return Err(format!("migration {version} was modified after it was applied"))
+            }
+            Some(_) => {}
+        }
+    }
+    let pending = migrator.iter().count() - applied.len();
+    let detail = match pending {
+        0 => format!("connected; all {} migrations applied", applied.len()),
+        n => format!(
+            "connected; {} migrations applied, {n} pending (applied at startup)",
+            applied.len()
+        ),
+    };
+    Ok((db, detail, pending == 0))
+}
+
+/// The risk limits startup would use: saved runtime limits if any, else
+/// the config file's. A database that isn't fully migrated has none saved.
+async fn check_risk(report: &mut Report, cfg: &Config, db: SqlitePool, migrated: bool) {
+    let saved = if migrated {
+        RiskStateStore::new(db).load_config().await
+    } else {
+        Ok(None)
+    };
+    match saved {
+        Ok(Some(saved)) => match saved.validate() {
+            Ok(()) => report.add(
+                Status::Pass,
+                "risk",
+                "saved runtime limits are valid and override the config file",
+            ),
+            Err(e) => report.add(Status::Fail, "risk", format!("saved runtime limits: {e}")),
+        },
+        Ok(None) => report.add(
+            Status::Pass,
+            "risk",
+            format!(
+                "stop loss {:.2}%, take profit {:.2}%, max drawdown {:.2}%, {} USD per trade",
+                cfg.risk.stop_loss_pct * 100.0,
+                cfg.risk.take_profit_pct * 100.0,
+                cfg.risk.max_drawdown_pct * 100.0,
+                cfg.risk.max_exposure_per_trade_usd
+            ),
+        ),
+        Err(e) => report.add(Status::Fail, "risk", format!("saved runtime limits: {e}")),
+    }
+}
+
+/// The key must read in every mode, and trade outside paper mode.
+async fn check_binance(report: &mut Report, cfg: &Config) {
+    let client = BinanceClient::new(&cfg.binance_api_key, &cfg.binance_secret);
+    let permissions = match probe_with_timeout(client.api_permissions()).await {
+        Ok(permissions) => permissions,
+        Err(e) => return report.add(Status::Fail, "exchange", format!("Binance: {e}")),
+    };
+    let trading_needed = cfg.trading_mode != TradingMode::Paper;
+    let (status, detail) = if !permissions.enable_reading {
+        (Status::Fail, "API key cannot read account data".to_string())
+    } else if trading_needed && !permissions.enable_spot_and_margin_trading {
+        (
+            Status::Fail,
+            format!(
+                "API key cannot trade spot, required for {}",
+                cfg.trading_mode
+            ),
+        )
+    } else if permissions.enable_withdrawals {
+        (
+            Status::Warn,
+            "API key can withdraw funds; the bot never needs this".to_string(),
+        )
+    } else {
+        let trade = if permissions.enable_spot_and_margin_trading {
+            "read + trade"
+        } else {
+            "read only"
+        };
+        let ip = if permissions.ip_restrict {
+            "IP-restricted"
+        } else {
+            "not IP-restricted"
+        };
+        (Status::Pass, format!("Binance API key: {trade}, {ip}"))
+    };
+    report.add(status, "exchange", detail);
+}
+
+async fn probe_with_timeout<T>(
+    check: impl Future<Output = common::Result<T>>,
+) -> Result<T, String> {
+    match tokio::time::timeout(PROBE_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", PROBE_TIMEOUT.as_secs())),
+    }
+}
+
+/// Run `f`, turning a panic (how config loaders report bad settings) into
+/// its message.
+fn catch<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, String> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(f);
+    std::panic::set_hook(hook);
+    result.map_err(|payload| {
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "unknown error".to_string());
+        message.trim_start_matches("ERROR: ").to_string()
+    })
+}
@@ -1,23 +1,36 @@
+mod check;
+
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use sqlx::SqlitePool;
 use tokio::sync::{broadcast, mpsc, RwLock};
-use tracing::info;
+use tracing::{error, info, warn};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
 
-use common::{Config, TradingMode};
-use engine::{BinanceClient, Engine, OrderExecutor};
+use common::{
+    history, Config, DataRetention, DatabaseBackup, EngineState, ExchangeKind, LogFormat,
+    LogRecord, LoggingConfig, PrunedTable, RetentionStats, RetryQueue, RotatingLog, Shutdown,
+    TradingMode,
+};
+use engine::{
+    BinanceClient, BybitClient, CandleStore, CoinbaseClient, Engine, Heartbeat, OrderExecutor,
+    PositionAuditor, SymbolFilterMap, TradeLedger, Watchdog,
+};
 use paper::PaperClient;
-use risk::{RiskConfig, RiskManager};
+use risk::{EquityRecorder, RiskEventJournal, RiskManager, RiskStateStore, SignalJournal};
 use strategy::{StrategyFileConfig, StrategyRegistry};
-use telegram_ctrl::{start_bot, BotDeps};
+use telegram_ctrl::{start_bot, AlertSubscriptions, BotDeps, DailySummary, TelegramProbe};
 
-/// A tracing layer that forwards formatted log lines to a broadcast channel
-/// so the dashboard WebSocket can stream them in real time.
+/// A tracing layer that forwards structured log records to a broadcast
+/// channel so dashboard clients can stream (and filter) them in real time.
 struct BroadcastLayer {
-    tx: broadcast::Sender<String>,
+    tx: broadcast::Sender<LogRecord>,
 }
 
 impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for BroadcastLayer {
@@ -26,48 +39,150 @@ impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for BroadcastLayer {
         event: &tracing::Event<'_>,
         _ctx: tracing_subscriber::layer::Context<'_, S>,
     ) {
-        let mut visitor = MessageVisitor(String::new());
-        event.record(&mut visitor);
-        let level = event.metadata().level();
-        let target = event.metadata().target();
-        let line = format!("{level} {target}: {}", visitor.0);
-        let _ = self.tx.send(line);
+        let _ = self.tx.send(LogRecord::from_event(event));
     }
 }
 
-struct MessageVisitor(String);
+/// How long subsystems get to stop after Ctrl-C or SIGTERM before the
+/// process exits anyway.
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// One-off commands run instead of the bot.
+enum HistoryCommand {
+    /// `clawbot export --out <file>`
+    Export(PathBuf),
+    /// `clawbot import --in <file>`
+    Import(PathBuf),
+}
+
+impl HistoryCommand {
+    /// The command on the command line, if any. Exits on anything else.
+    fn from_args() -> Option<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        match args.as_slice() {
+            [] => None,
+            [command, flag, path] if command == "export" && flag == "--out" => {
+                Some(HistoryCommand::Export(path.into()))
+            }
+            [command, flag, path] if command == "import" && flag == "--in" => {
+                Some(HistoryCommand::Import(path.into()))
+            }
+            _ => {
+                eprintln!(
+                    "usage: clawbot [export --out <file> | import --in <file> | check-config]"
+                );
+                std::process::exit(2);
+            }
+        }
+    }
 
-impl tracing::field::Visit for MessageVisitor {
-    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        if field.name() == "message" {
-            self.0 = format!("{value:?}");
-        } else if !self.0.is_empty() {
-            self.0.push_str(&format!(" {}={:?}", field.name(), value));
-        } else {
-            self.0 = format!("{}={:?}", field.name(), value);
+    /// Export or import trades, orders, fills, positions, and equity
+    /// snapshots. Files ending in `.gz` are gzipped.
+    async fn run(self, db: &SqlitePool) -> common::Result<()> {
+        match self {
+            HistoryCommand::Export(path) => {
+                let export = history::export(db).await?;
+                history::write_file(&export, &path)?;
+                let rows: usize = export.tables.values().map(Vec::len).sum();
+                info!(path = %path.display(), rows, "History exported");
+            }
+            HistoryCommand::Import(path) => {
+                let export = history::read_file(&path)?;
+                let inserted = history::import(db, &export).await?;
+                let rows: u64 = inserted.iter().map(|(_, n)| n).sum();
+                info!(path = %path.display(), rows, "History imported");
+            }
         }
+        Ok(())
     }
 }
 
 #[tokio::main]
 async fn main() {
+    let started_at = chrono::Utc::now();
+    // Validates and probes before logging is set up, so only the report is
+    // printed
+    if std::env::args().skip(1).eq(["check-config"]) {
+        std::process::exit(check::run().await);
+    }
+    let history_command = HistoryCommand::from_args();
     // ── Shared log broadcast (created early so tracing layer can use it) ────
-    let (log_tx, _) = broadcast::channel::<String>(1024);
+    let (log_tx, _) = broadcast::channel::<LogRecord>(1024);
 
     // ── Logging ──────────────────────────────────────────────────────────────
+    let logging = LoggingConfig::load();
     let broadcast_layer = BroadcastLayer { tx: log_tx.clone() };
+    let stdout_layer = match logging.format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .boxed(),
+    };
+    // Lines are written to the file from a background thread; the guard
+    // flushes what's left when main returns.
+    let (file_writer, _log_file_guard) = match &logging.file {
+        Some(path) => {
+            let log = RotatingLog::open(
+                path,
+                logging.file_rotation,
+                logging.file_max_bytes,
+                logging.file_keep,
+            )
+            .unwrap_or_else(|e| panic!("Failed to open LOG_FILE '{path}': {e}"));
+            let (writer, guard) = tracing_appender::non_blocking(log);
+            (Some(writer), Some(guard))
+        }
+        None => (None, None),
+    };
+    let file_layer = file_writer.map(|writer| match logging.format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(writer)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .boxed(),
+    });
     tracing_subscriber::registry()
         .with(EnvFilter::from_default_env().add_directive("info".parse().unwrap()))
-        .with(tracing_subscriber::fmt::layer())
+        .with(stdout_layer)
+        .with(file_layer)
         .with(broadcast_layer)
         .init();
+    // Panics go to the log (file and dashboard too), not only stderr
+    std::panic::set_hook(Box::new(|info| {
+        let backtrace = std::backtrace::Backtrace::capture();
+        match backtrace.status() {
+            std::backtrace::BacktraceStatus::Captured => {
+                error!(panic = %info, backtrace = %backtrace, "Panic")
+            }
+            _ => error!(panic = %info, "Panic"),
+        }
+    }));
 
     // ── Config ────────────────────────────────────────────────────────────────
-    let cfg = Config::from_env();
-    info!(mode = %cfg.trading_mode, "ClawBot starting");
+    let cfg = Config::load();
+    info!(mode = %cfg.trading_mode, account = %cfg.account, "ClawBot starting");
+    if let Some(path) = &cfg.config_file {
+        info!(path = %path, "Loaded config file");
+    }
 
     // ── Database ──────────────────────────────────────────────────────────────
-    let db = SqlitePool::connect(&cfg.database_url)
+    // WAL lets dashboard reads run alongside executor writes; writers that
+    // still collide wait up to the busy timeout instead of failing.
+    let db_options = SqliteConnectOptions::from_str(&cfg.database_url)
+        .unwrap_or_else(|e| panic!("Invalid DATABASE_URL: {e}"))
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(std::time::Duration::from_secs(
+            cfg.database_busy_timeout_secs,
+        ));
+    let db = SqlitePoolOptions::new()
+        .max_connections(cfg.database_max_connections)
+        .connect_with(db_options)
         .await
         .unwrap_or_else(|e| panic!("Failed to connect to database: {e}"));
     sqlx::migrate!("../../migrations")
@@ -76,67 +191,238 @@ async fn main() {
         .unwrap_or_else(|e| panic!("Database migration failed: {e}"));
     info!("Database ready");
 
+    if let Some(command) = history_command {
+        if let Err(e) = command.run(&db).await {
+            error!("History command failed: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    common::accounts::register(&db, &cfg.account, cfg.exchange)
+        .await
+        .unwrap_or_else(|e| panic!("Failed to register account: {e}"));
+
     // ── Shared state ──────────────────────────────────────────────────────────
+    // Every long-running task is spawned through `shutdown`, so Ctrl-C and
+    // SIGTERM stop them in order before the process exits
+    let shutdown = Shutdown::new();
     let open_positions: Arc<RwLock<Vec<common::Position>>> = Arc::new(RwLock::new(Vec::new()));
 
     // ── Engine ────────────────────────────────────────────────────────────────
     // Pairs to stream — read from strategy config
     let strategy_file = StrategyFileConfig::load(&cfg.strategy_config_path);
-    let pairs: Vec<String> = {
-        let mut seen = std::collections::HashSet::new();
-        strategy_file
-            .strategies
-            .iter()
-            .filter_map(|s| {
-                if seen.insert(s.pair.clone()) {
-                    Some(s.pair.clone())
-                } else {
-                    None
-                }
-            })
-            .collect()
-    };
+    let streams = strategy_file.pair_streams();
+    let pairs: Vec<String> = streams.iter().map(|(pair, _)| pair.clone()).collect();
 
-    let (engine, engine_handle) = Engine::new(pairs);
+    let (mut engine, engine_handle) = Engine::new(streams.clone());
+    engine.set_exchange(cfg.exchange);
+    engine.set_default_interval(strategy_file.interval);
+    engine.set_trade_pairs(strategy_file.trade_pairs.clone());
     // Use the engine's own state — single source of truth
     let engine_state = engine_handle.state_handle();
 
     // ── Exchange client (injected based on TRADING_MODE) ──────────────────────
+    let mut symbol_filters = SymbolFilterMap::new();
     let exchange_client: Arc<dyn common::ExchangeClient> = match cfg.trading_mode {
-        TradingMode::Live => {
-            info!("Live trading mode — using BinanceClient");
-            Arc::new(BinanceClient::new(
-                &cfg.binance_api_key,
-                &cfg.binance_secret,
-            ))
-        }
+        TradingMode::Live | TradingMode::LiveDryrun => match cfg.exchange {
+            ExchangeKind::Binance => {
+                let mut client = BinanceClient::new(&cfg.binance_api_key, &cfg.binance_secret);
+                if cfg.trading_mode == TradingMode::LiveDryrun {
+                    info!("Live dry-run mode — BinanceClient validates orders via /order/test");
+                    client.set_dry_run(true);
+                } else {
+                    info!("Live trading mode — using BinanceClient");
+                }
+                match client.symbol_filters(&pairs).await {
+                    Ok(filters) => {
+                        info!(symbols = filters.len(), "Loaded exchange symbol filters");
+                        symbol_filters = filters;
+                    }
+                    Err(e) => warn!("Failed to load exchange symbol filters: {e}"),
+                }
+                Arc::new(client)
+            }
+            ExchangeKind::Coinbase => {
+                info!("Live trading mode — using CoinbaseClient");
+                Arc::new(CoinbaseClient::new(
+                    &cfg.coinbase_api_key,
+                    &cfg.coinbase_secret,
+                ))
+            }
+            ExchangeKind::Bybit => {
+                info!("Live trading mode — using BybitClient");
+                Arc::new(BybitClient::new(&cfg.bybit_api_key, &cfg.bybit_secret))
+            }
+        },
         TradingMode::Paper => {
             info!(
                 slippage_bps = cfg.paper_slippage_bps,
                 "Paper trading mode — using PaperClient"
             );
-            Arc::new(PaperClient::new(
+            let client = Arc::new(PaperClient::new(
                 cfg.paper_initial_balance,
                 cfg.paper_slippage_bps,
-            ))
+            ));
+            shutdown.spawn(
+                "paper fills",
+                client.clone().follow_market(
+                    engine_handle.subscribe_market(),
+                    engine_handle.subscribe_trades(),
+                ),
+            );
+            client
         }
     };
 
+    // ── Standby client for runtime /mode switches ─────────────────────────────
+    let standby_client: Option<(TradingMode, Arc<dyn common::ExchangeClient>)> =
+        match cfg.trading_mode {
+            _ if cfg.mode_switch_user_ids.is_empty() => None,
+            TradingMode::Paper => {
+                let client: Arc<dyn common::ExchangeClient> = match cfg.exchange {
+                    ExchangeKind::Binance => {
+                        let client = BinanceClient::new(&cfg.binance_api_key, &cfg.binance_secret);
+                        match client.symbol_filters(&pairs).await {
+                            Ok(filters) => symbol_filters = filters,
+                            Err(e) => warn!("Failed to load exchange symbol filters: {e}"),
+                        }
+                        Arc::new(client)
+                    }
+                    ExchangeKind::Coinbase => Arc::new(CoinbaseClient::new(
+                        &cfg.coinbase_api_key,
+                        &cfg.coinbase_secret,
+                    )),
+                    ExchangeKind::Bybit => {
+                        Arc::new(BybitClient::new(&cfg.bybit_api_key, &cfg.bybit_secret))
+                    }
+                };
+                Some((TradingMode::Live, client))
+            }
+            TradingMode::Live => {
+                let client = Arc::new(PaperClient::new(
+                    cfg.paper_initial_balance,
+                    cfg.paper_slippage_bps,
+                ));
+                shutdown.spawn(
+                    "standby paper fills",
+                    client.clone().follow_market(
+                        engine_handle.subscribe_market(),
+                        engine_handle.subscribe_trades(),
+                    ),
+                );
+                Some((TradingMode::Paper, client))
+            }
+            // Dry-run is a rehearsal of live; it has nothing to switch to
+            TradingMode::LiveDryrun => None,
+        };
+    let trading_mode = Arc::new(RwLock::new(cfg.trading_mode));
+
     // ── Channels ──────────────────────────────────────────────────────────────
     let (signal_tx, signal_rx) = mpsc::channel::<common::Signal>(128);
+    let (risk_cmd_tx, risk_cmd_rx) = mpsc::channel::<common::RiskCommand>(16);
+    engine.set_risk_control(risk_cmd_tx.clone());
     let (order_tx, order_rx) = mpsc::channel::<common::Order>(128);
     let (risk_event_tx, mut risk_event_rx) = mpsc::channel::<common::RiskEvent>(64);
+    shutdown.set_panic_events(risk_event_tx.clone());
+    if cfg.market_stale_secs > 0 {
+        engine.set_staleness_watchdog(
+            std::time::Duration::from_secs(cfg.market_stale_secs),
+            risk_event_tx.clone(),
+        );
+    }
+    engine.set_candle_cache_size(cfg.candle_cache_size);
     let market_rx_strategy = engine_handle.subscribe_market();
     let market_rx_risk = engine_handle.subscribe_market();
 
     // ── Strategy registry ─────────────────────────────────────────────────────
-    let registry = StrategyRegistry::from_config(&strategy_file);
+    let mut registry = StrategyRegistry::from_config(&strategy_file);
+    let (strategy_cmd_tx, strategy_cmd_rx) = mpsc::channel::<common::StrategyCommand>(16);
+    registry.set_commands(strategy_cmd_rx);
+
+    // ── Stored candles (warm-up after a restart) ──────────────────────────────
+    if cfg.candle_retention_days > 0 {
+        let store = CandleStore::new(db.clone());
+        for (pair, interval) in &streams {
+            match store.recent(pair, *interval, cfg.candle_cache_size).await {
+                Ok(candles) if !candles.is_empty() => {
+                    info!(pair = %pair, candles = candles.len(), "Warming up from stored candles");
+                    engine.preload_candles(&candles);
+                    registry.warm_up(&candles);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(pair = %pair, error = %e, "Failed to load stored candles"),
+            }
+        }
+        shutdown.spawn("candle store", store.run(engine_handle.subscribe_market()));
+    }
+
+    // ── Position audit (startup and after every stream reconnect) ────────────
+    // The startup audit finishes before positions are recovered below, so
+    // the risk manager starts from the reconciled rows. Wired whenever live
+    // trading is possible, /mode included, and run only while it's live.
+    let live_client = match &standby_client {
+        _ if cfg.trading_mode == TradingMode::Live => Some(exchange_client.clone()),
+        Some((TradingMode::Live, client)) => Some(client.clone()),
+        _ => None,
+    };
+    if let Some(live_client) = live_client {
+        let mut auditor = PositionAuditor::new(
+            live_client,
+            db.clone(),
+            TradingMode::Live,
+            pairs.clone(),
+            risk_event_tx.clone(),
+        );
+        auditor.set_account(&cfg.account);
+        let auditor = Arc::new(auditor);
+        if cfg.trading_mode == TradingMode::Live {
+            if let Err(e) = auditor.run().await {
+                warn!("Position audit failed: {e}");
+            }
+        }
+        let trading_mode = trading_mode.clone();
+        engine.on_reconnect(move || {
+            let auditor = auditor.clone();
+            let trading_mode = trading_mode.clone();
+            tokio::spawn(async move {
+                if *trading_mode.read().await != TradingMode::Live {
+                    return;
+                }
+                if let Err(e) = auditor.run().await {
+                    warn!("Position audit failed: {e}");
+                }
+            });
+        });
+    }
+
+    // ── Open positions (recovered so SL/TP keep applying after a restart) ────
+    let mut ledger = TradeLedger::new(db.clone(), cfg.trading_mode);
+    ledger.set_account(&cfg.account);
+    match ledger.open_positions().await {
+        Ok(positions) => {
+            if !positions.is_empty() {
+                info!(count = positions.len(), "Recovered open positions");
+            }
+            *open_positions.write().await = positions;
+        }
+        Err(e) => panic!("Failed to load open positions: {e}"),
+    }
 
     // ── Risk manager ──────────────────────────────────────────────────────────
-    let risk_cfg = RiskConfig::default(); // TODO: load from file
-    let risk_manager = RiskManager::new(
+    // Runtime updates (API, Telegram) are saved and win over the config file
+    let risk_state_store = RiskStateStore::new(db.clone());
+    let risk_cfg = match risk_state_store.load_config().await {
+        Ok(Some(saved)) => {
+            info!("Restored risk config saved at runtime");
+            saved
+        }
+        Ok(None) => cfg.risk.clone(),
+        Err(e) => panic!("Failed to load saved risk config: {e}"),
+    };
+    let mut risk_manager = RiskManager::new(
         risk_cfg,
         signal_rx,
+        risk_cmd_rx,
         order_tx,
         risk_event_tx.clone(),
         market_rx_risk,
@@ -144,37 +430,172 @@ async fn main() {
         open_positions.clone(),
         cfg.paper_initial_balance,
     );
+    match risk_state_store.load().await {
+        Ok(Some(snapshot)) => {
+            info!(
+                peak = snapshot.portfolio_peak_usd,
+                value = snapshot.portfolio_value_usd,
+                halted = snapshot.halted,
+                "Restored risk state"
+            );
+            risk_manager.restore(&snapshot);
+            if snapshot.halted {
+                *engine_state.write().await = EngineState::Halted;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => panic!("Failed to load risk state: {e}"),
+    }
+    risk_manager.set_state_store(risk_state_store);
+    risk_manager.set_signal_journal(SignalJournal::new(db.clone()));
+    let equity_recorder =
+        EquityRecorder::new(db.clone(), trading_mode.clone(), risk_cmd_tx.clone());
+    shutdown.spawn(
+        "equity recorder",
+        equity_recorder.run(std::time::Duration::from_secs(60)),
+    );
+
+    // ── Data retention (hourly pruning of high-volume tables) ────────────────
+    let retention_stats = RetentionStats::new();
+    let mut retention = DataRetention::new(db.clone(), retention_stats.clone());
+    retention.keep(PrunedTable::Candles, cfg.candle_retention_days);
+    retention.keep(PrunedTable::Signals, cfg.signal_retention_days);
+    retention.keep(PrunedTable::RiskEvents, cfg.risk_event_retention_days);
+    if retention.is_enabled() {
+        shutdown.spawn(
+            "data retention",
+            retention.run(std::time::Duration::from_secs(60 * 60)),
+        );
+    }
+
+    // ── Database backups ──────────────────────────────────────────────────────
+    if let Some(dir) = &cfg.backup_dir {
+        info!(dir = %dir, every_hours = cfg.backup_interval_hours, keep = cfg.backup_keep, "Database backups enabled");
+        let backup = DatabaseBackup::new(db.clone(), dir, cfg.backup_keep);
+        shutdown.spawn(
+            "database backup",
+            backup.run(
+                std::time::Duration::from_secs(cfg.backup_interval_hours * 3600),
+                risk_event_tx.clone(),
+            ),
+        );
+    }
+
+    // ── No-data watchdog ──────────────────────────────────────────────────────
+    if cfg.watchdog_alert_mins > 0 {
+        let mut watchdog = Watchdog::new(
+            engine_handle.stream_health(),
+            engine_state.clone(),
+            std::time::Duration::from_secs(cfg.watchdog_alert_mins * 60),
+            risk_event_tx.clone(),
+        );
+        if cfg.exchange == ExchangeKind::Binance {
+            watchdog.set_rest_probe(Arc::new(BinanceClient::new(
+                &cfg.binance_api_key,
+                &cfg.binance_secret,
+            )));
+        }
+        shutdown.spawn("watchdog", watchdog.run());
+    }
+
+    // ── External heartbeat ────────────────────────────────────────────────────
+    if let Some(url) = &cfg.heartbeat_url {
+        info!(
+            every_secs = cfg.heartbeat_interval_secs,
+            "Heartbeat pings enabled"
+        );
+        // Same freshness bar as /readyz
+        let max_event_age = match cfg.market_stale_secs {
+            0 => 60,
+            secs => secs,
+        };
+        let heartbeat = Heartbeat::new(
+            url.clone(),
+            std::time::Duration::from_secs(cfg.heartbeat_interval_secs),
+            db.clone(),
+            engine_state.clone(),
+            engine_handle.stream_health(),
+            std::time::Duration::from_secs(max_event_age),
+        );
+        shutdown.spawn("heartbeat", heartbeat.run());
+    }
 
     // ── Order executor ────────────────────────────────────────────────────────
-    let executor = OrderExecutor::new(
+    let mut executor = OrderExecutor::new(
         order_rx,
         risk_event_tx.clone(),
         exchange_client,
         db.clone(),
         cfg.trading_mode,
     );
+    executor.set_account(&cfg.account);
+    executor.set_risk_control(risk_cmd_tx.clone());
+    if let Some((mode, client)) = standby_client {
+        info!(%mode, "Runtime switching with /mode enabled");
+        executor.add_standby_client(mode, client);
+    }
+    let (executor_cmd_tx, executor_cmd_rx) = mpsc::channel::<common::ExecutorCommand>(4);
+    executor.set_control(executor_cmd_rx);
+    engine.set_executor_control(executor_cmd_tx.clone());
+    executor.set_symbol_filters(symbol_filters);
+    let retry_queue = RetryQueue::new();
+    executor.set_retry_queue(retry_queue.clone());
+    let (dashboard_tx, _) = broadcast::channel::<common::DashboardEvent>(1024);
+    executor.set_dashboard_events(dashboard_tx.clone());
+    {
+        let mut market_rx = engine_handle.subscribe_market();
+        let dashboard_tx = dashboard_tx.clone();
+        shutdown.spawn("dashboard market feed", async move {
+            loop {
+                match market_rx.recv().await {
+                    Ok(event) => {
+                        let _ = dashboard_tx.send(common::DashboardEvent::Market(event));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    // ── Engine command bridge (shared by Telegram and the dashboard API) ──────
+    let engine_cmd_tx = {
+        let (tx, mut rx) = mpsc::channel::<common::EngineCommand>(32);
+        let handle = engine_handle.clone();
+        shutdown.spawn("engine command bridge", async move {
+            while let Some(cmd) = rx.recv().await {
+                handle.send(cmd).await;
+            }
+        });
+        tx
+    };
 
     // ── Telegram C2 ───────────────────────────────────────────────────────────
-    let allowed_ids: Vec<i64> = cfg.telegram_allowed_user_ids.clone();
+    let allowlist =
+        common::TelegramAllowlist::load(db.clone(), cfg.telegram_allowed_user_ids.clone())
+            .await
+            .unwrap_or_else(|e| panic!("Failed to load paired Telegram users: {e}"));
+    let audit = common::AuditLog::new(db.clone());
+    let alert_subscriptions = AlertSubscriptions::new(db.clone(), allowlist.clone());
     let bot_deps = BotDeps {
-        command_tx: {
-            // Create a command channel bridged to the engine handle
-            let (tx, mut rx) = mpsc::channel::<common::EngineCommand>(32);
-            let handle = engine_handle.clone();
-            tokio::spawn(async move {
-                while let Some(cmd) = rx.recv().await {
-                    handle.send(cmd).await;
-                }
-            });
-            tx
-        },
+        command_tx: engine_cmd_tx.clone(),
+        risk_tx: risk_cmd_tx.clone(),
+        executor_tx: executor_cmd_tx,
+        strategy_tx: strategy_cmd_tx.clone(),
         engine_state: engine_state.clone(),
-        trading_mode: cfg.trading_mode,
-        allowed_user_ids: Arc::new(allowed_ids),
+        trading_mode: trading_mode.clone(),
+        allowlist: allowlist.clone(),
+        mode_switch_user_ids: Arc::new(cfg.mode_switch_user_ids.clone()),
+        db: db.clone(),
+        stream_health: engine_handle.stream_health(),
+        market_stale_secs: cfg.market_stale_secs,
         alert_rx: Arc::new(tokio::sync::Mutex::new({
             let (_, rx) = mpsc::channel(1);
             rx
         })),
+        retry_queue: retry_queue.clone(),
+        audit: audit.clone(),
+        subscriptions: alert_subscriptions.clone(),
     };
 
     // ── Log buffer (keeps recent logs for new dashboard clients) ─────────────
@@ -182,74 +603,233 @@ async fn main() {
     {
         let buffer = log_buffer.clone();
         let mut rx = log_tx.subscribe();
-        tokio::spawn(async move {
-            while let Ok(line) = rx.recv().await {
-                buffer.push(line).await;
+        shutdown.spawn("log buffer", async move {
+            while let Ok(record) = rx.recv().await {
+                buffer.push(record).await;
             }
         });
     }
 
     // ── Dashboard API ─────────────────────────────────────────────────────────
+    let dashboard_auth = api::DashboardAuth::new(
+        cfg.dashboard_token.clone(),
+        cfg.dashboard_jwt_secret.clone(),
+    );
+    // The configured user is (re)created as an operator on every start
+    if let (Some(username), Some(password)) = (&cfg.dashboard_username, &cfg.dashboard_password) {
+        api::upsert_user(&db, username, password, api::Role::Operator)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to create dashboard user: {e}"));
+    }
+    match cfg.frontend_dir.as_deref() {
+        Some(dir) if std::path::Path::new(dir).is_dir() => {
+            info!(dir, "Serving dashboard frontend from directory")
+        }
+        Some(dir) => warn!(
+            dir,
+            "FRONTEND_DIR not found — serving the embedded frontend"
+        ),
+        None => {}
+    }
+    // /readyz probes. Market data comes from the configured exchange in every
+    // mode, so its REST API is checked even when paper trading.
+    let mut readiness_probes: Vec<Arc<dyn common::ReadinessProbe>> = vec![Arc::new(
+        TelegramProbe::new(teloxide::Bot::new(cfg.telegram_token.clone())),
+    )];
+    if cfg.exchange == ExchangeKind::Binance {
+        readiness_probes.push(Arc::new(BinanceClient::new(
+            &cfg.binance_api_key,
+            &cfg.binance_secret,
+        )));
+    }
     let api_state = api::AppState {
         db: db.clone(),
         engine_state: engine_state.clone(),
-        trading_mode: cfg.trading_mode,
-        dashboard_token: cfg.dashboard_token.clone(),
+        command_tx: engine_cmd_tx.clone(),
+        trading_mode: trading_mode.clone(),
+        auth: dashboard_auth,
         initial_balance: cfg.paper_initial_balance,
+        runtime_settings: cfg.runtime_settings(),
+        started_at,
+        risk_tx: risk_cmd_tx.clone(),
+        strategy_tx: strategy_cmd_tx,
+        retry_queue,
+        stream_health: engine_handle.stream_health(),
+        retention_stats,
+        readiness_probes,
+        audit,
+        telegram_allowlist: allowlist,
         log_tx: log_tx.clone(),
         log_buffer,
+        dashboard_tx: dashboard_tx.clone(),
+        frontend_dir: cfg.frontend_dir.as_ref().map(std::path::PathBuf::from),
+        ws_clients: Arc::new(tokio::sync::Semaphore::new(cfg.dashboard_ws_max_clients)),
+        shutdown: shutdown.token(),
     };
 
-    // ── Risk event forwarder (sends alerts to Telegram) ───────────────────────
+    // ── Risk event forwarder (persists events, alerts dashboard + Telegram) ───
+    // Telegram alerts are coalesced; the journal and dashboard get every event
+    let risk_journal = RiskEventJournal::new(db.clone());
+    let mut alert_throttle = common::AlertThrottle::new(chrono::Duration::minutes(10));
     let telegram_token = cfg.telegram_token.clone();
-    let alert_user_ids: Vec<i64> = cfg.telegram_allowed_user_ids.clone();
-    tokio::spawn(async move {
+    let subscriptions = alert_subscriptions.clone();
+    shutdown.spawn("risk event forwarder", async move {
         let bot = teloxide::Bot::new(telegram_token);
-        let chat_ids: Vec<teloxide::types::ChatId> = alert_user_ids
-            .iter()
-            .map(|&id| teloxide::types::ChatId(id))
-            .collect();
-
-        while let Some(event) = risk_event_rx.recv().await {
-            let msg = match event {
-                common::RiskEvent::StopLossTriggered { pair, close_price } => {
-                    format!(
-                        "⚠️ Stop-loss triggered on {pair}. Position closed at {close_price:.4}."
-                    )
-                }
-                common::RiskEvent::TakeProfitTriggered { pair, close_price } => {
-                    format!(
-                        "✅ Take-profit triggered on {pair}. Position closed at {close_price:.4}."
-                    )
-                }
-                common::RiskEvent::OrderFailed { pair, error } => {
-                    format!("🚨 Order failed on {pair}: {error}")
-                }
-                common::RiskEvent::DrawdownHaltEntered { drawdown_pct } => {
-                    format!("🛑 Max drawdown breached ({:.1}%). Engine halted. Use /reset-drawdown to resume.", drawdown_pct * 100.0)
-                }
-                common::RiskEvent::DrawdownHaltExited => {
-                    "✅ Drawdown halt cleared. Engine resuming.".to_string()
+        let send = |alert: common::Alert| {
+            let (bot, subscriptions) = (bot.clone(), subscriptions.clone());
+            async move {
+                let chat_ids = subscriptions.chats_for(alert.category).await;
+                telegram_ctrl::commands::send_alert(&bot, &chat_ids, &alert.message).await;
+            }
+        };
+
+        let mut flush = tokio::time::interval(std::time::Duration::from_secs(30));
+        loop {
+            tokio::select! {
+                event = risk_event_rx.recv() => {
+                    let Some(event) = event else { break };
+                    risk_journal.record(&event).await;
+                    let _ = dashboard_tx.send(common::DashboardEvent::Risk(event.clone()));
+                    let alert = common::Alert::from(&event);
+                    let _ = dashboard_tx.send(common::DashboardEvent::Alert(alert.clone()));
+                    if alert_throttle.admit(&alert) {
+                        send(alert).await;
+                    }
                 }
-                common::RiskEvent::OrderRejected { signal, reason } => {
-                    format!("⛔ Order rejected on {}: {reason}", signal.pair())
+                _ = flush.tick() => {
+                    for summary in alert_throttle.flush(chrono::Utc::now()) {
+                        send(summary).await;
+                    }
                 }
-            };
-            telegram_ctrl::commands::send_alert(&bot, &chat_ids, &msg).await;
+            }
         }
     });
 
+    let dashboard_tls = api::rustls_config(
+        cfg.dashboard_tls_cert.as_deref().map(std::path::Path::new),
+        cfg.dashboard_tls_key.as_deref().map(std::path::Path::new),
+        cfg.dashboard_tls_self_signed,
+        &cfg.dashboard_tls_hostnames,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("Failed to set up dashboard TLS: {e}"));
+
+    // ── Daily Telegram summary ────────────────────────────────────────────────
+    if let Some(at) = cfg.daily_summary_at {
+        let summary = DailySummary::new(
+            teloxide::Bot::new(cfg.telegram_token.clone()),
+            alert_subscriptions.clone(),
+            db.clone(),
+            trading_mode.clone(),
+            risk_cmd_tx.clone(),
+        );
+        shutdown.spawn(
+            "daily summary",
+            summary.run(at, cfg.daily_summary_utc_offset),
+        );
+    }
+
     // ── Spawn all tasks ───────────────────────────────────────────────────────
+    // The engine, executor and API stop themselves at a safe point; the
+    // rest are dropped. The strategy registry, risk manager, executor and
+    // Telegram bot are restarted if they panic, keeping their state; the
+    // engine owns the market streams and is not.
     let port = cfg.dashboard_port;
-    tokio::spawn(engine.run());
-    tokio::spawn(registry.run(market_rx_strategy, signal_tx, engine_state.clone()));
-    tokio::spawn(risk_manager.run());
-    tokio::spawn(executor.run());
-    tokio::spawn(start_bot(cfg.telegram_token.clone(), bot_deps));
-    tokio::spawn(api::serve(api_state, port));
-
-    // Keep main alive
+    engine.set_shutdown(shutdown.token());
+    executor.set_shutdown(shutdown.token());
+    shutdown.spawn_graceful("engine", engine.run());
+    {
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+        let handle = engine_handle.clone();
+        let engine_state = engine_state.clone();
+        let mut market_rx = Some(market_rx_strategy);
+        shutdown.supervise("strategy registry", move || {
+            // The first run keeps the receiver subscribed before warm-up
+            let market_rx = market_rx
+                .take()
+                .unwrap_or_else(|| handle.subscribe_market());
+            let (registry, signal_tx, engine_state) =
+                (registry.clone(), signal_tx.clone(), engine_state.clone());
+            async move {
+                registry
+                    .lock()
+                    .await
+                    .run(market_rx, signal_tx, engine_state)
+                    .await
+            }
+        });
+    }
+    {
+        let risk_manager = Arc::new(tokio::sync::Mutex::new(risk_manager));
+        shutdown.supervise("risk manager", move || {
+            let risk_manager = risk_manager.clone();
+            async move { risk_manager.lock().await.run().await }
+        });
+    }
+    {
+        let executor = Arc::new(tokio::sync::Mutex::new(executor));
+        shutdown.supervise_graceful("order executor", move || {
+            let executor = executor.clone();
+            async move { executor.lock().await.run().await }
+        });
+    }
+    {
+        let token = cfg.telegram_token.clone();
+        shutdown.supervise("telegram bot", move || {
+            start_bot(token.clone(), bot_deps.clone())
+        });
+    }
+    shutdown.spawn_graceful("dashboard api", async move {
+        if let Err(e) = api::serve(api_state, port, dashboard_tls).await {
+            error!(error = %e, "Dashboard API server failed");
+        }
+    });
+
+    // ── Shutdown cleanup, run after every task has stopped ────────────────────
+    {
+        let bot = teloxide::Bot::new(cfg.telegram_token.clone());
+        let subscriptions = alert_subscriptions.clone();
+        shutdown.on_shutdown("telegram", async move {
+            let chat_ids = subscriptions.chats_for(common::AlertCategory::Halts).await;
+            telegram_ctrl::commands::send_alert(
+                &bot,
+                &chat_ids,
+                "🛑 ClawBot is going offline. No trading or exits until it restarts.",
+            )
+            .await;
+        });
+    }
+    {
+        // Closing the pool waits for in-flight writes and checkpoints the WAL
+        let db = db.clone();
+        shutdown.on_shutdown("database", async move { db.close().await });
+    }
+
     info!("All subsystems started. Waiting for shutdown signal.");
+    shutdown_signal().await;
+    info!(
+        timeout_secs = SHUTDOWN_TIMEOUT.as_secs(),
+        "Shutdown signal received. Stopping subsystems."
+    );
+    if shutdown.shutdown(SHUTDOWN_TIMEOUT).await {
+        info!("Exiting.");
+    } else {
+        warn!("Exiting before every subsystem stopped cleanly.");
+    }
+}
+
+/// Resolves on Ctrl-C, or SIGTERM (what systemd sends on stop).
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("Failed to install SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
     tokio::signal::ctrl_c().await.unwrap();
-    info!("Shutdown signal received. Exiting.");
 }
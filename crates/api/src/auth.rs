@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use axum::{
     extract::{Request, State},
     http::{HeaderMap, StatusCode},
@@ -5,28 +7,324 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use sha2::Sha256;
+use sqlx::SqlitePool;
 
+use crate::users::{check_password, Role};
 use crate::AppState;
 
-/// Middleware that enforces bearer token authentication on all protected routes.
+/// Access tokens are short-lived; the dashboard refreshes them as needed.
+pub const ACCESS_TOKEN_TTL_SECS: i64 = 15 * 60;
+
+/// How long a session survives without being refreshed.
+pub const REFRESH_TOKEN_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Subject of sessions started with the dashboard token rather than a user
+/// login. Not allowed as a username.
+pub const TOKEN_SUBJECT: &str = "token";
+
+/// `{"alg":"HS256","typ":"JWT"}`, the only header we issue or accept.
+const JWT_HEADER: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    Access,
+    Refresh,
+}
+
+/// Claims of a dashboard session JWT. Every token issued for one login
+/// shares the session ID `sid`, so logging out revokes all of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub sid: String,
+    pub jti: String,
+    pub role: Role,
+    pub kind: TokenKind,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+impl Claims {
+    /// Who the session acts as in audit records, e.g. `api:alice`.
+    pub fn actor(&self) -> String {
+        format!("api:{}", self.sub)
+    }
+}
+
+/// The dashboard token and the key session JWTs are signed with. User
+/// accounts live in the `users` table.
+#[derive(Clone)]
+pub struct DashboardAuth {
+    token: String,
+    secret: Arc<Vec<u8>>,
+}
+
+impl DashboardAuth {
+    /// `token` logs in as an operator; `secret` signs session tokens.
+    pub fn new(token: String, secret: String) -> Self {
+        Self {
+            token,
+            secret: Arc::new(secret.into_bytes()),
+        }
+    }
+
+    /// Subject and role to issue a session for, if the credentials are
+    /// valid: either the dashboard token, or a user's name and password.
+    pub(crate) async fn login(
+        &self,
+        db: &SqlitePool,
+        username: Option<&str>,
+        password: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<Option<(String, Role)>, sqlx::Error> {
+        if let Some(token) = token {
+            let valid = constant_time_eq(token, &self.token);
+            return Ok(valid.then(|| (TOKEN_SUBJECT.to_string(), Role::Operator)));
+        }
+        let (Some(username), Some(password)) = (username, password) else {
+            return Ok(None);
+        };
+        let role = check_password(db, username, password).await?;
+        Ok(role.map(|role| (username.to_string(), role)))
+    }
+
+    /// A signed token of `kind` for session `sid`.
+    pub(crate) fn issue(
+        &self,
+        sub: &str,
+        sid: &str,
+        role: Role,
+        kind: TokenKind,
+    ) -> (String, Claims) {
+        let now = Utc::now().timestamp();
+        let ttl = match kind {
+            TokenKind::Access => ACCESS_TOKEN_TTL_SECS,
+            TokenKind::Refresh => REFRESH_TOKEN_TTL_SECS,
+        };
+        let claims = Claims {
+            sub: sub.to_string(),
+            sid: sid.to_string(),
+            role,
+            jti: uuid::Uuid::new_v4().to_string(),
+            kind,
+            iat: now,
+            exp: now + ttl,
+        };
+        (self.sign(JWT_HEADER, &claims), claims)
+    }
+
+    /// `claims` as a JWT with the base64url `header`, signed with our key.
+    fn sign(&self, header: &str, claims: &Claims) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap_or_default());
+        let signing_input = format!("{header}.{payload}");
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&signing_input).finalize().into_bytes());
+        format!("{signing_input}.{signature}")
+    }
+
+    /// Claims of `token` if it is a well-signed, unexpired token of `kind`.
+    /// Revocation is checked separately against the database.
+    pub(crate) fn verify(&self, token: &str, kind: TokenKind) -> Option<Claims> {
+        let (signing_input, signature) = token.rsplit_once('.')?;
+        let (header, payload) = signing_input.split_once('.')?;
+        if header != JWT_HEADER {
+            return None;
+        }
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        self.mac(signing_input).verify_slice(&signature).ok()?;
+        let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        (claims.kind == kind && claims.exp > Utc::now().timestamp()).then_some(claims)
+    }
+
+    fn mac(&self, input: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(input.as_bytes());
+        mac
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Claims of a valid, unrevoked access token.
+pub(crate) async fn authenticate(state: &AppState, token: &str) -> Option<Claims> {
+    let claims = state.auth.verify(token, TokenKind::Access)?;
+    match is_revoked(&state.db, &claims.sid).await {
+        Ok(false) => Some(claims),
+        _ => None,
+    }
+}
+
+/// Whether the session or refresh token `id` has been revoked.
+pub(crate) async fn is_revoked(db: &SqlitePool, id: &str) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!("SELECT id FROM revoked_tokens WHERE id = ?1", id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.is_some())
+}
+
+/// Revoke the session or refresh token `id` until `expires_at`. Returns
+/// `false` if it was already revoked.
+pub(crate) async fn revoke(
+    db: &SqlitePool,
+    id: &str,
+    expires_at: i64,
+) -> Result<bool, sqlx::Error> {
+    let now = Utc::now().timestamp();
+    sqlx::query!("DELETE FROM revoked_tokens WHERE expires_at < ?1", now)
+        .execute(db)
+        .await?;
+    let inserted = sqlx::query!(
+        "INSERT OR IGNORE INTO revoked_tokens (id, expires_at) VALUES (?1, ?2)",
+        id,
+        expires_at,
+    )
+    .execute(db)
+    .await?;
+    Ok(inserted.rows_affected() == 1)
+}
+
+fn forbidden() -> Response {
+    (StatusCode::FORBIDDEN, Json(json!({"error": "forbidden"}))).into_response()
+}
+
+pub(crate) fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({"error": "unauthorized"})),
+    )
+        .into_response()
+}
+
+/// The token of an `Authorization: Bearer <token>` header.
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// Middleware that requires a session access token (`Authorization: Bearer
+/// <jwt>`) on all protected routes. The token's claims are added to the
+/// request extensions.
 pub async fn require_auth(
     State(state): State<AppState>,
     headers: HeaderMap,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    let token = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|v| v.strip_prefix("Bearer "));
-
-    match token {
-        Some(t) if t == state.dashboard_token => next.run(request).await,
-        _ => (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "unauthorized"})),
-        )
-            .into_response(),
+    match bearer_token(&headers) {
+        Some(t) => match authenticate(&state, t).await {
+            Some(claims) => {
+                request.extensions_mut().insert(claims);
+                next.run(request).await
+            }
+            None => unauthorized(),
+        },
+        None => unauthorized(),
+    }
+}
+
+/// Route middleware that only lets operators through. Layer it inside
+/// `require_auth`, which provides the claims.
+pub async fn require_operator(request: Request, next: Next) -> Response {
+    match request.extensions().get::<Claims>() {
+        Some(claims) if claims.role == Role::Operator => next.run(request).await,
+        Some(_) => forbidden(),
+        None => unauthorized(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth() -> DashboardAuth {
+        DashboardAuth::new("dashboard-token".into(), "signing-secret".into())
+    }
+
+    #[test]
+    fn accepts_its_own_tokens() {
+        let auth = auth();
+        let (token, issued) = auth.issue("alice", "sid-1", Role::Viewer, TokenKind::Access);
+        let claims = auth.verify(&token, TokenKind::Access).expect("valid token");
+        assert_eq!(claims.sub, "alice");
+        assert_eq!(claims.sid, "sid-1");
+        assert_eq!(claims.jti, issued.jti);
+        assert_eq!(claims.role, Role::Viewer);
+    }
+
+    #[test]
+    fn rejects_a_tampered_token() {
+        let auth = auth();
+        let (token, mut claims) = auth.issue("alice", "sid-1", Role::Viewer, TokenKind::Access);
+
+        let (signing_input, signature) = token.rsplit_once('.').unwrap();
+        let flipped = if signature.starts_with('A') { "B" } else { "A" };
+        let bad_signature = format!("{signing_input}.{flipped}{}", &signature[1..]);
+        assert!(auth.verify(&bad_signature, TokenKind::Access).is_none());
+
+        // Promoting yourself keeps the old signature, which no longer matches
+        claims.role = Role::Operator;
+        let promoted = auth.sign(JWT_HEADER, &claims);
+        let (promoted_input, _) = promoted.rsplit_once('.').unwrap();
+        let forged = format!("{promoted_input}.{signature}");
+        assert!(auth.verify(&forged, TokenKind::Access).is_none());
+
+        // Signed with another key
+        let other = DashboardAuth::new("dashboard-token".into(), "other-secret".into());
+        assert!(other.verify(&token, TokenKind::Access).is_none());
+    }
+
+    #[test]
+    fn rejects_another_algorithm() {
+        let auth = auth();
+        let (_, claims) = auth.issue("alice", "sid-1", Role::Operator, TokenKind::Access);
+
+        let none = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","typ":"JWT"}"#);
+        let unsigned = auth.sign(&none, &claims);
+        let (unsigned_input, _) = unsigned.rsplit_once('.').unwrap();
+        assert!(auth
+            .verify(&format!("{unsigned_input}."), TokenKind::Access)
+            .is_none());
+        // Even correctly signed, only the HS256 header is accepted
+        assert!(auth.verify(&unsigned, TokenKind::Access).is_none());
+
+        let hs512 = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS512","typ":"JWT"}"#);
+        assert!(auth
+            .verify(&auth.sign(&hs512, &claims), TokenKind::Access)
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_an_expired_token() {
+        let auth = auth();
+        let (_, mut claims) = auth.issue("alice", "sid-1", Role::Operator, TokenKind::Access);
+        claims.exp = Utc::now().timestamp() - 1;
+        let expired = auth.sign(JWT_HEADER, &claims);
+        assert!(auth.verify(&expired, TokenKind::Access).is_none());
+    }
+
+    #[test]
+    fn refresh_token_is_not_an_access_token() {
+        let auth = auth();
+        let (refresh, _) = auth.issue("alice", "sid-1", Role::Operator, TokenKind::Refresh);
+        assert!(auth.verify(&refresh, TokenKind::Access).is_none());
+        assert!(auth.verify(&refresh, TokenKind::Refresh).is_some());
+
+        let (access, _) = auth.issue("alice", "sid-1", Role::Operator, TokenKind::Access);
+        assert!(auth.verify(&access, TokenKind::Refresh).is_none());
     }
 }
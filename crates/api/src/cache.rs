@@ -0,0 +1,76 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// JSON responses may be stored by the browser but must be revalidated,
+/// which is cheap thanks to their ETag.
+const JSON_CACHE_CONTROL: &str = "private, no-cache";
+
+/// Weak ETag for a response body. Weak because compression changes the
+/// bytes on the wire but not the content.
+pub(crate) fn etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{b:02x}")).collect();
+    HeaderValue::from_str(&format!("W/\"{hex}\"")).expect("hex is a valid header value")
+}
+
+/// Whether the request's `If-None-Match` already names `etag`.
+pub(crate) fn not_modified(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Some(tags) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default();
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    tags.split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Middleware that adds an ETag and `Cache-Control` to successful JSON
+/// `GET` responses, and answers `304 Not Modified` when the client already
+/// has the same body. Streams and non-JSON responses pass through untouched.
+pub async fn json_etag(request: Request, next: Next) -> Response {
+    let conditional = request.method() == Method::GET;
+    let headers = request.headers().clone();
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !conditional || response.status() != StatusCode::OK || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let tag = etag(&bytes);
+    parts.headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(JSON_CACHE_CONTROL),
+    );
+    if not_modified(&headers, &tag) {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, tag),
+                (
+                    header::CACHE_CONTROL,
+                    HeaderValue::from_static(JSON_CACHE_CONTROL),
+                ),
+            ],
+        )
+            .into_response();
+    }
+    parts.headers.insert(header::ETAG, tag);
+    Response::from_parts(parts, Body::from(bytes))
+}
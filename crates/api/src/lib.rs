@@ -1,22 +1,42 @@
 mod auth;
+mod cache;
+mod metrics;
 pub mod routes;
+mod tls;
+mod users;
+
+pub use auth::DashboardAuth;
+pub use tls::rustls_config;
+pub use users::{upsert_user, Role};
 
 use std::collections::VecDeque;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::Router;
+use axum::{middleware, Router};
+use axum_server::{tls_rustls::RustlsConfig, Handle};
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
-use tokio::sync::{broadcast, Mutex, RwLock};
-use tower_http::cors::{Any, CorsLayer};
-use tracing::info;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tower_http::{
+    compression::CompressionLayer,
+    cors::{Any, CorsLayer},
+};
+use tracing::{info, warn};
 
-use common::{EngineState, TradingMode};
+use common::{
+    AuditLog, DashboardEvent, EngineCommand, EngineState, LogRecord, ReadinessProbe,
+    RetentionStats, RetryQueue, RiskCommand, RuntimeSettings, StrategyCommand, StreamHealth,
+    TelegramAllowlist, TradingMode,
+};
 
-/// Ring buffer that keeps recent log lines so new clients get history.
+/// Ring buffer that keeps recent log records so new clients get history.
 #[derive(Clone)]
 pub struct LogBuffer {
-    inner: Arc<Mutex<VecDeque<String>>>,
+    inner: Arc<Mutex<VecDeque<LogRecord>>>,
     capacity: usize,
 }
 
@@ -28,15 +48,15 @@ impl LogBuffer {
         }
     }
 
-    pub async fn push(&self, line: String) {
+    pub async fn push(&self, record: LogRecord) {
         let mut buf = self.inner.lock().await;
         if buf.len() >= self.capacity {
             buf.pop_front();
         }
-        buf.push_back(line);
+        buf.push_back(record);
     }
 
-    pub async fn snapshot(&self) -> Vec<String> {
+    pub async fn snapshot(&self) -> Vec<LogRecord> {
         self.inner.lock().await.iter().cloned().collect()
     }
 }
@@ -46,17 +66,129 @@ impl LogBuffer {
 pub struct AppState {
     pub db: SqlitePool,
     pub engine_state: Arc<RwLock<EngineState>>,
-    pub trading_mode: TradingMode,
-    pub dashboard_token: String,
+    /// Command channel into the engine (start/stop/flatten/...).
+    pub command_tx: mpsc::Sender<EngineCommand>,
+    /// Current trading mode; Telegram's `/mode` may switch it at runtime.
+    pub trading_mode: Arc<RwLock<TradingMode>>,
+    /// Dashboard login credentials and session signing key.
+    pub auth: DashboardAuth,
     pub initial_balance: f64,
-    /// Broadcast channel for streaming log lines to WebSocket clients.
-    pub log_tx: broadcast::Sender<String>,
+    /// Non-secret startup settings, shown by `/api/config`.
+    pub runtime_settings: RuntimeSettings,
+    /// When the process started, for the uptime in `/api/runtime`.
+    pub started_at: DateTime<Utc>,
+    /// Control channel into the Risk Manager (runtime config reads/updates).
+    pub risk_tx: mpsc::Sender<RiskCommand>,
+    /// Control channel into the Strategy Registry (list, toggle, edit).
+    pub strategy_tx: mpsc::Sender<StrategyCommand>,
+    /// Failed orders awaiting retry, shared with the executor.
+    pub retry_queue: RetryQueue,
+    /// Per-pair market data stream health, maintained by the engine.
+    pub stream_health: StreamHealth,
+    /// Rows deleted by data retention, shown by `/api/runtime`.
+    pub retention_stats: RetentionStats,
+    /// External dependencies checked by `/readyz`.
+    pub readiness_probes: Vec<Arc<dyn ReadinessProbe>>,
+    /// Journal of operator actions, shared with the Telegram bot.
+    pub audit: AuditLog,
+    /// Telegram users allowed to control the bot; the dashboard issues
+    /// pairing codes for new ones.
+    pub telegram_allowlist: TelegramAllowlist,
+    /// Broadcast channel for streaming log records to WebSocket/SSE clients.
+    pub log_tx: broadcast::Sender<LogRecord>,
     /// Recent log history for new clients.
     pub log_buffer: LogBuffer,
+    /// Broadcast of trades, market data, and risk events for `/ws/stream`.
+    pub dashboard_tx: broadcast::Sender<DashboardEvent>,
+    /// Directory of frontend files served ahead of the embedded bundle.
+    pub frontend_dir: Option<PathBuf>,
+    /// One permit per WebSocket client allowed at once.
+    pub ws_clients: Arc<Semaphore>,
+    /// Cancelled when the process is shutting down. The server stops
+    /// accepting connections and WebSocket/SSE streams close.
+    pub shutdown: CancellationToken,
+}
+
+/// State over an in-memory database, for route tests. Engine, risk, and
+/// strategy commands go nowhere.
+#[cfg(test)]
+pub(crate) async fn test_state() -> AppState {
+    let db = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../../migrations").run(&db).await.unwrap();
+    let (command_tx, _) = mpsc::channel(1);
+    let (risk_tx, _) = mpsc::channel(1);
+    let (strategy_tx, _) = mpsc::channel(1);
+    let (log_tx, _) = broadcast::channel(1);
+    let (dashboard_tx, _) = broadcast::channel(1);
+    AppState {
+        engine_state: Arc::new(RwLock::new(EngineState::default())),
+        command_tx,
+        trading_mode: Arc::new(RwLock::new(TradingMode::Paper)),
+        auth: DashboardAuth::new("dashboard-token".into(), "signing-secret".into()),
+        initial_balance: 10_000.0,
+        runtime_settings: RuntimeSettings {
+            account: "default".into(),
+            exchange: common::ExchangeKind::Binance,
+            trading_mode: TradingMode::Paper,
+            dashboard_port: 3000,
+            dashboard_ws_max_clients: 1,
+            paper_slippage_bps: 0.0,
+            paper_initial_balance: 10_000.0,
+            market_stale_secs: 60,
+            watchdog_alert_mins: 5,
+            heartbeat_interval_secs: None,
+            candle_cache_size: 500,
+            candle_retention_days: 30,
+            signal_retention_days: 30,
+            risk_event_retention_days: 30,
+            database_max_connections: 1,
+            database_busy_timeout_secs: 5,
+            backup_dir: None,
+            backup_interval_hours: 24,
+            backup_keep: 7,
+            strategy_config_path: "strategies.toml".into(),
+            config_file: None,
+            telegram_allowed_users: 0,
+            daily_summary: None,
+        },
+        started_at: Utc::now(),
+        risk_tx,
+        strategy_tx,
+        retry_queue: RetryQueue::default(),
+        stream_health: StreamHealth::default(),
+        retention_stats: RetentionStats::default(),
+        readiness_probes: Vec::new(),
+        audit: AuditLog::new(db.clone()),
+        telegram_allowlist: TelegramAllowlist::load(db.clone(), Vec::new())
+            .await
+            .unwrap(),
+        log_tx,
+        log_buffer: LogBuffer::new(1),
+        dashboard_tx,
+        frontend_dir: None,
+        ws_clients: Arc::new(Semaphore::new(1)),
+        shutdown: CancellationToken::new(),
+        db,
+    }
+}
+
+/// How long in-flight requests and streams get to finish after shutdown is
+/// signalled before their connections are dropped.
+pub const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Resolves once `shutdown` is cancelled.
+pub(crate) async fn shutdown_signal(shutdown: CancellationToken) {
+    shutdown.cancelled_owned().await;
 }
 
-/// Build and run the Axum API server.
-pub async fn serve(state: AppState, port: u16) {
+/// Build and run the Axum API server, over HTTPS when `tls` is set. Returns
+/// once `state.shutdown` fires and open connections have drained, or after
+/// [`SHUTDOWN_GRACE`].
+pub async fn serve(state: AppState, port: u16, tls: Option<RustlsConfig>) -> std::io::Result<()> {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
 
     let cors = CorsLayer::new()
@@ -64,15 +196,58 @@ pub async fn serve(state: AppState, port: u16) {
         .allow_headers(Any)
         .allow_methods(Any);
 
+    let shutdown = shutdown_signal(state.shutdown.clone());
     let app = Router::new()
         .merge(routes::api_router(state.clone()))
+        .merge(routes::session_router(state.clone()))
+        .merge(routes::users_router(state.clone()))
+        .merge(routes::events_router())
+        .merge(routes::openapi_router())
         .merge(routes::ws_router())
         .merge(routes::health_router())
         .merge(routes::static_router())
         .with_state(state)
+        .layer(middleware::from_fn(metrics::track_requests))
+        .layer(middleware::from_fn(cache::json_etag))
+        .layer(CompressionLayer::new())
         .layer(cors);
 
-    info!(%addr, "Dashboard API listening");
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    match tls {
+        Some(tls) => {
+            let handle = Handle::new();
+            let draining = handle.clone();
+            tokio::spawn(async move {
+                shutdown.await;
+                info!("Draining dashboard API connections");
+                draining.graceful_shutdown(Some(SHUTDOWN_GRACE));
+            });
+            info!(%addr, "Dashboard API listening (HTTPS)");
+            axum_server::bind_rustls(addr, tls)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            info!(%addr, "Dashboard API listening");
+            let (drain_tx, drain_rx) = tokio::sync::oneshot::channel::<()>();
+            let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+                shutdown.await;
+                info!("Draining dashboard API connections");
+                let _ = drain_tx.send(());
+            });
+            // axum waits for connections indefinitely; cap it like axum-server
+            let deadline = async move {
+                let _ = drain_rx.await;
+                tokio::time::sleep(SHUTDOWN_GRACE).await;
+            };
+            tokio::select! {
+                result = server => result,
+                _ = deadline => {
+                    warn!("Dashboard API connections still open after grace period; dropping them");
+                    Ok(())
+                }
+            }
+        }
+    }
 }
@@ -1,36 +1,184 @@
+use std::collections::BTreeMap;
+
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     middleware,
-    routing::get,
-    Json, Router,
+    routing::{delete, get, patch, post, MethodRouter},
+    Extension, Json, Router,
 };
-use serde::Deserialize;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tokio::sync::oneshot;
 use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+
+use common::{
+    AlertSeverity, EngineCommand, OrderSide, RiskCommand, RiskConfig, RuntimeSettings, Signal,
+    StrategyCommand, StrategyStatus, Symbol,
+};
 
-use crate::{auth::require_auth, AppState};
+use super::openapi::ErrorBody;
+use crate::auth::{require_auth, require_operator, Claims};
+use crate::AppState;
 
+/// Every route needs a session; those wrapped in `operator` also need the
+/// operator role.
 pub fn api_router(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/api/portfolio", get(get_portfolio))
+        .route("/api/portfolio/allocation", get(get_allocation))
+        .route("/api/accounts", get(get_accounts))
         .route("/api/trades", get(get_trades))
+        .route(
+            "/api/orders",
+            get(get_orders).merge(operator(post(post_order))),
+        )
+        .route("/api/orders/retries", get(get_retries))
+        .route("/api/orders/retries/:id", operator(delete(delete_retry)))
+        .route("/api/orders/retries/:id/retry", operator(post(post_retry)))
         .route("/api/performance", get(get_performance))
-        .route("/api/config", get(get_config).post(post_config))
+        .route("/api/performance/breakdown", get(get_performance_breakdown))
+        .route("/api/pnl/daily", get(get_pnl_daily))
+        .route("/api/stats/pairs", get(get_pair_stats))
+        .route(
+            "/api/config",
+            get(get_config).merge(operator(post(post_config))),
+        )
+        .route(
+            "/api/risk",
+            get(get_risk).merge(operator(patch(patch_risk))),
+        )
+        .route("/api/runtime", get(get_runtime))
+        .route("/api/signals", get(get_signals))
+        .route("/api/risk-events", get(get_risk_events))
+        .route("/api/alerts", get(get_alerts))
+        .route("/api/audit", operator(get(get_audit)))
+        .route("/api/strategies", get(get_strategies))
+        .route("/api/strategies/:name", operator(patch(patch_strategy)))
+        .route("/api/engine/flatten", operator(post(post_flatten)))
+        .route("/api/engine/resume", operator(post(post_resume)))
+        .route(
+            "/api/engine/pairs/:pair",
+            operator(post(post_pair).delete(delete_pair)),
+        )
         .route_layer(middleware::from_fn_with_state(state, require_auth))
 }
 
+pub(super) fn operator(route: MethodRouter<AppState>) -> MethodRouter<AppState> {
+    route.route_layer(middleware::from_fn(require_operator))
+}
+
 // ─── Portfolio ────────────────────────────────────────────────────────────────
 
+#[utoipa::path(
+    get,
+    path = "/api/portfolio",
+    tag = "portfolio",
+    responses(
+        (status = 200, description = "Open positions", body = Object, example = json!({"positions": [{"id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "pair": "BTCUSDT", "side": "BUY", "entry_price": 64000.0, "quantity": 0.01, "mode": "paper", "opened_at": "2026-10-16T12:00:00+00:00", "account_id": "default"}], "total_open": 1})),
+    )
+)]
 async fn get_portfolio(State(state): State<AppState>) -> Json<Value> {
+    let pos_json = open_positions(&state.db).await;
+    Json(json!({
+        "positions": pos_json,
+        "total_open": pos_json.len(),
+    }))
+}
+
+/// Current exposure by asset and by strategy, in USD and as a fraction of
+/// equity, for the allocation chart. Positions are valued at the latest
+/// market price, or their entry price until one arrives. `cash_usd` is
+/// equity not held in positions; positions opened outside a strategy are
+/// grouped under a `null` strategy.
+#[utoipa::path(
+    get,
+    path = "/api/portfolio/allocation",
+    tag = "portfolio",
+    responses(
+        (status = 200, description = "Exposure by asset and by strategy", body = Object, example = json!({"equity_usd": 10000.0, "exposure_usd": 2500.0, "cash_usd": 7500.0, "by_asset": [{"asset": "BTC", "exposure_usd": 2000.0, "pct_of_equity": 0.2}, {"asset": "ETH", "exposure_usd": 500.0, "pct_of_equity": 0.05}], "by_strategy": [{"strategy": "btc-rsi", "exposure_usd": 2000.0, "pct_of_equity": 0.2}, {"strategy": null, "exposure_usd": 500.0, "pct_of_equity": 0.05}]})),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn get_allocation(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let (equity_tx, equity_rx) = oneshot::channel();
+    let (prices_tx, prices_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetEquity { reply: equity_tx })
+        .await;
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetPrices { reply: prices_tx })
+        .await;
+    let (Ok(equity), Ok(prices)) = (equity_rx.await, prices_rx.await) else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        );
+    };
+    let positions = sqlx::query!("SELECT pair, entry_price, quantity, strategy FROM positions")
+        .fetch_all(&state.db)
+        .await
+        .unwrap_or_default();
+
+    let mut by_asset: BTreeMap<String, f64> = BTreeMap::new();
+    let mut by_strategy: BTreeMap<Option<String>, f64> = BTreeMap::new();
+    for p in positions {
+        let price = prices.get(&p.pair).copied().unwrap_or(p.entry_price);
+        let exposure = p.quantity * price;
+        let asset = Symbol::parse(&p.pair).map_or(p.pair, |s| s.base);
+        *by_asset.entry(asset).or_default() += exposure;
+        *by_strategy.entry(p.strategy).or_default() += exposure;
+    }
+
+    let equity_usd = equity.equity_usd;
+    let share = |usd: f64| {
+        if equity_usd > 0.0 {
+            usd / equity_usd
+        } else {
+            0.0
+        }
+    };
+    let exposure_usd: f64 = by_asset.values().sum();
+    let by_asset: Vec<Value> = by_asset
+        .into_iter()
+        .map(|(asset, usd)| json!({ "asset": asset, "exposure_usd": usd, "pct_of_equity": share(usd) }))
+        .collect();
+    let by_strategy: Vec<Value> = by_strategy
+        .into_iter()
+        .map(|(strategy, usd)| {
+            json!({ "strategy": strategy, "exposure_usd": usd, "pct_of_equity": share(usd) })
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "equity_usd": equity_usd,
+            "exposure_usd": exposure_usd,
+            "cash_usd": equity_usd - exposure_usd,
+            "by_asset": by_asset,
+            "by_strategy": by_strategy,
+        })),
+    )
+}
+
+/// Open positions as JSON, shared with the `/ws/stream` positions channel.
+pub(super) async fn open_positions(db: &SqlitePool) -> Vec<Value> {
     let positions = sqlx::query!(
-        r#"SELECT id, pair, side, entry_price, quantity, mode, opened_at FROM positions"#
+        r#"SELECT id, pair, side, entry_price, quantity, mode, opened_at, account_id
+           FROM positions"#
     )
-    .fetch_all(&state.db)
+    .fetch_all(db)
     .await
     .unwrap_or_default();
 
-    let pos_json: Vec<Value> = positions
+    positions
         .iter()
         .map(|p| {
             json!({
@@ -41,145 +189,2001 @@ async fn get_portfolio(State(state): State<AppState>) -> Json<Value> {
                 "quantity": p.quantity,
                 "mode": p.mode,
                 "opened_at": p.opened_at,
+                "account_id": p.account_id,
             })
         })
-        .collect();
+        .collect()
+}
 
-    Json(json!({
-        "positions": pos_json,
-        "total_open": pos_json.len(),
-    }))
+// ─── Accounts ─────────────────────────────────────────────────────────────────
+
+/// Every account that has traded into this database, with how many open
+/// positions, trades, and orders it holds. `last_started_at` is when a
+/// process last started trading it.
+#[utoipa::path(
+    get,
+    path = "/api/accounts",
+    tag = "portfolio",
+    responses(
+        (status = 200, description = "Known accounts", body = Object, example = json!({"accounts": [{"id": "default", "exchange": "binance", "created_at": "2026-10-16T12:00:00+00:00", "last_started_at": "2026-10-16T12:00:00+00:00", "open_positions": 1, "trades": 12, "orders": 30}]})),
+    )
+)]
+async fn get_accounts(State(state): State<AppState>) -> Json<Value> {
+    let rows = sqlx::query!(
+        r#"SELECT a.id AS "id!", a.exchange, a.created_at, a.last_started_at,
+                  (SELECT COUNT(*) FROM positions p WHERE p.account_id = a.id)
+                      AS "open_positions!: i64",
+                  (SELECT COUNT(*) FROM trades t WHERE t.account_id = a.id) AS "trades!: i64",
+                  (SELECT COUNT(*) FROM orders o WHERE o.account_id = a.id) AS "orders!: i64"
+           FROM accounts a ORDER BY a.id"#
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let accounts: Vec<Value> = rows
+        .iter()
+        .map(|a| {
+            json!({
+                "id": a.id,
+                "exchange": a.exchange,
+                "created_at": a.created_at,
+                "last_started_at": a.last_started_at,
+                "open_positions": a.open_positions,
+                "trades": a.trades,
+                "orders": a.orders,
+            })
+        })
+        .collect();
+    Json(json!({ "accounts": accounts }))
 }
 
 // ─── Trades ───────────────────────────────────────────────────────────────────
 
-#[derive(Deserialize)]
+/// Filters, sort order, and pagination for `/api/trades`. `from`/`to` take
+/// the same forms as the performance filters and bound `closed_at`.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct TradesQuery {
     page: Option<i64>,
     limit: Option<i64>,
     pair: Option<String>,
+    /// `BUY` or `SELL`: the side of the position the trade closed.
+    side: Option<String>,
+    mode: Option<String>,
+    strategy: Option<String>,
+    account: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    /// `desc` (newest first, the default) or `asc`, by `closed_at`.
+    sort: Option<String>,
+    /// `next_cursor` of the previous response; takes precedence over `page`.
+    cursor: Option<String>,
+}
+
+/// `TradesQuery` validated and normalized for binding.
+struct TradeListFilter {
+    pair: Option<String>,
+    side: Option<String>,
+    mode: Option<String>,
+    strategy: Option<String>,
+    account: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    ascending: bool,
+    /// `(closed_at, id)` of the last trade already returned.
+    after: Option<(String, String)>,
 }
 
-async fn get_trades(State(state): State<AppState>, Query(q): Query<TradesQuery>) -> Json<Value> {
+impl TradesQuery {
+    fn filter(&self) -> Result<TradeListFilter, String> {
+        let side = self.side.as_deref().map(str::to_uppercase);
+        if let Some(side) = &side {
+            if side != "BUY" && side != "SELL" {
+                return Err(format!("unknown side '{side}'"));
+            }
+        }
+        if let Some(mode) = &self.mode {
+            check_mode(mode)?;
+        }
+        let ascending = match self.sort.as_deref() {
+            None | Some("desc") => false,
+            Some("asc") => true,
+            Some(other) => return Err(format!("unknown sort '{other}', expected asc or desc")),
+        };
+        Ok(TradeListFilter {
+            pair: self.pair.clone(),
+            side,
+            mode: self.mode.clone(),
+            strategy: self.strategy.clone(),
+            account: self.account.clone(),
+            from: self
+                .from
+                .as_deref()
+                .map(|s| time_bound(s, false))
+                .transpose()?,
+            to: self
+                .to
+                .as_deref()
+                .map(|s| time_bound(s, true))
+                .transpose()?,
+            ascending,
+            after: self.cursor.as_deref().map(decode_cursor).transpose()?,
+        })
+    }
+}
+
+impl TradeListFilter {
+    /// Append the `WHERE` clause for every filter but the cursor.
+    fn push_where<'a>(&'a self, qb: &mut QueryBuilder<'a, Sqlite>) {
+        qb.push(" WHERE 1 = 1");
+        let columns = [
+            ("pair", &self.pair),
+            ("side", &self.side),
+            ("mode", &self.mode),
+            ("strategy", &self.strategy),
+            ("account_id", &self.account),
+        ];
+        for (column, value) in columns {
+            if let Some(value) = value {
+                qb.push(format_args!(" AND {column} = ")).push_bind(value);
+            }
+        }
+        if let Some(from) = &self.from {
+            qb.push(" AND closed_at >= ").push_bind(from);
+        }
+        if let Some(to) = &self.to {
+            qb.push(" AND closed_at < ").push_bind(to);
+        }
+    }
+}
+
+/// Cursors are opaque to clients: `closed_at` and `id` of the last trade.
+fn encode_cursor(closed_at: &str, id: &str) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{closed_at}|{id}"))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(String, String), String> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|s| {
+            s.split_once('|')
+                .map(|(at, id)| (at.to_string(), id.to_string()))
+        })
+        .ok_or_else(|| "invalid cursor".to_string())
+}
+
+#[derive(sqlx::FromRow, Serialize)]
+struct TradeRow {
+    id: String,
+    pair: String,
+    side: String,
+    entry_price: f64,
+    exit_price: f64,
+    quantity: f64,
+    pnl_usd: f64,
+    mode: String,
+    strategy: Option<String>,
+    account_id: String,
+    opened_at: String,
+    closed_at: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/trades",
+    tag = "portfolio",
+    params(TradesQuery),
+    responses(
+        (status = 200, description = "Closed trades, newest first unless `sort=asc`. `next_cursor` is null on the last page.", body = Object, example = json!({"trades": [{"id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "pair": "BTCUSDT", "side": "BUY", "entry_price": 64000.0, "exit_price": 64500.0, "quantity": 0.01, "pnl_usd": 5.0, "mode": "paper", "strategy": "btc-rsi", "account_id": "default", "opened_at": "2026-10-16T12:00:00+00:00", "closed_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50, "next_cursor": null})),
+        (status = 400, description = "Invalid filter, sort, or cursor", body = ErrorBody),
+    )
+)]
+async fn get_trades(
+    State(state): State<AppState>,
+    Query(q): Query<TradesQuery>,
+) -> (StatusCode, Json<Value>) {
+    let filter = match q.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
     let page = q.page.unwrap_or(1).max(1);
-    let limit = q.limit.unwrap_or(50).min(200);
-    let offset = (page - 1) * limit;
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
 
-    if let Some(pair) = &q.pair {
-        let rows = sqlx::query!(
-            r#"SELECT id, pair, side, entry_price, exit_price, quantity, pnl_usd, mode, opened_at, closed_at
-               FROM trades WHERE pair = ?1 ORDER BY closed_at DESC LIMIT ?2 OFFSET ?3"#,
-            pair, limit, offset
-        )
+    let mut qb = QueryBuilder::new(
+        "SELECT id, pair, side, entry_price, exit_price, quantity, pnl_usd, mode, strategy, \
+         account_id, opened_at, closed_at FROM trades",
+    );
+    filter.push_where(&mut qb);
+    let (cmp, dir) = if filter.ascending {
+        (">", "ASC")
+    } else {
+        ("<", "DESC")
+    };
+    if let Some((closed_at, id)) = &filter.after {
+        qb.push(format_args!(" AND (closed_at {cmp} "))
+            .push_bind(closed_at)
+            .push(" OR (closed_at = ")
+            .push_bind(closed_at)
+            .push(format_args!(" AND id {cmp} "))
+            .push_bind(id)
+            .push("))");
+    }
+    // One extra row tells whether there is a next page
+    qb.push(format_args!(" ORDER BY closed_at {dir}, id {dir} LIMIT "))
+        .push_bind(limit + 1);
+    if filter.after.is_none() {
+        qb.push(" OFFSET ").push_bind((page - 1) * limit);
+    }
+    let mut trades: Vec<TradeRow> = qb
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .unwrap_or_default();
+    let next_cursor = (trades.len() as i64 > limit).then(|| {
+        trades.truncate(limit as usize);
+        trades
+            .last()
+            .map(|t| encode_cursor(&t.closed_at, &t.id))
+            .unwrap_or_default()
+    });
 
-        let total: i32 = sqlx::query_scalar!("SELECT COUNT(*) FROM trades WHERE pair = ?1", pair)
-            .fetch_one(&state.db)
-            .await
-            .unwrap_or(0);
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM trades");
+    filter.push_where(&mut count);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or(0);
 
-        let trades: Vec<Value> = rows
-            .iter()
-            .map(|t| {
-                json!({
-                    "id": t.id, "pair": t.pair, "side": t.side,
-                    "entry_price": t.entry_price, "exit_price": t.exit_price,
-                    "quantity": t.quantity, "pnl_usd": t.pnl_usd,
-                    "mode": t.mode, "opened_at": t.opened_at, "closed_at": t.closed_at,
-                })
+    (
+        StatusCode::OK,
+        Json(json!({
+            "trades": trades,
+            "total": total,
+            "page": page,
+            "limit": limit,
+            "next_cursor": next_cursor,
+        })),
+    )
+}
+
+// ─── Orders ───────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct OrdersQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+    status: Option<String>,
+    pair: Option<String>,
+    account: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/orders",
+    tag = "orders",
+    params(OrdersQuery),
+    responses(
+        (status = 200, description = "Order journal, newest first", body = Object, example = json!({"orders": [{"id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "pair": "BTCUSDT", "side": "BUY", "order_type": "market", "quantity": 0.01, "price": null, "position_id": null, "status": "filled", "exchange_order_id": "28457", "filled_quantity": 0.01, "average_price": 64000.0, "error": null, "attempts": 1, "mode": "paper", "account_id": "default", "created_at": "2026-10-16T12:00:00+00:00", "updated_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50})),
+    )
+)]
+async fn get_orders(State(state): State<AppState>, Query(q): Query<OrdersQuery>) -> Json<Value> {
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(50).min(200);
+    let offset = (page - 1) * limit;
+
+    let rows = sqlx::query!(
+        r#"SELECT id, pair, side, order_type, quantity, price, position_id, status,
+                  exchange_order_id, filled_quantity, average_price, error, attempts, mode,
+                  account_id, created_at, updated_at
+           FROM orders
+           WHERE (?1 IS NULL OR status = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?5 IS NULL OR account_id = ?5)
+           ORDER BY created_at DESC LIMIT ?3 OFFSET ?4"#,
+        q.status,
+        q.pair,
+        limit,
+        offset,
+        q.account,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let total: i32 = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) FROM orders
+           WHERE (?1 IS NULL OR status = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR account_id = ?3)"#,
+        q.status,
+        q.pair,
+        q.account,
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let orders: Vec<Value> = rows
+        .iter()
+        .map(|o| {
+            json!({
+                "id": o.id, "pair": o.pair, "side": o.side, "order_type": o.order_type,
+                "quantity": o.quantity, "price": o.price, "position_id": o.position_id,
+                "status": o.status, "exchange_order_id": o.exchange_order_id,
+                "filled_quantity": o.filled_quantity, "average_price": o.average_price,
+                "error": o.error, "attempts": o.attempts, "mode": o.mode,
+                "account_id": o.account_id, "created_at": o.created_at, "updated_at": o.updated_at,
             })
-            .collect();
-        Json(json!({ "trades": trades, "total": total, "page": page, "limit": limit }))
-    } else {
-        let rows = sqlx::query!(
-            r#"SELECT id, pair, side, entry_price, exit_price, quantity, pnl_usd, mode, opened_at, closed_at
-               FROM trades ORDER BY closed_at DESC LIMIT ?1 OFFSET ?2"#,
-            limit, offset
+        })
+        .collect();
+    Json(json!({ "orders": orders, "total": total, "page": page, "limit": limit }))
+}
+
+/// How long `POST /api/orders` waits for the executor's answer before
+/// replying with the order still `submitted`.
+const ORDER_OUTCOME_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often the order journal is checked while waiting.
+const ORDER_OUTCOME_POLL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// A manual order. Give either `quantity` or `quote_quantity`.
+#[derive(Deserialize, ToSchema)]
+struct OrderRequest {
+    pair: String,
+    side: OrderSide,
+    /// Base asset quantity.
+    quantity: Option<Decimal>,
+    /// Market orders only: spend (buy) or receive (sell) this much of the
+    /// quote asset.
+    quote_quantity: Option<Decimal>,
+    /// Limit price; omit for a market order.
+    limit_price: Option<Decimal>,
+}
+
+impl OrderRequest {
+    fn signal(self) -> Result<Signal, String> {
+        let pair = self.pair.trim().to_uppercase();
+        if pair.is_empty() || !pair.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("invalid pair '{}'", self.pair));
+        }
+        if self.limit_price.is_some_and(|p| p <= Decimal::ZERO) {
+            return Err("limit_price must be positive".into());
+        }
+        match (self.quantity, self.quote_quantity) {
+            (Some(quantity), None) if quantity > Decimal::ZERO => Ok(Signal {
+                limit_price: self.limit_price,
+                ..Signal::new(pair, self.side, quantity)
+            }),
+            (None, Some(quote)) if quote > Decimal::ZERO => match self.limit_price {
+                Some(_) => Err("quote_quantity is for market orders only".into()),
+                None => Ok(Signal::spend(pair, self.side, quote)),
+            },
+            (Some(_), Some(_)) => Err("give quantity or quote_quantity, not both".into()),
+            (None, None) => Err("quantity or quote_quantity is required".into()),
+            _ => Err("quantity must be positive".into()),
+        }
+    }
+}
+
+/// Place an order by hand. It enters the risk pipeline as a signal, so every
+/// pre-trade check applies; approved orders go to the executor, and the
+/// response carries the order's state once the exchange has answered.
+#[utoipa::path(
+    post,
+    path = "/api/orders",
+    tag = "orders",
+    request_body = OrderRequest,
+    responses(
+        (status = 200, description = "Approved; the executor's answer (`filled`, `partially_filled`, `resting`, `failed`, or `rejected` by exchange filters)", body = Object, example = json!({"approved": true, "order_id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "status": "filled", "exchange_order_id": "28457", "filled_quantity": 0.01, "average_price": 64000.0, "error": null})),
+        (status = 202, description = "Approved; no answer from the exchange yet", body = Object, example = json!({"approved": true, "order_id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "status": "submitted"})),
+        (status = 400, description = "Invalid order", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 422, description = "Rejected by the risk manager", body = Object, example = json!({"approved": false, "reason": "exposure limit exceeded"})),
+    )
+)]
+async fn post_order(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<OrderRequest>,
+) -> (StatusCode, Json<Value>) {
+    let signal = match req.signal() {
+        Ok(signal) => signal,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let payload = json!({
+        "pair": signal.pair, "side": signal.side, "quantity": signal.quantity,
+        "quote_quantity": signal.quote_quantity, "limit_price": signal.limit_price,
+    });
+    state
+        .audit
+        .record(&claims.actor(), "order.submit", payload)
+        .await;
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::SubmitSignal {
+            signal: Box::new(signal),
+            reply: reply_tx,
+        })
+        .await;
+    let order = match reply_rx.await {
+        Ok(Ok(order)) => order,
+        Ok(Err(reason)) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "approved": false, "reason": reason.to_string() })),
+            )
+        }
+        Err(_) => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "error": "risk manager unavailable" })),
+            )
+        }
+    };
+
+    let deadline = tokio::time::Instant::now() + ORDER_OUTCOME_TIMEOUT;
+    loop {
+        let row = sqlx::query!(
+            r#"SELECT status, exchange_order_id, filled_quantity, average_price, error
+               FROM orders WHERE id = ?1"#,
+            order.id
         )
-        .fetch_all(&state.db)
+        .fetch_optional(&state.db)
         .await
         .unwrap_or_default();
+        if let Some(o) = row.filter(|o| o.status != "submitted") {
+            return (
+                StatusCode::OK,
+                Json(json!({
+                    "approved": true, "order_id": order.id, "status": o.status,
+                    "exchange_order_id": o.exchange_order_id,
+                    "filled_quantity": o.filled_quantity, "average_price": o.average_price,
+                    "error": o.error,
+                })),
+            );
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return (
+                StatusCode::ACCEPTED,
+                Json(json!({ "approved": true, "order_id": order.id, "status": "submitted" })),
+            );
+        }
+        tokio::time::sleep(ORDER_OUTCOME_POLL).await;
+    }
+}
 
-        let total: i32 = sqlx::query_scalar!("SELECT COUNT(*) FROM trades")
-            .fetch_one(&state.db)
-            .await
-            .unwrap_or(0);
+// ─── Retry queue ──────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/api/orders/retries",
+    tag = "orders",
+    responses(
+        (status = 200, description = "Failed orders awaiting retry, oldest first", body = Object, example = json!({"total": 1, "orders": [{"order": {"id": "9f0c7a52-3a4e-4d7e-9a7b-1f2e3d4c5b6a", "pair": "BTCUSDT", "side": "BUY", "quantity": 0.01}, "error": "timeout", "rounds": 0, "failed_at": "2026-10-16T12:00:00+00:00", "expires_at": "2026-10-16T12:00:00+00:00", "next_retry_at": "2026-10-16T12:00:00+00:00"}]})),
+    )
+)]
+async fn get_retries(State(state): State<AppState>) -> Json<Value> {
+    let queue = state.retry_queue.list();
+    Json(json!({ "total": queue.len(), "orders": queue }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/orders/retries/{id}/retry",
+    tag = "orders",
+    params(("id" = String, Path, description = "Order ID")),
+    responses(
+        (status = 200, description = "Retry scheduled for the next executor tick", body = Object, example = json!({"status": "retry scheduled"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 404, description = "Order not in the retry queue", body = ErrorBody),
+    )
+)]
+async fn post_retry(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    if state.retry_queue.retry_now(&id) {
+        warn!(order_id = %id, "Order retry requested via API");
+        let payload = json!({ "order_id": id });
+        state
+            .audit
+            .record(&claims.actor(), "order.retry", payload)
+            .await;
+        (StatusCode::OK, Json(json!({ "status": "retry scheduled" })))
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "order not in retry queue" })),
+        )
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/orders/retries/{id}",
+    tag = "orders",
+    params(("id" = String, Path, description = "Order ID")),
+    responses(
+        (status = 200, description = "Order dropped without retrying", body = Object, example = json!({"status": "discarded"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 404, description = "Order not in the retry queue", body = ErrorBody),
+    )
+)]
+async fn delete_retry(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    match state.retry_queue.discard(&id) {
+        Some(_) => {
+            warn!(order_id = %id, "Queued order discarded via API");
+            let payload = json!({ "order_id": id });
+            state
+                .audit
+                .record(&claims.actor(), "order.discard", payload)
+                .await;
+            (StatusCode::OK, Json(json!({ "status": "discarded" })))
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "order not in retry queue" })),
+        ),
+    }
+}
+
+// ─── Performance ──────────────────────────────────────────────────────────────
+
+/// Trade filters shared by the performance endpoints: `from`/`to` as RFC 3339
+/// timestamps or `YYYY-MM-DD` dates (a `to` date includes that whole day),
+/// and `mode` (`paper`, `live`, or `live-dryrun`).
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PerformanceQuery {
+    from: Option<String>,
+    to: Option<String>,
+    mode: Option<String>,
+}
+
+/// `PerformanceQuery` normalized for comparison against stored values:
+/// `closed_at >= from AND closed_at < to AND mode = mode`.
+struct TradeFilter {
+    from: Option<String>,
+    to: Option<String>,
+    mode: Option<String>,
+}
+
+impl PerformanceQuery {
+    fn filter(&self) -> Result<TradeFilter, String> {
+        if let Some(mode) = &self.mode {
+            check_mode(mode)?;
+        }
+        Ok(TradeFilter {
+            from: self
+                .from
+                .as_deref()
+                .map(|s| time_bound(s, false))
+                .transpose()?,
+            to: self
+                .to
+                .as_deref()
+                .map(|s| time_bound(s, true))
+                .transpose()?,
+            mode: self.mode.clone(),
+        })
+    }
+}
+
+fn check_mode(mode: &str) -> Result<(), String> {
+    if ["paper", "live", "live-dryrun"].contains(&mode) {
+        Ok(())
+    } else {
+        Err(format!("unknown mode '{mode}'"))
+    }
+}
+
+/// Parse a `from`/`to` bound into the RFC 3339 UTC form `closed_at` uses.
+/// A date is its midnight, or the next midnight for an end bound.
+fn time_bound(value: &str, end: bool) -> Result<String, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc).to_rfc3339());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{value}', expected YYYY-MM-DD or RFC 3339"))?;
+    let date = if end { date + Duration::days(1) } else { date };
+    Ok(date.and_time(NaiveTime::MIN).and_utc().to_rfc3339())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/performance",
+    tag = "performance",
+    params(PerformanceQuery),
+    responses(
+        (status = 200, description = "Equity curve and summary metrics", body = Object, example = json!({"equity_curve": [{"timestamp": "2026-10-16T12:00:00+00:00", "value": 10005.0}], "equity_source": "snapshots", "win_rate": 0.6, "total_pnl_usd": 5.0, "trade_count": 5, "max_drawdown_pct": 0.01, "value_at_risk_usd": 42.0})),
+        (status = 400, description = "Invalid date or mode", body = ErrorBody),
+    )
+)]
+async fn get_performance(
+    State(state): State<AppState>,
+    Query(q): Query<PerformanceQuery>,
+) -> (StatusCode, Json<Value>) {
+    let filter = match q.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let trades = sqlx::query!(
+        r#"SELECT pnl_usd, closed_at FROM trades
+           WHERE (?1 IS NULL OR closed_at >= ?1) AND (?2 IS NULL OR closed_at < ?2)
+             AND (?3 IS NULL OR mode = ?3)
+           ORDER BY closed_at ASC"#,
+        filter.from,
+        filter.to,
+        filter.mode,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
 
-        let trades: Vec<Value> = rows
+    let snapshots = sqlx::query!(
+        r#"SELECT equity_usd, taken_at FROM equity_snapshots
+           WHERE (?1 IS NULL OR taken_at >= ?1) AND (?2 IS NULL OR taken_at < ?2)
+             AND (?3 IS NULL OR mode = ?3)
+           ORDER BY taken_at ASC"#,
+        filter.from,
+        filter.to,
+        filter.mode,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let value_at_risk = current_var(&state).await;
+
+    // Prefer mark-to-market snapshots; ranges recorded before snapshots
+    // existed fall back to an equity curve of realized PnL only
+    let (equity_source, points): (&str, Vec<(String, f64)>) = if snapshots.is_empty() {
+        let mut equity = state.initial_balance;
+        let points = trades
             .iter()
             .map(|t| {
-                json!({
-                    "id": t.id, "pair": t.pair, "side": t.side,
-                    "entry_price": t.entry_price, "exit_price": t.exit_price,
-                    "quantity": t.quantity, "pnl_usd": t.pnl_usd,
-                    "mode": t.mode, "opened_at": t.opened_at, "closed_at": t.closed_at,
-                })
+                equity += t.pnl_usd;
+                (t.closed_at.clone(), equity)
             })
             .collect();
-        Json(json!({ "trades": trades, "total": total, "page": page, "limit": limit }))
+        ("trades", points)
+    } else {
+        let points = snapshots
+            .into_iter()
+            .map(|s| (s.taken_at, s.equity_usd))
+            .collect();
+        ("snapshots", points)
+    };
+
+    let mut peak = state.initial_balance;
+    let mut max_dd = 0.0f64;
+    let mut curve: Vec<Value> = Vec::new();
+    for (timestamp, equity) in points {
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            max_dd = max_dd.max((peak - equity) / peak);
+        }
+        curve.push(json!({ "timestamp": timestamp, "value": equity }));
     }
+
+    let wins = trades.iter().filter(|t| t.pnl_usd > 0.0).count();
+    let win_rate = if trades.is_empty() {
+        0.0
+    } else {
+        wins as f64 / trades.len() as f64
+    };
+    let total_pnl: f64 = trades.iter().map(|t| t.pnl_usd).sum();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "equity_curve": curve,
+            "equity_source": equity_source,
+            "win_rate": win_rate,
+            "total_pnl_usd": total_pnl,
+            "trade_count": trades.len(),
+            "max_drawdown_pct": max_dd,
+            "value_at_risk_usd": value_at_risk,
+        })),
+    )
 }
 
-// ─── Performance ──────────────────────────────────────────────────────────────
+/// Per-group performance over closed trades, fed in close order.
+#[derive(Default, Serialize, ToSchema)]
+pub(super) struct TradeStats {
+    trade_count: usize,
+    wins: usize,
+    win_rate: f64,
+    total_pnl_usd: f64,
+    /// Average PnL per trade.
+    expectancy_usd: f64,
+    /// Largest peak-to-trough fall of the group's cumulative PnL.
+    max_drawdown_usd: f64,
+    #[serde(skip)]
+    peak_pnl: f64,
+}
 
-async fn get_performance(State(state): State<AppState>) -> Json<Value> {
-    let trades = sqlx::query!(r#"SELECT pnl_usd, closed_at FROM trades ORDER BY closed_at ASC"#)
-        .fetch_all(&state.db)
+impl TradeStats {
+    fn push(&mut self, pnl_usd: f64) {
+        self.trade_count += 1;
+        if pnl_usd > 0.0 {
+            self.wins += 1;
+        }
+        self.total_pnl_usd += pnl_usd;
+        self.peak_pnl = self.peak_pnl.max(self.total_pnl_usd);
+        self.max_drawdown_usd = self
+            .max_drawdown_usd
+            .max(self.peak_pnl - self.total_pnl_usd);
+        self.win_rate = self.wins as f64 / self.trade_count as f64;
+        self.expectancy_usd = self.total_pnl_usd / self.trade_count as f64;
+    }
+}
+
+/// Performance grouped by strategy and by pair, with the same filters as
+/// `/api/performance`. Trades closing positions opened outside a strategy
+/// are grouped under a `null` strategy.
+#[utoipa::path(
+    get,
+    path = "/api/performance/breakdown",
+    tag = "performance",
+    params(PerformanceQuery),
+    responses(
+        (status = 200, description = "`TradeStats` per strategy and per pair, each with a `strategy` or `pair` key", body = Object, example = json!({"by_strategy": [{"strategy": "btc-rsi", "trade_count": 4, "wins": 3, "win_rate": 0.75, "total_pnl_usd": 12.5, "expectancy_usd": 3.125, "max_drawdown_usd": 2.0}], "by_pair": [{"pair": "BTCUSDT", "trade_count": 4, "wins": 3, "win_rate": 0.75, "total_pnl_usd": 12.5, "expectancy_usd": 3.125, "max_drawdown_usd": 2.0}]})),
+        (status = 400, description = "Invalid date or mode", body = ErrorBody),
+    )
+)]
+async fn get_performance_breakdown(
+    State(state): State<AppState>,
+    Query(q): Query<PerformanceQuery>,
+) -> (StatusCode, Json<Value>) {
+    let filter = match q.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let trades = sqlx::query!(
+        r#"SELECT strategy, pair, pnl_usd FROM trades
+           WHERE (?1 IS NULL OR closed_at >= ?1) AND (?2 IS NULL OR closed_at < ?2)
+             AND (?3 IS NULL OR mode = ?3)
+           ORDER BY closed_at ASC"#,
+        filter.from,
+        filter.to,
+        filter.mode,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut by_strategy: BTreeMap<Option<String>, TradeStats> = BTreeMap::new();
+    let mut by_pair: BTreeMap<String, TradeStats> = BTreeMap::new();
+    for t in trades {
+        by_strategy.entry(t.strategy).or_default().push(t.pnl_usd);
+        by_pair.entry(t.pair).or_default().push(t.pnl_usd);
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "by_strategy": grouped("strategy", by_strategy),
+            "by_pair": grouped("pair", by_pair),
+        })),
+    )
+}
+
+/// Realized PnL per UTC calendar day and per ISO week (e.g. `2026-W42`),
+/// with the same filters as `/api/performance`.
+#[utoipa::path(
+    get,
+    path = "/api/pnl/daily",
+    tag = "performance",
+    params(PerformanceQuery),
+    responses(
+        (status = 200, description = "`TradeStats` per UTC day (`date`) and ISO week (`week`)", body = Object, example = json!({"days": [{"date": "2026-10-16", "trade_count": 2, "wins": 1, "win_rate": 0.5, "total_pnl_usd": 1.5, "expectancy_usd": 0.75, "max_drawdown_usd": 1.0}], "weeks": [{"week": "2026-W42", "trade_count": 2, "wins": 1, "win_rate": 0.5, "total_pnl_usd": 1.5, "expectancy_usd": 0.75, "max_drawdown_usd": 1.0}]})),
+        (status = 400, description = "Invalid date or mode", body = ErrorBody),
+    )
+)]
+async fn get_pnl_daily(
+    State(state): State<AppState>,
+    Query(q): Query<PerformanceQuery>,
+) -> (StatusCode, Json<Value>) {
+    let filter = match q.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let trades = sqlx::query!(
+        r#"SELECT pnl_usd, closed_at FROM trades
+           WHERE (?1 IS NULL OR closed_at >= ?1) AND (?2 IS NULL OR closed_at < ?2)
+             AND (?3 IS NULL OR mode = ?3)
+           ORDER BY closed_at ASC"#,
+        filter.from,
+        filter.to,
+        filter.mode,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut days: BTreeMap<NaiveDate, TradeStats> = BTreeMap::new();
+    let mut weeks: BTreeMap<String, TradeStats> = BTreeMap::new();
+    for t in trades {
+        let Ok(closed_at) = DateTime::parse_from_rfc3339(&t.closed_at) else {
+            continue;
+        };
+        let day = closed_at.with_timezone(&Utc).date_naive();
+        let week = day.iso_week();
+        days.entry(day).or_default().push(t.pnl_usd);
+        weeks
+            .entry(format!("{}-W{:02}", week.year(), week.week()))
+            .or_default()
+            .push(t.pnl_usd);
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "days": grouped("date", days),
+            "weeks": grouped("week", weeks),
+        })),
+    )
+}
+
+/// One JSON object per group: its stats plus the group name under `key`.
+/// Trade statistics per pair, aggregated in SQL, with the same filters as
+/// `/api/performance`. Holding time runs from position open to trade close;
+/// fees are the commissions of the entry and exit fills.
+#[utoipa::path(
+    get,
+    path = "/api/stats/pairs",
+    tag = "performance",
+    params(PerformanceQuery),
+    responses(
+        (status = 200, description = "Statistics per pair, by pair name", body = Object, example = json!({"pairs": [{"pair": "BTCUSDT", "trade_count": 4, "wins": 3, "win_rate": 0.75, "avg_pnl_usd": 3.125, "total_pnl_usd": 12.5, "avg_holding_secs": 5400.0, "total_fees_usd": 0.82}]})),
+        (status = 400, description = "Invalid date or mode", body = ErrorBody),
+    )
+)]
+async fn get_pair_stats(
+    State(state): State<AppState>,
+    Query(q): Query<PerformanceQuery>,
+) -> (StatusCode, Json<Value>) {
+    let filter = match q.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let rows = sqlx::query!(
+        r#"SELECT pair,
+                  COUNT(*) AS "trade_count!: i64",
+                  SUM(pnl_usd > 0) AS "wins!: i64",
+                  AVG(pnl_usd) AS "avg_pnl_usd!: f64",
+                  SUM(pnl_usd) AS "total_pnl_usd!: f64",
+                  AVG((julianday(closed_at) - julianday(opened_at)) * 86400) AS "avg_holding_secs: f64",
+                  SUM(fee_usd) AS "total_fees_usd!: f64"
+           FROM trades
+           WHERE (?1 IS NULL OR closed_at >= ?1) AND (?2 IS NULL OR closed_at < ?2)
+             AND (?3 IS NULL OR mode = ?3)
+           GROUP BY pair ORDER BY pair"#,
+        filter.from,
+        filter.to,
+        filter.mode,
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let pairs: Vec<Value> = rows
+        .iter()
+        .map(|r| {
+            json!({
+                "pair": r.pair, "trade_count": r.trade_count, "wins": r.wins,
+                "win_rate": r.wins as f64 / r.trade_count as f64,
+                "avg_pnl_usd": r.avg_pnl_usd, "total_pnl_usd": r.total_pnl_usd,
+                "avg_holding_secs": r.avg_holding_secs, "total_fees_usd": r.total_fees_usd,
+            })
+        })
+        .collect();
+    (StatusCode::OK, Json(json!({ "pairs": pairs })))
+}
+
+fn grouped<K: Serialize>(key: &str, groups: BTreeMap<K, TradeStats>) -> Vec<Value> {
+    groups
+        .into_iter()
+        .map(|(name, stats)| {
+            let mut entry = json!(stats);
+            entry[key] = json!(name);
+            entry
+        })
+        .collect()
+}
+
+/// Current portfolio VaR from the risk manager; `None` if it is unavailable
+/// or does not yet have enough price history.
+async fn current_var(state: &AppState) -> Option<f64> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetValueAtRisk { reply: reply_tx })
+        .await;
+    reply_rx.await.ok().flatten()
+}
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, ToSchema)]
+struct ConfigBody {
+    risk: RiskConfig,
+    runtime: RuntimeSettings,
+}
+
+/// Effective risk config plus the non-secret startup settings.
+#[utoipa::path(
+    get,
+    path = "/api/config",
+    tag = "config",
+    responses(
+        (status = 200, description = "Effective risk config and startup settings", body = ConfigBody),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn get_config(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetConfig { reply: reply_tx })
+        .await;
+    match reply_rx.await {
+        Ok(risk) => (
+            StatusCode::OK,
+            Json(json!(ConfigBody {
+                risk,
+                runtime: state.runtime_settings.clone(),
+            })),
+        ),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        ),
+    }
+}
+
+/// What the running process is actually using: startup settings, streamed
+/// pairs with their candle intervals, the effective risk config, rows
+/// deleted by data retention, versions, and uptime. Credentials and tokens
+/// are never included.
+#[utoipa::path(
+    get,
+    path = "/api/runtime",
+    tag = "config",
+    responses(
+        (status = 200, description = "Effective runtime configuration", body = Object, example = json!({"engine": "running", "runtime": {"exchange": "binance", "trading_mode": "paper", "dashboard_port": 8080}, "pairs": [{"pair": "BTCUSDT", "interval": "1m"}], "risk": {"max_open_positions": 3}, "retention": [{"table": "signals", "retention_days": 90, "deleted_rows": 1200, "last_deleted_rows": 14, "last_pruned_at": "2026-10-16T12:00:00+00:00", "last_error": null}], "versions": {"clawbot": "0.1.0", "schema": 12}, "started_at": "2026-10-16T12:00:00+00:00", "uptime_secs": 3600})),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn get_runtime(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetConfig { reply: reply_tx })
+        .await;
+    let Ok(risk) = reply_rx.await else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        );
+    };
+    let engine = *state.engine_state.read().await;
+    let pairs: Vec<Value> = state
+        .stream_health
+        .snapshot()
+        .into_iter()
+        .map(|s| json!({ "pair": s.pair, "interval": s.interval }))
+        .collect();
+    // Latest applied migration; the table is created by `sqlx::migrate!`
+    let schema: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+        .fetch_one(&state.db)
         .await
         .unwrap_or_default();
+    let uptime_secs = (Utc::now() - state.started_at).num_seconds();
+    (
+        StatusCode::OK,
+        Json(json!({
+            "engine": engine.to_string(),
+            "runtime": state.runtime_settings,
+            "pairs": pairs,
+            "risk": risk,
+            "retention": state.retention_stats.snapshot(),
+            "versions": { "clawbot": env!("CARGO_PKG_VERSION"), "schema": schema },
+            "started_at": state.started_at.to_rfc3339(),
+            "uptime_secs": uptime_secs,
+        })),
+    )
+}
 
-    if trades.is_empty() {
-        return Json(json!({
-            "equity_curve": [],
-            "win_rate": 0.0,
-            "total_pnl_usd": 0.0,
-            "trade_count": 0,
-            "max_drawdown_pct": 0.0,
-        }));
+/// Apply `{"risk": {...}}`, a partial risk config update, live. The risk
+/// manager validates and saves it, so it survives a restart. Runtime
+/// settings are read-only: a `runtime` section is accepted only unchanged,
+/// so the body of `GET /api/config` can be edited and posted back.
+#[utoipa::path(
+    post,
+    path = "/api/config",
+    tag = "config",
+    request_body = Object,
+    responses(
+        (status = 200, description = "Updated config", body = ConfigBody),
+        (status = 400, description = "Invalid or unknown section, changed runtime settings, or rejected risk values", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn post_config(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(body): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    let Value::Object(mut fields) = body else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "config update must be an object" })),
+        );
+    };
+    let runtime = fields.remove("runtime");
+    if runtime.is_some_and(|r| r != json!(state.runtime_settings)) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(
+                json!({ "error": "runtime settings are read-only; change the environment and restart" }),
+            ),
+        );
+    }
+    let Some(patch) = fields.remove("risk") else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "expected a 'risk' object" })),
+        );
+    };
+    if let Some(key) = fields.keys().next() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": format!("unknown config section '{key}'") })),
+        );
     }
 
-    let mut equity = state.initial_balance;
-    let mut peak = equity;
-    let mut max_dd = 0.0f64;
-    let mut wins = 0usize;
-    let mut curve: Vec<Value> = Vec::new();
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::UpdateConfig {
+            patch: patch.clone(),
+            actor: claims.actor(),
+            reply: reply_tx,
+        })
+        .await;
+    match reply_rx.await {
+        Ok(Ok(risk)) => {
+            let payload = json!({ "risk": patch });
+            state
+                .audit
+                .record(&claims.actor(), "config.update", payload)
+                .await;
+            (
+                StatusCode::OK,
+                Json(json!(ConfigBody {
+                    risk,
+                    runtime: state.runtime_settings.clone(),
+                })),
+            )
+        }
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        ),
+    }
+}
 
-    for t in &trades {
-        equity += t.pnl_usd;
-        if equity > peak {
-            peak = equity;
+// ─── Engine control ───────────────────────────────────────────────────────────
+
+/// Kill-switch: close every open position and pause new entries.
+#[utoipa::path(
+    post,
+    path = "/api/engine/flatten",
+    tag = "engine",
+    responses(
+        (status = 200, description = "Closing all positions and pausing", body = Object, example = json!({"status": "flattening"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn post_flatten(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Json<Value> {
+    warn!("POST /api/engine/flatten received");
+    let _ = state.command_tx.send(EngineCommand::Flatten).await;
+    state
+        .audit
+        .record(&claims.actor(), "engine.flatten", json!({}))
+        .await;
+    Json(json!({ "status": "flattening" }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/engine/resume",
+    tag = "engine",
+    responses(
+        (status = 200, description = "Trading resumed", body = Object, example = json!({"status": "resuming"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn post_resume(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Json<Value> {
+    let _ = state.command_tx.send(EngineCommand::Resume).await;
+    state
+        .audit
+        .record(&claims.actor(), "engine.resume", json!({}))
+        .await;
+    Json(json!({ "status": "resuming" }))
+}
+
+/// Start streaming market data for a pair without restarting the engine.
+#[utoipa::path(
+    post,
+    path = "/api/engine/pairs/{pair}",
+    tag = "engine",
+    params(("pair" = String, Path, description = "Exchange symbol, e.g. ETHUSDT")),
+    responses(
+        (status = 200, description = "Pair is being added to the market stream", body = Object, example = json!({"status": "adding", "pair": "ETHUSDT"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn post_pair(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(pair): Path<String>,
+) -> Json<Value> {
+    let pair = pair.to_uppercase();
+    warn!(pair = %pair, "Pair added via API");
+    let _ = state
+        .command_tx
+        .send(EngineCommand::AddPair(pair.clone()))
+        .await;
+    state
+        .audit
+        .record(&claims.actor(), "pair.add", json!({ "pair": pair }))
+        .await;
+    Json(json!({ "status": "adding", "pair": pair }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/engine/pairs/{pair}",
+    tag = "engine",
+    params(("pair" = String, Path, description = "Exchange symbol, e.g. ETHUSDT")),
+    responses(
+        (status = 200, description = "Pair is being removed from the market stream", body = Object, example = json!({"status": "removing", "pair": "ETHUSDT"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn delete_pair(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(pair): Path<String>,
+) -> Json<Value> {
+    let pair = pair.to_uppercase();
+    warn!(pair = %pair, "Pair removed via API");
+    let _ = state
+        .command_tx
+        .send(EngineCommand::RemovePair(pair.clone()))
+        .await;
+    state
+        .audit
+        .record(&claims.actor(), "pair.remove", json!({ "pair": pair }))
+        .await;
+    Json(json!({ "status": "removing", "pair": pair }))
+}
+
+// ─── Signals ──────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SignalsQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+    strategy: Option<String>,
+    pair: Option<String>,
+    /// `approved`, `rejected`, or `filled`.
+    outcome: Option<String>,
+    /// Start date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    from: Option<String>,
+    /// End date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    to: Option<String>,
+}
+
+/// Every signal the risk manager decided on: approved (with the order it
+/// became, `filled` once that order fills) or rejected with the reason.
+/// Manual orders appear with a `null` strategy.
+#[utoipa::path(
+    get,
+    path = "/api/signals",
+    tag = "orders",
+    params(SignalsQuery),
+    responses(
+        (status = 200, description = "Signals, newest first", body = Object, example = json!({"signals": [{"id": 12, "strategy": "btc-rsi", "pair": "BTCUSDT", "side": "BUY", "quantity": 0.01, "quote_quantity": null, "limit_price": null, "outcome": "rejected", "reason": "exposure limit exceeded", "order_id": null, "created_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50})),
+        (status = 400, description = "Invalid outcome or date", body = ErrorBody),
+    )
+)]
+async fn get_signals(
+    State(state): State<AppState>,
+    Query(q): Query<SignalsQuery>,
+) -> (StatusCode, Json<Value>) {
+    if let Some(outcome) = &q.outcome {
+        if !["approved", "rejected", "filled"].contains(&outcome.as_str()) {
+            let error = "outcome must be approved, rejected, or filled";
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": error })));
         }
-        let dd = (peak - equity) / peak;
-        if dd > max_dd {
-            max_dd = dd;
+    }
+    let bounds = (
+        q.from.as_deref().map(|v| time_bound(v, false)).transpose(),
+        q.to.as_deref().map(|v| time_bound(v, true)).transpose(),
+    );
+    let (from, to) = match bounds {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * limit;
+
+    let rows = sqlx::query!(
+        r#"SELECT id, strategy, pair, side, quantity, quote_quantity, limit_price, outcome,
+                  reason, order_id, created_at
+           FROM signals
+           WHERE (?1 IS NULL OR strategy = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR outcome = ?3)
+             AND (?4 IS NULL OR created_at >= ?4)
+             AND (?5 IS NULL OR created_at < ?5)
+           ORDER BY created_at DESC, id DESC LIMIT ?6 OFFSET ?7"#,
+        q.strategy,
+        q.pair,
+        q.outcome,
+        from,
+        to,
+        limit,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let total: i32 = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) FROM signals
+           WHERE (?1 IS NULL OR strategy = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR outcome = ?3)
+             AND (?4 IS NULL OR created_at >= ?4)
+             AND (?5 IS NULL OR created_at < ?5)"#,
+        q.strategy,
+        q.pair,
+        q.outcome,
+        from,
+        to
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let signals: Vec<Value> = rows
+        .iter()
+        .map(|s| {
+            json!({
+                "id": s.id, "strategy": s.strategy, "pair": s.pair, "side": s.side,
+                "quantity": s.quantity, "quote_quantity": s.quote_quantity,
+                "limit_price": s.limit_price, "outcome": s.outcome, "reason": s.reason,
+                "order_id": s.order_id, "created_at": s.created_at,
+            })
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(json!({ "signals": signals, "total": total, "page": page, "limit": limit })),
+    )
+}
+
+// ─── Risk events ──────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RiskEventsQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+    kind: Option<String>,
+    pair: Option<String>,
+    /// Inclusive lower bound on `created_at` (ISO-8601).
+    from: Option<String>,
+    /// Exclusive upper bound on `created_at` (ISO-8601).
+    to: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/risk-events",
+    tag = "risk",
+    params(RiskEventsQuery),
+    responses(
+        (status = 200, description = "Risk events, newest first", body = Object, example = json!({"events": [{"id": 1, "kind": "stop_loss_triggered", "pair": "BTCUSDT", "details": {}, "created_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50})),
+    )
+)]
+async fn get_risk_events(
+    State(state): State<AppState>,
+    Query(q): Query<RiskEventsQuery>,
+) -> Json<Value> {
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1).saturating_mul(limit);
+
+    let rows = sqlx::query!(
+        r#"SELECT id, kind, pair, details, created_at FROM risk_events
+           WHERE (?1 IS NULL OR kind = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)
+           ORDER BY created_at DESC, id DESC LIMIT ?5 OFFSET ?6"#,
+        q.kind,
+        q.pair,
+        q.from,
+        q.to,
+        limit,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let total: i32 = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) FROM risk_events
+           WHERE (?1 IS NULL OR kind = ?1)
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)"#,
+        q.kind,
+        q.pair,
+        q.from,
+        q.to
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let events: Vec<Value> = rows
+        .iter()
+        .map(|e| {
+            json!({
+                "id": e.id, "kind": e.kind, "pair": e.pair,
+                "details": serde_json::from_str::<Value>(&e.details).unwrap_or(Value::Null),
+                "created_at": e.created_at,
+            })
+        })
+        .collect();
+    Json(json!({ "events": events, "total": total, "page": page, "limit": limit }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AlertsQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+    /// Minimum severity: `info` (default), `warning`, or `critical`.
+    severity: Option<String>,
+    pair: Option<String>,
+    /// Start date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    from: Option<String>,
+    /// End date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    to: Option<String>,
+}
+
+/// Risk events as operator alerts — the same text Telegram receives — for
+/// the dashboard's alert feed. New alerts are pushed on the `alerts` channel
+/// of `/ws/stream`.
+#[utoipa::path(
+    get,
+    path = "/api/alerts",
+    tag = "risk",
+    params(AlertsQuery),
+    responses(
+        (status = 200, description = "Alerts, newest first", body = Object, example = json!({"alerts": [{"id": 7, "kind": "order_failed", "pair": "BTCUSDT", "severity": "critical", "message": "🚨 Order failed on BTCUSDT after 3 attempt(s): insufficient balance", "created_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50})),
+        (status = 400, description = "Invalid severity or date", body = ErrorBody),
+    )
+)]
+async fn get_alerts(
+    State(state): State<AppState>,
+    Query(q): Query<AlertsQuery>,
+) -> (StatusCode, Json<Value>) {
+    let severity = match q.severity.as_deref().map(AlertSeverity::parse) {
+        None => AlertSeverity::Info,
+        Some(Some(severity)) => severity,
+        Some(None) => {
+            let error = "severity must be info, warning, or critical";
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": error })));
         }
-        if t.pnl_usd > 0.0 {
-            wins += 1;
+    };
+    let bounds = (
+        q.from.as_deref().map(|v| time_bound(v, false)).transpose(),
+        q.to.as_deref().map(|v| time_bound(v, true)).transpose(),
+    );
+    let (from, to) = match bounds {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * limit;
+    let min_rank = severity as i64;
+
+    let rows = sqlx::query!(
+        r#"SELECT id, kind, pair, severity, message, created_at FROM risk_events
+           WHERE CASE severity WHEN 'critical' THEN 2 WHEN 'warning' THEN 1 ELSE 0 END >= ?1
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)
+           ORDER BY created_at DESC, id DESC LIMIT ?5 OFFSET ?6"#,
+        min_rank,
+        q.pair,
+        from,
+        to,
+        limit,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let total: i32 = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) FROM risk_events
+           WHERE CASE severity WHEN 'critical' THEN 2 WHEN 'warning' THEN 1 ELSE 0 END >= ?1
+             AND (?2 IS NULL OR pair = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)"#,
+        min_rank,
+        q.pair,
+        from,
+        to
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let alerts: Vec<Value> = rows
+        .iter()
+        .map(|a| {
+            json!({
+                "id": a.id, "kind": a.kind, "pair": a.pair, "severity": a.severity,
+                "message": a.message, "created_at": a.created_at,
+            })
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(json!({ "alerts": alerts, "total": total, "page": page, "limit": limit })),
+    )
+}
+
+// ─── Audit log ────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AuditQuery {
+    page: Option<i64>,
+    limit: Option<i64>,
+    /// Exact actor, e.g. `api:alice` or `telegram:123456`.
+    actor: Option<String>,
+    /// Exact action, e.g. `engine.flatten`.
+    action: Option<String>,
+    /// Start date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    from: Option<String>,
+    /// End date (`YYYY-MM-DD` or RFC 3339), inclusive.
+    to: Option<String>,
+}
+
+/// State-changing actions taken through the API or Telegram: who did what,
+/// when, and with which parameters.
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    tag = "audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit entries, newest first", body = Object, example = json!({"entries": [{"id": 3, "actor": "api:alice", "action": "risk.update", "payload": {"max_open_positions": 3}, "created_at": "2026-10-16T12:00:00+00:00"}], "total": 1, "page": 1, "limit": 50})),
+        (status = 400, description = "Invalid date", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn get_audit(
+    State(state): State<AppState>,
+    Query(q): Query<AuditQuery>,
+) -> (StatusCode, Json<Value>) {
+    let bounds = (
+        q.from.as_deref().map(|v| time_bound(v, false)).transpose(),
+        q.to.as_deref().map(|v| time_bound(v, true)).transpose(),
+    );
+    let (from, to) = match bounds {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let page = q.page.unwrap_or(1).max(1);
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * limit;
+
+    let rows = sqlx::query!(
+        r#"SELECT id, actor, action, payload, created_at FROM audit_log
+           WHERE (?1 IS NULL OR actor = ?1)
+             AND (?2 IS NULL OR action = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)
+           ORDER BY created_at DESC, id DESC LIMIT ?5 OFFSET ?6"#,
+        q.actor,
+        q.action,
+        from,
+        to,
+        limit,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let total: i32 = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) FROM audit_log
+           WHERE (?1 IS NULL OR actor = ?1)
+             AND (?2 IS NULL OR action = ?2)
+             AND (?3 IS NULL OR created_at >= ?3)
+             AND (?4 IS NULL OR created_at < ?4)"#,
+        q.actor,
+        q.action,
+        from,
+        to
+    )
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    let entries: Vec<Value> = rows
+        .iter()
+        .map(|e| {
+            json!({
+                "id": e.id, "actor": e.actor, "action": e.action,
+                "payload": serde_json::from_str::<Value>(&e.payload).unwrap_or(Value::Null),
+                "created_at": e.created_at,
+            })
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(json!({ "entries": entries, "total": total, "page": page, "limit": limit })),
+    )
+}
+
+// ─── Risk ─────────────────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/api/risk",
+    tag = "risk",
+    responses(
+        (status = 200, description = "Effective risk config", body = RiskConfig),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn get_risk(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::GetConfig { reply: reply_tx })
+        .await;
+    match reply_rx.await {
+        Ok(config) => (StatusCode::OK, Json(json!(config))),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        ),
+    }
+}
+
+/// Apply a partial risk config update, e.g. `{"stop_loss_pct": 0.015}`.
+/// Takes effect immediately without restarting or touching open positions.
+#[utoipa::path(
+    patch,
+    path = "/api/risk",
+    tag = "risk",
+    request_body = Object,
+    responses(
+        (status = 200, description = "Updated risk config", body = RiskConfig),
+        (status = 400, description = "Rejected risk values", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 503, description = "Risk manager unavailable", body = ErrorBody),
+    )
+)]
+async fn patch_risk(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(patch): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .risk_tx
+        .send(RiskCommand::UpdateConfig {
+            patch: patch.clone(),
+            actor: claims.actor(),
+            reply: reply_tx,
+        })
+        .await;
+    match reply_rx.await {
+        Ok(Ok(config)) => {
+            state
+                .audit
+                .record(&claims.actor(), "risk.update", patch)
+                .await;
+            (StatusCode::OK, Json(json!(config)))
         }
-        curve.push(json!({ "timestamp": t.closed_at, "value": equity }));
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "risk manager unavailable" })),
+        ),
     }
+}
 
-    let win_rate = wins as f64 / trades.len() as f64;
-    let total_pnl: f64 = trades.iter().map(|t| t.pnl_usd).sum();
+// ─── Strategies ───────────────────────────────────────────────────────────────
 
-    Json(json!({
-        "equity_curve": curve,
-        "win_rate": win_rate,
-        "total_pnl_usd": total_pnl,
-        "trade_count": trades.len(),
-        "max_drawdown_pct": max_dd,
-    }))
+#[utoipa::path(
+    get,
+    path = "/api/strategies",
+    tag = "strategies",
+    responses(
+        (status = 200, description = "Registered strategies", body = [StrategyStatus]),
+        (status = 503, description = "Strategy registry unavailable", body = ErrorBody),
+    )
+)]
+async fn get_strategies(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .strategy_tx
+        .send(StrategyCommand::List { reply: reply_tx })
+        .await;
+    match reply_rx.await {
+        Ok(strategies) => (StatusCode::OK, Json(json!(strategies))),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "strategy registry unavailable" })),
+        ),
+    }
 }
 
-// ─── Config ───────────────────────────────────────────────────────────────────
-
-async fn get_config() -> Json<Value> {
-    Json(json!({ "message": "Config endpoint active." }))
+/// Toggle or edit a strategy, e.g. `{"enabled": false}` or
+/// `{"params": {"period": 21}}`. Takes effect on the next candle and lasts
+/// until restart.
+#[utoipa::path(
+    patch,
+    path = "/api/strategies/{name}",
+    tag = "strategies",
+    params(("name" = String, Path, description = "Strategy name")),
+    request_body = Object,
+    responses(
+        (status = 200, description = "Updated strategy", body = StrategyStatus),
+        (status = 400, description = "Invalid patch", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 404, description = "No strategy with that name", body = ErrorBody),
+        (status = 503, description = "Strategy registry unavailable", body = ErrorBody),
+    )
+)]
+async fn patch_strategy(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(name): Path<String>,
+    Json(patch): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = state
+        .strategy_tx
+        .send(StrategyCommand::Update {
+            name: name.clone(),
+            patch: patch.clone(),
+            actor: claims.actor(),
+            reply: reply_tx,
+        })
+        .await;
+    match reply_rx.await {
+        Ok(Ok(Some(status))) => {
+            let payload = json!({ "strategy": name, "changes": patch });
+            state
+                .audit
+                .record(&claims.actor(), "strategy.update", payload)
+                .await;
+            (StatusCode::OK, Json(json!(status)))
+        }
+        Ok(Ok(None)) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "strategy not found" })),
+        ),
+        Ok(Err(e)) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "strategy registry unavailable" })),
+        ),
+    }
 }
 
-async fn post_config(Json(_body): Json<Value>) -> (StatusCode, Json<Value>) {
-    warn!("POST /api/config received");
-    (StatusCode::OK, Json(json!({ "status": "accepted" })))
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+
+    use super::*;
+    use crate::test_state;
+
+    /// A closed trade; the rest of the columns don't matter to listing.
+    struct Trade {
+        id: &'static str,
+        pair: &'static str,
+        side: &'static str,
+        mode: &'static str,
+        strategy: Option<&'static str>,
+        account: &'static str,
+        closed_at: &'static str,
+    }
+
+    const TRADE: Trade = Trade {
+        id: "",
+        pair: "BTCUSDT",
+        side: "BUY",
+        mode: "paper",
+        strategy: None,
+        account: "default",
+        closed_at: "2026-10-01T12:00:00+00:00",
+    };
+
+    async fn insert(db: &SqlitePool, trades: &[Trade]) {
+        for t in trades {
+            sqlx::query(
+                "INSERT INTO trades (id, pair, side, entry_price, exit_price, quantity, pnl_usd, \
+                 mode, strategy, account_id, opened_at, closed_at) \
+                 VALUES (?1, ?2, ?3, 100, 110, 1, 10, ?4, ?5, ?6, ?7, ?7)",
+            )
+            .bind(t.id)
+            .bind(t.pair)
+            .bind(t.side)
+            .bind(t.mode)
+            .bind(t.strategy)
+            .bind(t.account)
+            .bind(t.closed_at)
+            .execute(db)
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn list(state: &AppState, query: &str) -> (StatusCode, Value) {
+        let uri: Uri = format!("/api/trades?{query}").parse().unwrap();
+        let (status, Json(body)) =
+            get_trades(State(state.clone()), Query::try_from_uri(&uri).unwrap()).await;
+        (status, body)
+    }
+
+    fn ids(body: &Value) -> Vec<String> {
+        body["trades"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    /// Every trade id `query` lists, following `next_cursor` page by page.
+    async fn walk(state: &AppState, query: &str) -> Vec<String> {
+        let (status, mut body) = list(state, query).await;
+        assert_eq!(status, StatusCode::OK);
+        let mut seen = ids(&body);
+        while let Some(cursor) = body["next_cursor"].as_str() {
+            let (status, next) = list(state, &format!("{query}&cursor={cursor}")).await;
+            assert_eq!(status, StatusCode::OK);
+            seen.extend(ids(&next));
+            body = next;
+        }
+        seen
+    }
+
+    #[tokio::test]
+    async fn cursor_pages_keep_their_order_across_equal_timestamps() {
+        let state = test_state().await;
+        let tied = "2026-10-01T12:00:00+00:00";
+        insert(
+            &state.db,
+            &[
+                Trade {
+                    id: "c",
+                    closed_at: tied,
+                    ..TRADE
+                },
+                Trade {
+                    id: "a",
+                    closed_at: tied,
+                    ..TRADE
+                },
+                Trade {
+                    id: "e",
+                    closed_at: tied,
+                    ..TRADE
+                },
+                Trade {
+                    id: "b",
+                    closed_at: tied,
+                    ..TRADE
+                },
+                Trade {
+                    id: "d",
+                    closed_at: tied,
+                    ..TRADE
+                },
+                Trade {
+                    id: "f",
+                    closed_at: "2026-10-02T12:00:00+00:00",
+                    ..TRADE
+                },
+                Trade {
+                    id: "0",
+                    closed_at: "2026-09-30T12:00:00+00:00",
+                    ..TRADE
+                },
+            ],
+        )
+        .await;
+
+        // Ties on closed_at are broken by id, so no page repeats or skips one
+        assert_eq!(
+            walk(&state, "limit=2").await,
+            ["f", "e", "d", "c", "b", "a", "0"]
+        );
+        assert_eq!(
+            walk(&state, "limit=2&sort=asc").await,
+            ["0", "a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(walk(&state, "limit=7").await.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn malformed_cursor_is_a_bad_request() {
+        let state = test_state().await;
+        insert(&state.db, &[Trade { id: "a", ..TRADE }]).await;
+
+        let no_separator = URL_SAFE_NO_PAD.encode("2026-10-01T12:00:00+00:00");
+        let not_utf8 = URL_SAFE_NO_PAD.encode([0xff, 0xfe, b'|', b'a']);
+        for cursor in ["not*base64", no_separator.as_str(), not_utf8.as_str()] {
+            let (status, body) = list(&state, &format!("cursor={cursor}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "cursor {cursor}");
+            assert_eq!(body["error"], "invalid cursor");
+        }
+    }
+
+    #[tokio::test]
+    async fn filters_combine_and_carry_across_pages() {
+        let state = test_state().await;
+        insert(
+            &state.db,
+            &[
+                Trade {
+                    id: "btc-buy",
+                    ..TRADE
+                },
+                Trade {
+                    id: "btc-sell",
+                    side: "SELL",
+                    ..TRADE
+                },
+                Trade {
+                    id: "eth-sell",
+                    pair: "ETHUSDT",
+                    side: "SELL",
+                    ..TRADE
+                },
+                Trade {
+                    id: "btc-rsi",
+                    strategy: Some("rsi"),
+                    ..TRADE
+                },
+                Trade {
+                    id: "btc-rsi-live",
+                    strategy: Some("rsi"),
+                    mode: "live",
+                    ..TRADE
+                },
+                Trade {
+                    id: "btc-rsi-later",
+                    strategy: Some("rsi"),
+                    closed_at: "2026-10-03T08:00:00+00:00",
+                    ..TRADE
+                },
+                Trade {
+                    id: "btc-other",
+                    account: "other",
+                    ..TRADE
+                },
+            ],
+        )
+        .await;
+
+        let cases: [(&str, &[&str]); 6] = [
+            ("pair=BTCUSDT&side=sell", &["btc-sell"]),
+            ("side=SELL", &["eth-sell", "btc-sell"]),
+            ("strategy=rsi&mode=paper", &["btc-rsi-later", "btc-rsi"]),
+            (
+                "strategy=rsi&mode=paper&from=2026-10-02",
+                &["btc-rsi-later"],
+            ),
+            ("strategy=rsi&to=2026-10-01", &["btc-rsi-live", "btc-rsi"]),
+            ("account=other&pair=BTCUSDT", &["btc-other"]),
+        ];
+        for (query, expected) in cases {
+            let (status, body) = list(&state, query).await;
+            assert_eq!(status, StatusCode::OK, "{query}");
+            assert_eq!(ids(&body), expected, "{query}");
+            assert_eq!(body["total"], expected.len(), "{query}");
+            // Paging one at a time yields the same trades
+            assert_eq!(walk(&state, &format!("{query}&limit=1")).await, expected);
+        }
+
+        for query in ["side=long", "mode=demo", "sort=newest", "from=yesterday"] {
+            assert_eq!(
+                list(&state, query).await.0,
+                StatusCode::BAD_REQUEST,
+                "{query}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn risk_event_paging_is_bounded() {
+        let state = test_state().await;
+        for (kind, created_at) in [
+            ("stop_loss_triggered", "2026-10-01T10:00:00+00:00"),
+            ("order_rejected", "2026-10-01T11:00:00+00:00"),
+            ("stop_loss_triggered", "2026-10-01T12:00:00+00:00"),
+        ] {
+            sqlx::query(
+                "INSERT INTO risk_events (kind, pair, details, created_at) \
+                 VALUES (?1, 'BTCUSDT', '{}', ?2)",
+            )
+            .bind(kind)
+            .bind(created_at)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        }
+
+        let cases = [
+            ("limit=0", 1, 1, 1),
+            ("limit=-5", 1, 1, 1),
+            ("limit=1000", 1, 200, 3),
+            ("page=-3&limit=2", 1, 2, 2),
+            ("page=2&limit=2", 2, 2, 1),
+            ("page=9223372036854775807&limit=200", i64::MAX, 200, 0),
+            ("kind=stop_loss_triggered&limit=1&page=2", 2, 1, 1),
+        ];
+        for (query, page, limit, listed) in cases {
+            let uri: Uri = format!("/api/risk-events?{query}").parse().unwrap();
+            let Json(body) =
+                get_risk_events(State(state.clone()), Query::try_from_uri(&uri).unwrap()).await;
+            assert_eq!(body["page"], page, "{query}");
+            assert_eq!(body["limit"], limit, "{query}");
+            assert_eq!(body["events"].as_array().unwrap().len(), listed, "{query}");
+        }
+    }
 }
@@ -0,0 +1,155 @@
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use common::{DashboardEvent, LogFilter, RiskEvent};
+
+use crate::auth::{authenticate, bearer_token, unauthorized};
+use crate::{shutdown_signal, AppState};
+
+pub fn events_router() -> Router<AppState> {
+    Router::new().route("/api/events", get(get_events))
+}
+
+/// Response for an invalid `level`/`target` filter or `format`.
+pub(super) fn bad_filter(error: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response()
+}
+
+/// Log history followed by live log lines matching `filter`: the feed
+/// behind both `/ws/logs` and `/api/events`. Lines are JSON objects when
+/// `json` is set, text otherwise. Lines a slow client misses are skipped.
+pub(super) async fn log_feed(
+    state: &AppState,
+    filter: LogFilter,
+    json: bool,
+) -> impl Stream<Item = String> {
+    // Subscribe first so nothing falls between the snapshot and live lines
+    let rx = state.log_tx.subscribe();
+    let history = state.log_buffer.snapshot().await;
+    let live = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(record) => return Some((record, rx)),
+                Err(RecvError::Lagged(n)) => warn!(dropped = n, "Log stream client lagged"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    stream::iter(history)
+        .chain(live)
+        .filter(move |record| std::future::ready(filter.matches(record)))
+        .map(move |record| {
+            if json {
+                record.to_json().to_string()
+            } else {
+                record.to_string()
+            }
+        })
+}
+
+/// Live risk events (alerts) from the dashboard broadcast.
+fn risk_feed(state: &AppState) -> impl Stream<Item = RiskEvent> {
+    stream::unfold(state.dashboard_tx.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(DashboardEvent::Risk(event)) => return Some((event, rx)),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(n)) => warn!(dropped = n, "Event stream client lagged"),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[derive(Deserialize)]
+pub(super) struct LogQuery {
+    token: Option<String>,
+    /// Minimum level of log lines: `error`, `warn`, `info`, `debug`, `trace`.
+    level: Option<String>,
+    /// Comma-separated log targets, each matching its submodules too.
+    target: Option<String>,
+    /// `text` (the default) or `json` log lines.
+    format: Option<String>,
+}
+
+impl LogQuery {
+    pub(super) fn log_filter(&self) -> Result<LogFilter, String> {
+        LogFilter::parse(self.level.as_deref(), self.target.as_deref())
+    }
+
+    /// Whether the client asked for JSON log lines.
+    pub(super) fn json_lines(&self) -> Result<bool, String> {
+        match self.format.as_deref() {
+            None | Some("text") => Ok(false),
+            Some("json") => Ok(true),
+            Some(other) => Err(format!("unknown format '{other}', expected text or json")),
+        }
+    }
+
+    /// Whether the access token in the header or, failing that, `?token=` is
+    /// valid.
+    pub(super) async fn authenticate(&self, state: &AppState, headers: &HeaderMap) -> bool {
+        match bearer_token(headers).or(self.token.as_deref()) {
+            Some(token) => authenticate(state, token).await.is_some(),
+            None => false,
+        }
+    }
+}
+
+/// Server-Sent Events version of `/ws/logs`, plus risk alerts, for clients
+/// and proxies where WebSockets are awkward. Sends `log` events (one log
+/// line each, history first, filtered by `level`/`target`, JSON objects with
+/// `format=json`) and `risk_event`
+/// events (JSON) until the server shuts down. Takes the access token as a bearer header or, for
+/// `EventSource`, `?token=`.
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "streams",
+    params(
+        ("token" = Option<String>, Query, description = "Access token, for clients that can't set headers"),
+        ("level" = Option<String>, Query, description = "Minimum log level: error, warn, info, debug, or trace"),
+        ("target" = Option<String>, Query, description = "Comma-separated log targets, e.g. `risk,engine::executor`"),
+        ("format" = Option<String>, Query, description = "`text` (default) or `json` log lines"),
+    ),
+    responses(
+        (status = 200, description = "`text/event-stream` of `log` and `risk_event` events", content_type = "text/event-stream", body = String),
+        (status = 400, description = "Unknown log level or format", body = super::openapi::ErrorBody),
+        (status = 401, description = "Missing, expired, or revoked token", body = super::openapi::ErrorBody),
+    )
+)]
+async fn get_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(q): Query<LogQuery>,
+) -> Response {
+    if !q.authenticate(&state, &headers).await {
+        return unauthorized();
+    }
+    let (filter, json) = match (q.log_filter(), q.json_lines()) {
+        (Ok(filter), Ok(json)) => (filter, json),
+        (Err(e), _) | (_, Err(e)) => return bad_filter(e),
+    };
+
+    let logs = log_feed(&state, filter, json)
+        .await
+        .map(|line| Ok(Event::default().event("log").data(line)));
+    let risk = risk_feed(&state).map(|event| Event::default().event("risk_event").json_data(event));
+    let events = stream::select(logs, risk).take_until(shutdown_signal(state.shutdown.clone()));
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
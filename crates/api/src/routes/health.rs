@@ -1,19 +1,153 @@
-use axum::{extract::State, routing::get, Json, Router};
-use serde_json::{json, Value};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use futures_util::future::join_all;
+use serde_json::{json, Map, Value};
 
 use crate::AppState;
 
+/// Longest a dependency check may take before it counts as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Market data age at which a pair counts as not ready, when the staleness
+/// watchdog (`MARKET_STALE_SECS`) is disabled.
+const DEFAULT_MAX_EVENT_AGE_SECS: u64 = 60;
+
 pub fn health_router() -> Router<AppState> {
-    Router::new().route("/healthz", get(healthz))
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(get_metrics))
 }
 
 /// Health check endpoint — no auth required.
 /// Used by systemd post-deploy check and ops scripts.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Engine state, trading mode, and per-pair stream health", body = Object),
+    )
+)]
 async fn healthz(State(state): State<AppState>) -> Json<Value> {
     let engine_state = *state.engine_state.read().await;
     Json(json!({
         "status": "ok",
         "engine": engine_state.to_string(),
-        "mode": state.trading_mode.to_string(),
+        "mode": state.trading_mode.read().await.to_string(),
+        "streams": state.stream_health.snapshot(),
     }))
 }
+
+/// Readiness check — no auth required. Unlike `/healthz`, this verifies the
+/// database, market data freshness on every streamed pair, and each
+/// external dependency (exchange REST API, Telegram), and answers 503 if
+/// any of them fails.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "Every dependency is ready", body = Object, example = json!({"status": "ready", "checks": {"database": {"ok": true, "latency_ms": 1, "error": null}, "streams": {"ok": true, "max_event_age_secs": 60, "pairs": [{"pair": "BTCUSDT", "ok": true, "last_event_age_secs": 2}]}, "binance_rest": {"ok": true, "latency_ms": 84, "error": null}, "telegram": {"ok": true, "latency_ms": 120, "error": null}}})),
+        (status = 503, description = "At least one dependency failed; same breakdown", body = Object),
+    )
+)]
+async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let database = timed(async {
+        sqlx::query("SELECT 1").execute(&state.db).await?;
+        Ok(())
+    });
+    let probes = join_all(
+        state
+            .readiness_probes
+            .iter()
+            .map(|probe| async move { (probe.name(), timed(probe.check()).await) }),
+    );
+    let (database, probes) = tokio::join!(database, probes);
+
+    let mut checks = Map::new();
+    checks.insert("database".into(), database);
+    checks.insert("streams".into(), stream_freshness(&state));
+    for (name, result) in probes {
+        checks.insert(name.into(), result);
+    }
+
+    let ready = checks.values().all(|c| c["ok"] == true);
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (code, Json(json!({ "status": status, "checks": checks })))
+}
+
+/// Prometheus metrics of every subsystem, in OpenMetrics text format — no
+/// auth required, like the health checks.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    security(()),
+    responses(
+        (status = 200, description = "OpenMetrics exposition", content_type = "application/openmetrics-text", body = String),
+    )
+)]
+async fn get_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, common::metrics::CONTENT_TYPE)],
+        common::metrics().encode(),
+    )
+}
+
+/// Run one dependency check under `PROBE_TIMEOUT`.
+async fn timed(check: impl Future<Output = common::Result<()>>) -> Value {
+    let started = Instant::now();
+    let result = match tokio::time::timeout(PROBE_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", PROBE_TIMEOUT.as_secs())),
+    };
+    json!({
+        "ok": result.is_ok(),
+        "latency_ms": started.elapsed().as_millis() as u64,
+        "error": result.err(),
+    })
+}
+
+/// Whether every streamed pair has had market data recently. A pair that
+/// has never received an event is not ready.
+fn stream_freshness(state: &AppState) -> Value {
+    let max_age = match state.runtime_settings.market_stale_secs {
+        0 => DEFAULT_MAX_EVENT_AGE_SECS,
+        secs => secs,
+    };
+    let now = Utc::now();
+    let pairs: Vec<Value> = state
+        .stream_health
+        .snapshot()
+        .into_iter()
+        .map(|p| {
+            let age = p.last_event_at.map(|at| (now - at).num_seconds());
+            json!({
+                "pair": p.pair,
+                "ok": age.is_some_and(|age| age <= max_age as i64),
+                "last_event_age_secs": age,
+            })
+        })
+        .collect();
+    json!({
+        "ok": pairs.iter().all(|p| p["ok"] == true),
+        "max_event_age_secs": max_age,
+        "pairs": pairs,
+    })
+}
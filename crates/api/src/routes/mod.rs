@@ -1,9 +1,17 @@
 mod api;
+mod events;
 mod health;
+mod openapi;
+mod session;
 mod static_files;
+mod users;
 mod ws;
 
 pub use api::api_router;
+pub use events::events_router;
 pub use health::health_router;
+pub use openapi::openapi_router;
+pub use session::session_router;
 pub use static_files::static_router;
+pub use users::users_router;
 pub use ws::ws_router;
@@ -0,0 +1,95 @@
+use axum::Router;
+use serde::Serialize;
+use utoipa::{
+    openapi::security::{Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::{api, events, health, session, users};
+use crate::AppState;
+
+/// The OpenAPI document for the dashboard API, generated from the handler
+/// annotations. WebSocket endpoints (`/ws/logs`, `/ws/stream`) can't be
+/// described in OpenAPI and are documented in the dashboard-api spec.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "ClawBot dashboard API"),
+    paths(
+        api::get_portfolio,
+        api::get_allocation,
+        api::get_accounts,
+        api::get_trades,
+        api::get_orders,
+        api::post_order,
+        api::get_retries,
+        api::post_retry,
+        api::delete_retry,
+        api::get_performance,
+        api::get_performance_breakdown,
+        api::get_pnl_daily,
+        api::get_pair_stats,
+        api::get_config,
+        api::post_config,
+        api::get_runtime,
+        api::get_risk,
+        api::patch_risk,
+        api::get_signals,
+        api::get_risk_events,
+        api::get_alerts,
+        api::get_audit,
+        api::get_strategies,
+        api::patch_strategy,
+        api::post_flatten,
+        api::post_resume,
+        api::post_pair,
+        api::delete_pair,
+        session::post_login,
+        session::post_refresh,
+        session::post_logout,
+        users::get_users,
+        users::post_user,
+        users::delete_user_route,
+        users::post_pairing_code,
+        events::get_events,
+        health::healthz,
+        health::readyz,
+        health::get_metrics,
+    ),
+    components(schemas(ErrorBody, api::TradeStats)),
+    modifiers(&BearerAuth),
+    security(("bearer" = [])),
+)]
+struct ApiDoc;
+
+/// Body of every error response.
+#[derive(Serialize, ToSchema)]
+pub(super) struct ErrorBody {
+    error: String,
+}
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                Http::builder()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// `/api/openapi.json` and Swagger UI at `/api/docs`. No auth: the
+/// contract is not secret, and the UI's "Authorize" button takes an access
+/// token from `/api/login`.
+pub fn openapi_router() -> Router<AppState> {
+    SwaggerUi::new("/api/docs")
+        .url("/api/openapi.json", ApiDoc::openapi())
+        .into()
+}
@@ -0,0 +1,360 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::post,
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use super::openapi::ErrorBody;
+use crate::auth::{
+    is_revoked, require_auth, revoke, unauthorized, Claims, TokenKind, ACCESS_TOKEN_TTL_SECS,
+    REFRESH_TOKEN_TTL_SECS, TOKEN_SUBJECT,
+};
+use crate::users::{role_of, Role};
+use crate::AppState;
+
+pub fn session_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/logout",
+            post(post_logout).route_layer(middleware::from_fn_with_state(state, require_auth)),
+        )
+        .route("/api/login", post(post_login))
+        .route("/api/refresh", post(post_refresh))
+}
+
+/// Either `username` + `password`, or the dashboard `token`.
+#[derive(Deserialize, ToSchema)]
+struct LoginRequest {
+    username: Option<String>,
+    password: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct SessionTokens {
+    /// Bearer token for API requests and the `token` WebSocket parameter.
+    access_token: String,
+    /// Single-use token for `/api/refresh`.
+    refresh_token: String,
+    token_type: &'static str,
+    /// Seconds until the access token expires.
+    expires_in: i64,
+    role: Role,
+}
+
+/// A fresh access/refresh token pair for session `sid`.
+fn session_tokens(state: &AppState, sub: &str, sid: &str, role: Role) -> Response {
+    let (access_token, _) = state.auth.issue(sub, sid, role, TokenKind::Access);
+    let (refresh_token, _) = state.auth.issue(sub, sid, role, TokenKind::Refresh);
+    Json(SessionTokens {
+        access_token,
+        refresh_token,
+        token_type: "Bearer",
+        expires_in: ACCESS_TOKEN_TTL_SECS,
+        role,
+    })
+    .into_response()
+}
+
+fn server_error(e: sqlx::Error) -> Response {
+    error!(error = %e, "Session store failed");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "session store unavailable"})),
+    )
+        .into_response()
+}
+
+/// Start a session. No auth required.
+#[utoipa::path(
+    post,
+    path = "/api/login",
+    tag = "session",
+    request_body = LoginRequest,
+    security(()),
+    responses(
+        (status = 200, description = "New session", body = SessionTokens),
+        (status = 401, description = "Invalid credentials", body = ErrorBody),
+    )
+)]
+async fn post_login(State(state): State<AppState>, Json(req): Json<LoginRequest>) -> Response {
+    let login = state
+        .auth
+        .login(
+            &state.db,
+            req.username.as_deref(),
+            req.password.as_deref(),
+            req.token.as_deref(),
+        )
+        .await;
+    match login {
+        Ok(Some((sub, role))) => {
+            let sid = uuid::Uuid::new_v4().to_string();
+            info!(user = %sub, role = role.as_str(), "Dashboard login");
+            session_tokens(&state, &sub, &sid, role)
+        }
+        Ok(None) => {
+            warn!(
+                user = req.username.as_deref().unwrap_or(TOKEN_SUBJECT),
+                "Dashboard login failed"
+            );
+            unauthorized()
+        }
+        Err(e) => server_error(e),
+    }
+}
+
+/// Trade a refresh token for a new token pair. Each refresh token works
+/// once; replaying a used one means it leaked, so the whole session is
+/// revoked. The user's role is looked up again, so role changes and deleted
+/// users take effect here.
+#[utoipa::path(
+    post,
+    path = "/api/refresh",
+    tag = "session",
+    request_body = RefreshRequest,
+    security(()),
+    responses(
+        (status = 200, description = "Rotated session tokens", body = SessionTokens),
+        (status = 401, description = "Invalid, expired, or already used refresh token; reuse also ends the session", body = ErrorBody),
+    )
+)]
+async fn post_refresh(State(state): State<AppState>, Json(req): Json<RefreshRequest>) -> Response {
+    let Some(claims) = state.auth.verify(&req.refresh_token, TokenKind::Refresh) else {
+        return unauthorized();
+    };
+    match is_revoked(&state.db, &claims.sid).await {
+        Ok(false) => {}
+        Ok(true) => return unauthorized(),
+        Err(e) => return server_error(e),
+    }
+    let role = if claims.sub == TOKEN_SUBJECT {
+        Some(Role::Operator)
+    } else {
+        match role_of(&state.db, &claims.sub).await {
+            Ok(role) => role,
+            Err(e) => return server_error(e),
+        }
+    };
+    let Some(role) = role else {
+        return unauthorized();
+    };
+    match revoke(&state.db, &claims.jti, claims.exp).await {
+        Ok(true) => session_tokens(&state, &claims.sub, &claims.sid, role),
+        Ok(false) => {
+            // Whoever holds the other copy must not keep the session either
+            warn!(user = %claims.sub, "Refresh token reused; revoking session");
+            let until = Utc::now().timestamp() + REFRESH_TOKEN_TTL_SECS;
+            match revoke(&state.db, &claims.sid, until).await {
+                Ok(_) => unauthorized(),
+                Err(e) => server_error(e),
+            }
+        }
+        Err(e) => server_error(e),
+    }
+}
+
+/// End the caller's session, revoking its access and refresh tokens.
+#[utoipa::path(
+    post,
+    path = "/api/logout",
+    tag = "session",
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Not logged in", body = ErrorBody),
+    )
+)]
+async fn post_logout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Response {
+    // Outstanding refresh tokens expire within REFRESH_TOKEN_TTL_SECS
+    let until = Utc::now().timestamp() + REFRESH_TOKEN_TTL_SECS;
+    match revoke(&state.db, &claims.sid, until).await {
+        Ok(_) => {
+            info!(user = %claims.sub, "Dashboard logout");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => server_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use serde_json::Value;
+    use tower::Service;
+
+    use super::*;
+    use crate::{test_state, upsert_user};
+
+    fn app(state: AppState) -> Router {
+        Router::new()
+            .merge(session_router(state.clone()))
+            .merge(super::super::api_router(state.clone()))
+            .with_state(state)
+    }
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        bearer: Option<&str>,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if let Some(token) = bearer {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let request = request.body(Body::from(body.to_string())).unwrap();
+        let response = app.clone().call(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    async fn get(app: &Router, uri: &str, bearer: &str) -> StatusCode {
+        send(app, "GET", uri, Some(bearer), Value::Null).await.0
+    }
+
+    /// `(access_token, refresh_token)` of a successful login or refresh.
+    fn tokens(body: &Value) -> (String, String) {
+        (
+            body["access_token"].as_str().unwrap().to_string(),
+            body["refresh_token"].as_str().unwrap().to_string(),
+        )
+    }
+
+    async fn login(app: &Router) -> (String, String) {
+        let (status, body) = send(
+            app,
+            "POST",
+            "/api/login",
+            None,
+            json!({"token": "dashboard-token"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        tokens(&body)
+    }
+
+    async fn refresh(app: &Router, refresh_token: &str) -> (StatusCode, Value) {
+        send(
+            app,
+            "POST",
+            "/api/refresh",
+            None,
+            json!({"refresh_token": refresh_token}),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn refresh_rotates_the_token_pair() {
+        let app = app(test_state().await);
+        let (_, first) = login(&app).await;
+
+        let (status, body) = refresh(&app, &first).await;
+        assert_eq!(status, StatusCode::OK);
+        let (access, second) = tokens(&body);
+        assert_ne!(second, first);
+        assert_eq!(get(&app, "/api/trades", &access).await, StatusCode::OK);
+
+        let (status, _) = refresh(&app, &second).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn replayed_refresh_token_revokes_the_session() {
+        let app = app(test_state().await);
+        let (_, stolen) = login(&app).await;
+        let (_, body) = refresh(&app, &stolen).await;
+        let (access, current) = tokens(&body);
+
+        assert_eq!(refresh(&app, &stolen).await.0, StatusCode::UNAUTHORIZED);
+
+        // Neither holder keeps the session
+        assert_eq!(refresh(&app, &current).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            get(&app, "/api/trades", &access).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn logout_revokes_access_and_refresh_tokens() {
+        let app = app(test_state().await);
+        let (access, refresh_token) = login(&app).await;
+
+        let (status, _) = send(&app, "POST", "/api/logout", Some(&access), Value::Null).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        assert_eq!(
+            get(&app, "/api/trades", &access).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            refresh(&app, &refresh_token).await.0,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn viewer_is_forbidden_operator_routes() {
+        let state = test_state().await;
+        upsert_user(&state.db, "vera", "hunter22", Role::Viewer)
+            .await
+            .unwrap();
+        let app = app(state);
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/api/login",
+            None,
+            json!({"username": "vera", "password": "hunter22"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["role"], "viewer");
+        let (viewer, _) = tokens(&body);
+
+        assert_eq!(get(&app, "/api/trades", &viewer).await, StatusCode::OK);
+        assert_eq!(
+            get(&app, "/api/audit", &viewer).await,
+            StatusCode::FORBIDDEN
+        );
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/engine/resume",
+            Some(&viewer),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (operator, _) = login(&app).await;
+        assert_eq!(get(&app, "/api/audit", &operator).await, StatusCode::OK);
+    }
+}
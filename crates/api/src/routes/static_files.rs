@@ -1,48 +1,96 @@
+use std::path::{Component, Path};
+
 use axum::{
     body::Body,
-    http::{header, StatusCode, Uri},
+    extract::State,
+    http::{header, HeaderMap, StatusCode, Uri},
     response::{IntoResponse, Response},
     Router,
 };
 use rust_embed::RustEmbed;
 
+use crate::cache::{etag, not_modified};
 use crate::AppState;
 
-/// Embeds the compiled Vue frontend from `frontend/dist/` at compile time.
-/// Run `npm run build` in the `frontend/` directory before compiling.
+/// Vite puts content-hashed bundles under `assets/`; a new build gets new
+/// names, so they never need revalidating.
+const HASHED_ASSET_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Everything else (notably `index.html`) is revalidated on each load.
+const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+/// Embeds the compiled Vue frontend from `frontend/dist/` at compile time,
+/// if it has been built (`npm run build` in `frontend/`). Without it the
+/// dashboard is only available from `FRONTEND_DIR`.
 #[derive(RustEmbed)]
 #[folder = "../../frontend/dist/"]
+#[allow_missing = true]
 struct FrontendAssets;
 
 pub fn static_router() -> Router<AppState> {
     Router::new().fallback(serve_static)
 }
 
-async fn serve_static(uri: Uri) -> Response {
+/// Files come from `FRONTEND_DIR` first, then the embedded bundle. Unknown
+/// paths get `index.html` (SPA routing), again preferring the directory.
+async fn serve_static(State(state): State<AppState>, headers: HeaderMap, uri: Uri) -> Response {
     let path = uri.path().trim_start_matches('/');
     let path = if path.is_empty() { "index.html" } else { path };
+    let dir = state.frontend_dir.as_deref();
+
+    if let Some(content) = read_from_dir(dir, path).await {
+        return file_response(&headers, path, content);
+    }
+    if let Some(content) = FrontendAssets::get(path) {
+        return file_response(&headers, path, content.data.into_owned());
+    }
+    // SPA fallback: serve index.html for all unmatched paths
+    if let Some(index) = read_from_dir(dir, "index.html").await {
+        return file_response(&headers, "index.html", index);
+    }
+    match FrontendAssets::get("index.html") {
+        Some(index) => file_response(&headers, "index.html", index.data.into_owned()),
+        None => (StatusCode::NOT_FOUND, "Frontend not built").into_response(),
+    }
+}
+
+/// Contents of `path` under `dir`. Paths that could leave the directory
+/// (`..`, absolute paths) are never read.
+async fn read_from_dir(dir: Option<&Path>, path: &str) -> Option<Vec<u8>> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    tokio::fs::read(dir?.join(relative)).await.ok()
+}
 
-    match FrontendAssets::get(path) {
-        Some(content) => {
-            let mime = mime_guess::from_path(path)
-                .first_or_octet_stream()
-                .to_string();
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, mime)
-                .body(Body::from(content.data.into_owned()))
-                .unwrap()
-        }
-        None => {
-            // SPA fallback: serve index.html for all unmatched paths
-            match FrontendAssets::get("index.html") {
-                Some(index) => Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
-                    .body(Body::from(index.data.into_owned()))
-                    .unwrap(),
-                None => (StatusCode::NOT_FOUND, "Frontend not built").into_response(),
-            }
-        }
+/// `content` with its type, ETag, and caching policy, or `304 Not Modified`
+/// if the client's copy is current.
+fn file_response(headers: &HeaderMap, path: &str, content: Vec<u8>) -> Response {
+    let tag = etag(&content);
+    let cache_control = if path.starts_with("assets/") {
+        HASHED_ASSET_CACHE_CONTROL
+    } else {
+        REVALIDATE_CACHE_CONTROL
+    };
+    let response = Response::builder()
+        .header(header::ETAG, tag.clone())
+        .header(header::CACHE_CONTROL, cache_control);
+    if not_modified(headers, &tag) {
+        return response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .unwrap();
     }
+    let mime = mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string();
+    response
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, mime)
+        .body(Body::from(content))
+        .unwrap()
 }
@@ -0,0 +1,171 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    middleware,
+    routing::{delete, get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use super::api::operator;
+use super::openapi::ErrorBody;
+use crate::auth::{require_auth, Claims, TOKEN_SUBJECT};
+use crate::users::{delete_user, list_users, upsert_user, Role, User};
+use crate::AppState;
+
+/// User management, for operators only.
+pub fn users_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/api/users", operator(get(get_users).post(post_user)))
+        .route("/api/users/:username", operator(delete(delete_user_route)))
+        .route(
+            "/api/telegram/pairing-code",
+            operator(post(post_pairing_code)),
+        )
+        .route_layer(middleware::from_fn_with_state(state, require_auth))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct UserRequest {
+    username: String,
+    password: String,
+    role: Role,
+}
+
+fn store_error(e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    error!(error = %e, "User store failed");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": "user store unavailable" })),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    tag = "users",
+    responses(
+        (status = 200, description = "Dashboard users", body = [User]),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn get_users(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    match list_users(&state.db).await {
+        Ok(users) => (StatusCode::OK, Json(json!(users))),
+        Err(e) => store_error(e),
+    }
+}
+
+/// Create a user, or reset an existing user's password and role. Open
+/// sessions pick up a role change when they next refresh.
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "users",
+    request_body = UserRequest,
+    responses(
+        (status = 200, description = "User saved", body = Object, example = json!({"username": "alice", "role": "viewer"})),
+        (status = 400, description = "Invalid username or password too short", body = ErrorBody),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn post_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<UserRequest>,
+) -> (StatusCode, Json<Value>) {
+    let username = req.username.trim();
+    if username.is_empty() || username == TOKEN_SUBJECT {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid username" })),
+        );
+    }
+    if req.password.len() < 8 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "password must be at least 8 characters" })),
+        );
+    }
+    match upsert_user(&state.db, username, &req.password, req.role).await {
+        Ok(()) => {
+            info!(user = username, role = req.role.as_str(), actor = %claims.sub, "Dashboard user saved");
+            let payload = json!({ "username": username, "role": req.role });
+            state
+                .audit
+                .record(&claims.actor(), "user.save", payload)
+                .await;
+            (
+                StatusCode::OK,
+                Json(json!({ "username": username, "role": req.role })),
+            )
+        }
+        Err(e) => store_error(e),
+    }
+}
+
+/// Delete a user. Their sessions end at the next refresh.
+#[utoipa::path(
+    delete,
+    path = "/api/users/{username}",
+    tag = "users",
+    params(("username" = String, Path)),
+    responses(
+        (status = 200, description = "User deleted", body = Object, example = json!({"deleted": "alice"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+        (status = 404, description = "No such user", body = ErrorBody),
+    )
+)]
+async fn delete_user_route(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(username): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    match delete_user(&state.db, &username).await {
+        Ok(true) => {
+            info!(user = %username, actor = %claims.sub, "Dashboard user deleted");
+            let payload = json!({ "username": username });
+            state
+                .audit
+                .record(&claims.actor(), "user.delete", payload)
+                .await;
+            (StatusCode::OK, Json(json!({ "deleted": username })))
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "user not found" })),
+        ),
+        Err(e) => store_error(e),
+    }
+}
+
+/// Issue a one-time code that pairs the Telegram user who sends
+/// `/pair <code>` to the bot. Issuing a new code invalidates the previous
+/// one.
+#[utoipa::path(
+    post,
+    path = "/api/telegram/pairing-code",
+    tag = "users",
+    responses(
+        (status = 200, description = "Pairing code issued", body = Object, example = json!({"code": "04718325", "expires_at": "2026-10-16T12:10:00+00:00"})),
+        (status = 403, description = "Viewer session", body = ErrorBody),
+    )
+)]
+async fn post_pairing_code(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> (StatusCode, Json<Value>) {
+    let (code, expires_at) = state.telegram_allowlist.issue_code().await;
+    let payload = json!({ "expires_at": expires_at.to_rfc3339() });
+    state
+        .audit
+        .record(&claims.actor(), "telegram.pairing_code", payload)
+        .await;
+    (
+        StatusCode::OK,
+        Json(json!({ "code": code, "expires_at": expires_at.to_rfc3339() })),
+    )
+}
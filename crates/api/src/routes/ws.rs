@@ -1,19 +1,33 @@
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         Query, State,
     },
-    response::Response,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
     Router,
 };
+use futures_util::{Stream, StreamExt};
+use std::collections::HashSet;
+use std::time::Duration;
+
 use serde::Deserialize;
-use tracing::warn;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use common::DashboardEvent;
 
-use crate::AppState;
+use super::events::{bad_filter, log_feed, LogQuery};
+use crate::{auth::authenticate, shutdown_signal, AppState};
 
 pub fn ws_router() -> Router<AppState> {
-    Router::new().route("/ws/logs", get(ws_logs_handler))
+    Router::new()
+        .route("/ws/logs", get(ws_logs_handler))
+        .route("/ws/stream", get(ws_stream_handler))
 }
 
 #[derive(Deserialize)]
@@ -21,60 +35,302 @@ struct WsQuery {
     token: Option<String>,
 }
 
-/// WebSocket endpoint that streams real-time log lines to the dashboard.
-/// Auth via query param `?token=<DASHBOARD_TOKEN>` (header auth not supported
-/// in browser WebSocket API).
+impl WsQuery {
+    // Browsers can't set custom WS headers, so the token comes as a query param
+    async fn authorized(&self, state: &AppState) -> bool {
+        match &self.token {
+            Some(token) => authenticate(state, token).await.is_some(),
+            None => false,
+        }
+    }
+}
+
+/// Interval between server pings to each WebSocket client.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Clients that send nothing, not even a pong, for this long are dropped.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Ping schedule and idle tracking for one WebSocket client, so connections
+/// from closed tabs or dead networks don't linger.
+struct Keepalive {
+    ping: Interval,
+    last_seen: Instant,
+}
+
+impl Keepalive {
+    fn new() -> Self {
+        let mut ping = tokio::time::interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            ping,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Note that the client sent something (a pong counts).
+    fn seen(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    /// The next ping to send, or `None` once the client has gone quiet for
+    /// longer than `IDLE_TIMEOUT`.
+    async fn tick(&mut self) -> Option<Message> {
+        self.ping.tick().await;
+        (self.last_seen.elapsed() < IDLE_TIMEOUT).then(|| Message::Ping(Vec::new()))
+    }
+}
+
+/// A slot under `DASHBOARD_WS_MAX_CLIENTS`, held for the life of the
+/// connection. `None` when all are taken.
+fn client_slot(state: &AppState) -> Option<OwnedSemaphorePermit> {
+    let slot = state.ws_clients.clone().try_acquire_owned().ok();
+    if slot.is_none() {
+        warn!("WebSocket client limit reached; refusing connection");
+    }
+    slot
+}
+
+fn too_many_clients() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "too many WebSocket clients",
+    )
+        .into_response()
+}
+
+/// Close frame sent to clients when the server shuts down.
+fn going_away() -> Message {
+    Message::Close(Some(CloseFrame {
+        code: close_code::AWAY,
+        reason: "server shutting down".into(),
+    }))
+}
+
+fn unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, "unauthorized").into_response()
+}
+
+/// WebSocket endpoint that streams real-time log lines to the dashboard,
+/// optionally filtered with `?level=warn&target=risk`, as JSON objects with
+/// `?format=json`. Auth via query param
+/// `?token=<access token>` (header auth not supported in browser WebSocket
+/// API).
 async fn ws_logs_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-    Query(q): Query<WsQuery>,
+    headers: HeaderMap,
+    Query(q): Query<LogQuery>,
 ) -> Response {
-    // Authenticate via query token (browsers can't set custom WS headers)
-    let authed = q
-        .token
-        .as_deref()
-        .map(|t| t == state.dashboard_token)
-        .unwrap_or(false);
-
-    if !authed {
-        return axum::response::IntoResponse::into_response((
-            axum::http::StatusCode::UNAUTHORIZED,
-            "unauthorized",
-        ));
+    if !q.authenticate(&state, &headers).await {
+        return unauthorized();
     }
+    let (filter, json) = match (q.log_filter(), q.json_lines()) {
+        (Ok(filter), Ok(json)) => (filter, json),
+        (Err(e), _) | (_, Err(e)) => return bad_filter(e),
+    };
+
+    let Some(slot) = client_slot(&state) else {
+        return too_many_clients();
+    };
 
-    let log_buffer = state.log_buffer.clone();
-    let log_rx = state.log_tx.subscribe();
-    ws.on_upgrade(move |socket| handle_ws(socket, log_rx, log_buffer))
+    let lines = log_feed(&state, filter, json)
+        .await
+        .take_until(shutdown_signal(state.shutdown.clone()));
+    ws.on_upgrade(move |socket| handle_ws(socket, lines, slot))
 }
 
+/// Send log history first so the client sees previous logs, then live lines
+/// until the feed ends at shutdown.
 async fn handle_ws(
     mut socket: WebSocket,
-    mut log_rx: tokio::sync::broadcast::Receiver<String>,
-    log_buffer: crate::LogBuffer,
+    lines: impl Stream<Item = String>,
+    _slot: OwnedSemaphorePermit,
 ) {
-    // Send log history first so the client sees previous logs
-    let history = log_buffer.snapshot().await;
-    for line in history {
-        if socket.send(Message::Text(line)).await.is_err() {
+    let mut lines = std::pin::pin!(lines);
+    let mut keepalive = Keepalive::new();
+    loop {
+        let message = tokio::select! {
+            line = lines.next() => match line {
+                Some(line) => Message::Text(line),
+                None => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {
+                    keepalive.seen();
+                    continue;
+                }
+            },
+            ping = keepalive.tick() => match ping {
+                Some(ping) => ping,
+                None => {
+                    debug!("Dropping idle log WebSocket client");
+                    return;
+                }
+            },
+        };
+        if socket.send(message).await.is_err() {
             return;
         }
     }
+    let _ = socket.send(going_away()).await;
+}
 
-    // Then stream live logs
+// ─── Multiplexed dashboard stream ─────────────────────────────────────────────
+
+/// Request from a `/ws/stream` client, e.g.
+/// `{"op": "subscribe", "channels": ["positions", "market:BTCUSDT"]}`.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum StreamRequest {
+    Subscribe { channels: Vec<String> },
+    Unsubscribe { channels: Vec<String> },
+}
+
+/// Whether `channel` is one clients can subscribe to.
+fn valid_channel(channel: &str) -> bool {
+    match channel {
+        "positions" | "trades" | "risk_events" | "alerts" => true,
+        _ => channel
+            .strip_prefix("market:")
+            .is_some_and(|pair| !pair.is_empty()),
+    }
+}
+
+/// WebSocket endpoint multiplexing typed JSON messages by channel:
+/// `positions` (snapshot on subscribe and after every trade), `trades`,
+/// `market:<PAIR>`, `risk_events`, and `alerts`. Every message carries
+/// `channel`, `type`, and `data`. Same query-token auth as `/ws/logs`.
+async fn ws_stream_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(q): Query<WsQuery>,
+) -> Response {
+    if !q.authorized(&state).await {
+        return unauthorized();
+    }
+
+    let Some(slot) = client_slot(&state) else {
+        return too_many_clients();
+    };
+
+    let events = state.dashboard_tx.subscribe();
+    ws.on_upgrade(move |socket| handle_stream(socket, events, state, slot))
+}
+
+async fn handle_stream(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<DashboardEvent>,
+    state: AppState,
+    _slot: OwnedSemaphorePermit,
+) {
+    let mut channels: HashSet<String> = HashSet::new();
+    let mut shutdown = std::pin::pin!(shutdown_signal(state.shutdown.clone()));
+    let mut keepalive = Keepalive::new();
     loop {
-        match log_rx.recv().await {
-            Ok(line) => {
-                if socket.send(Message::Text(line)).await.is_err() {
-                    break;
+        let outgoing = tokio::select! {
+            _ = &mut shutdown => {
+                let _ = socket.send(going_away()).await;
+                return;
+            }
+            ping = keepalive.tick() => {
+                let Some(ping) = ping else {
+                    debug!("Dropping idle stream WebSocket client");
+                    return;
+                };
+                if socket.send(ping).await.is_err() {
+                    return;
                 }
+                continue;
             }
-            Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
-                warn!(dropped = n, "WebSocket log client lagged");
+            message = socket.recv() => {
+                if let Some(Ok(_)) = message {
+                    keepalive.seen();
+                }
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                match serde_json::from_str::<StreamRequest>(&text) {
+                    Ok(StreamRequest::Subscribe { channels: requested }) => {
+                        let (valid, invalid): (Vec<_>, Vec<_>) =
+                            requested.into_iter().partition(|c| valid_channel(c));
+                        let mut out = Vec::new();
+                        if !invalid.is_empty() {
+                            out.push(control("error", json!({ "unknown_channels": invalid })));
+                        }
+                        let wants_positions =
+                            valid.iter().any(|c| c == "positions") && !channels.contains("positions");
+                        channels.extend(valid);
+                        out.push(control("subscribed", json!(sorted(&channels))));
+                        if wants_positions {
+                            out.push(positions_message(&state).await);
+                        }
+                        out
+                    }
+                    Ok(StreamRequest::Unsubscribe { channels: dropped }) => {
+                        for channel in &dropped {
+                            channels.remove(channel);
+                        }
+                        vec![control("subscribed", json!(sorted(&channels)))]
+                    }
+                    Err(e) => vec![control("error", json!({ "message": e.to_string() }))],
+                }
             }
-            Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                break;
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let mut out = Vec::new();
+                    let channel = event.channel();
+                    let is_trade = matches!(event, DashboardEvent::Trade { .. });
+                    if channels.contains(&channel) {
+                        out.push(event_message(&channel, &event));
+                    }
+                    // Every fill may open, shrink, or close a position
+                    if is_trade && channels.contains("positions") {
+                        out.push(positions_message(&state).await);
+                    }
+                    out
+                }
+                Err(RecvError::Lagged(n)) => {
+                    warn!(dropped = n, "WebSocket stream client lagged");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        for message in outgoing {
+            if socket
+                .send(Message::Text(message.to_string()))
+                .await
+                .is_err()
+            {
+                return;
             }
         }
     }
 }
+
+fn sorted(channels: &HashSet<String>) -> Vec<&String> {
+    let mut list: Vec<_> = channels.iter().collect();
+    list.sort();
+    list
+}
+
+fn control(kind: &str, data: Value) -> Value {
+    json!({ "channel": "control", "type": kind, "data": data })
+}
+
+fn event_message(channel: &str, event: &DashboardEvent) -> Value {
+    let mut message = serde_json::to_value(event).unwrap_or_default();
+    if let Value::Object(fields) = &mut message {
+        fields.insert("channel".into(), json!(channel));
+    }
+    message
+}
+
+async fn positions_message(state: &AppState) -> Value {
+    let positions = super::api::open_positions(&state.db).await;
+    json!({ "channel": "positions", "type": "positions", "data": positions })
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use axum_server::tls_rustls::RustlsConfig;
+use tracing::info;
+
+use common::{Error, Result};
+
+/// TLS settings for the dashboard server, or `None` to serve plain HTTP.
+///
+/// Uses the PEM `cert` and `key` files when both exist. Otherwise, with
+/// `self_signed`, generates a certificate for `hostnames`; it is written to
+/// `cert`/`key` when those are set, so browsers only need to trust it once.
+pub async fn rustls_config(
+    cert: Option<&Path>,
+    key: Option<&Path>,
+    self_signed: bool,
+    hostnames: &[String],
+) -> Result<Option<RustlsConfig>> {
+    // Only the ring provider is compiled in; make it the process default
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let files = cert.zip(key);
+    if let Some((cert, key)) = files.filter(|(c, k)| c.exists() && k.exists()) {
+        let config = RustlsConfig::from_pem_file(cert, key).await?;
+        info!(cert = %cert.display(), "Dashboard TLS certificate loaded");
+        return Ok(Some(config));
+    }
+    if !self_signed {
+        return match files {
+            Some((cert, _)) => Err(Error::Config(format!(
+                "dashboard TLS certificate or key missing (cert: {})",
+                cert.display()
+            ))),
+            None => Ok(None),
+        };
+    }
+
+    let generated = rcgen::generate_simple_self_signed(hostnames.to_vec())
+        .map_err(|e| Error::Config(format!("self-signed certificate generation failed: {e}")))?;
+    let (cert_pem, key_pem) = (generated.cert.pem(), generated.key_pair.serialize_pem());
+    match files {
+        Some((cert, key)) => {
+            tokio::fs::write(cert, &cert_pem).await?;
+            tokio::fs::write(key, &key_pem).await?;
+            info!(cert = %cert.display(), hostnames = ?hostnames, "Generated self-signed dashboard certificate");
+        }
+        None => {
+            info!(hostnames = ?hostnames, "Generated in-memory self-signed dashboard certificate")
+        }
+    }
+    let config = RustlsConfig::from_pem(cert_pem.into_bytes(), key_pem.into_bytes()).await?;
+    Ok(Some(config))
+}
@@ -0,0 +1,137 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use utoipa::ToSchema;
+
+/// What a dashboard user may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Read-only access to every view and stream.
+    Viewer,
+    /// Can also start/stop the engine, trade, and change configuration.
+    Operator,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Operator => "operator",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "viewer" => Some(Role::Viewer),
+            "operator" => Some(Role::Operator),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct User {
+    pub username: String,
+    pub role: Role,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Create `username`, or reset its password and role if it exists.
+pub async fn upsert_user(
+    db: &SqlitePool,
+    username: &str,
+    password: &str,
+    role: Role,
+) -> Result<(), sqlx::Error> {
+    let password_hash = hash_password(password);
+    let role = role.as_str();
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        r#"
+        INSERT INTO users (username, password_hash, role, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?4)
+        ON CONFLICT(username) DO UPDATE SET
+            password_hash = excluded.password_hash, role = excluded.role,
+            updated_at = excluded.updated_at
+        "#,
+        username,
+        password_hash,
+        role,
+        now,
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Role of `username` if the user exists and `password` matches.
+pub(crate) async fn check_password(
+    db: &SqlitePool,
+    username: &str,
+    password: &str,
+) -> Result<Option<Role>, sqlx::Error> {
+    let row = sqlx::query!(
+        "SELECT password_hash, role FROM users WHERE username = ?1",
+        username,
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(row.and_then(|r| {
+        let hash = PasswordHash::new(&r.password_hash).ok()?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .ok()?;
+        Role::parse(&r.role)
+    }))
+}
+
+/// Current role of `username`, or `None` if the user no longer exists.
+pub(crate) async fn role_of(db: &SqlitePool, username: &str) -> Result<Option<Role>, sqlx::Error> {
+    let row = sqlx::query!("SELECT role FROM users WHERE username = ?1", username)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.and_then(|r| Role::parse(&r.role)))
+}
+
+pub(crate) async fn list_users(db: &SqlitePool) -> Result<Vec<User>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT username as "username!", role, created_at, updated_at FROM users ORDER BY username ASC"#
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|r| {
+            Some(User {
+                role: Role::parse(&r.role)?,
+                username: r.username,
+                created_at: r.created_at,
+                updated_at: r.updated_at,
+            })
+        })
+        .collect())
+}
+
+/// Delete `username`. Returns `false` if there was no such user.
+pub(crate) async fn delete_user(db: &SqlitePool, username: &str) -> Result<bool, sqlx::Error> {
+    let deleted = sqlx::query!("DELETE FROM users WHERE username = ?1", username)
+        .execute(db)
+        .await?;
+    Ok(deleted.rows_affected() > 0)
+}
+
+fn hash_password(password: &str) -> String {
+    // A v4 UUID is 122 random bits from the OS RNG, plenty for a salt
+    let salt = SaltString::encode_b64(uuid::Uuid::new_v4().as_bytes())
+        .expect("16 bytes is a valid salt length");
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("Argon2 accepts any password")
+        .to_string()
+}
@@ -0,0 +1,52 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::ExchangeKind;
+
+/// Account that history recorded before accounts existed belongs to, and
+/// the one traded when `ACCOUNT` is unset.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Record in `accounts` that this process trades as `account` on
+/// `exchange`, creating the row on first use.
+pub async fn register(db: &SqlitePool, account: &str, exchange: ExchangeKind) -> crate::Result<()> {
+    let exchange = exchange.to_string();
+    let now = Utc::now().to_rfc3339();
+    sqlx::query!(
+        r#"
+        INSERT INTO accounts (id, exchange, created_at, last_started_at)
+        VALUES (?1, ?2, ?3, ?3)
+        ON CONFLICT(id) DO UPDATE SET
+            exchange = excluded.exchange, last_started_at = excluded.last_started_at
+        "#,
+        account,
+        exchange,
+        now,
+    )
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Whether `name` can name an account: lowercase letters, digits, and `_`,
+/// so its credentials can be set as `<NAME>_BINANCE_API_KEY` and the like.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_names() {
+        assert!(is_valid_name("default"));
+        assert!(is_valid_name("sandbox_2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("Main"));
+        assert!(!is_valid_name("my-account"));
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::Alert;
+
+/// Coalesces repeated alerts so a burst (an order failing on every retry,
+/// rejections on every candle while halted) doesn't flood the chat. The
+/// first alert of a kind on a pair goes out at once; repeats within
+/// `window` are held back and reported as one "×N" message when the
+/// window closes.
+#[derive(Debug)]
+pub struct AlertThrottle {
+    window: Duration,
+    open: HashMap<(String, Option<String>), Burst>,
+}
+
+#[derive(Debug)]
+struct Burst {
+    started_at: DateTime<Utc>,
+    /// Alerts held back since the first.
+    suppressed: u32,
+    last: Alert,
+}
+
+impl AlertThrottle {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Whether `alert` should be sent now. Repeats of an alert already sent
+    /// within the window are counted instead.
+    pub fn admit(&mut self, alert: &Alert) -> bool {
+        let key = (alert.kind.clone(), alert.pair.clone());
+        match self.open.get_mut(&key) {
+            Some(burst) if alert.created_at - burst.started_at < self.window => {
+                burst.suppressed += 1;
+                burst.last = alert.clone();
+                false
+            }
+            _ => {
+                self.open.insert(
+                    key,
+                    Burst {
+                        started_at: alert.created_at,
+                        suppressed: 0,
+                        last: alert.clone(),
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Close the windows that have ended by `now`, returning the last alert
+    /// of each that held alerts back, its message marked with the count.
+    pub fn flush(&mut self, now: DateTime<Utc>) -> Vec<Alert> {
+        let window = self.window;
+        let mut summaries = Vec::new();
+        self.open.retain(|_, burst| {
+            if now - burst.started_at < window {
+                return true;
+            }
+            if burst.suppressed > 0 {
+                let mut summary = burst.last.clone();
+                summary.message = format!(
+                    "{} (\u{00d7}{} in the last {} min)",
+                    summary.message,
+                    burst.suppressed + 1,
+                    window.num_minutes()
+                );
+                summaries.push(summary);
+            }
+            false
+        });
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlertCategory, AlertSeverity};
+
+    fn alert(kind: &str, pair: &str, at: DateTime<Utc>) -> Alert {
+        Alert {
+            kind: kind.into(),
+            pair: Some(pair.into()),
+            severity: AlertSeverity::Warning,
+            category: AlertCategory::Errors,
+            message: format!("{kind} on {pair}"),
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn repeats_are_coalesced_until_the_window_closes() {
+        let mut throttle = AlertThrottle::new(Duration::minutes(10));
+        let t0 = Utc::now();
+
+        assert!(throttle.admit(&alert("order_failed", "BTCUSDT", t0)));
+        for i in 1..=3 {
+            let at = t0 + Duration::minutes(i);
+            assert!(!throttle.admit(&alert("order_failed", "BTCUSDT", at)));
+        }
+        // Other pairs and kinds are throttled separately
+        assert!(throttle.admit(&alert("order_failed", "ETHUSDT", t0)));
+        assert!(throttle.admit(&alert("order_rejected", "BTCUSDT", t0)));
+
+        assert!(throttle.flush(t0 + Duration::minutes(5)).is_empty());
+        let summaries = throttle.flush(t0 + Duration::minutes(10));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(
+            summaries[0].message,
+            "order_failed on BTCUSDT (\u{00d7}4 in the last 10 min)"
+        );
+        assert!(throttle.admit(&alert(
+            "order_failed",
+            "BTCUSDT",
+            t0 + Duration::minutes(11)
+        )));
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use tracing::{error, info};
+
+/// Journal of state-changing operator actions taken through the dashboard
+/// API or Telegram, kept in the `audit_log` table.
+#[derive(Clone)]
+pub struct AuditLog {
+    db: SqlitePool,
+}
+
+impl AuditLog {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Record that `actor` (`api:<user>` or `telegram:<user id>`) performed
+    /// `action` (e.g. `engine.flatten`) with `payload`. The entry is also
+    /// logged under the `audit` target. Failures are logged, never
+    /// propagated — a lost audit row must not block the action itself.
+    pub async fn record(&self, actor: &str, action: &str, payload: Value) {
+        info!(target: "audit", actor, action, payload = %payload, "Operator action");
+        let payload = payload.to_string();
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            "INSERT INTO audit_log (actor, action, payload, created_at) VALUES (?1, ?2, ?3, ?4)",
+            actor,
+            action,
+            payload,
+            now,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(actor, action, error = %e, "Failed to persist audit entry");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::RiskEvent;
+
+/// File names of backups start with this and end in `.db`.
+const BACKUP_PREFIX: &str = "clawbot-";
+
+/// Copies the live database into `dir` with `VACUUM INTO` on a schedule,
+/// keeping the newest `keep` copies. The copy is consistent even while the
+/// bot keeps writing.
+pub struct DatabaseBackup {
+    db: SqlitePool,
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl DatabaseBackup {
+    pub fn new(db: SqlitePool, dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            db,
+            dir: dir.into(),
+            keep: keep.max(1),
+        }
+    }
+
+    /// Back up every `every`, reporting failures as `BackupFailed` on
+    /// `events`. Call from `tokio::spawn`.
+    pub async fn run(self, every: Duration, events: mpsc::Sender<RiskEvent>) {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match self.backup().await {
+                Ok(path) => info!(path = %path.display(), "Database backed up"),
+                Err(e) => {
+                    error!(error = %e, "Database backup failed");
+                    let event = RiskEvent::BackupFailed {
+                        error: e.to_string(),
+                    };
+                    if events.send(event).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write one backup and delete the ones beyond `keep`.
+    pub async fn backup(&self) -> crate::Result<PathBuf> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let name = format!("{BACKUP_PREFIX}{}.db", Utc::now().format("%Y%m%d-%H%M%S"));
+        let path = self.dir.join(name);
+        // VACUUM INTO refuses to overwrite, so a leftover file is an error
+        sqlx::query("VACUUM INTO ?1")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.db)
+            .await?;
+        self.rotate().await?;
+        Ok(path)
+    }
+
+    async fn rotate(&self) -> crate::Result<()> {
+        let mut names = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        for name in expired_backups(names, self.keep) {
+            let path = self.dir.join(&name);
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                warn!(path = %path.display(), error = %e, "Failed to delete old backup");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Backups among `names` beyond the newest `keep`. Timestamped names sort
+/// oldest first; files that aren't backups are left alone.
+fn expired_backups(mut names: Vec<String>, keep: usize) -> Vec<String> {
+    names.retain(|n| n.starts_with(BACKUP_PREFIX) && n.ends_with(".db"));
+    names.sort();
+    let expired = names.len().saturating_sub(keep);
+    names.truncate(expired);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_backups_expire_first() {
+        let names = vec![
+            "clawbot-20261016-080000.db".to_string(),
+            "notes.txt".to_string(),
+            "clawbot-20261014-080000.db".to_string(),
+            "clawbot-20261015-080000.db".to_string(),
+        ];
+        assert_eq!(
+            expired_backups(names.clone(), 2),
+            vec!["clawbot-20261014-080000.db"]
+        );
+        assert!(expired_backups(names, 3).is_empty());
+    }
+}
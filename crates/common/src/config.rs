@@ -1,46 +1,311 @@
-use crate::TradingMode;
+use std::collections::HashMap;
+use std::str::FromStr;
 
-/// All configuration loaded from environment variables at startup.
-/// Missing required variables cause an immediate panic with a clear message.
+use chrono::{FixedOffset, NaiveTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{accounts, Error, LogRotation, Result, RiskConfig, TradingMode, DEFAULT_ACCOUNT};
+
+/// Config file read when `CONFIG_FILE` is unset, relative to the working
+/// directory.
+pub const DEFAULT_CONFIG_FILE: &str = "clawbot.toml";
+
+/// Exchange the bot trades on and streams market data from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeKind {
+    Binance,
+    Coinbase,
+    Bybit,
+}
+
+impl std::fmt::Display for ExchangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExchangeKind::Binance => write!(f, "binance"),
+            ExchangeKind::Coinbase => write!(f, "coinbase"),
+            ExchangeKind::Bybit => write!(f, "bybit"),
+        }
+    }
+}
+
+/// How log lines are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    Text,
+    /// One JSON object per line, for log shippers (Loki, Elastic).
+    Json,
+}
+
+/// Logging settings. Read before the rest of the config, since logging is
+/// set up first.
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Also write logs to this file, in `format`. Unset disables it.
+    pub file: Option<String>,
+    pub file_rotation: LogRotation,
+    /// Size at which the log file is rotated early. `0` disables it.
+    pub file_max_bytes: u64,
+    /// Rotated log files kept; older ones are deleted.
+    pub file_keep: usize,
+}
+
+impl LoggingConfig {
+    /// Load from environment variables and the config file.
+    pub fn load() -> Self {
+        let src = Source::load();
+        let format = match src.get("LOG_FORMAT").as_deref() {
+            None | Some("text") => LogFormat::Text,
+            Some("json") => LogFormat::Json,
+            Some(other) => panic!("ERROR: LOG_FORMAT must be 'text' or 'json', got: '{other}'"),
+        };
+        let file_rotation = match src.get("LOG_FILE_ROTATION").as_deref() {
+            None | Some("daily") => LogRotation::Daily,
+            Some("hourly") => LogRotation::Hourly,
+            Some("never") => LogRotation::Never,
+            Some(other) => panic!(
+                "ERROR: LOG_FILE_ROTATION must be 'daily', 'hourly' or 'never', got: '{other}'"
+            ),
+        };
+        Self {
+            format,
+            file: src.get("LOG_FILE"),
+            file_rotation,
+            file_max_bytes: src.parse::<u64>("LOG_FILE_MAX_MB", 100) * 1024 * 1024,
+            file_keep: src.parse("LOG_FILE_KEEP", 14),
+        }
+    }
+}
+
+/// All configuration loaded at startup from environment variables and the
+/// config file, environment first. Missing required settings and invalid
+/// values cause an immediate panic with a clear message.
 #[derive(Debug, Clone)]
 pub struct Config {
-    // Exchange credentials
+    /// Account this process trades and records history under.
+    pub account: String,
+
+    // Exchange selection and credentials
+    pub exchange: ExchangeKind,
     pub binance_api_key: String,
     pub binance_secret: String,
+    /// Coinbase Advanced Trade API key and secret (HMAC key pair).
+    pub coinbase_api_key: String,
+    pub coinbase_secret: String,
+    /// Bybit v5 API key and secret.
+    pub bybit_api_key: String,
+    pub bybit_secret: String,
 
     // Telegram
     pub telegram_token: String,
     pub telegram_allowed_user_ids: Vec<i64>,
+    /// Local time of day to send the daily summary to the allowed users;
+    /// `None` disables it.
+    pub daily_summary_at: Option<NaiveTime>,
+    /// Offset from UTC that `daily_summary_at` is in. Fixed, so it does not
+    /// follow daylight saving changes.
+    pub daily_summary_utc_offset: FixedOffset,
+    /// Telegram users allowed to switch between paper and live trading at
+    /// runtime with `/mode`. Empty disables runtime switching.
+    pub mode_switch_user_ids: Vec<i64>,
 
     // Dashboard
     pub dashboard_token: String,
+    /// Optional dashboard operator account, created or reset at startup.
+    /// The token always works as an operator login too.
+    pub dashboard_username: Option<String>,
+    pub dashboard_password: Option<String>,
+    /// Key for signing dashboard session JWTs. Defaults to the token.
+    pub dashboard_jwt_secret: String,
     pub dashboard_port: u16,
+    /// PEM certificate chain and private key for serving the dashboard over
+    /// HTTPS. Set both or neither.
+    pub dashboard_tls_cert: Option<String>,
+    pub dashboard_tls_key: Option<String>,
+    /// Generate a self-signed certificate when no certificate files exist
+    /// (written to the cert/key paths if those are set).
+    pub dashboard_tls_self_signed: bool,
+    /// Names the self-signed certificate is issued for.
+    pub dashboard_tls_hostnames: Vec<String>,
+    /// Serve the dashboard frontend from this directory (e.g. a fresh
+    /// `frontend/dist`), falling back to the bundle embedded at build time.
+    pub frontend_dir: Option<String>,
+    /// Most WebSocket clients (`/ws/logs` and `/ws/stream` together) served
+    /// at once; further upgrades are refused with 503.
+    pub dashboard_ws_max_clients: usize,
 
     // Trading
     pub trading_mode: TradingMode,
     pub paper_slippage_bps: f64,
     pub paper_initial_balance: f64,
+    /// Seconds without market data on a pair before the stream is considered
+    /// stale and reconnected. `0` disables the watchdog.
+    pub market_stale_secs: u64,
+    /// Minutes without any market data while running, or without reaching
+    /// the exchange REST API, before the watchdog alerts. `0` disables it.
+    pub watchdog_alert_mins: u64,
+    /// URL pinged every `heartbeat_interval_secs` while the bot is healthy,
+    /// for an external dead-man's switch. Unset disables the heartbeat.
+    pub heartbeat_url: Option<String>,
+    pub heartbeat_interval_secs: u64,
+    /// Closed candles the engine keeps per pair for late subscribers.
+    pub candle_cache_size: usize,
+    /// Days closed candles are kept in the `candles` table. `0` disables
+    /// storing them.
+    pub candle_retention_days: u32,
+    /// Days rows are kept in `signals`. `0` keeps them forever.
+    pub signal_retention_days: u32,
+    /// Days rows are kept in `risk_events`. `0` keeps them forever.
+    pub risk_event_retention_days: u32,
+    /// Risk limits from the config file's `[risk]` section, or the defaults.
+    pub risk: RiskConfig,
 
     // Database
     pub database_url: String,
+    /// Connections in the SQLite pool.
+    pub database_max_connections: u32,
+    /// Seconds a query waits for a lock held by another connection before
+    /// failing with "database is locked".
+    pub database_busy_timeout_secs: u64,
+    /// Directory for scheduled database backups. Unset disables them.
+    pub backup_dir: Option<String>,
+    pub backup_interval_hours: u64,
+    /// Backups kept in `backup_dir`; older ones are deleted.
+    pub backup_keep: usize,
 
     // Strategy config file path
     pub strategy_config_path: String,
+    /// The config file that was read, if any.
+    pub config_file: Option<String>,
+}
+
+/// Startup settings that are safe to show on the dashboard: everything but
+/// credentials and tokens. Changing them requires a restart.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RuntimeSettings {
+    pub account: String,
+    pub exchange: ExchangeKind,
+    pub trading_mode: TradingMode,
+    pub dashboard_port: u16,
+    pub dashboard_ws_max_clients: usize,
+    pub paper_slippage_bps: f64,
+    pub paper_initial_balance: f64,
+    pub market_stale_secs: u64,
+    pub watchdog_alert_mins: u64,
+    /// Seconds between heartbeat pings, when `HEARTBEAT_URL` is set. The URL
+    /// itself is not shown: it usually embeds the monitor's secret check ID.
+    pub heartbeat_interval_secs: Option<u64>,
+    pub candle_cache_size: usize,
+    pub candle_retention_days: u32,
+    pub signal_retention_days: u32,
+    pub risk_event_retention_days: u32,
+    pub database_max_connections: u32,
+    pub database_busy_timeout_secs: u64,
+    pub backup_dir: Option<String>,
+    pub backup_interval_hours: u64,
+    pub backup_keep: usize,
+    pub strategy_config_path: String,
+    pub config_file: Option<String>,
+    /// Number of Telegram users allowed to control the bot.
+    pub telegram_allowed_users: usize,
+    /// When the daily Telegram summary is sent, e.g. `08:00 +02:00`, if enabled.
+    pub daily_summary: Option<String>,
 }
 
 impl Config {
-    /// Load all configuration from environment variables.
-    /// Loads `.env` if present. Panics on any missing required variable.
-    pub fn from_env() -> Self {
-        let _ = dotenvy::dotenv(); // ignore error if .env not present
+    /// The non-secret subset of this config.
+    pub fn runtime_settings(&self) -> RuntimeSettings {
+        RuntimeSettings {
+            account: self.account.clone(),
+            exchange: self.exchange,
+            trading_mode: self.trading_mode,
+            dashboard_port: self.dashboard_port,
+            dashboard_ws_max_clients: self.dashboard_ws_max_clients,
+            paper_slippage_bps: self.paper_slippage_bps,
+            paper_initial_balance: self.paper_initial_balance,
+            market_stale_secs: self.market_stale_secs,
+            watchdog_alert_mins: self.watchdog_alert_mins,
+            heartbeat_interval_secs: self
+                .heartbeat_url
+                .as_ref()
+                .map(|_| self.heartbeat_interval_secs),
+            candle_cache_size: self.candle_cache_size,
+            candle_retention_days: self.candle_retention_days,
+            signal_retention_days: self.signal_retention_days,
+            risk_event_retention_days: self.risk_event_retention_days,
+            database_max_connections: self.database_max_connections,
+            database_busy_timeout_secs: self.database_busy_timeout_secs,
+            backup_dir: self.backup_dir.clone(),
+            backup_interval_hours: self.backup_interval_hours,
+            backup_keep: self.backup_keep,
+            strategy_config_path: self.strategy_config_path.clone(),
+            config_file: self.config_file.clone(),
+            telegram_allowed_users: self.telegram_allowed_user_ids.len(),
+            daily_summary: self
+                .daily_summary_at
+                .map(|at| format!("{} {}", at.format("%H:%M"), self.daily_summary_utc_offset)),
+        }
+    }
+
+    /// Load all configuration from environment variables and the config
+    /// file, loading `.env` if present. Panics on any missing required
+    /// setting or invalid value.
+    pub fn load() -> Self {
+        let src = Source::load();
 
-        let trading_mode = match required_env("TRADING_MODE").to_lowercase().as_str() {
+        let trading_mode = match src.required("TRADING_MODE").to_lowercase().as_str() {
             "paper" => TradingMode::Paper,
             "live" => TradingMode::Live,
-            other => panic!("ERROR: TRADING_MODE must be 'paper' or 'live', got: '{other}'"),
+            "live-dryrun" => TradingMode::LiveDryrun,
+            other => panic!(
+                "ERROR: TRADING_MODE must be 'paper', 'live' or 'live-dryrun', got: '{other}'"
+            ),
+        };
+
+        let exchange = match src
+            .get("EXCHANGE")
+            .unwrap_or_else(|| "binance".into())
+            .to_lowercase()
+            .as_str()
+        {
+            "binance" => ExchangeKind::Binance,
+            "coinbase" => ExchangeKind::Coinbase,
+            "bybit" => ExchangeKind::Bybit,
+            other => {
+                panic!("ERROR: EXCHANGE must be 'binance', 'coinbase' or 'bybit', got: '{other}'")
+            }
         };
+        if trading_mode == TradingMode::LiveDryrun && exchange != ExchangeKind::Binance {
+            panic!("ERROR: TRADING_MODE=live-dryrun is only supported with EXCHANGE=binance");
+        }
 
-        let telegram_allowed_user_ids = required_env("TELEGRAM_ALLOWED_USER_IDS")
+        let account = src
+            .get("ACCOUNT")
+            .unwrap_or_else(|| DEFAULT_ACCOUNT.to_string());
+        if !accounts::is_valid_name(&account) {
+            panic!("ERROR: ACCOUNT must be lowercase letters, digits and '_', got: '{account}'");
+        }
+
+        // Only the selected exchange's credentials are required. A named
+        // account's own credentials (e.g. SANDBOX_BINANCE_API_KEY) take
+        // precedence over the shared ones.
+        let prefix = (account != DEFAULT_ACCOUNT).then(|| format!("{}_", account.to_uppercase()));
+        let credential = |key: &str, needed: bool| {
+            let own = prefix
+                .as_ref()
+                .and_then(|prefix| src.get(&format!("{prefix}{key}")));
+            match own.or_else(|| src.get(key)) {
+                Some(value) => value,
+                None if needed => src.required(key),
+                None => String::new(),
+            }
+        };
+
+        let telegram_allowed_user_ids = src
+            .required("TELEGRAM_ALLOWED_USER_IDS")
             .split(',')
             .map(|s| {
                 s.trim().parse::<i64>().unwrap_or_else(|_| {
@@ -52,35 +317,426 @@ impl Config {
             })
             .collect();
 
+        let mode_switch_user_ids = src
+            .get("MODE_SWITCH_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<i64>().unwrap_or_else(|_| {
+                    panic!("MODE_SWITCH_USER_IDS contains non-numeric ID: '{s}'")
+                })
+            })
+            .collect();
+
+        let daily_summary_at = src.get("DAILY_SUMMARY_AT").map(|v| {
+            NaiveTime::parse_from_str(&v, "%H:%M").unwrap_or_else(|_| {
+                panic!("ERROR: DAILY_SUMMARY_AT must be a time like '08:00', got: '{v}'")
+            })
+        });
+        let daily_summary_utc_offset = match src.get("DAILY_SUMMARY_UTC_OFFSET") {
+            Some(v) => v.parse().unwrap_or_else(|_| {
+                panic!(
+                    "ERROR: DAILY_SUMMARY_UTC_OFFSET must be an offset like '+02:00', got: '{v}'"
+                )
+            }),
+            None => FixedOffset::east_opt(0).expect("zero offset is valid"),
+        };
+
+        let dashboard_token = src.required("DASHBOARD_TOKEN");
+        let dashboard_jwt_secret = src.get("DASHBOARD_JWT_SECRET").unwrap_or_else(|| {
+            tracing::warn!(
+                "DASHBOARD_JWT_SECRET is not set; signing session tokens with DASHBOARD_TOKEN. Set a separate secret."
+            );
+            dashboard_token.clone()
+        });
+        let dashboard_tls_cert = src.get("DASHBOARD_TLS_CERT");
+        let dashboard_tls_key = src.get("DASHBOARD_TLS_KEY");
+        if dashboard_tls_cert.is_some() != dashboard_tls_key.is_some() {
+            panic!("ERROR: DASHBOARD_TLS_CERT and DASHBOARD_TLS_KEY must be set together");
+        }
+
         Config {
-            binance_api_key: required_env("BINANCE_API_KEY"),
-            binance_secret: required_env("BINANCE_SECRET"),
-            telegram_token: required_env("TELEGRAM_TOKEN"),
+            account,
+            exchange,
+            binance_api_key: credential("BINANCE_API_KEY", exchange == ExchangeKind::Binance),
+            binance_secret: credential("BINANCE_SECRET", exchange == ExchangeKind::Binance),
+            coinbase_api_key: credential("COINBASE_API_KEY", exchange == ExchangeKind::Coinbase),
+            coinbase_secret: credential("COINBASE_SECRET", exchange == ExchangeKind::Coinbase),
+            bybit_api_key: credential("BYBIT_API_KEY", exchange == ExchangeKind::Bybit),
+            bybit_secret: credential("BYBIT_SECRET", exchange == ExchangeKind::Bybit),
+            telegram_token: src.required("TELEGRAM_TOKEN"),
             telegram_allowed_user_ids,
-            dashboard_token: required_env("DASHBOARD_TOKEN"),
-            dashboard_port: optional_env("DASHBOARD_PORT")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(8080),
+            daily_summary_at,
+            daily_summary_utc_offset,
+            mode_switch_user_ids,
+            dashboard_token,
+            dashboard_username: src.get("DASHBOARD_USERNAME"),
+            dashboard_password: src.get("DASHBOARD_PASSWORD"),
+            dashboard_jwt_secret,
+            dashboard_port: src.parse("DASHBOARD_PORT", 8080),
+            dashboard_tls_cert,
+            dashboard_tls_key,
+            dashboard_tls_self_signed: src
+                .get("DASHBOARD_TLS_SELF_SIGNED")
+                .is_some_and(|v| v == "true" || v == "1"),
+            dashboard_tls_hostnames: src
+                .get("DASHBOARD_TLS_HOSTNAMES")
+                .unwrap_or_else(|| "localhost".to_string())
+                .split(',')
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+                .collect(),
+            frontend_dir: src.get("FRONTEND_DIR"),
+            dashboard_ws_max_clients: src.parse("DASHBOARD_WS_MAX_CLIENTS", 16),
             trading_mode,
-            paper_slippage_bps: optional_env("PAPER_SLIPPAGE_BPS")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(10.0),
-            paper_initial_balance: optional_env("PAPER_INITIAL_BALANCE")
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(10_000.0),
-            database_url: required_env("DATABASE_URL"),
-            strategy_config_path: optional_env("STRATEGY_CONFIG_PATH")
+            paper_slippage_bps: src.parse("PAPER_SLIPPAGE_BPS", 10.0),
+            paper_initial_balance: src.parse("PAPER_INITIAL_BALANCE", 10_000.0),
+            market_stale_secs: src.parse("MARKET_STALE_SECS", 60),
+            watchdog_alert_mins: src.parse("WATCHDOG_ALERT_MINUTES", 5),
+            heartbeat_url: src.get("HEARTBEAT_URL").filter(|url| !url.is_empty()),
+            heartbeat_interval_secs: match src.parse("HEARTBEAT_INTERVAL_SECS", 60) {
+                0 => 60,
+                secs => secs,
+            },
+            candle_cache_size: src.parse("CANDLE_CACHE_SIZE", 500),
+            candle_retention_days: src.parse("CANDLE_RETENTION_DAYS", 0),
+            signal_retention_days: src.parse("SIGNAL_RETENTION_DAYS", 0),
+            risk_event_retention_days: src.parse("RISK_EVENT_RETENTION_DAYS", 0),
+            risk: src.risk.clone(),
+            database_url: src.required("DATABASE_URL"),
+            database_max_connections: match src.parse("DATABASE_MAX_CONNECTIONS", 8) {
+                0 => 8,
+                n => n,
+            },
+            database_busy_timeout_secs: src.parse("DATABASE_BUSY_TIMEOUT_SECS", 5),
+            backup_dir: src.get("BACKUP_DIR"),
+            backup_interval_hours: match src.parse("BACKUP_INTERVAL_HOURS", 24) {
+                0 => 24,
+                hours => hours,
+            },
+            backup_keep: src.parse("BACKUP_KEEP", 7),
+            strategy_config_path: src
+                .get("STRATEGY_CONFIG_PATH")
                 .unwrap_or_else(|| "config/strategies.toml".to_string()),
+            config_file: src.path.clone(),
         }
     }
 }
 
-fn required_env(key: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| {
-        panic!("Required environment variable '{key}' is not set. Check your .env file.")
-    })
+/// The config file, `clawbot.toml` unless `CONFIG_FILE` says otherwise. It
+/// holds the same settings as the environment variables, grouped by
+/// section: `[telegram] token` is `TELEGRAM_TOKEN`, and so on. Settings
+/// without a key here are environment-only.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    account: Option<String>,
+    exchange: Option<String>,
+    trading_mode: Option<String>,
+    strategy_config_path: Option<String>,
+    binance: Credentials,
+    coinbase: Credentials,
+    bybit: Credentials,
+    telegram: TelegramSection,
+    dashboard: DashboardSection,
+    paper: PaperSection,
+    database: DatabaseSection,
+    log: LogSection,
+    /// Risk limits, over the `RiskConfig` defaults. Limits changed at
+    /// runtime are saved and win over these.
+    risk: Option<toml::Table>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Credentials {
+    api_key: Option<String>,
+    secret: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TelegramSection {
+    token: Option<String>,
+    allowed_user_ids: Option<Vec<i64>>,
+    mode_switch_user_ids: Option<Vec<i64>>,
+    daily_summary_at: Option<String>,
+    daily_summary_utc_offset: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DashboardSection {
+    token: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    jwt_secret: Option<String>,
+    port: Option<u16>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    tls_self_signed: Option<bool>,
+    tls_hostnames: Option<Vec<String>>,
+    frontend_dir: Option<String>,
+    ws_max_clients: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PaperSection {
+    slippage_bps: Option<f64>,
+    initial_balance: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct DatabaseSection {
+    url: Option<String>,
+    max_connections: Option<u32>,
+    busy_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LogSection {
+    format: Option<String>,
+    file: Option<String>,
+    file_rotation: Option<String>,
+    file_max_mb: Option<u64>,
+    file_keep: Option<usize>,
+}
+
+impl ConfigFile {
+    /// Each setting under the name of the environment variable it stands
+    /// for. Lists become comma-separated.
+    fn values(&self) -> HashMap<&'static str, String> {
+        fn list<T: ToString>(items: &[T]) -> String {
+            items
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+        let (telegram, dashboard, database, log) =
+            (&self.telegram, &self.dashboard, &self.database, &self.log);
+        let settings = [
+            ("ACCOUNT", self.account.clone()),
+            ("EXCHANGE", self.exchange.clone()),
+            ("TRADING_MODE", self.trading_mode.clone()),
+            ("STRATEGY_CONFIG_PATH", self.strategy_config_path.clone()),
+            ("BINANCE_API_KEY", self.binance.api_key.clone()),
+            ("BINANCE_SECRET", self.binance.secret.clone()),
+            ("COINBASE_API_KEY", self.coinbase.api_key.clone()),
+            ("COINBASE_SECRET", self.coinbase.secret.clone()),
+            ("BYBIT_API_KEY", self.bybit.api_key.clone()),
+            ("BYBIT_SECRET", self.bybit.secret.clone()),
+            ("TELEGRAM_TOKEN", telegram.token.clone()),
+            (
+                "TELEGRAM_ALLOWED_USER_IDS",
+                telegram.allowed_user_ids.as_deref().map(list),
+            ),
+            (
+                "MODE_SWITCH_USER_IDS",
+                telegram.mode_switch_user_ids.as_deref().map(list),
+            ),
+            ("DAILY_SUMMARY_AT", telegram.daily_summary_at.clone()),
+            (
+                "DAILY_SUMMARY_UTC_OFFSET",
+                telegram.daily_summary_utc_offset.clone(),
+            ),
+            ("DASHBOARD_TOKEN", dashboard.token.clone()),
+            ("DASHBOARD_USERNAME", dashboard.username.clone()),
+            ("DASHBOARD_PASSWORD", dashboard.password.clone()),
+            ("DASHBOARD_JWT_SECRET", dashboard.jwt_secret.clone()),
+            ("DASHBOARD_PORT", dashboard.port.map(|v| v.to_string())),
+            ("DASHBOARD_TLS_CERT", dashboard.tls_cert.clone()),
+            ("DASHBOARD_TLS_KEY", dashboard.tls_key.clone()),
+            (
+                "DASHBOARD_TLS_SELF_SIGNED",
+                dashboard.tls_self_signed.map(|v| v.to_string()),
+            ),
+            (
+                "DASHBOARD_TLS_HOSTNAMES",
+                dashboard.tls_hostnames.as_deref().map(list),
+            ),
+            ("FRONTEND_DIR", dashboard.frontend_dir.clone()),
+            (
+                "DASHBOARD_WS_MAX_CLIENTS",
+                dashboard.ws_max_clients.map(|v| v.to_string()),
+            ),
+            (
+                "PAPER_SLIPPAGE_BPS",
+                self.paper.slippage_bps.map(|v| v.to_string()),
+            ),
+            (
+                "PAPER_INITIAL_BALANCE",
+                self.paper.initial_balance.map(|v| v.to_string()),
+            ),
+            ("DATABASE_URL", database.url.clone()),
+            (
+                "DATABASE_MAX_CONNECTIONS",
+                database.max_connections.map(|v| v.to_string()),
+            ),
+            (
+                "DATABASE_BUSY_TIMEOUT_SECS",
+                database.busy_timeout_secs.map(|v| v.to_string()),
+            ),
+            ("LOG_FORMAT", log.format.clone()),
+            ("LOG_FILE", log.file.clone()),
+            ("LOG_FILE_ROTATION", log.file_rotation.clone()),
+            ("LOG_FILE_MAX_MB", log.file_max_mb.map(|v| v.to_string())),
+            ("LOG_FILE_KEEP", log.file_keep.map(|v| v.to_string())),
+        ];
+        settings
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect()
+    }
+
+    /// `[risk]` over the `RiskConfig` defaults, validated.
+    fn risk_config(&self) -> Result<RiskConfig> {
+        let Some(overrides) = &self.risk else {
+            return Ok(RiskConfig::default());
+        };
+        let mut table = toml::Table::try_from(RiskConfig::default())
+            .map_err(|e| Error::Config(e.to_string()))?;
+        for (key, value) in overrides {
+            if !table.contains_key(key) {
+                return Err(Error::Config(format!("unknown risk setting '{key}'")));
+            }
+            table.insert(key.clone(), value.clone());
+        }
+        let risk: RiskConfig = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| Error::Config(e.message().to_string()))?;
+        risk.validate()?;
+        Ok(risk)
+    }
+}
+
+/// Where settings are looked up: an environment variable always wins over
+/// the config file.
+struct Source {
+    /// The config file that was read, if any.
+    path: Option<String>,
+    file: HashMap<&'static str, String>,
+    risk: RiskConfig,
+}
+
+impl Source {
+    /// Load `.env` if present, then the config file. A missing
+    /// `clawbot.toml` is fine; a missing `CONFIG_FILE` or an invalid file
+    /// panics.
+    fn load() -> Self {
+        let _ = dotenvy::dotenv(); // ignore error if .env not present
+        let (path, explicit) = match optional_env("CONFIG_FILE") {
+            Some(path) => (path, true),
+            None => (DEFAULT_CONFIG_FILE.to_string(), false),
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if explicit || e.kind() != std::io::ErrorKind::NotFound => {
+                panic!("ERROR: Failed to read config file '{path}': {e}")
+            }
+            Err(_) => {
+                return Self {
+                    path: None,
+                    file: HashMap::new(),
+                    risk: RiskConfig::default(),
+                }
+            }
+        };
+        let file: ConfigFile = toml::from_str(&content)
+            .unwrap_or_else(|e| panic!("ERROR: Invalid config file '{path}': {e}"));
+        let risk = file
+            .risk_config()
+            .unwrap_or_else(|e| panic!("ERROR: Invalid [risk] in config file '{path}': {e}"));
+        Self {
+            file: file.values(),
+            path: Some(path),
+            risk,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        optional_env(key).or_else(|| self.file.get(key).cloned())
+    }
+
+    fn required(&self, key: &str) -> String {
+        self.get(key).unwrap_or_else(|| {
+            panic!(
+                "Required setting '{key}' is not set. Set it in the environment, your .env file, or {DEFAULT_CONFIG_FILE}."
+            )
+        })
+    }
+
+    /// `key` parsed, or `default` when unset. Panics on a value that doesn't
+    /// parse, rather than silently falling back to the default.
+    fn parse<T: FromStr>(&self, key: &str, default: T) -> T
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.get(key) {
+            Some(v) => v
+                .trim()
+                .parse()
+                .unwrap_or_else(|e| panic!("ERROR: {key} has an invalid value '{v}': {e}")),
+            None => default,
+        }
+    }
 }
 
 fn optional_env(key: &str) -> Option<String> {
     std::env::var(key).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_settings_map_to_env_names() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            trading_mode = "paper"
+            [telegram]
+            token = "1:x"
+            allowed_user_ids = [1, 2]
+            [dashboard]
+            port = 9090
+            tls_self_signed = true
+            [paper]
+            slippage_bps = 5.0
+            "#,
+        )
+        .unwrap();
+        let values = file.values();
+        assert_eq!(values["TRADING_MODE"], "paper");
+        assert_eq!(values["TELEGRAM_TOKEN"], "1:x");
+        assert_eq!(values["TELEGRAM_ALLOWED_USER_IDS"], "1,2");
+        assert_eq!(values["DASHBOARD_PORT"], "9090");
+        assert_eq!(values["DASHBOARD_TLS_SELF_SIGNED"], "true");
+        assert_eq!(values["PAPER_SLIPPAGE_BPS"], "5");
+        assert!(!values.contains_key("DATABASE_URL"));
+
+        let err = toml::from_str::<ConfigFile>("[dashboard]\nport = \"high\"\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert!(toml::from_str::<ConfigFile>("[telegram]\ntokn = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn risk_section_overrides_defaults() {
+        let file: ConfigFile =
+            toml::from_str("[risk]\nstop_loss_pct = 0.01\nmax_open_positions = 3\n").unwrap();
+        let risk = file.risk_config().unwrap();
+        assert_eq!(risk.stop_loss_pct, 0.01);
+        assert_eq!(risk.max_open_positions, 3);
+        assert_eq!(risk.take_profit_pct, RiskConfig::default().take_profit_pct);
+
+        let file: ConfigFile = toml::from_str("[risk]\nstop_loss_pct = 2.0\n").unwrap();
+        assert!(file.risk_config().is_err());
+        let file: ConfigFile = toml::from_str("[risk]\nstop_loss = 0.01\n").unwrap();
+        assert!(file.risk_config().is_err());
+    }
+}
@@ -0,0 +1,14 @@
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+/// Float value of a decimal, for consumers that only deal in `f64`
+/// (SQLite `REAL` columns, indicators, percentage maths).
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or_default()
+}
+
+/// Decimal from a float such as a streamed market price. Non-finite or
+/// out-of-range values become zero.
+pub fn from_f64(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default()
+}
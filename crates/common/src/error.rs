@@ -7,6 +7,10 @@ pub enum Error {
     #[error("Exchange API error: {0}")]
     Exchange(String),
 
+    /// Server-side failure (5xx) that may succeed if retried.
+    #[error("Exchange temporarily unavailable: {0}")]
+    ExchangeUnavailable(String),
+
     #[error("WebSocket error: {0}")]
     WebSocket(String),
 
@@ -32,4 +36,12 @@ pub enum Error {
     Other(String),
 }
 
+impl Error {
+    /// Whether the operation might succeed if retried unchanged (network
+    /// failures and exchange 5xx responses).
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Http(_) | Error::ExchangeUnavailable(_))
+    }
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
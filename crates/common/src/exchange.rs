@@ -1,11 +1,14 @@
 use async_trait::async_trait;
+use rust_decimal::Decimal;
 
-use crate::{Fill, Order, Position, Result};
+use crate::{
+    Balance, BracketLegs, BracketOrder, Error, Fill, Order, OrderStatusReport, Position, Result,
+};
 
 /// Abstraction over the exchange connection.
 ///
-/// `BinanceClient` implements this for live trading.
-/// `PaperClient` implements this for simulation.
+/// `BinanceClient`, `CoinbaseClient` and `BybitClient` implement this for
+/// live trading. `PaperClient` implements this for simulation.
 ///
 /// Only `OrderExecutor` in `crates/engine` should hold a reference to a
 /// `dyn ExchangeClient`. All order flow must go through the Risk Manager
@@ -18,6 +21,35 @@ pub trait ExchangeClient: Send + Sync {
     /// Query currently open positions from the exchange.
     async fn open_positions(&self) -> Result<Vec<Position>>;
 
+    /// Query non-zero asset balances, cash included.
+    async fn balances(&self) -> Result<Vec<Balance>>;
+
     /// Get the latest price for a trading pair.
-    async fn current_price(&self, pair: &str) -> Result<f64>;
+    async fn current_price(&self, pair: &str) -> Result<Decimal>;
+
+    /// Look up the current state of an order by the ID returned in its `Fill`.
+    async fn order_status(&self, pair: &str, order_id: &str) -> Result<OrderStatusReport>;
+
+    /// Cancel a resting order by the ID returned in its `Fill`.
+    async fn cancel_order(&self, pair: &str, order_id: &str) -> Result<()>;
+
+    /// Place a one-cancels-other exit bracket. Returns the exchange's ID for
+    /// the pair of orders and each leg's client order ID.
+    async fn place_bracket(&self, _bracket: &BracketOrder) -> Result<BracketLegs> {
+        Err(Error::Exchange(
+            "bracket orders are not supported by this client".into(),
+        ))
+    }
+
+    /// Cancel a bracket previously returned by `place_bracket`.
+    async fn cancel_bracket(&self, _pair: &str, _bracket_id: &str) -> Result<()> {
+        Err(Error::Exchange(
+            "bracket orders are not supported by this client".into(),
+        ))
+    }
+
+    /// Start afresh when trading switches to this client at runtime.
+    /// Simulated clients drop their balance, positions and orders; a real
+    /// exchange has nothing to forget.
+    async fn reset(&self) {}
 }
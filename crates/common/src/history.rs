@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, SqlitePool, TypeInfo, ValueRef};
+use tracing::{info, warn};
+
+use crate::{Error, Result};
+
+/// Identifies a history export file.
+const FORMAT: &str = "clawbot-history";
+const VERSION: u32 = 1;
+
+/// Tables carried by an export, in the order they are restored.
+pub const HISTORY_TABLES: &[&str] = &[
+    "accounts",
+    "positions",
+    "orders",
+    "fills",
+    "trades",
+    "equity_snapshots",
+];
+
+/// The trading history of one database: every row of [`HISTORY_TABLES`],
+/// as column → value maps, so it can be restored into another host's
+/// database or loaded into another store.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryExport {
+    pub format: String,
+    pub version: u32,
+    /// Latest migration applied to the exporting database.
+    pub schema: Option<i64>,
+    pub exported_at: DateTime<Utc>,
+    pub tables: BTreeMap<String, Vec<Map<String, Value>>>,
+}
+
+/// Read every history table from `db`.
+pub async fn export(db: &SqlitePool) -> Result<HistoryExport> {
+    let schema: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+        .fetch_one(db)
+        .await?;
+    let mut tables = BTreeMap::new();
+    for &table in HISTORY_TABLES {
+        let rows = sqlx::query(&format!("SELECT * FROM {table}"))
+            .fetch_all(db)
+            .await?;
+        let rows = rows.iter().map(row_to_json).collect::<Result<Vec<_>>>()?;
+        tables.insert(table.to_string(), rows);
+    }
+    Ok(HistoryExport {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        schema,
+        exported_at: Utc::now(),
+        tables,
+    })
+}
+
+/// Restore `history` into `db` in one transaction. Rows whose key already
+/// exists are skipped, so importing the same file twice changes nothing.
+/// Columns the database doesn't have are dropped with a warning. Returns
+/// the rows inserted per table.
+pub async fn import(db: &SqlitePool, history: &HistoryExport) -> Result<Vec<(String, u64)>> {
+    if history.format != FORMAT || history.version != VERSION {
+        return Err(Error::Other(format!(
+            "not a version {VERSION} history export (format '{}', version {})",
+            history.format, history.version
+        )));
+    }
+    let mut tx = db.begin().await?;
+    let mut inserted = Vec::new();
+    for &table in HISTORY_TABLES {
+        let Some(rows) = history.tables.get(table) else {
+            continue;
+        };
+        let known: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+                .fetch_all(&mut *tx)
+                .await?;
+        let mut count = 0;
+        for row in rows {
+            let (columns, values): (Vec<&String>, Vec<&Value>) =
+                row.iter().filter(|(c, _)| known.contains(c)).unzip();
+            if columns.len() < row.len() {
+                warn!(table, "Dropping columns unknown to this database");
+            }
+            if columns.is_empty() {
+                continue;
+            }
+            let sql = format!(
+                "INSERT OR IGNORE INTO {table} ({}) VALUES ({})",
+                columns
+                    .iter()
+                    .map(|c| format!("\"{c}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                vec!["?"; columns.len()].join(", ")
+            );
+            let mut query = sqlx::query(&sql);
+            for value in values {
+                query = match value {
+                    Value::Null => query.bind(None::<String>),
+                    Value::Bool(b) => query.bind(i64::from(*b)),
+                    Value::Number(n) => match n.as_i64() {
+                        Some(i) => query.bind(i),
+                        None => query.bind(n.as_f64()),
+                    },
+                    Value::String(s) => query.bind(s.clone()),
+                    other => query.bind(other.to_string()),
+                };
+            }
+            count += query.execute(&mut *tx).await?.rows_affected();
+        }
+        info!(
+            table,
+            rows = rows.len(),
+            inserted = count,
+            "History table imported"
+        );
+        inserted.push((table.to_string(), count));
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// Write `history` to `path` as JSON, gzipped if the name ends in `.gz`.
+pub fn write_file(history: &HistoryExport, path: &Path) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    if is_gzip(path) {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        serde_json::to_writer(&mut encoder, history)?;
+        encoder.finish()?.flush()?;
+    } else {
+        serde_json::to_writer(std::io::BufWriter::new(file), history)?;
+    }
+    Ok(())
+}
+
+/// Read a file written by [`write_file`].
+pub fn read_file(path: &Path) -> Result<HistoryExport> {
+    let file = std::fs::File::open(path)?;
+    let mut json = Vec::new();
+    if is_gzip(path) {
+        GzDecoder::new(file).read_to_end(&mut json)?;
+    } else {
+        std::io::BufReader::new(file).read_to_end(&mut json)?;
+    }
+    Ok(serde_json::from_slice(&json)?)
+}
+
+fn is_gzip(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// One row as a JSON object, keeping SQLite's storage class of each value.
+fn row_to_json(row: &SqliteRow) -> Result<Map<String, Value>> {
+    let mut object = Map::new();
+    for column in row.columns() {
+        let i = column.ordinal();
+        let raw = row.try_get_raw(i)?;
+        let value = if raw.is_null() {
+            Value::Null
+        } else {
+            match raw.type_info().name() {
+                "INTEGER" => Value::from(row.try_get::<i64, _>(i)?),
+                "REAL" => Number::from_f64(row.try_get::<f64, _>(i)?)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null),
+                "TEXT" => Value::from(row.try_get::<String, _>(i)?),
+                other => {
+                    return Err(Error::Other(format!(
+                        "unsupported {other} value in column '{}'",
+                        column.name()
+                    )))
+                }
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_round_trip() {
+        let mut row = Map::new();
+        row.insert("id".into(), Value::from("t1"));
+        row.insert("pnl_usd".into(), Value::from(-1.5));
+        row.insert("strategy".into(), Value::Null);
+        let history = HistoryExport {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            schema: Some(19),
+            exported_at: Utc::now(),
+            tables: BTreeMap::from([("trades".to_string(), vec![row])]),
+        };
+        for name in ["history-test.json", "history-test.json.gz"] {
+            let path = std::env::temp_dir().join(format!("{}-{name}", std::process::id()));
+            write_file(&history, &path).unwrap();
+            let read = read_file(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(read.tables, history.tables);
+            assert_eq!(read.schema, Some(19));
+        }
+    }
+}
@@ -1,9 +1,41 @@
+pub mod accounts;
+pub mod alert_throttle;
+pub mod audit;
+pub mod backup;
 pub mod config;
+pub mod decimal;
 pub mod error;
 pub mod exchange;
+pub mod history;
+pub mod log_file;
+pub mod log_record;
+pub mod metrics;
+pub mod readiness;
+pub mod retention;
+pub mod retry_queue;
+pub mod risk;
+pub mod shutdown;
+pub mod stream_health;
+pub mod symbol;
+pub mod telegram_users;
 pub mod types;
 
-pub use config::Config;
+pub use accounts::DEFAULT_ACCOUNT;
+pub use alert_throttle::AlertThrottle;
+pub use audit::AuditLog;
+pub use backup::DatabaseBackup;
+pub use config::{Config, ExchangeKind, LogFormat, LoggingConfig, RuntimeSettings};
 pub use error::{Error, Result};
 pub use exchange::ExchangeClient;
+pub use log_file::{LogRotation, RotatingLog};
+pub use log_record::{LogFilter, LogRecord};
+pub use metrics::metrics;
+pub use readiness::ReadinessProbe;
+pub use retention::{DataRetention, PrunedTable, RetentionStats, TableRetention};
+pub use retry_queue::{FailedOrder, RetryQueue};
+pub use risk::{ConflictPolicy, CorrelationGroup, RiskConfig, RiskOverrides, TakeProfitLevel};
+pub use shutdown::Shutdown;
+pub use stream_health::{PairStreamStatus, StreamHealth};
+pub use symbol::Symbol;
+pub use telegram_users::TelegramAllowlist;
 pub use types::*;
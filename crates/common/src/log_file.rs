@@ -0,0 +1,196 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+/// When a log file is rotated, besides reaching its size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    /// Only when the size limit is reached.
+    Never,
+}
+
+impl LogRotation {
+    /// The rotation period `at` falls in. The file is rotated when it
+    /// changes.
+    fn period(self, at: DateTime<Utc>) -> String {
+        match self {
+            LogRotation::Hourly => at.format("%Y%m%d%H").to_string(),
+            LogRotation::Daily => at.format("%Y%m%d").to_string(),
+            LogRotation::Never => String::new(),
+        }
+    }
+}
+
+/// A log file that is rotated every period and whenever it would grow past
+/// `max_bytes`. The full file is renamed to `<name>.<UTC timestamp>` next
+/// to it, and only the newest `keep` rotated files are kept.
+pub struct RotatingLog {
+    path: PathBuf,
+    rotation: LogRotation,
+    /// `0` disables size-based rotation.
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    size: u64,
+    period: String,
+}
+
+impl RotatingLog {
+    /// Open (or create) the log at `path`, creating its directory. An
+    /// existing file is appended to, and rotated on the first write if it
+    /// was last written in an earlier period.
+    pub fn open(
+        path: impl Into<PathBuf>,
+        rotation: LogRotation,
+        max_bytes: u64,
+        keep: usize,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        Ok(Self {
+            path,
+            rotation,
+            max_bytes,
+            keep: keep.max(1),
+            file,
+            size: metadata.len(),
+            period: rotation.period(modified),
+        })
+    }
+
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let rotated = format!(
+            "{}.{}",
+            self.file_name(),
+            Utc::now().format("%Y%m%d-%H%M%S%.3f")
+        );
+        fs::rename(&self.path, self.path.with_file_name(rotated))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.prune();
+        Ok(())
+    }
+
+    /// Delete rotated files beyond `keep`. Failures go to stderr: this is
+    /// the logger, so there is nowhere else to report them.
+    fn prune(&self) {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let names = match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to list log directory {}: {e}", dir.display());
+                return;
+            }
+        };
+        for name in expired_logs(names, &self.file_name(), self.keep) {
+            let path = dir.join(&name);
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Failed to delete old log {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+impl Write for RotatingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let period = self.rotation.period(Utc::now());
+        let full = self.max_bytes > 0 && self.size + buf.len() as u64 > self.max_bytes;
+        if self.size > 0 && (period != self.period || full) {
+            self.rotate()?;
+        }
+        self.period = period;
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Rotated copies of the log `name` among `names`, beyond the newest
+/// `keep`. Timestamped names sort oldest first.
+fn expired_logs(mut names: Vec<String>, name: &str, keep: usize) -> Vec<String> {
+    let prefix = format!("{name}.");
+    names.retain(|n| n.starts_with(&prefix));
+    names.sort();
+    let expired = names.len().saturating_sub(keep);
+    names.truncate(expired);
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn oldest_rotated_logs_expire_first() {
+        let names = vec![
+            "clawbot.log".to_string(),
+            "clawbot.log.20261016-080000.000".to_string(),
+            "other.log.20261001-080000.000".to_string(),
+            "clawbot.log.20261014-080000.000".to_string(),
+            "clawbot.log.20261015-080000.000".to_string(),
+        ];
+        assert_eq!(
+            expired_logs(names.clone(), "clawbot.log", 2),
+            vec!["clawbot.log.20261014-080000.000"]
+        );
+        assert!(expired_logs(names, "clawbot.log", 3).is_empty());
+    }
+
+    #[test]
+    fn periods_change_on_the_boundary() {
+        let before = Utc.with_ymd_and_hms(2026, 10, 16, 23, 59, 59).unwrap();
+        let after = Utc.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2026, 10, 17, 0, 30, 0).unwrap();
+        assert_ne!(
+            LogRotation::Daily.period(before),
+            LogRotation::Daily.period(after)
+        );
+        assert_eq!(
+            LogRotation::Daily.period(after),
+            LogRotation::Daily.period(later)
+        );
+        assert_eq!(
+            LogRotation::Hourly.period(after),
+            LogRotation::Hourly.period(later)
+        );
+        assert_eq!(
+            LogRotation::Never.period(before),
+            LogRotation::Never.period(after)
+        );
+    }
+}
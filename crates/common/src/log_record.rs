@@ -0,0 +1,203 @@
+use std::fmt;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::Level;
+
+/// One log event as streamed to dashboard clients.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: Level,
+    /// Module path the event was logged from, e.g. `risk::manager`.
+    pub target: String,
+    pub message: String,
+    /// Structured fields other than the message, in logging order. Numbers
+    /// and booleans keep their type; everything else is its formatted text.
+    pub fields: Vec<(String, Value)>,
+}
+
+impl LogRecord {
+    pub fn from_event(event: &tracing::Event<'_>) -> Self {
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        Self {
+            timestamp: Utc::now(),
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        }
+    }
+
+    /// The record as one JSON object, fields flattened in alongside
+    /// `timestamp`, `level`, `target`, and `message`, the same shape as
+    /// `LOG_FORMAT=json` stdout lines.
+    pub fn to_json(&self) -> Value {
+        let mut object = Map::new();
+        object.insert(
+            "timestamp".into(),
+            json!(self.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true)),
+        );
+        object.insert("level".into(), json!(self.level.as_str()));
+        object.insert("target".into(), json!(self.target));
+        object.insert("message".into(), json!(self.message));
+        for (name, value) in &self.fields {
+            object.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+        Value::Object(object)
+    }
+}
+
+/// `LEVEL target: message key=value ...`, the dashboard log line format.
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)?;
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            let sep = if i == 0 && self.message.is_empty() {
+                ""
+            } else {
+                " "
+            };
+            match value {
+                Value::String(text) => write!(f, "{sep}{name}={text}")?,
+                other => write!(f, "{sep}{name}={other}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: Vec<(String, Value)>,
+}
+
+impl RecordVisitor {
+    fn push(&mut self, field: &Field, value: Value) {
+        self.fields.push((field.name().to_string(), value));
+    }
+}
+
+impl Visit for RecordVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.push(field, json!(value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.push(field, json!(format!("{value:?}")));
+        }
+    }
+}
+
+/// Which log records a client wants: a minimum level and, optionally, a
+/// set of targets. A target matches itself and its submodules, so `risk`
+/// selects `risk` and `risk::manager` but not `risky`.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub level: Option<Level>,
+    pub targets: Vec<String>,
+}
+
+impl LogFilter {
+    /// Parse `level` (`error`, `warn`, `info`, `debug`, `trace`) and a
+    /// comma-separated `target` list, as given in query parameters.
+    pub fn parse(level: Option<&str>, target: Option<&str>) -> Result<Self, String> {
+        let level = level
+            .map(|l| {
+                l.parse::<Level>()
+                    .map_err(|_| format!("unknown log level '{l}'"))
+            })
+            .transpose()?;
+        let targets = target
+            .map(|t| {
+                t.split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self { level, targets })
+    }
+
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        // Levels order by verbosity: ERROR < WARN < ... < TRACE
+        let level_ok = self.level.is_none_or(|min| record.level <= min);
+        let target_ok = self.targets.is_empty()
+            || self.targets.iter().any(|t| {
+                record
+                    .target
+                    .strip_prefix(t.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            });
+        level_ok && target_ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: Level, target: &str) -> LogRecord {
+        LogRecord {
+            timestamp: Utc::now(),
+            level,
+            target: target.into(),
+            message: "Order rejected".into(),
+            fields: vec![
+                ("pair".into(), json!("BTCUSDT")),
+                ("attempts".into(), json!(2)),
+            ],
+        }
+    }
+
+    #[test]
+    fn filters_by_minimum_level_and_target_prefix() {
+        let filter = LogFilter::parse(Some("warn"), Some("risk, engine::executor")).unwrap();
+        assert!(filter.matches(&record(Level::ERROR, "risk::manager")));
+        assert!(filter.matches(&record(Level::WARN, "engine::executor")));
+        assert!(!filter.matches(&record(Level::INFO, "risk")));
+        assert!(!filter.matches(&record(Level::WARN, "risky")));
+        assert!(!filter.matches(&record(Level::WARN, "engine::stream")));
+        assert!(LogFilter::parse(Some("loud"), None).is_err());
+        assert_eq!(
+            record(Level::WARN, "risk").to_string(),
+            "WARN risk: Order rejected pair=BTCUSDT attempts=2"
+        );
+    }
+
+    #[test]
+    fn json_keeps_field_types() {
+        let json = record(Level::WARN, "risk").to_json();
+        assert_eq!(json["level"], "WARN");
+        assert_eq!(json["message"], "Order rejected");
+        assert_eq!(json["pair"], "BTCUSDT");
+        assert_eq!(json["attempts"], 2);
+    }
+}
@@ -0,0 +1,227 @@
+use std::sync::atomic::AtomicU64;
+use std::sync::OnceLock;
+
+use prometheus_client::encoding::{text, EncodeLabelSet};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use prometheus_client::registry::{Registry, Unit};
+
+/// Content type of [`Metrics::encode`]'s output.
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct PairLabels {
+    pub pair: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct StrategyLabels {
+    pub strategy: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct SignalLabels {
+    pub strategy: String,
+    pub side: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ReasonLabels {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct HttpLabels {
+    pub method: String,
+    /// The route pattern (e.g. `/api/strategies/:name`), not the raw path,
+    /// so label values stay bounded.
+    pub route: String,
+    pub status: u16,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct WatchdogLabels {
+    /// `market_data` or `exchange_rest`.
+    pub check: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TaskLabels {
+    pub task: String,
+}
+
+type HistogramFamily<S> = Family<S, Histogram, fn() -> Histogram>;
+
+/// Process-wide Prometheus metrics. Every subsystem records into the one
+/// registry returned by [`metrics`]; the dashboard server exposes it at
+/// `/metrics` in OpenMetrics text format.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    /// Market data events received, per pair. `rate()` gives events/sec.
+    pub market_events: Family<PairLabels, Counter>,
+    /// Market stream reconnects after a dropped connection.
+    pub stream_reconnects: Counter,
+    /// Signals emitted, per strategy and side.
+    pub signals: Family<SignalLabels, Counter>,
+    /// Time spent in one strategy evaluation.
+    pub strategy_evaluation: HistogramFamily<StrategyLabels>,
+    /// Signals rejected by the risk manager, per reason.
+    pub risk_rejections: Family<ReasonLabels, Counter>,
+    /// Current drawdown from the portfolio peak, as a fraction.
+    pub drawdown: Gauge<f64, AtomicU64>,
+    /// Time from submitting an order to the exchange's answer, retries
+    /// included.
+    pub order_latency: Histogram,
+    /// Orders that failed or were refused, per reason (`filters`,
+    /// `transient`, `permanent`).
+    pub order_failures: Family<ReasonLabels, Counter>,
+    /// Dashboard API request durations, per method, route, and status.
+    pub http_requests: HistogramFamily<HttpLabels>,
+    /// `1` while a watchdog check is alarming, `0` once it recovers.
+    pub watchdog_alarm: Family<WatchdogLabels, Gauge>,
+    /// Panics in spawned tasks, per task.
+    pub task_panics: Family<TaskLabels, Counter>,
+}
+
+/// The process-wide metrics, registered on first use.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+fn evaluation_histogram() -> Histogram {
+    // 1µs .. ~0.5s
+    Histogram::new(exponential_buckets(1e-6, 4.0, 10))
+}
+
+fn request_histogram() -> Histogram {
+    // 1ms .. ~16s
+    Histogram::new(exponential_buckets(0.001, 4.0, 8))
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let mut metrics = Self {
+            registry: Registry::with_prefix("clawbot"),
+            market_events: Family::default(),
+            stream_reconnects: Counter::default(),
+            signals: Family::default(),
+            strategy_evaluation: Family::new_with_constructor(evaluation_histogram),
+            risk_rejections: Family::default(),
+            drawdown: Gauge::default(),
+            // 10ms .. ~40s
+            order_latency: Histogram::new(exponential_buckets(0.01, 4.0, 7)),
+            order_failures: Family::default(),
+            http_requests: Family::new_with_constructor(request_histogram),
+            watchdog_alarm: Family::default(),
+            task_panics: Family::default(),
+        };
+        let registry = &mut metrics.registry;
+        registry.register(
+            "market_events",
+            "Market data events received",
+            metrics.market_events.clone(),
+        );
+        registry.register(
+            "stream_reconnects",
+            "Market stream reconnects",
+            metrics.stream_reconnects.clone(),
+        );
+        registry.register(
+            "signals",
+            "Strategy signals emitted",
+            metrics.signals.clone(),
+        );
+        registry.register_with_unit(
+            "strategy_evaluation",
+            "Time spent evaluating a strategy on one event",
+            Unit::Seconds,
+            metrics.strategy_evaluation.clone(),
+        );
+        registry.register(
+            "risk_rejections",
+            "Signals rejected by the risk manager",
+            metrics.risk_rejections.clone(),
+        );
+        registry.register(
+            "drawdown_ratio",
+            "Drawdown from the portfolio peak",
+            metrics.drawdown.clone(),
+        );
+        registry.register_with_unit(
+            "order_latency",
+            "Time from order submission to the exchange's answer",
+            Unit::Seconds,
+            metrics.order_latency.clone(),
+        );
+        registry.register(
+            "order_failures",
+            "Orders that failed or were refused",
+            metrics.order_failures.clone(),
+        );
+        registry.register_with_unit(
+            "http_request_duration",
+            "Dashboard API request durations",
+            Unit::Seconds,
+            metrics.http_requests.clone(),
+        );
+        registry.register(
+            "watchdog_alarm",
+            "Whether a watchdog check is alarming",
+            metrics.watchdog_alarm.clone(),
+        );
+        registry.register(
+            "task_panics",
+            "Panics in spawned tasks",
+            metrics.task_panics.clone(),
+        );
+        metrics
+    }
+
+    /// Every metric in OpenMetrics text format.
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        text::encode(&mut out, &self.registry).expect("writing to a String cannot fail");
+        out
+    }
+
+    /// Count a risk rejection under its `reason` code.
+    pub fn count_rejection(&self, reason: &str) {
+        self.risk_rejections
+            .get_or_create(&ReasonLabels {
+                reason: reason.to_string(),
+            })
+            .inc();
+    }
+
+    /// Count an order failure under `reason`.
+    pub fn count_order_failure(&self, reason: &str) {
+        self.order_failures
+            .get_or_create(&ReasonLabels {
+                reason: reason.to_string(),
+            })
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_registered_metrics() {
+        let metrics = Metrics::new();
+        metrics.stream_reconnects.inc();
+        metrics.count_rejection("drawdown_halt");
+        metrics.drawdown.set(0.05);
+        let text = metrics.encode();
+        assert!(text.contains("clawbot_stream_reconnects_total 1"));
+        assert!(text.contains("clawbot_risk_rejections_total{reason=\"drawdown_halt\"} 1"));
+        assert!(text.contains("clawbot_drawdown_ratio 0.05"));
+        assert!(text.contains("# TYPE clawbot_order_latency_seconds histogram"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
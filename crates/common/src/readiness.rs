@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+use crate::Result;
+
+/// An external dependency checked by the dashboard's `/readyz` endpoint,
+/// e.g. the exchange REST API or the Telegram bot API.
+#[async_trait]
+pub trait ReadinessProbe: Send + Sync {
+    /// Key of this dependency in the readiness breakdown.
+    fn name(&self) -> &'static str;
+
+    /// Make one cheap request to the dependency.
+    async fn check(&self) -> Result<()>;
+}
@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tracing::{error, info};
+
+/// High-volume tables that are pruned by age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrunedTable {
+    Candles,
+    Signals,
+    RiskEvents,
+}
+
+impl PrunedTable {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PrunedTable::Candles => "candles",
+            PrunedTable::Signals => "signals",
+            PrunedTable::RiskEvents => "risk_events",
+        }
+    }
+}
+
+/// Retention of one table and what pruning has deleted from it since
+/// startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableRetention {
+    pub table: &'static str,
+    pub retention_days: u32,
+    /// Rows deleted since startup.
+    pub deleted_rows: u64,
+    /// Rows deleted by the latest prune.
+    pub last_deleted_rows: u64,
+    pub last_pruned_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Rows deleted per table by [`DataRetention`], for `/api/runtime`. Cheap
+/// to clone.
+#[derive(Clone, Default)]
+pub struct RetentionStats {
+    inner: Arc<Mutex<BTreeMap<PrunedTable, TableRetention>>>,
+}
+
+impl RetentionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn track(&self, table: PrunedTable, retention_days: u32) {
+        self.inner.lock().unwrap().insert(
+            table,
+            TableRetention {
+                table: table.as_str(),
+                retention_days,
+                deleted_rows: 0,
+                last_deleted_rows: 0,
+                last_pruned_at: None,
+                last_error: None,
+            },
+        );
+    }
+
+    fn pruned(&self, table: PrunedTable, deleted: u64, at: DateTime<Utc>) {
+        if let Some(t) = self.inner.lock().unwrap().get_mut(&table) {
+            t.deleted_rows += deleted;
+            t.last_deleted_rows = deleted;
+            t.last_pruned_at = Some(at);
+            t.last_error = None;
+        }
+    }
+
+    fn failed(&self, table: PrunedTable, error: String) {
+        if let Some(t) = self.inner.lock().unwrap().get_mut(&table) {
+            t.last_error = Some(error);
+        }
+    }
+
+    /// Every table with a retention window.
+    pub fn snapshot(&self) -> Vec<TableRetention> {
+        self.inner.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// Deletes rows older than each table's retention window on a schedule, so
+/// a long-running bot doesn't grow the database without bound. Tables
+/// without a window are kept forever.
+pub struct DataRetention {
+    db: SqlitePool,
+    stats: RetentionStats,
+    tables: Vec<(PrunedTable, u32)>,
+}
+
+impl DataRetention {
+    pub fn new(db: SqlitePool, stats: RetentionStats) -> Self {
+        Self {
+            db,
+            stats,
+            tables: Vec::new(),
+        }
+    }
+
+    /// Keep rows of `table` for `days`. `0` keeps them forever.
+    pub fn keep(&mut self, table: PrunedTable, days: u32) {
+        self.tables.retain(|(t, _)| *t != table);
+        if days > 0 {
+            self.tables.push((table, days));
+            self.stats.track(table, days);
+        }
+    }
+
+    /// Whether any table has a retention window.
+    pub fn is_enabled(&self) -> bool {
+        !self.tables.is_empty()
+    }
+
+    /// Prune every `every`, starting now. Call from `tokio::spawn`.
+    pub async fn run(self, every: Duration) {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+            for &(table, days) in &self.tables {
+                let cutoff = (now - chrono::Duration::days(days.into())).to_rfc3339();
+                match self.prune(table, &cutoff).await {
+                    Ok(deleted) => {
+                        if deleted > 0 {
+                            info!(table = table.as_str(), deleted, "Pruned old rows");
+                        }
+                        self.stats.pruned(table, deleted, now);
+                    }
+                    Err(e) => {
+                        error!(table = table.as_str(), error = %e, "Failed to prune old rows");
+                        self.stats.failed(table, e.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Delete the rows of `table` from before `cutoff`.
+    async fn prune(&self, table: PrunedTable, cutoff: &str) -> Result<u64, sqlx::Error> {
+        let done = match table {
+            PrunedTable::Candles => {
+                sqlx::query!("DELETE FROM candles WHERE closed_at < ?1", cutoff)
+                    .execute(&self.db)
+                    .await?
+            }
+            PrunedTable::Signals => {
+                sqlx::query!("DELETE FROM signals WHERE created_at < ?1", cutoff)
+                    .execute(&self.db)
+                    .await?
+            }
+            PrunedTable::RiskEvents => {
+                sqlx::query!("DELETE FROM risk_events WHERE created_at < ?1", cutoff)
+                    .execute(&self.db)
+                    .await?
+            }
+        };
+        Ok(done.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleted_rows_accumulate() {
+        let stats = RetentionStats::new();
+        stats.track(PrunedTable::Signals, 30);
+        stats.pruned(PrunedTable::Signals, 5, Utc::now());
+        stats.failed(PrunedTable::Signals, "locked".to_string());
+        stats.pruned(PrunedTable::Signals, 2, Utc::now());
+        // Untracked tables are ignored
+        stats.pruned(PrunedTable::Candles, 9, Utc::now());
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].table, "signals");
+        assert_eq!(snapshot[0].deleted_rows, 7);
+        assert_eq!(snapshot[0].last_deleted_rows, 2);
+        assert!(snapshot[0].last_error.is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::Order;
+
+/// Retry rounds after the initial failure before an order is dropped.
+pub const MAX_RETRY_ROUNDS: u32 = 5;
+
+/// How long a failed order stays retryable. Older signals are stale.
+pub const RETRY_EXPIRY: Duration = Duration::minutes(15);
+
+/// Delay before the first automatic retry; doubles each round.
+const RETRY_BASE_DELAY: Duration = Duration::seconds(30);
+
+/// An order whose submission failed, waiting to be retried.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedOrder {
+    pub order: Order,
+    pub error: String,
+    /// Retry rounds already spent (0 after the first failure).
+    pub rounds: u32,
+    pub failed_at: DateTime<Utc>,
+    /// When the order drops out of the queue.
+    pub expires_at: DateTime<Utc>,
+    /// Next automatic retry. `None` for non-transient failures, which are
+    /// only retried on operator request.
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+/// Failed orders awaiting retry, shared between the executor (which retries
+/// them) and the operator surfaces (API, Telegram) that list, force, or
+/// discard them. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct RetryQueue {
+    inner: Arc<Mutex<Vec<FailedOrder>>>,
+}
+
+impl RetryQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `order` after a failed submission. `rounds` is the number of
+    /// retries already spent on it; returns `false` (and drops it) once
+    /// `MAX_RETRY_ROUNDS` is exhausted. Transient failures are retried
+    /// automatically with exponential backoff.
+    pub fn push(&self, order: Order, error: String, rounds: u32, transient: bool) -> bool {
+        if rounds > MAX_RETRY_ROUNDS {
+            return false;
+        }
+        let now = Utc::now();
+        let next_retry_at = transient.then(|| now + RETRY_BASE_DELAY * 2i32.pow(rounds));
+        let mut queue = self.inner.lock().unwrap();
+        // Keep the original deadline across rounds
+        let expires_at = queue
+            .iter()
+            .position(|f| f.order.id == order.id)
+            .map(|idx| queue.remove(idx).expires_at)
+            .unwrap_or(now + RETRY_EXPIRY);
+        queue.push(FailedOrder {
+            order,
+            error,
+            rounds,
+            failed_at: now,
+            expires_at,
+            next_retry_at,
+        });
+        true
+    }
+
+    /// Snapshot of the queue, oldest first.
+    pub fn list(&self) -> Vec<FailedOrder> {
+        self.inner.lock().unwrap().clone()
+    }
+
+    /// Schedule the order with this ID for retry on the next executor tick.
+    pub fn retry_now(&self, order_id: &str) -> bool {
+        let mut queue = self.inner.lock().unwrap();
+        match queue.iter_mut().find(|f| f.order.id == order_id) {
+            Some(failed) => {
+                failed.next_retry_at = Some(Utc::now());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop the order with this ID without retrying it.
+    pub fn discard(&self, order_id: &str) -> Option<FailedOrder> {
+        let mut queue = self.inner.lock().unwrap();
+        let idx = queue.iter().position(|f| f.order.id == order_id)?;
+        Some(queue.remove(idx))
+    }
+
+    /// Remove and return entries due for retry at `now`, and separately
+    /// those that expired first.
+    pub fn take_due(&self, now: DateTime<Utc>) -> (Vec<FailedOrder>, Vec<FailedOrder>) {
+        let mut queue = self.inner.lock().unwrap();
+        let (expired, live): (Vec<_>, Vec<_>) = queue.drain(..).partition(|f| f.expires_at <= now);
+        let (due, waiting): (Vec<_>, Vec<_>) = live
+            .into_iter()
+            .partition(|f| f.next_retry_at.is_some_and(|at| at <= now));
+        *queue = waiting;
+        (due, expired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderSide;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn transient_failures_come_due_and_others_wait_for_operator() {
+        let queue = RetryQueue::new();
+        let transient = Order::market("BTCUSDT", OrderSide::Buy, Decimal::new(1, 2));
+        let permanent = Order::market("ETHUSDT", OrderSide::Buy, Decimal::new(1, 1));
+        assert!(queue.push(transient.clone(), "timeout".into(), 0, true));
+        assert!(queue.push(permanent.clone(), "insufficient balance".into(), 0, false));
+
+        let (due, expired) = queue.take_due(Utc::now() + Duration::minutes(1));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].order.id, transient.id);
+        assert!(expired.is_empty());
+
+        assert!(queue.retry_now(&permanent.id));
+        let (due, _) = queue.take_due(Utc::now());
+        assert_eq!(due[0].order.id, permanent.id);
+        assert!(queue.list().is_empty());
+
+        assert!(!queue.push(transient, "timeout".into(), MAX_RETRY_ROUNDS + 1, true));
+    }
+}
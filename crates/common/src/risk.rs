@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::{Error, Result};
+
+/// Hard ceiling on simultaneous open positions. Compiled-in constant — not
+/// user-configurable — as a last-resort safeguard against runaway trading.
+/// `RiskConfig::max_open_positions` may only lower it.
+pub const MAX_OPEN_ORDERS: usize = 5;
+
+/// User-configurable risk parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RiskConfig {
+    /// Maximum loss on a single position before auto-close (e.g. 0.02 = 2%).
+    pub stop_loss_pct: f64,
+    /// Target gain on a single position before auto-close (e.g. 0.03 = 3%).
+    pub take_profit_pct: f64,
+    /// Maximum USD notional for a single order.
+    pub max_exposure_per_trade_usd: f64,
+    /// Portfolio drawdown from peak that triggers a halt (e.g. 0.10 = 10%).
+    pub max_drawdown_pct: f64,
+    /// Seconds to block new entries on a pair after its stop-loss fires.
+    /// `0` disables the cooldown.
+    pub stop_loss_cooldown_secs: u64,
+    /// Groups of highly correlated pairs treated as a single risk bucket.
+    #[serde(default)]
+    pub correlation_groups: Vec<CorrelationGroup>,
+    /// Window (ms) in which opposite-side signals on the same pair are treated
+    /// as conflicting. Approved orders are held this long before forwarding.
+    /// `0` disables arbitration.
+    #[serde(default)]
+    pub conflict_window_ms: u64,
+    /// How conflicting signals within the window are resolved.
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// Strategy names from highest to lowest priority, used by
+    /// `ConflictPolicy::Priority`. Unlisted strategies rank lowest.
+    #[serde(default)]
+    pub strategy_priority: Vec<String>,
+    /// Maximum orders approved per pair per minute (token bucket). Guards
+    /// against signal-spamming strategy bugs and exchange order-rate bans.
+    /// `0` disables the limit.
+    #[serde(default = "default_max_orders_per_minute")]
+    pub max_orders_per_minute_per_pair: u32,
+    /// Unrealized gain at which a position's stop moves to break-even
+    /// (e.g. 0.015 = +1.5%). `0` disables break-even stops.
+    #[serde(default)]
+    pub break_even_trigger_pct: f64,
+    /// Offset above entry (below for shorts) for the break-even stop, to cover
+    /// fees and slippage (e.g. 0.002 = 0.2%).
+    #[serde(default)]
+    pub break_even_offset_pct: f64,
+    /// Laddered take-profit levels, ascending by `pct`. When empty, the whole
+    /// position closes at `take_profit_pct`. The last level always closes
+    /// whatever quantity remains.
+    #[serde(default)]
+    pub take_profit_levels: Vec<TakeProfitLevel>,
+    /// Consecutive losing closes that halt trading until reset.
+    /// `0` disables the check.
+    #[serde(default)]
+    pub max_consecutive_losses: u32,
+    /// Fill-price deviation from the price at approval, in basis points, that
+    /// raises an alert. `0` disables the guard.
+    #[serde(default)]
+    pub max_fill_deviation_bps: f64,
+    /// Seconds to block new entries on a pair after a fill-deviation alert.
+    /// `0` alerts without pausing.
+    #[serde(default)]
+    pub fill_deviation_pause_secs: u64,
+    /// Portfolio value-at-risk limit in USD; entries that would push
+    /// historical VaR above it are rejected. `0` disables the limit.
+    #[serde(default)]
+    pub max_var_usd: f64,
+    /// Confidence level for VaR (e.g. 0.95).
+    #[serde(default = "default_var_confidence")]
+    pub var_confidence: f64,
+    /// Number of recent candle-close returns per pair used for VaR.
+    #[serde(default = "default_var_lookback")]
+    pub var_lookback: usize,
+    /// Place an exchange-side OCO stop-loss/take-profit bracket after each
+    /// live entry fill, so exits still execute if the bot goes down.
+    #[serde(default)]
+    pub exchange_brackets: bool,
+    /// Maximum simultaneous open positions, between 1 and `MAX_OPEN_ORDERS`.
+    #[serde(default = "default_max_open_positions")]
+    pub max_open_positions: usize,
+    /// Bid/ask spread, in basis points, above which new entries on a pair
+    /// are rejected. `0` disables the guard.
+    #[serde(default)]
+    pub max_spread_bps: f64,
+}
+
+/// One rung of a take-profit ladder, e.g. close 50% at +2%.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TakeProfitLevel {
+    /// Unrealized gain that triggers this level (e.g. 0.02 = +2%).
+    pub pct: f64,
+    /// Fraction of the position's original quantity to close (0, 1].
+    pub fraction: f64,
+}
+
+fn default_max_orders_per_minute() -> u32 {
+    10
+}
+
+fn default_var_confidence() -> f64 {
+    0.95
+}
+
+fn default_var_lookback() -> usize {
+    100
+}
+
+fn default_max_open_positions() -> usize {
+    MAX_OPEN_ORDERS
+}
+
+/// Resolution rule for a Buy and a Sell on the same pair within the window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Keep the earlier signal, reject the later one.
+    #[default]
+    FirstWins,
+    /// Reject both signals.
+    CancelBoth,
+    /// Keep the signal from the higher-priority strategy (ties: first wins).
+    Priority,
+}
+
+/// A set of pairs whose combined open exposure is capped together
+/// (e.g. BTCUSDT + ETHUSDT move closely enough to count as one bet).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CorrelationGroup {
+    pub name: String,
+    pub pairs: Vec<String>,
+    /// Maximum combined USD notional across all pairs in the group.
+    pub max_exposure_usd: f64,
+}
+
+/// Per-strategy replacements for a subset of the global risk parameters.
+/// Unset fields fall back to the global `RiskConfig`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskOverrides {
+    pub stop_loss_pct: Option<f64>,
+    pub take_profit_pct: Option<f64>,
+    pub max_exposure_per_trade_usd: Option<f64>,
+}
+
+impl RiskOverrides {
+    /// Check the overridden values against the same bounds as `RiskConfig`.
+    pub fn validate(&self) -> Result<()> {
+        RiskConfig::default().with_overrides(self).validate()
+    }
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            stop_loss_pct: 0.02,
+            take_profit_pct: 0.04,
+            max_exposure_per_trade_usd: 100.0,
+            max_drawdown_pct: 0.10,
+            stop_loss_cooldown_secs: 900,
+            correlation_groups: Vec::new(),
+            conflict_window_ms: 0,
+            conflict_policy: ConflictPolicy::FirstWins,
+            strategy_priority: Vec::new(),
+            max_orders_per_minute_per_pair: default_max_orders_per_minute(),
+            break_even_trigger_pct: 0.0,
+            break_even_offset_pct: 0.0,
+            take_profit_levels: Vec::new(),
+            max_consecutive_losses: 0,
+            max_fill_deviation_bps: 0.0,
+            fill_deviation_pause_secs: 0,
+            max_var_usd: 0.0,
+            var_confidence: default_var_confidence(),
+            var_lookback: default_var_lookback(),
+            exchange_brackets: false,
+            max_open_positions: default_max_open_positions(),
+            max_spread_bps: 0.0,
+        }
+    }
+}
+
+impl RiskConfig {
+    /// Check that every parameter is within a sane range.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.stop_loss_pct > 0.0 && self.stop_loss_pct < 1.0) {
+            return Err(Error::Config(
+                "stop_loss_pct must be between 0 and 1".into(),
+            ));
+        }
+        if !(self.take_profit_pct > 0.0 && self.take_profit_pct.is_finite()) {
+            return Err(Error::Config("take_profit_pct must be positive".into()));
+        }
+        if !(self.max_exposure_per_trade_usd > 0.0 && self.max_exposure_per_trade_usd.is_finite()) {
+            return Err(Error::Config(
+                "max_exposure_per_trade_usd must be positive".into(),
+            ));
+        }
+        if !(self.max_drawdown_pct > 0.0 && self.max_drawdown_pct <= 1.0) {
+            return Err(Error::Config("max_drawdown_pct must be in (0, 1]".into()));
+        }
+        if !(self.break_even_trigger_pct >= 0.0 && self.break_even_offset_pct >= 0.0) {
+            return Err(Error::Config(
+                "break-even trigger and offset must be non-negative".into(),
+            ));
+        }
+        if self.break_even_trigger_pct > 0.0
+            && self.break_even_offset_pct >= self.break_even_trigger_pct
+        {
+            return Err(Error::Config(
+                "break_even_offset_pct must be below break_even_trigger_pct".into(),
+            ));
+        }
+        if !(self.max_fill_deviation_bps >= 0.0 && self.max_fill_deviation_bps.is_finite()) {
+            return Err(Error::Config(
+                "max_fill_deviation_bps must be non-negative".into(),
+            ));
+        }
+        if !(self.max_var_usd >= 0.0 && self.max_var_usd.is_finite()) {
+            return Err(Error::Config("max_var_usd must be non-negative".into()));
+        }
+        if !(self.var_confidence >= 0.5 && self.var_confidence < 1.0) {
+            return Err(Error::Config("var_confidence must be in [0.5, 1)".into()));
+        }
+        if self.var_lookback < 10 {
+            return Err(Error::Config("var_lookback must be at least 10".into()));
+        }
+        if !(1..=MAX_OPEN_ORDERS).contains(&self.max_open_positions) {
+            return Err(Error::Config(format!(
+                "max_open_positions must be between 1 and {MAX_OPEN_ORDERS}"
+            )));
+        }
+        if !(self.max_spread_bps >= 0.0 && self.max_spread_bps.is_finite()) {
+            return Err(Error::Config("max_spread_bps must be non-negative".into()));
+        }
+        let mut prev_pct = 0.0;
+        for level in &self.take_profit_levels {
+            if !(level.pct > prev_pct && level.pct.is_finite()) {
+                return Err(Error::Config(
+                    "take_profit_levels must have positive, strictly ascending pct".into(),
+                ));
+            }
+            if !(level.fraction > 0.0 && level.fraction <= 1.0) {
+                return Err(Error::Config(
+                    "take_profit_levels fraction must be in (0, 1]".into(),
+                ));
+            }
+            prev_pct = level.pct;
+        }
+        for group in &self.correlation_groups {
+            if group.pairs.is_empty() {
+                return Err(Error::Config(format!(
+                    "correlation group '{}' has no pairs",
+                    group.name
+                )));
+            }
+            if !(group.max_exposure_usd > 0.0 && group.max_exposure_usd.is_finite()) {
+                return Err(Error::Config(format!(
+                    "correlation group '{}' max_exposure_usd must be positive",
+                    group.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the effective config for a signal or position carrying `overrides`.
+    pub fn with_overrides(&self, overrides: &RiskOverrides) -> RiskConfig {
+        RiskConfig {
+            stop_loss_pct: overrides.stop_loss_pct.unwrap_or(self.stop_loss_pct),
+            take_profit_pct: overrides.take_profit_pct.unwrap_or(self.take_profit_pct),
+            max_exposure_per_trade_usd: overrides
+                .max_exposure_per_trade_usd
+                .unwrap_or(self.max_exposure_per_trade_usd),
+            ..self.clone()
+        }
+    }
+
+    /// Return a copy with the fields in `patch` (a JSON object) overwritten.
+    /// Unknown fields and invalid values are rejected.
+    pub fn apply_patch(&self, patch: &Value) -> Result<RiskConfig> {
+        let Value::Object(fields) = patch else {
+            return Err(Error::Config("risk config update must be an object".into()));
+        };
+
+        let mut merged = serde_json::to_value(self)?;
+        let target = merged
+            .as_object_mut()
+            .expect("RiskConfig serializes to an object");
+        for (key, value) in fields {
+            if !target.contains_key(key) {
+                return Err(Error::Config(format!("unknown risk parameter '{key}'")));
+            }
+            target.insert(key.clone(), value.clone());
+        }
+
+        let updated: RiskConfig = serde_json::from_value(merged)
+            .map_err(|e| Error::Config(format!("invalid risk parameter: {e}")))?;
+        updated.validate()?;
+        Ok(updated)
+    }
+}
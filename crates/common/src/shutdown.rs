@@ -0,0 +1,324 @@
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{error, info, warn};
+
+use crate::metrics::{metrics, TaskLabels};
+use crate::types::RiskEvent;
+
+/// Longest one cleanup step may take.
+const CLEANUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest wait before restarting a task that keeps panicking.
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// A supervised task that ran this long before panicking restarts without
+/// delay, as if it had never panicked.
+const HEALTHY_RUN: Duration = Duration::from_secs(300);
+
+type Cleanup = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Coordinates graceful shutdown. Long-running tasks are spawned through
+/// it, and cleanup steps (goodbye messages, closing the database) are
+/// registered with [`Shutdown::on_shutdown`]. [`Shutdown::shutdown`]
+/// cancels every task, waits for them, then runs the cleanups in order.
+///
+/// Every task is named and watched for panics: a panic is logged, counted
+/// in `clawbot_task_panics`, and sent as a critical
+/// [`RiskEvent::TaskPanicked`] (see [`Shutdown::set_panic_events`]).
+/// Tasks started with [`Shutdown::supervise`] are also restarted.
+/// Cheap to clone.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tasks: TaskTracker,
+    cleanups: Arc<Mutex<Vec<(&'static str, Cleanup)>>>,
+    panic_tx: Arc<OnceLock<mpsc::Sender<RiskEvent>>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancelled once shutdown starts, for tasks that stop themselves at a
+    /// safe point (see [`Shutdown::spawn_graceful`]).
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Send a [`RiskEvent::TaskPanicked`] to `tx` whenever a task panics.
+    /// Only the first call has an effect.
+    pub fn set_panic_events(&self, tx: mpsc::Sender<RiskEvent>) {
+        let _ = self.panic_tx.set(tx);
+    }
+
+    /// Spawn `task`, dropping it at its next `.await` once shutdown starts.
+    /// For tasks with nothing to finish.
+    pub fn spawn<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.launch(name, false, false, once(task));
+    }
+
+    /// Spawn `task`, which watches [`Shutdown::token`] and returns on its
+    /// own; shutdown waits for it.
+    pub fn spawn_graceful<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.launch(name, true, false, once(task));
+    }
+
+    /// Like [`Shutdown::spawn`], but restart the task with a fresh future
+    /// from `task` whenever it panics, after a delay that doubles with each
+    /// consecutive panic up to a minute. A task that returns normally is not
+    /// restarted. To keep state across restarts, `task` should run a
+    /// component held behind a shared lock rather than consume it.
+    pub fn supervise<F>(&self, name: &'static str, task: impl FnMut() -> F + Send + 'static)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.launch(name, false, true, restarts(task));
+    }
+
+    /// [`Shutdown::supervise`] for a task that stops itself like
+    /// [`Shutdown::spawn_graceful`]'s.
+    pub fn supervise_graceful<F>(
+        &self,
+        name: &'static str,
+        task: impl FnMut() -> F + Send + 'static,
+    ) where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.launch(name, true, true, restarts(task));
+    }
+
+    /// Run the future `next` yields on its own tokio task, so a panic ends
+    /// only that future, and with `restart` run the next one after a panic.
+    /// Stops when a future returns normally or shutdown starts: a graceful
+    /// future is awaited through shutdown, any other is aborted.
+    fn launch<F>(
+        &self,
+        name: &'static str,
+        graceful: bool,
+        restart: bool,
+        mut next: impl FnMut() -> Option<F> + Send + 'static,
+    ) where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.token.clone();
+        let panic_tx = self.panic_tx.clone();
+        self.tasks.spawn(async move {
+            let mut panics = 0;
+            while let Some(task) = next() {
+                let started = tokio::time::Instant::now();
+                let mut handle = tokio::spawn(task);
+                let result = if graceful {
+                    handle.await
+                } else {
+                    tokio::select! {
+                        _ = token.cancelled() => {
+                            handle.abort();
+                            return;
+                        }
+                        result = &mut handle => result,
+                    }
+                };
+                let error = match result {
+                    Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                    _ => return,
+                };
+
+                if started.elapsed() >= HEALTHY_RUN {
+                    panics = 0;
+                }
+                panics += 1;
+                let restarted = restart && !token.is_cancelled();
+                error!(task = name, error = %error, restarted, "Task panicked");
+                metrics()
+                    .task_panics
+                    .get_or_create(&TaskLabels {
+                        task: name.to_string(),
+                    })
+                    .inc();
+                // Never wait on the channel: its reader may be the task that died
+                if let Some(tx) = panic_tx.get() {
+                    let _ = tx.try_send(RiskEvent::TaskPanicked {
+                        task: name.to_string(),
+                        error,
+                        restarted,
+                    });
+                }
+                if !restarted {
+                    return;
+                }
+                tokio::select! {
+                    _ = token.cancelled() => return,
+                    _ = tokio::time::sleep(restart_delay(panics)) => {}
+                }
+                info!(task = name, "Restarting task");
+            }
+        });
+    }
+
+    /// Run `cleanup` after every task has stopped, following cleanups
+    /// registered before it.
+    pub fn on_shutdown<F>(&self, name: &'static str, cleanup: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.cleanups
+            .lock()
+            .expect("cleanup list poisoned")
+            .push((name, Box::pin(cleanup)));
+    }
+
+    /// Cancel every task and wait up to `timeout` for them to stop, then
+    /// run each cleanup for up to five seconds. Returns whether everything
+    /// finished in time.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.token.cancel();
+        self.tasks.close();
+        let mut clean = true;
+        if tokio::time::timeout(timeout, self.tasks.wait())
+            .await
+            .is_err()
+        {
+            warn!(
+                still_running = self.tasks.len(),
+                timeout_secs = timeout.as_secs(),
+                "Tasks did not stop in time"
+            );
+            clean = false;
+        }
+        let cleanups = std::mem::take(&mut *self.cleanups.lock().expect("cleanup list poisoned"));
+        for (name, cleanup) in cleanups {
+            match tokio::time::timeout(CLEANUP_TIMEOUT, cleanup).await {
+                Ok(()) => info!(step = name, "Shutdown step done"),
+                Err(_) => {
+                    warn!(step = name, "Shutdown step timed out");
+                    clean = false;
+                }
+            }
+        }
+        clean
+    }
+}
+
+/// How long to wait before restarting a task after its `panics`th
+/// consecutive panic: one second, doubling up to [`MAX_RESTART_DELAY`].
+fn restart_delay(panics: u32) -> Duration {
+    Duration::from_secs(1u64 << panics.saturating_sub(1).min(6)).min(MAX_RESTART_DELAY)
+}
+
+/// The message a panic was raised with.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Yields `task` once.
+fn once<F>(task: F) -> impl FnMut() -> Option<F> + Send + 'static
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let mut task = Some(task);
+    move || task.take()
+}
+
+/// Yields a fresh future from `task` every time.
+fn restarts<F>(mut task: impl FnMut() -> F + Send + 'static) -> impl FnMut() -> Option<F> + Send
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    move || Some(task())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_for_graceful_tasks_then_cleans_up() {
+        let shutdown = Shutdown::new();
+        shutdown.spawn("idle", std::future::pending());
+        let finished = Arc::new(AtomicBool::new(false));
+        {
+            let (token, finished) = (shutdown.token(), finished.clone());
+            shutdown.spawn_graceful("graceful", async move {
+                token.cancelled().await;
+                finished.store(true, Ordering::SeqCst);
+            });
+        }
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for step in ["telegram", "database"] {
+            let (order, finished) = (order.clone(), finished.clone());
+            shutdown.on_shutdown(step, async move {
+                assert!(finished.load(Ordering::SeqCst));
+                order.lock().unwrap().push(step);
+            });
+        }
+
+        assert!(shutdown.shutdown(Duration::from_secs(1)).await);
+        assert_eq!(*order.lock().unwrap(), ["telegram", "database"]);
+    }
+
+    #[tokio::test]
+    async fn restarts_supervised_tasks_after_a_panic() {
+        let shutdown = Shutdown::new();
+        let (tx, mut rx) = mpsc::channel(4);
+        shutdown.set_panic_events(tx);
+        let runs = Arc::new(AtomicUsize::new(0));
+        {
+            let runs = runs.clone();
+            shutdown.supervise("flaky", move || {
+                let run = runs.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if run == 0 {
+                        panic!("boom");
+                    }
+                    std::future::pending::<()>().await;
+                }
+            });
+        }
+        shutdown.spawn("fragile", async { panic!("bang") });
+
+        let mut events = [rx.recv().await.unwrap(), rx.recv().await.unwrap()];
+        events.sort_by_key(|e| e.message());
+        assert_eq!(
+            events.iter().map(|e| e.message()).collect::<Vec<_>>(),
+            [
+                "🚨 flaky crashed and was restarted: boom",
+                "🚨 fragile crashed and is not running: bang. Restart the bot.",
+            ]
+        );
+        // Restarted after a one-second delay
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while runs.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("supervised task was not restarted");
+        assert!(shutdown.shutdown(Duration::from_secs(1)).await);
+    }
+
+    #[test]
+    fn restart_delay_backs_off_to_a_minute() {
+        let delays: Vec<u64> = (1..=8).map(|n| restart_delay(n).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+}
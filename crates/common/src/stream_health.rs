@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::KlineInterval;
+
+/// Health of the market data stream as seen by one pair.
+///
+/// All pairs share one exchange connection, so connection state, reconnects
+/// and errors move together; `last_event_at` and `stale_reconnects` are the
+/// pair's own.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairStreamStatus {
+    pub pair: String,
+    pub interval: KlineInterval,
+    pub connected: bool,
+    /// Start of the current connection, while connected.
+    pub connected_since: Option<DateTime<Utc>>,
+    /// Seconds since `connected_since`; 0 while disconnected.
+    pub uptime_secs: i64,
+    /// Reconnects since the pair was added.
+    pub reconnects: u32,
+    /// Reconnects forced because this pair went quiet.
+    pub stale_reconnects: u32,
+    /// Times the stream task died and was restarted by the engine.
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub last_event_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct PairHealth {
+    interval: KlineInterval,
+    reconnects: u32,
+    stale_reconnects: u32,
+    restarts: u32,
+    last_event_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    pairs: BTreeMap<String, PairHealth>,
+    connected_since: Option<DateTime<Utc>>,
+    last_error: Option<(String, DateTime<Utc>)>,
+}
+
+/// Per-pair supervision map for the market data stream. Written by the
+/// engine, read by the health endpoint. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct StreamHealth {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl StreamHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking `pair`, or update its interval if already tracked.
+    pub fn track(&self, pair: &str, interval: KlineInterval) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .pairs
+            .entry(pair.to_string())
+            .and_modify(|p| p.interval = interval)
+            .or_insert(PairHealth {
+                interval,
+                reconnects: 0,
+                stale_reconnects: 0,
+                restarts: 0,
+                last_event_at: None,
+            });
+    }
+
+    pub fn untrack(&self, pair: &str) {
+        self.inner.lock().unwrap().pairs.remove(pair);
+    }
+
+    /// The stream (re)connected. `reconnect` is false for the first
+    /// connection of a freshly spawned stream task.
+    pub fn connected(&self, reconnect: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.connected_since = Some(Utc::now());
+        if reconnect {
+            for pair in inner.pairs.values_mut() {
+                pair.reconnects += 1;
+            }
+        }
+    }
+
+    /// The connection dropped with `error`.
+    pub fn disconnected(&self, error: String) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.connected_since = None;
+        inner.last_error = Some((error, Utc::now()));
+    }
+
+    /// The stream task exited and is being respawned.
+    pub fn restarted(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.connected_since = None;
+        for pair in inner.pairs.values_mut() {
+            pair.restarts += 1;
+        }
+    }
+
+    /// The stream was stopped on purpose.
+    pub fn stopped(&self) {
+        self.inner.lock().unwrap().connected_since = None;
+    }
+
+    /// A market event arrived for `pair`.
+    pub fn event(&self, pair: &str) {
+        if let Some(p) = self.inner.lock().unwrap().pairs.get_mut(pair) {
+            p.last_event_at = Some(Utc::now());
+        }
+    }
+
+    /// A reconnect was forced because `pair` went quiet.
+    pub fn stale(&self, pair: &str) {
+        if let Some(p) = self.inner.lock().unwrap().pairs.get_mut(pair) {
+            p.stale_reconnects += 1;
+        }
+    }
+
+    /// Status of every tracked pair, in pair order.
+    pub fn snapshot(&self) -> Vec<PairStreamStatus> {
+        let inner = self.inner.lock().unwrap();
+        let now = Utc::now();
+        let uptime_secs = inner
+            .connected_since
+            .map(|since| (now - since).num_seconds())
+            .unwrap_or(0);
+        inner
+            .pairs
+            .iter()
+            .map(|(pair, health)| PairStreamStatus {
+                pair: pair.clone(),
+                interval: health.interval,
+                connected: inner.connected_since.is_some(),
+                connected_since: inner.connected_since,
+                uptime_secs,
+                reconnects: health.reconnects,
+                stale_reconnects: health.stale_reconnects,
+                restarts: health.restarts,
+                last_error: inner.last_error.as_ref().map(|(e, _)| e.clone()),
+                last_error_at: inner.last_error.as_ref().map(|(_, at)| *at),
+                last_event_at: health.last_event_at,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnects_count_per_pair_from_when_it_was_added() {
+        let health = StreamHealth::new();
+        health.track("BTCUSDT", KlineInterval::OneMinute);
+        health.connected(false);
+        health.disconnected("reset by peer".into());
+        health.connected(true);
+        health.track("ETHUSDT", KlineInterval::FiveMinutes);
+        health.stale("ETHUSDT");
+        health.connected(true);
+
+        let status = health.snapshot();
+        assert_eq!(status[0].pair, "BTCUSDT");
+        assert_eq!(status[0].reconnects, 2);
+        assert_eq!(status[1].reconnects, 1);
+        assert_eq!(status[1].stale_reconnects, 1);
+        assert!(status.iter().all(|s| s.connected));
+        assert_eq!(status[0].last_error.as_deref(), Some("reset by peer"));
+    }
+}
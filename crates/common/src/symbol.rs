@@ -0,0 +1,83 @@
+/// Quote assets recognised when splitting a concatenated pair such as
+/// `BTCUSDT`. Longer codes come first so `USDT` wins over `USD`.
+const QUOTE_ASSETS: &[&str] = &["FDUSD", "USDT", "USDC", "USD", "EUR", "GBP", "BTC", "ETH"];
+
+/// A trading pair split into base and quote asset.
+///
+/// Internally pairs use the concatenated Binance form (`BTCUSDT`); each
+/// exchange integration converts to its own naming at the edge.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    pub base: String,
+    pub quote: String,
+}
+
+impl Symbol {
+    /// Parse `BTCUSDT`, `BTC-USD`, `BTC/USDT` or `btc_usdt`. Concatenated
+    /// pairs are split on a known quote asset suffix.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_uppercase();
+        if let Some((base, quote)) = s.split_once(['-', '/', '_']) {
+            return (!base.is_empty() && !quote.is_empty()).then(|| Self {
+                base: base.to_string(),
+                quote: quote.to_string(),
+            });
+        }
+        QUOTE_ASSETS.iter().find_map(|quote| {
+            let base = s.strip_suffix(quote)?;
+            (!base.is_empty()).then(|| Self {
+                base: base.to_string(),
+                quote: quote.to_string(),
+            })
+        })
+    }
+
+    /// Internal pair name, e.g. `BTCUSDT`.
+    pub fn pair(&self) -> String {
+        format!("{}{}", self.base, self.quote)
+    }
+
+    /// Coinbase product ID, e.g. `BTC-USD`.
+    pub fn coinbase_product(&self) -> String {
+        format!("{}-{}", self.base, self.quote)
+    }
+}
+
+/// Coinbase product ID for an internal pair (`BTCUSD` → `BTC-USD`). Pairs
+/// that cannot be split are passed through unchanged.
+pub fn to_coinbase_product(pair: &str) -> String {
+    Symbol::parse(pair)
+        .map(|s| s.coinbase_product())
+        .unwrap_or_else(|| pair.to_string())
+}
+
+/// Internal pair name for a Coinbase product ID (`BTC-USD` → `BTCUSD`).
+pub fn from_coinbase_product(product_id: &str) -> String {
+    Symbol::parse(product_id)
+        .map(|s| s.pair())
+        .unwrap_or_else(|| product_id.to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_concatenated_and_delimited_pairs() {
+        let btc_usdt = Symbol {
+            base: "BTC".into(),
+            quote: "USDT".into(),
+        };
+        assert_eq!(Symbol::parse("BTCUSDT"), Some(btc_usdt.clone()));
+        assert_eq!(Symbol::parse("btc/usdt"), Some(btc_usdt.clone()));
+        assert_eq!(Symbol::parse("BTC-USDT"), Some(btc_usdt));
+        assert_eq!(Symbol::parse("USDT"), None);
+    }
+
+    #[test]
+    fn maps_to_and_from_coinbase_products() {
+        assert_eq!(to_coinbase_product("BTCUSD"), "BTC-USD");
+        assert_eq!(to_coinbase_product("ETHUSDC"), "ETH-USDC");
+        assert_eq!(from_coinbase_product("ETH-USD"), "ETHUSD");
+    }
+}
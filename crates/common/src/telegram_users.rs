@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+/// How long a pairing code can be redeemed.
+pub const PAIRING_CODE_TTL_SECS: i64 = 10 * 60;
+
+/// Wrong guesses a pairing code survives.
+const PAIRING_ATTEMPTS: u32 = 5;
+
+/// Telegram users allowed to control the bot: the IDs in
+/// `TELEGRAM_ALLOWED_USER_IDS` plus users paired at runtime by sending
+/// `/pair <code>` with a one-time code, kept in the `telegram_users` table.
+#[derive(Clone)]
+pub struct TelegramAllowlist {
+    db: SqlitePool,
+    configured: Arc<Vec<i64>>,
+    paired: Arc<RwLock<Vec<i64>>>,
+    pending: Arc<Mutex<Option<PairingCode>>>,
+}
+
+impl TelegramAllowlist {
+    /// The configured IDs plus every user paired so far.
+    pub async fn load(db: SqlitePool, configured: Vec<i64>) -> crate::Result<Self> {
+        let paired = sqlx::query_scalar!("SELECT user_id FROM telegram_users")
+            .fetch_all(&db)
+            .await?;
+        Ok(Self {
+            db,
+            configured: Arc::new(configured),
+            paired: Arc::new(RwLock::new(paired)),
+            pending: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    pub async fn contains(&self, user_id: i64) -> bool {
+        self.configured.contains(&user_id) || self.paired.read().await.contains(&user_id)
+    }
+
+    /// Every allowed user, configured ones first.
+    pub async fn user_ids(&self) -> Vec<i64> {
+        let mut ids = self.configured.to_vec();
+        ids.extend(self.paired.read().await.iter().copied());
+        ids
+    }
+
+    /// Start pairing with a fresh code, replacing any code not yet
+    /// redeemed. The code is logged so it can be read from the logs too.
+    pub async fn issue_code(&self) -> (String, DateTime<Utc>) {
+        let code = format!("{:08}", uuid::Uuid::new_v4().as_u128() % 100_000_000);
+        let expires_at = Utc::now() + Duration::seconds(PAIRING_CODE_TTL_SECS);
+        info!(%code, %expires_at, "Telegram pairing code issued; send /pair <code> to the bot");
+        *self.pending.lock().await = Some(PairingCode {
+            code: code.clone(),
+            expires_at,
+            attempts_left: PAIRING_ATTEMPTS,
+        });
+        (code, expires_at)
+    }
+
+    /// Allow `user_id` if `code` is the pending pairing code. The code
+    /// works once; returns `false` if it is wrong, used, or expired.
+    pub async fn pair(
+        &self,
+        user_id: i64,
+        username: Option<&str>,
+        code: &str,
+    ) -> crate::Result<bool> {
+        {
+            let mut pending = self.pending.lock().await;
+            let Some(current) = pending.as_mut() else {
+                return Ok(false);
+            };
+            match current.redeem(code, Utc::now()) {
+                Redeem::Accepted => *pending = None,
+                Redeem::Rejected => return Ok(false),
+                Redeem::Exhausted => {
+                    warn!(
+                        user_id,
+                        "Too many wrong pairing codes; pairing code revoked"
+                    );
+                    *pending = None;
+                    return Ok(false);
+                }
+            }
+        }
+        let now = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO telegram_users (user_id, username, paired_at) VALUES (?1, ?2, ?3)
+            ON CONFLICT(user_id) DO UPDATE SET
+                username = excluded.username, paired_at = excluded.paired_at
+            "#,
+            user_id,
+            username,
+            now,
+        )
+        .execute(&self.db)
+        .await?;
+        let mut paired = self.paired.write().await;
+        if !paired.contains(&user_id) {
+            paired.push(user_id);
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Debug)]
+struct PairingCode {
+    code: String,
+    expires_at: DateTime<Utc>,
+    attempts_left: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Redeem {
+    Accepted,
+    Rejected,
+    /// Wrong, and no attempts are left; the code must be discarded.
+    Exhausted,
+}
+
+impl PairingCode {
+    fn redeem(&mut self, code: &str, now: DateTime<Utc>) -> Redeem {
+        if now >= self.expires_at {
+            return Redeem::Exhausted;
+        }
+        if code == self.code {
+            return Redeem::Accepted;
+        }
+        self.attempts_left = self.attempts_left.saturating_sub(1);
+        if self.attempts_left == 0 {
+            Redeem::Exhausted
+        } else {
+            Redeem::Rejected
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_expires_and_survives_few_wrong_guesses() {
+        let now = Utc::now();
+        let mut code = PairingCode {
+            code: "12345678".into(),
+            expires_at: now + Duration::minutes(10),
+            attempts_left: PAIRING_ATTEMPTS,
+        };
+        for _ in 1..PAIRING_ATTEMPTS {
+            assert_eq!(code.redeem("00000000", now), Redeem::Rejected);
+        }
+        assert_eq!(code.redeem("12345678", now), Redeem::Accepted);
+        assert_eq!(code.redeem("00000000", now), Redeem::Exhausted);
+
+        let mut code = PairingCode {
+            code: "12345678".into(),
+            expires_at: now,
+            attempts_left: PAIRING_ATTEMPTS,
+        };
+        assert_eq!(code.redeem("12345678", now), Redeem::Exhausted);
+    }
+}
@@ -1,11 +1,62 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Candle interval of a kline stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KlineInterval {
+    #[default]
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+}
+
+impl KlineInterval {
+    /// Binance interval code, as used in stream names (e.g. `btcusdt@kline_5m`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KlineInterval::OneMinute => "1m",
+            KlineInterval::FiveMinutes => "5m",
+            KlineInterval::FifteenMinutes => "15m",
+            KlineInterval::OneHour => "1h",
+        }
+    }
+}
+
+impl std::fmt::Display for KlineInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for KlineInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(KlineInterval::OneMinute),
+            "5m" => Ok(KlineInterval::FiveMinutes),
+            "15m" => Ok(KlineInterval::FifteenMinutes),
+            "1h" => Ok(KlineInterval::OneHour),
+            other => Err(format!("unsupported kline interval '{other}'")),
+        }
+    }
+}
 
 /// Live market data event from the exchange stream.
-/// Emitted on every kline update (1-minute candles from Binance).
+/// Emitted on every kline update of the pair's configured interval.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketEvent {
     pub pair: String,
+    /// Candle interval this event belongs to.
+    #[serde(default)]
+    pub interval: KlineInterval,
     /// Latest close price of the current candle.
     pub price: f64,
     pub open: f64,
@@ -16,10 +67,65 @@ pub struct MarketEvent {
     /// process events where `is_candle_closed == true`.
     pub is_candle_closed: bool,
     pub timestamp: DateTime<Utc>,
+    /// Best bid from the pair's book ticker, once one has been received.
+    #[serde(default)]
+    pub best_bid: Option<f64>,
+    /// Best ask from the pair's book ticker, once one has been received.
+    #[serde(default)]
+    pub best_ask: Option<f64>,
+}
+
+impl MarketEvent {
+    /// Bid/ask spread in basis points of the mid price, if quotes are known.
+    pub fn spread_bps(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid?, self.best_ask?);
+        let mid = (bid + ask) / 2.0;
+        (mid > 0.0).then(|| (ask - bid) / mid * 10_000.0)
+    }
+}
+
+/// Aggregated public trade from the exchange stream (Binance `aggTrade`).
+/// Published only for pairs with trade streaming enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeEvent {
+    pub pair: String,
+    /// Aggregate trade ID.
+    pub trade_id: u64,
+    pub price: f64,
+    pub quantity: f64,
+    /// True when the buyer was the resting (maker) side, i.e. a market sell.
+    pub is_buyer_maker: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A position, or the part of one, closed by a fill, as booked by the
+/// trade ledger in the `trades` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedTrade {
+    pub id: String,
+    pub pair: String,
+    /// Side of the position that was closed.
+    pub side: OrderSide,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub quantity: f64,
+    /// Realized PnL before fees.
+    pub pnl_usd: f64,
+    /// This trade's share of the entry and exit fees.
+    pub fee_usd: f64,
+    pub strategy: Option<String>,
+    pub closed_at: DateTime<Utc>,
+}
+
+impl ClosedTrade {
+    /// Realized PnL after fees.
+    pub fn net_pnl_usd(&self) -> f64 {
+        self.pnl_usd - self.fee_usd
+    }
 }
 
 /// Side of a trade.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[serde(rename_all = "UPPERCASE")]
 #[sqlx(type_name = "TEXT", rename_all = "UPPERCASE")]
 pub enum OrderSide {
@@ -27,6 +133,16 @@ pub enum OrderSide {
     Sell,
 }
 
+impl OrderSide {
+    /// The side that closes a position opened on this side.
+    pub fn opposite(self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+}
+
 impl std::fmt::Display for OrderSide {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -42,59 +158,249 @@ pub struct Order {
     pub id: String,
     pub pair: String,
     pub side: OrderSide,
-    pub quantity: f64,
+    /// Base asset quantity. Zero for quote-quantity orders until filled.
+    pub quantity: Decimal,
+    /// Market orders only: spend (buy) or receive (sell) this much of the
+    /// quote asset and let the exchange work out the base quantity.
+    #[serde(default)]
+    pub quote_quantity: Option<Decimal>,
     /// `None` = market order; `Some(price)` = limit order.
-    pub price: Option<f64>,
+    pub price: Option<Decimal>,
+    /// ID of the open position this order reduces or closes; `None` for entries.
+    #[serde(default)]
+    pub position_id: Option<String>,
+    /// Last market price seen when the order was approved, used to measure
+    /// fill deviation.
+    #[serde(default)]
+    pub reference_price: Option<Decimal>,
+    /// Makes this a conditional order that rests on the exchange until the
+    /// stop price trades. With `price` set it becomes a stop-limit order.
+    #[serde(default)]
+    pub trigger: Option<OrderTrigger>,
+    /// For entries: protect the resulting position with an exchange-side
+    /// stop-loss/take-profit bracket placed right after the fill.
+    #[serde(default)]
+    pub bracket: Option<BracketSpec>,
+    /// Strategy whose signal produced this order, recorded on the position
+    /// it opens and the trades that later close it.
+    #[serde(default)]
+    pub strategy: Option<String>,
+}
+
+/// Stop-loss and take-profit distances for an exchange-side bracket, as
+/// fractions of the entry fill price.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BracketSpec {
+    pub stop_loss_pct: f64,
+    pub take_profit_pct: f64,
+}
+
+/// Lifecycle state of an order on the exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Expired,
+    Rejected,
+}
+
+impl OrderStatus {
+    /// Whether the order can no longer change.
+    pub fn is_terminal(self) -> bool {
+        !matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            OrderStatus::New => "new",
+            OrderStatus::PartiallyFilled => "partially filled",
+            OrderStatus::Filled => "filled",
+            OrderStatus::Canceled => "canceled",
+            OrderStatus::Expired => "expired",
+            OrderStatus::Rejected => "rejected",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Current state of a previously submitted order, as reported by the exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderStatusReport {
+    pub status: OrderStatus,
+    pub executed_quantity: Decimal,
+    /// Volume-weighted fill price; `None` while nothing has executed.
+    pub average_price: Option<Decimal>,
+    /// The exchange's own ID for the order, if it assigns one.
+    pub exchange_order_id: Option<String>,
+}
+
+/// A one-cancels-other pair of exits resting on the exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketOrder {
+    pub pair: String,
+    /// Side of both exit legs (opposite of the position).
+    pub side: OrderSide,
+    pub quantity: Decimal,
+    /// Limit price of the take-profit leg.
+    pub take_profit_price: Decimal,
+    /// Trigger price of the stop-loss leg, which executes at market.
+    pub stop_price: Decimal,
+}
+
+/// A bracket placed on the exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketLegs {
+    /// The exchange's ID for the pair of orders, for `cancel_bracket`.
+    pub id: String,
+    /// Client order ID of the take-profit leg, for `order_status`.
+    pub take_profit_order_id: String,
+    /// Client order ID of the stop-loss leg, for `order_status`.
+    pub stop_order_id: String,
+}
+
+/// Trigger condition for an exchange-side conditional order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OrderTrigger {
+    /// Fires when price moves against the position to `stop_price`.
+    StopLoss { stop_price: Decimal },
+    /// Fires when price moves in favour of the position to `stop_price`.
+    TakeProfit { stop_price: Decimal },
 }
 
 impl Order {
-    pub fn market(pair: impl Into<String>, side: OrderSide, quantity: f64) -> Self {
+    pub fn market(pair: impl Into<String>, side: OrderSide, quantity: Decimal) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             pair: pair.into(),
             side,
             quantity,
+            quote_quantity: None,
             price: None,
+            position_id: None,
+            reference_price: None,
+            trigger: None,
+            bracket: None,
+            strategy: None,
+        }
+    }
+
+    /// Market order for `quote_quantity` worth of the quote asset, e.g. "buy
+    /// 50 USDT of BTC".
+    pub fn market_quote(pair: impl Into<String>, side: OrderSide, quote_quantity: Decimal) -> Self {
+        Self {
+            quote_quantity: Some(quote_quantity),
+            ..Self::market(pair, side, Decimal::ZERO)
+        }
+    }
+
+    /// Base quantity this order trades if it fills at `price`.
+    pub fn base_quantity_at(&self, price: Decimal) -> Decimal {
+        match self.quote_quantity {
+            Some(quote) if price > Decimal::ZERO => quote / price,
+            Some(_) => Decimal::ZERO,
+            None => self.quantity,
+        }
+    }
+
+    /// Market order that closes `quantity` of an open position.
+    pub fn close(position: &Position, quantity: Decimal) -> Self {
+        Self {
+            position_id: Some(position.id.clone()),
+            ..Self::market(&position.pair, position.side.opposite(), quantity)
         }
     }
 }
 
-/// Confirmation of a filled order returned by the exchange.
+/// Confirmation of a filled order returned by the exchange. Describes the
+/// order's execution so far: a partly filled order reports again with a
+/// larger quantity as it fills, and the trade ledger books the difference.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fill {
     pub order_id: String,
+    /// The exchange's own ID for the order; `None` where it assigns none
+    /// (paper and dry-run fills).
+    #[serde(default)]
+    pub exchange_order_id: Option<String>,
     pub pair: String,
     pub side: OrderSide,
-    pub fill_price: f64,
-    pub quantity: f64,
+    /// Average price of everything executed so far.
+    pub fill_price: Decimal,
+    /// Cumulative executed quantity.
+    pub quantity: Decimal,
+    /// Commission paid so far, in the quote asset. Zero when the exchange doesn't
+    /// report it, or charges it in a third asset (e.g. BNB).
+    #[serde(default)]
+    pub fee: Decimal,
     pub timestamp: DateTime<Utc>,
 }
 
 /// Signal emitted by a strategy, passed to the Risk Manager.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Signal {
-    Buy { pair: String, quantity: f64 },
-    Sell { pair: String, quantity: f64 },
+pub struct Signal {
+    pub pair: String,
+    pub side: OrderSide,
+    pub quantity: Decimal,
+    /// Size the order in quote asset instead (e.g. 50 USDT worth); `quantity`
+    /// is then ignored. Market orders only.
+    #[serde(default)]
+    pub quote_quantity: Option<Decimal>,
+    /// Name of the strategy that emitted the signal, if any.
+    #[serde(default)]
+    pub strategy: Option<String>,
+    /// Strategy-specific risk parameters that replace the global ones for
+    /// this signal and the position it opens.
+    #[serde(default)]
+    pub risk: Option<crate::RiskOverrides>,
+    /// Limit price for the resulting order; `None` submits a market order.
+    #[serde(default)]
+    pub limit_price: Option<Decimal>,
 }
 
 impl Signal {
-    pub fn pair(&self) -> &str {
-        match self {
-            Signal::Buy { pair, .. } | Signal::Sell { pair, .. } => pair,
+    pub fn buy(pair: impl Into<String>, quantity: Decimal) -> Self {
+        Self::new(pair, OrderSide::Buy, quantity)
+    }
+
+    pub fn sell(pair: impl Into<String>, quantity: Decimal) -> Self {
+        Self::new(pair, OrderSide::Sell, quantity)
+    }
+
+    pub fn new(pair: impl Into<String>, side: OrderSide, quantity: Decimal) -> Self {
+        Self {
+            pair: pair.into(),
+            side,
+            quantity,
+            quote_quantity: None,
+            strategy: None,
+            risk: None,
+            limit_price: None,
         }
     }
 
-    pub fn quantity(&self) -> f64 {
-        match self {
-            Signal::Buy { quantity, .. } | Signal::Sell { quantity, .. } => *quantity,
+    /// Market signal for `quote_quantity` worth of the quote asset.
+    pub fn spend(pair: impl Into<String>, side: OrderSide, quote_quantity: Decimal) -> Self {
+        Self {
+            quote_quantity: Some(quote_quantity),
+            ..Self::new(pair, side, Decimal::ZERO)
         }
     }
 
+    pub fn pair(&self) -> &str {
+        &self.pair
+    }
+
+    pub fn quantity(&self) -> Decimal {
+        self.quantity
+    }
+
     pub fn side(&self) -> OrderSide {
-        match self {
-            Signal::Buy { .. } => OrderSide::Buy,
-            Signal::Sell { .. } => OrderSide::Sell,
-        }
+        self.side
     }
 }
 
@@ -104,19 +410,40 @@ pub struct Position {
     pub id: String,
     pub pair: String,
     pub side: OrderSide,
-    pub entry_price: f64,
-    pub quantity: f64,
+    pub entry_price: Decimal,
+    pub quantity: Decimal,
     pub mode: TradingMode,
     pub opened_at: DateTime<Utc>,
 }
 
+/// Holdings of one asset on the exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Balance {
+    pub asset: String,
+    /// Available for new orders.
+    pub free: Decimal,
+    /// Held by open orders.
+    pub locked: Decimal,
+}
+
+impl Balance {
+    pub fn total(&self) -> Decimal {
+        self.free + self.locked
+    }
+}
+
 /// Whether the bot is running against the real exchange or simulating.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[serde(rename_all = "lowercase")]
 #[sqlx(type_name = "TEXT", rename_all = "lowercase")]
 pub enum TradingMode {
     Live,
     Paper,
+    /// Orders are validated by the real exchange (`/api/v3/order/test`) but
+    /// never executed.
+    #[serde(rename = "live-dryrun")]
+    #[sqlx(rename = "live-dryrun")]
+    LiveDryrun,
 }
 
 impl std::fmt::Display for TradingMode {
@@ -124,6 +451,7 @@ impl std::fmt::Display for TradingMode {
         match self {
             TradingMode::Live => write!(f, "live"),
             TradingMode::Paper => write!(f, "paper"),
+            TradingMode::LiveDryrun => write!(f, "live-dryrun"),
         }
     }
 }
@@ -135,9 +463,38 @@ pub enum RejectionReason {
     StopLossProximity,
     HardCeilingReached,
     DrawdownHalt,
+    CooldownActive,
+    CorrelatedExposureExceeded,
+    EntriesPaused,
+    ConflictingSignal,
+    RateLimited,
+    VarLimitExceeded,
+    PositionLimitReached,
+    SpreadTooWide,
     Other(String),
 }
 
+impl RejectionReason {
+    /// Short snake_case code, e.g. for metric labels.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RejectionReason::ExposureLimitExceeded => "exposure_limit_exceeded",
+            RejectionReason::StopLossProximity => "stop_loss_proximity",
+            RejectionReason::HardCeilingReached => "hard_ceiling_reached",
+            RejectionReason::DrawdownHalt => "drawdown_halt",
+            RejectionReason::CooldownActive => "cooldown_active",
+            RejectionReason::CorrelatedExposureExceeded => "correlated_exposure_exceeded",
+            RejectionReason::EntriesPaused => "entries_paused",
+            RejectionReason::ConflictingSignal => "conflicting_signal",
+            RejectionReason::RateLimited => "rate_limited",
+            RejectionReason::VarLimitExceeded => "var_limit_exceeded",
+            RejectionReason::PositionLimitReached => "position_limit_reached",
+            RejectionReason::SpreadTooWide => "spread_too_wide",
+            RejectionReason::Other(_) => "other",
+        }
+    }
+}
+
 impl std::fmt::Display for RejectionReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -145,6 +502,20 @@ impl std::fmt::Display for RejectionReason {
             RejectionReason::StopLossProximity => write!(f, "stop-loss proximity"),
             RejectionReason::HardCeilingReached => write!(f, "hard order ceiling reached"),
             RejectionReason::DrawdownHalt => write!(f, "max drawdown halt active"),
+            RejectionReason::CooldownActive => write!(f, "stop-loss cooldown active"),
+            RejectionReason::CorrelatedExposureExceeded => {
+                write!(f, "correlated exposure limit exceeded")
+            }
+            RejectionReason::EntriesPaused => write!(f, "new entries paused"),
+            RejectionReason::ConflictingSignal => write!(f, "conflicting signal on pair"),
+            RejectionReason::RateLimited => write!(f, "per-pair order rate limit reached"),
+            RejectionReason::VarLimitExceeded => {
+                write!(f, "portfolio value-at-risk limit exceeded")
+            }
+            RejectionReason::PositionLimitReached => {
+                write!(f, "configured max open positions reached")
+            }
+            RejectionReason::SpreadTooWide => write!(f, "bid/ask spread too wide"),
             RejectionReason::Other(s) => write!(f, "{s}"),
         }
     }
@@ -173,17 +544,197 @@ impl std::fmt::Display for EngineState {
 }
 
 /// Commands sent to the engine via the command channel.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum EngineCommand {
     Start,
-    Stop,
+    /// Close every open position, then stop the market streams. The reply
+    /// says how the closes went once the engine has stopped.
+    Stop {
+        reply: tokio::sync::oneshot::Sender<StopOutcome>,
+    },
     Pause,
     Resume,
     ResetDrawdown,
+    /// Emergency kill-switch: close every open position, then pause entries
+    /// until `Resume`.
+    Flatten,
+    /// Pause entries and have the order executor cancel every resting
+    /// order, leaving streams, positions and exit brackets alone.
+    Halt {
+        reply: tokio::sync::oneshot::Sender<HaltOutcome>,
+    },
+    /// Start streaming market data for an additional pair at the engine's
+    /// default interval. No-op if the pair is already streamed.
+    AddPair(String),
+    /// Start streaming market data for an additional pair, or switch an
+    /// already-streamed pair to a different interval.
+    SubscribePair(String, KlineInterval),
+    /// Stop streaming market data for a pair.
+    RemovePair(String),
+}
+
+/// What became of the open positions when the engine stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// This many positions were closed and their fills reported.
+    Closed(usize),
+    /// Closes were submitted but had not all filled when the wait ran out.
+    TimedOut,
+    /// A close order failed; its position is still open.
+    CloseFailed(String),
+    /// The risk manager could not be asked; positions were left open.
+    LeftOpen,
+}
+
+/// What an engine `Halt` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HaltOutcome {
+    /// Engine state afterwards: `Paused` unless it was not running.
+    pub state: EngineState,
+    /// Resting orders cancelled and orders that failed to cancel, or `None`
+    /// if the order executor did not answer.
+    pub orders: Option<(usize, usize)>,
+}
+
+/// Commands sent to the Risk Manager via its control channel.
+#[derive(Debug)]
+pub enum RiskCommand {
+    /// Reply with the currently effective risk configuration.
+    GetConfig {
+        reply: tokio::sync::oneshot::Sender<crate::RiskConfig>,
+    },
+    /// Apply a partial update (JSON object of field → value). The reply carries
+    /// the new effective config, or the validation error.
+    UpdateConfig {
+        patch: serde_json::Value,
+        /// Who requested the change, for the audit trail (e.g. "api", "telegram:123").
+        actor: String,
+        reply: tokio::sync::oneshot::Sender<Result<crate::RiskConfig, String>>,
+    },
+    /// Submit market closes for every open position immediately.
+    Flatten,
+    /// Close every open position and reply with the number closed once all
+    /// of their fills have been reported, or with the error of the first
+    /// close that failed. Used for a graceful stop.
+    CloseAll {
+        reply: tokio::sync::oneshot::Sender<Result<usize, String>>,
+    },
+    /// Submit market closes for the open positions on `pair`, or on every
+    /// pair if `None`, and reply with the number of closes submitted. Unlike
+    /// `Flatten`, entries are not paused.
+    ClosePositions {
+        pair: Option<String>,
+        reply: tokio::sync::oneshot::Sender<usize>,
+    },
+    /// The drawdown halt was cleared by an operator: restart peak tracking
+    /// from the current portfolio value.
+    ResetDrawdown,
+    /// Report of an executed order, sent back by the executor, with what
+    /// the trade ledger booked for it. Realized PnL is booked from the
+    /// trades; the positions keep the shared open positions in step with
+    /// the ledger.
+    OrderFilled {
+        order: Box<Order>,
+        fill: Fill,
+        trades: Vec<ClosedTrade>,
+        /// The position the fill opened, if any.
+        opened: Option<Box<Position>>,
+        /// Positions the fill closed part of, with the quantity still open
+        /// (zero once closed entirely).
+        reduced: Vec<Position>,
+    },
+    /// Report of an order that failed or ended without executing, sent back
+    /// by the executor.
+    OrderFailed { order: Box<Order>, error: String },
+    /// Reply with the current portfolio VaR in USD, or `None` while there is
+    /// not enough price history to estimate it.
+    GetValueAtRisk {
+        reply: tokio::sync::oneshot::Sender<Option<f64>>,
+    },
+    /// Reply with portfolio equity marked to the latest prices.
+    GetEquity {
+        reply: tokio::sync::oneshot::Sender<EquitySnapshot>,
+    },
+    /// Reply with the latest price of every pair seen on the market stream.
+    GetPrices {
+        reply: tokio::sync::oneshot::Sender<std::collections::HashMap<String, f64>>,
+    },
+    /// Run an operator's signal through the risk checks. The reply carries
+    /// the order forwarded to the executor, or why the signal was rejected.
+    SubmitSignal {
+        signal: Box<Signal>,
+        reply: tokio::sync::oneshot::Sender<Result<Order, RejectionReason>>,
+    },
+}
+
+/// Control messages into the order executor.
+#[derive(Debug)]
+pub enum ExecutorCommand {
+    /// Cancel every order resting on the exchange. Exit brackets are left in
+    /// place. The reply carries the number cancelled and the number whose
+    /// cancellation failed.
+    CancelResting {
+        reply: tokio::sync::oneshot::Sender<(usize, usize)>,
+    },
+    /// Trade through the client registered for `mode` from now on. Refused
+    /// while orders or exit brackets rest on the current exchange.
+    SwitchMode {
+        mode: TradingMode,
+        reply: tokio::sync::oneshot::Sender<Result<(), String>>,
+    },
+}
+
+/// Portfolio equity at a point in time, marked to the latest prices.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EquitySnapshot {
+    /// Starting balance plus realized PnL.
+    pub realized_usd: f64,
+    /// PnL of open positions at the latest prices; positions on pairs
+    /// without a price yet count as zero.
+    pub unrealized_usd: f64,
+    pub equity_usd: f64,
+    /// How far equity is below the peak portfolio value, as a fraction.
+    pub drawdown_pct: f64,
+}
+
+/// Control messages into the Strategy Registry.
+#[derive(Debug)]
+pub enum StrategyCommand {
+    /// Reply with the status of every registered strategy.
+    List {
+        reply: tokio::sync::oneshot::Sender<Vec<StrategyStatus>>,
+    },
+    /// Apply a partial update (`enabled`, `quantity`, `quote_quantity`,
+    /// `params`) to the named strategy. The reply carries its new status
+    /// (`None` if no strategy has that name), or the validation error.
+    Update {
+        name: String,
+        patch: serde_json::Value,
+        /// Who requested the change, for the audit trail.
+        actor: String,
+        reply: tokio::sync::oneshot::Sender<Result<Option<StrategyStatus>, String>>,
+    },
+}
+
+/// A registered strategy as reported by the Strategy Registry.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StrategyStatus {
+    pub name: String,
+    pub pair: String,
+    #[serde(rename = "type")]
+    pub strategy_type: String,
+    #[schema(value_type = Object)]
+    pub params: serde_json::Value,
+    pub enabled: bool,
+    pub quantity: Decimal,
+    pub quote_quantity: Option<Decimal>,
+    pub last_signal: Option<OrderSide>,
+    pub last_signal_at: Option<DateTime<Utc>>,
 }
 
 /// Events emitted by the Risk Manager.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum RiskEvent {
     OrderRejected {
         signal: Signal,
@@ -200,9 +751,478 @@ pub enum RiskEvent {
     OrderFailed {
         pair: String,
         error: String,
+        /// Submission attempts made before giving up.
+        attempts: u32,
     },
     DrawdownHaltEntered {
         drawdown_pct: f64,
     },
     DrawdownHaltExited,
+    LossStreakHaltEntered {
+        consecutive_losses: u32,
+    },
+    ConfigUpdated {
+        actor: String,
+        changes: String,
+    },
+    PositionsFlattened {
+        count: usize,
+    },
+    BreakEvenStopSet {
+        pair: String,
+        stop_price: f64,
+    },
+    PartialTakeProfit {
+        pair: String,
+        close_price: f64,
+        closed_quantity: f64,
+        remaining_quantity: f64,
+    },
+    OrderStatusChanged {
+        pair: String,
+        order_id: String,
+        status: OrderStatus,
+        filled_quantity: f64,
+    },
+    FillDeviationExceeded {
+        pair: String,
+        reference_price: f64,
+        fill_price: f64,
+        deviation_bps: f64,
+        /// Whether new entries on the pair were paused as a result.
+        pair_paused: bool,
+    },
+    /// No market data arrived for a pair within the staleness window; the
+    /// stream is being reconnected.
+    MarketDataStale {
+        pair: String,
+        silent_secs: u64,
+    },
+    /// Position audit found local and exchange holdings disagreeing.
+    PositionMismatch {
+        pair: String,
+        local_quantity: f64,
+        exchange_quantity: f64,
+        /// Whether the local store was corrected to match the exchange.
+        reconciled: bool,
+    },
+    /// The trade ledger booked a closed trade.
+    TradeClosed {
+        trade: ClosedTrade,
+    },
+    /// A scheduled database backup could not be written.
+    BackupFailed {
+        error: String,
+    },
+    /// The watchdog saw no market event on any pair for this long while the
+    /// engine was running.
+    NoMarketData {
+        silent_secs: u64,
+    },
+    /// The watchdog couldn't reach the exchange REST API for this long.
+    ExchangeUnreachable {
+        error: String,
+        down_secs: u64,
+    },
+    /// A watchdog check that alarmed (`market_data` or `exchange_rest`) is
+    /// passing again.
+    WatchdogRecovered {
+        check: String,
+    },
+    /// An order filled on the exchange but the trade ledger couldn't book
+    /// it, so positions and PnL no longer match the exchange.
+    FillNotBooked {
+        pair: String,
+        order_id: String,
+        error: String,
+    },
+    /// A spawned task panicked. Core subsystems are restarted; others stay
+    /// down until the bot restarts.
+    TaskPanicked {
+        task: String,
+        error: String,
+        restarted: bool,
+    },
+}
+
+impl RiskEvent {
+    /// Stable snake_case identifier used for persistence and filtering.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RiskEvent::OrderRejected { .. } => "order_rejected",
+            RiskEvent::StopLossTriggered { .. } => "stop_loss_triggered",
+            RiskEvent::TakeProfitTriggered { .. } => "take_profit_triggered",
+            RiskEvent::OrderFailed { .. } => "order_failed",
+            RiskEvent::DrawdownHaltEntered { .. } => "drawdown_halt_entered",
+            RiskEvent::DrawdownHaltExited => "drawdown_halt_exited",
+            RiskEvent::LossStreakHaltEntered { .. } => "loss_streak_halt_entered",
+            RiskEvent::ConfigUpdated { .. } => "config_updated",
+            RiskEvent::PositionsFlattened { .. } => "positions_flattened",
+            RiskEvent::BreakEvenStopSet { .. } => "break_even_stop_set",
+            RiskEvent::PartialTakeProfit { .. } => "partial_take_profit",
+            RiskEvent::FillDeviationExceeded { .. } => "fill_deviation_exceeded",
+            RiskEvent::OrderStatusChanged { .. } => "order_status_changed",
+            RiskEvent::MarketDataStale { .. } => "market_data_stale",
+            RiskEvent::PositionMismatch { .. } => "position_mismatch",
+            RiskEvent::TradeClosed { .. } => "trade_closed",
+            RiskEvent::BackupFailed { .. } => "backup_failed",
+            RiskEvent::NoMarketData { .. } => "no_market_data",
+            RiskEvent::ExchangeUnreachable { .. } => "exchange_unreachable",
+            RiskEvent::WatchdogRecovered { .. } => "watchdog_recovered",
+            RiskEvent::FillNotBooked { .. } => "fill_not_booked",
+            RiskEvent::TaskPanicked { .. } => "task_panicked",
+        }
+    }
+
+    /// The pair this event concerns, if it is pair-specific.
+    pub fn pair(&self) -> Option<&str> {
+        match self {
+            RiskEvent::OrderRejected { signal, .. } => Some(signal.pair()),
+            RiskEvent::StopLossTriggered { pair, .. }
+            | RiskEvent::TakeProfitTriggered { pair, .. }
+            | RiskEvent::OrderFailed { pair, .. }
+            | RiskEvent::BreakEvenStopSet { pair, .. }
+            | RiskEvent::PartialTakeProfit { pair, .. }
+            | RiskEvent::FillNotBooked { pair, .. }
+            | RiskEvent::FillDeviationExceeded { pair, .. }
+            | RiskEvent::OrderStatusChanged { pair, .. }
+            | RiskEvent::MarketDataStale { pair, .. }
+            | RiskEvent::PositionMismatch { pair, .. } => Some(pair),
+            RiskEvent::TradeClosed { trade } => Some(&trade.pair),
+            RiskEvent::DrawdownHaltEntered { .. }
+            | RiskEvent::DrawdownHaltExited
+            | RiskEvent::LossStreakHaltEntered { .. }
+            | RiskEvent::ConfigUpdated { .. }
+            | RiskEvent::PositionsFlattened { .. }
+            | RiskEvent::BackupFailed { .. }
+            | RiskEvent::NoMarketData { .. }
+            | RiskEvent::ExchangeUnreachable { .. }
+            | RiskEvent::WatchdogRecovered { .. }
+            | RiskEvent::TaskPanicked { .. } => None,
+        }
+    }
+
+    /// How urgently an operator should look at this event.
+    pub fn severity(&self) -> AlertSeverity {
+        match self {
+            RiskEvent::OrderFailed { .. }
+            | RiskEvent::DrawdownHaltEntered { .. }
+            | RiskEvent::LossStreakHaltEntered { .. }
+            | RiskEvent::PositionsFlattened { .. }
+            | RiskEvent::BackupFailed { .. }
+            | RiskEvent::NoMarketData { .. }
+            | RiskEvent::ExchangeUnreachable { .. }
+            | RiskEvent::TaskPanicked { .. }
+            | RiskEvent::FillNotBooked { .. }
+            | RiskEvent::PositionMismatch {
+                reconciled: false, ..
+            } => AlertSeverity::Critical,
+            RiskEvent::OrderRejected { .. }
+            | RiskEvent::StopLossTriggered { .. }
+            | RiskEvent::FillDeviationExceeded { .. }
+            | RiskEvent::MarketDataStale { .. }
+            | RiskEvent::PositionMismatch { .. } => AlertSeverity::Warning,
+            RiskEvent::TakeProfitTriggered { .. }
+            | RiskEvent::PartialTakeProfit { .. }
+            | RiskEvent::DrawdownHaltExited
+            | RiskEvent::ConfigUpdated { .. }
+            | RiskEvent::BreakEvenStopSet { .. }
+            | RiskEvent::OrderStatusChanged { .. }
+            | RiskEvent::TradeClosed { .. }
+            | RiskEvent::WatchdogRecovered { .. } => AlertSeverity::Info,
+        }
+    }
+
+    /// Which Telegram alert subscription the event belongs to.
+    pub fn category(&self) -> AlertCategory {
+        match self {
+            RiskEvent::StopLossTriggered { .. }
+            | RiskEvent::TakeProfitTriggered { .. }
+            | RiskEvent::PartialTakeProfit { .. }
+            | RiskEvent::BreakEvenStopSet { .. }
+            | RiskEvent::PositionsFlattened { .. }
+            | RiskEvent::OrderStatusChanged { .. }
+            | RiskEvent::TradeClosed { .. } => AlertCategory::Trades,
+            RiskEvent::OrderRejected { .. } => AlertCategory::Rejections,
+            RiskEvent::DrawdownHaltEntered { .. }
+            | RiskEvent::DrawdownHaltExited
+            | RiskEvent::LossStreakHaltEntered { .. }
+            | RiskEvent::ConfigUpdated { .. } => AlertCategory::Halts,
+            RiskEvent::OrderFailed { .. }
+            | RiskEvent::FillDeviationExceeded { .. }
+            | RiskEvent::MarketDataStale { .. }
+            | RiskEvent::PositionMismatch { .. }
+            | RiskEvent::BackupFailed { .. }
+            | RiskEvent::NoMarketData { .. }
+            | RiskEvent::ExchangeUnreachable { .. }
+            | RiskEvent::WatchdogRecovered { .. }
+            | RiskEvent::TaskPanicked { .. }
+            | RiskEvent::FillNotBooked { .. } => AlertCategory::Errors,
+        }
+    }
+
+    /// Human-readable alert text, as sent to Telegram and the dashboard.
+    pub fn message(&self) -> String {
+        match self {
+            RiskEvent::StopLossTriggered { pair, close_price } => {
+                format!("⚠️ Stop-loss triggered on {pair}. Position closed at {close_price:.4}.")
+            }
+            RiskEvent::TakeProfitTriggered { pair, close_price } => {
+                format!("✅ Take-profit triggered on {pair}. Position closed at {close_price:.4}.")
+            }
+            RiskEvent::OrderFailed {
+                pair,
+                error,
+                attempts,
+            } => {
+                format!("🚨 Order failed on {pair} after {attempts} attempt(s): {error}")
+            }
+            RiskEvent::DrawdownHaltEntered { drawdown_pct } => {
+                format!("🛑 Max drawdown breached ({:.1}%). Engine halted. Use /reset-drawdown to resume.", drawdown_pct * 100.0)
+            }
+            RiskEvent::LossStreakHaltEntered { consecutive_losses } => {
+                format!("🛑 {consecutive_losses} losing trades in a row. Engine halted. Use /reset-drawdown to resume.")
+            }
+            RiskEvent::DrawdownHaltExited => {
+                "✅ Drawdown halt cleared. Engine resuming.".to_string()
+            }
+            RiskEvent::OrderRejected { signal, reason } => {
+                format!("⛔ Order rejected on {}: {reason}", signal.pair())
+            }
+            RiskEvent::ConfigUpdated { actor, changes } => {
+                format!("🔧 Risk config updated by {actor}: {changes}")
+            }
+            RiskEvent::BreakEvenStopSet { pair, stop_price } => {
+                format!("🔒 Stop on {pair} moved to break-even at {stop_price:.4}.")
+            }
+            RiskEvent::PartialTakeProfit {
+                pair,
+                close_price,
+                closed_quantity,
+                remaining_quantity,
+            } => {
+                format!(
+                    "✅ Partial take-profit on {pair}: closed {closed_quantity} at {close_price:.4}, {remaining_quantity} remaining."
+                )
+            }
+            RiskEvent::FillDeviationExceeded {
+                pair,
+                reference_price,
+                fill_price,
+                deviation_bps,
+                pair_paused,
+            } => {
+                let paused = if *pair_paused {
+                    " New entries on the pair paused."
+                } else {
+                    ""
+                };
+                format!(
+                    "⚠️ Fill deviation on {pair}: filled at {fill_price:.4} vs {reference_price:.4} ({deviation_bps:.0} bps).{paused}"
+                )
+            }
+            RiskEvent::OrderStatusChanged {
+                pair,
+                order_id,
+                status,
+                filled_quantity,
+            } => {
+                format!("📋 Order {order_id} on {pair} {status} ({filled_quantity} filled).")
+            }
+            RiskEvent::MarketDataStale { pair, silent_secs } => {
+                format!("📡 No market data for {pair} in {silent_secs}s. Reconnecting stream.")
+            }
+            RiskEvent::PositionMismatch {
+                pair,
+                local_quantity,
+                exchange_quantity,
+                reconciled,
+            } => {
+                let action = if *reconciled {
+                    "Local record corrected."
+                } else {
+                    "Check manually."
+                };
+                format!(
+                    "⚠️ Position mismatch on {pair}: local {local_quantity}, exchange {exchange_quantity}. {action}"
+                )
+            }
+            RiskEvent::PositionsFlattened { count } => {
+                format!("🚨 Kill-switch: closing {count} open position(s). Entries paused until /resume.")
+            }
+            RiskEvent::BackupFailed { error } => {
+                format!("🚨 Database backup failed: {error}")
+            }
+            RiskEvent::NoMarketData { silent_secs } => {
+                format!(
+                    "🚨 No market data on any pair for {} min while running. Check the stream and network.",
+                    silent_secs / 60
+                )
+            }
+            RiskEvent::ExchangeUnreachable { error, down_secs } => {
+                format!(
+                    "🚨 Exchange REST API unreachable for {} min: {error}",
+                    down_secs / 60
+                )
+            }
+            RiskEvent::WatchdogRecovered { check } => {
+                let what = match check.as_str() {
+                    "market_data" => "Market data is flowing",
+                    "exchange_rest" => "Exchange REST API is reachable",
+                    other => other,
+                };
+                format!("✅ {what} again.")
+            }
+            RiskEvent::FillNotBooked {
+                pair,
+                order_id,
+                error,
+            } => {
+                format!("🚨 {pair} order {order_id} filled but could not be booked: {error}. Positions and PnL no longer match the exchange; check the database.")
+            }
+            RiskEvent::TaskPanicked {
+                task,
+                error,
+                restarted: true,
+            } => {
+                format!("🚨 {task} crashed and was restarted: {error}")
+            }
+            RiskEvent::TaskPanicked {
+                task,
+                error,
+                restarted: false,
+            } => {
+                format!("🚨 {task} crashed and is not running: {error}. Restart the bot.")
+            }
+            RiskEvent::TradeClosed { trade } => {
+                format!(
+                    "💰 Trade closed on {}: {} {} @ {:.4} → {:.4}, PnL {:+.2} USD after {:.2} fees.",
+                    trade.pair,
+                    trade.side,
+                    trade.quantity,
+                    trade.entry_price,
+                    trade.exit_price,
+                    trade.net_pnl_usd(),
+                    trade.fee_usd
+                )
+            }
+        }
+    }
+}
+
+/// Severity of an operator alert, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "info" => Some(AlertSeverity::Info),
+            "warning" => Some(AlertSeverity::Warning),
+            "critical" => Some(AlertSeverity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// What an alert is about, for per-chat Telegram subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCategory {
+    /// Exits and other position changes.
+    Trades,
+    Rejections,
+    /// Halts and their clearing, and risk config changes.
+    Halts,
+    /// Failed orders, bad fills, stale data, position mismatches.
+    Errors,
+    /// The daily summary.
+    Summaries,
+}
+
+impl AlertCategory {
+    pub const ALL: [AlertCategory; 5] = [
+        AlertCategory::Trades,
+        AlertCategory::Rejections,
+        AlertCategory::Halts,
+        AlertCategory::Errors,
+        AlertCategory::Summaries,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertCategory::Trades => "trades",
+            AlertCategory::Rejections => "rejections",
+            AlertCategory::Halts => "halts",
+            AlertCategory::Errors => "errors",
+            AlertCategory::Summaries => "summaries",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+}
+
+/// A risk event as shown to operators: its severity and alert text.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Alert {
+    pub kind: String,
+    pub pair: Option<String>,
+    pub severity: AlertSeverity,
+    pub category: AlertCategory,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&RiskEvent> for Alert {
+    fn from(event: &RiskEvent) -> Self {
+        Self {
+            kind: event.kind().to_string(),
+            pair: event.pair().map(str::to_string),
+            severity: event.severity(),
+            category: event.category(),
+            message: event.message(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Real-time update pushed to dashboard clients over `/ws/stream`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum DashboardEvent {
+    /// A fill was booked; `realized_pnl` is non-zero when it closed positions.
+    Trade {
+        fill: Fill,
+        realized_pnl: Decimal,
+    },
+    Market(MarketEvent),
+    Risk(RiskEvent),
+    Alert(Alert),
+}
+
+impl DashboardEvent {
+    /// Channel clients subscribe to for this event.
+    pub fn channel(&self) -> String {
+        match self {
+            DashboardEvent::Trade { .. } => "trades".to_string(),
+            DashboardEvent::Market(event) => format!("market:{}", event.pair),
+            DashboardEvent::Risk(_) => "risk_events".to_string(),
+            DashboardEvent::Alert(_) => "alerts".to_string(),
+        }
+    }
 }
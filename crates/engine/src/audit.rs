@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use common::{decimal, ExchangeClient, OrderSide, Result, RiskEvent, TradingMode, DEFAULT_ACCOUNT};
+
+/// Relative quantity difference tolerated before local and exchange holdings
+/// count as mismatched (covers commission taken in the base asset).
+const QUANTITY_TOLERANCE: f64 = 0.01;
+
+/// Reconciles the exchange's open positions with the local `positions` table
+/// for the traded pairs:
+/// - held on the exchange but not recorded locally: recorded locally
+/// - recorded locally but gone from the exchange: local rows removed
+/// - held on both with different quantities: alert only
+///
+/// Every discrepancy is logged and raised as `RiskEvent::PositionMismatch`.
+pub struct PositionAuditor {
+    exchange: Arc<dyn ExchangeClient>,
+    db: SqlitePool,
+    mode: TradingMode,
+    account: String,
+    pairs: Vec<String>,
+    risk_event_tx: mpsc::Sender<RiskEvent>,
+}
+
+impl PositionAuditor {
+    pub fn new(
+        exchange: Arc<dyn ExchangeClient>,
+        db: SqlitePool,
+        mode: TradingMode,
+        pairs: Vec<String>,
+        risk_event_tx: mpsc::Sender<RiskEvent>,
+    ) -> Self {
+        Self {
+            exchange,
+            db,
+            mode,
+            account: DEFAULT_ACCOUNT.to_string(),
+            pairs,
+            risk_event_tx,
+        }
+    }
+
+    /// Audit `account`'s positions instead of the default account's.
+    pub fn set_account(&mut self, account: &str) {
+        self.account = account.to_string();
+    }
+
+    /// Run one audit. Returns the number of mismatched pairs.
+    pub async fn run(&self) -> Result<usize> {
+        let mut exchange_qty: HashMap<String, f64> = HashMap::new();
+        for position in self.exchange.open_positions().await? {
+            *exchange_qty.entry(position.pair).or_default() += decimal::to_f64(position.quantity);
+        }
+
+        let mode = self.mode.to_string();
+        let rows = sqlx::query!(
+            "SELECT pair, quantity FROM positions WHERE mode = ?1 AND account_id = ?2",
+            mode,
+            self.account,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        let mut local_qty: HashMap<String, f64> = HashMap::new();
+        for row in rows {
+            *local_qty.entry(row.pair).or_default() += row.quantity;
+        }
+
+        let mut mismatches = 0;
+        for pair in &self.pairs {
+            let local = local_qty.get(pair).copied().unwrap_or(0.0);
+            let exchange = exchange_qty.get(pair).copied().unwrap_or(0.0);
+            if quantities_match(local, exchange) {
+                continue;
+            }
+            mismatches += 1;
+
+            let reconciled = if local <= 0.0 {
+                self.record_orphan(pair, exchange).await?;
+                true
+            } else if exchange <= 0.0 {
+                sqlx::query!(
+                    "DELETE FROM positions WHERE pair = ?1 AND mode = ?2 AND account_id = ?3",
+                    pair,
+                    mode,
+                    self.account,
+                )
+                .execute(&self.db)
+                .await?;
+                true
+            } else {
+                false
+            };
+
+            warn!(
+                pair = %pair,
+                local_quantity = local,
+                exchange_quantity = exchange,
+                reconciled,
+                "Position audit mismatch"
+            );
+            let _ = self
+                .risk_event_tx
+                .send(RiskEvent::PositionMismatch {
+                    pair: pair.clone(),
+                    local_quantity: local,
+                    exchange_quantity: exchange,
+                    reconciled,
+                })
+                .await;
+        }
+
+        if mismatches == 0 {
+            info!(pairs = self.pairs.len(), "Position audit clean");
+        }
+        Ok(mismatches)
+    }
+
+    /// Record a position found on the exchange with no local row. The entry
+    /// price is unknown, so the current price stands in for it.
+    async fn record_orphan(&self, pair: &str, quantity: f64) -> Result<()> {
+        let entry_price = self
+            .exchange
+            .current_price(pair)
+            .await
+            .map(decimal::to_f64)
+            .unwrap_or(0.0);
+        let id = uuid::Uuid::new_v4().to_string();
+        let side = OrderSide::Buy.to_string();
+        let mode = self.mode.to_string();
+        let opened_at = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO positions (id, pair, side, entry_price, quantity, mode, opened_at,
+                                   account_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            id,
+            pair,
+            side,
+            entry_price,
+            quantity,
+            mode,
+            opened_at,
+            self.account,
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+fn quantities_match(local: f64, exchange: f64) -> bool {
+    let scale = local.abs().max(exchange.abs());
+    scale <= 1e-9 || (local - exchange).abs() <= scale * QUANTITY_TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantity_tolerance() {
+        assert!(quantities_match(0.0, 0.0));
+        assert!(quantities_match(1.0, 0.995));
+        assert!(!quantities_match(1.0, 0.9));
+        assert!(!quantities_match(0.0, 0.5));
+    }
+}
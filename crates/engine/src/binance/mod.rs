@@ -1,5 +1,6 @@
 mod rest;
 mod stream;
+mod weight;
 
-pub use rest::BinanceClient;
+pub use rest::{ApiPermissions, BinanceClient};
 pub use stream::BinanceStream;
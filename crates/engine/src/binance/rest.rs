@@ -1,14 +1,22 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use chrono::Utc;
 use hmac::{Hmac, Mac};
 use reqwest::Client;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use sha2::Sha256;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
-use common::{Error, ExchangeClient, Fill, Order, OrderSide, Position, Result, TradingMode};
+use super::weight::{WeightBudget, DEFAULT_WEIGHT_LIMIT};
+use crate::symbol_filters::{SymbolFilterMap, SymbolFilters};
+
+use common::{
+    Balance, BracketLegs, BracketOrder, Error, ExchangeClient, Fill, Order, OrderSide, OrderStatus,
+    OrderStatusReport, OrderTrigger, Position, ReadinessProbe, Result, Symbol, TradingMode,
+};
 
 const BASE_URL: &str = "https://api.binance.com";
 
@@ -17,6 +25,10 @@ pub struct BinanceClient {
     api_key: String,
     secret: String,
     http: Client,
+    /// Request-weight budget shared by every call on this client.
+    budget: Mutex<WeightBudget>,
+    /// Send orders to `/api/v3/order/test` instead of executing them.
+    dry_run: bool,
 }
 
 impl BinanceClient {
@@ -28,9 +40,65 @@ impl BinanceClient {
                 .use_rustls_tls()
                 .build()
                 .expect("Failed to build HTTP client"),
+            budget: Mutex::new(WeightBudget::new(DEFAULT_WEIGHT_LIMIT)),
+            dry_run: false,
         }
     }
 
+    /// Validate orders against the real exchange without executing them:
+    /// each order is signed and sent to `/api/v3/order/test`, and reported
+    /// as filled once accepted.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Fetch LOT_SIZE, PRICE_FILTER, and notional filters for `pairs` from
+    /// `/api/v3/exchangeInfo`.
+    pub async fn symbol_filters(&self, pairs: &[String]) -> Result<SymbolFilterMap> {
+        let symbols = serde_json::to_string(pairs)?;
+        self.throttle().await;
+        let request = self
+            .http
+            .get(format!("{BASE_URL}/api/v3/exchangeInfo"))
+            .query(&[("symbols", symbols)]);
+        let body = self.dispatch(request).await?;
+        let info: ExchangeInfoResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+
+        let parse = |v: &Option<String>| v.as_deref().and_then(|s| s.parse::<Decimal>().ok());
+        Ok(info
+            .symbols
+            .into_iter()
+            .map(|s| {
+                let mut filters = SymbolFilters::default();
+                for f in &s.filters {
+                    match f.filter_type.as_str() {
+                        "LOT_SIZE" => {
+                            filters.step_size = parse(&f.step_size).unwrap_or_default();
+                            filters.min_qty = parse(&f.min_qty).unwrap_or_default();
+                        }
+                        "PRICE_FILTER" => {
+                            filters.tick_size = parse(&f.tick_size).unwrap_or_default()
+                        }
+                        "MIN_NOTIONAL" | "NOTIONAL" => {
+                            filters.min_notional = parse(&f.min_notional).unwrap_or_default()
+                        }
+                        _ => {}
+                    }
+                }
+                (s.symbol, filters)
+            })
+            .collect())
+    }
+
+    /// What the API key may do, from `/sapi/v1/account/apiRestrictions`.
+    pub async fn api_permissions(&self) -> Result<ApiPermissions> {
+        let body = self
+            .signed_get("/sapi/v1/account/apiRestrictions", "")
+            .await?;
+        serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))
+    }
+
     fn timestamp_ms() -> u64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -47,48 +115,96 @@ impl BinanceClient {
     }
 
     async fn signed_get(&self, path: &str, params: &str) -> Result<String> {
+        self.throttle().await;
         let ts = Self::timestamp_ms();
         let query = format!("{params}&timestamp={ts}");
         let signature = self.sign(&query);
         let url = format!("{BASE_URL}{path}?{query}&signature={signature}");
 
-        let resp = self
-            .http
-            .get(&url)
-            .header("X-MBX-APIKEY", &self.api_key)
-            .send()
-            .await
-            .map_err(|e| Error::Http(e.to_string()))?;
+        let request = self.http.get(&url).header("X-MBX-APIKEY", &self.api_key);
+        self.dispatch(request).await
+    }
 
-        let status = resp.status();
-        let body = resp.text().await.map_err(|e| Error::Http(e.to_string()))?;
+    async fn signed_post(&self, path: &str, params: &str) -> Result<String> {
+        self.signed_send(reqwest::Method::POST, path, params).await
+    }
 
-        if !status.is_success() {
-            return Err(Error::Exchange(format!("HTTP {status}: {body}")));
-        }
-        Ok(body)
+    async fn signed_delete(&self, path: &str, params: &str) -> Result<String> {
+        self.signed_send(reqwest::Method::DELETE, path, params)
+            .await
     }
 
-    async fn signed_post(&self, path: &str, params: &str) -> Result<String> {
+    /// Send a signed request with the parameters in a form-encoded body.
+    async fn signed_send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        params: &str,
+    ) -> Result<String> {
+        self.throttle().await;
         let ts = Self::timestamp_ms();
         let query = format!("{params}&timestamp={ts}");
         let signature = self.sign(&query);
         let body = format!("{query}&signature={signature}");
         let url = format!("{BASE_URL}{path}");
 
-        let resp = self
+        let request = self
             .http
-            .post(&url)
+            .request(method, &url)
             .header("X-MBX-APIKEY", &self.api_key)
             .header("Content-Type", "application/x-www-form-urlencoded")
-            .body(body)
+            .body(body);
+        self.dispatch(request).await
+    }
+
+    /// Wait out any back-off or near-exhausted weight budget. Call before
+    /// signing so the request timestamp is fresh when it is sent.
+    async fn throttle(&self) {
+        let delay = self.budget.lock().unwrap().delay(Self::timestamp_ms());
+        if let Some(delay) = delay {
+            warn!(
+                delay_ms = delay.as_millis() as u64,
+                "Throttling Binance REST request"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Send a request, update the weight budget from the response headers,
+    /// and map non-2xx responses to errors. 429/418 responses also record
+    /// their Retry-After so later requests back off.
+    async fn dispatch(&self, request: reqwest::RequestBuilder) -> Result<String> {
+        let resp = request
             .send()
             .await
             .map_err(|e| Error::Http(e.to_string()))?;
 
         let status = resp.status();
-        let text = resp.text().await.map_err(|e| Error::Http(e.to_string()))?;
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+        let used = header("X-MBX-USED-WEIGHT-1M");
+        let retry_after = header("Retry-After");
+        {
+            let now = Self::timestamp_ms();
+            let mut budget = self.budget.lock().unwrap();
+            if let Some(used) = used {
+                budget.record_used(used as u32, now);
+            }
+            if status.as_u16() == 429 || status.as_u16() == 418 {
+                let wait = Duration::from_secs(retry_after.unwrap_or(60));
+                warn!(status = %status, retry_after_secs = wait.as_secs(), "Binance rate limit hit");
+                budget.record_retry_after(wait, now);
+            }
+        }
 
+        let text = resp.text().await.map_err(|e| Error::Http(e.to_string()))?;
+        if status.is_server_error() {
+            return Err(Error::ExchangeUnavailable(format!("HTTP {status}: {text}")));
+        }
         if !status.is_success() {
             return Err(Error::Exchange(format!("HTTP {status}: {text}")));
         }
@@ -96,23 +212,109 @@ impl BinanceClient {
     }
 }
 
+#[async_trait]
+impl ReadinessProbe for BinanceClient {
+    fn name(&self) -> &'static str {
+        "binance_rest"
+    }
+
+    /// `GET /api/v3/ping`: unsigned, weight 1.
+    async fn check(&self) -> Result<()> {
+        self.throttle().await;
+        self.dispatch(self.http.get(format!("{BASE_URL}/api/v3/ping")))
+            .await
+            .map(|_| ())
+    }
+}
+
+impl BinanceClient {
+    /// Dry-run an order: Binance validates the symbol, filters and
+    /// signature, and a buy is checked against the free quote balance. The
+    /// order is reported as fully filled at its limit price, or at the
+    /// current price for market orders.
+    async fn test_order(&self, order: &Order, params: &str) -> Result<Fill> {
+        debug!(pair = %order.pair, side = %order.side, "Testing order against Binance");
+        self.signed_post("/api/v3/order/test", params).await?;
+
+        let fill_price = match order.price {
+            Some(price) => price,
+            None => self.current_price(&order.pair).await?,
+        };
+        // The test endpoint skips the balance check. Only buys can be
+        // checked: dry-run entries never acquire the base asset to sell.
+        if order.side == OrderSide::Buy {
+            self.check_quote_balance(order, fill_price).await?;
+        }
+
+        let quantity = order.base_quantity_at(fill_price);
+        info!(pair = %order.pair, side = %order.side, quantity = %quantity, "Dry-run order accepted by Binance");
+        Ok(Fill {
+            order_id: order.id.clone(),
+            exchange_order_id: None,
+            pair: order.pair.clone(),
+            side: order.side,
+            fill_price,
+            quantity,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Fail unless the account's free quote balance covers buying `order`
+    /// at `price`.
+    async fn check_quote_balance(&self, order: &Order, price: Decimal) -> Result<()> {
+        let Some(symbol) = Symbol::parse(&order.pair) else {
+            return Ok(());
+        };
+        let free = self
+            .balances()
+            .await?
+            .into_iter()
+            .find(|b| b.asset == symbol.quote)
+            .map(|b| b.free)
+            .unwrap_or_default();
+        let needed = order
+            .quote_quantity
+            .unwrap_or_else(|| order.quantity * price);
+        if free < needed {
+            return Err(Error::Exchange(format!(
+                "insufficient {} balance: {free} free, {needed} needed",
+                symbol.quote
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl ExchangeClient for BinanceClient {
     async fn submit_order(&self, order: &Order) -> Result<Fill> {
         let side = order.side.to_string();
-        let order_type = if order.price.is_some() {
-            "LIMIT"
-        } else {
-            "MARKET"
+        // Our order ID doubles as the client order ID, making retries idempotent
+        let size = match order.quote_quantity {
+            Some(quote) => format!("quoteOrderQty={quote}"),
+            None => format!("quantity={}", order.quantity),
         };
-
         let mut params = format!(
-            "symbol={}&side={}&type={}&quantity={}",
-            order.pair, side, order_type, order.quantity
+            "symbol={}&side={}&type={}&{size}&newClientOrderId={}",
+            order.pair,
+            side,
+            order_type(order),
+            order.id
         );
         if let Some(price) = order.price {
             params.push_str(&format!("&price={}&timeInForce=GTC", price));
         }
+        if let Some(
+            OrderTrigger::StopLoss { stop_price } | OrderTrigger::TakeProfit { stop_price },
+        ) = order.trigger
+        {
+            params.push_str(&format!("&stopPrice={stop_price}"));
+        }
+
+        if self.dry_run {
+            return self.test_order(order, &params).await;
+        }
 
         debug!(pair = %order.pair, side = %side, "Submitting order to Binance");
         let body = self.signed_post("/api/v3/order", &params).await?;
@@ -120,73 +322,177 @@ impl ExchangeClient for BinanceClient {
         let resp: OrderResponse =
             serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
 
-        let fill_price = resp
-            .fills
-            .first()
-            .and_then(|f| f.price.parse::<f64>().ok())
-            .unwrap_or_else(|| order.price.unwrap_or(0.0));
+        // Resting orders (untriggered stops, unfilled limits) report 0 executed
+        let executed = resp
+            .executed_qty
+            .as_deref()
+            .and_then(|q| q.parse::<Decimal>().ok());
+        // Average over every level the order swept, not just the first
+        let average = resp
+            .cummulative_quote_qty
+            .as_deref()
+            .and_then(|q| q.parse::<Decimal>().ok())
+            .zip(executed.filter(|q| *q > Decimal::ZERO))
+            .map(|(quote, qty)| quote / qty);
+        let fill_price = average
+            .or_else(|| {
+                resp.fills
+                    .first()
+                    .and_then(|f| f.price.parse::<Decimal>().ok())
+            })
+            .unwrap_or_else(|| order.price.unwrap_or_default());
+        let quantity = executed.unwrap_or_else(|| order.base_quantity_at(fill_price));
 
         Ok(Fill {
             order_id: resp.client_order_id,
+            exchange_order_id: resp.order_id.map(|id| id.to_string()),
             pair: order.pair.clone(),
             side: order.side,
             fill_price,
-            quantity: order.quantity,
+            quantity,
+            fee: quote_fee(&order.pair, &resp.fills),
             timestamp: Utc::now(),
         })
     }
 
     async fn open_positions(&self) -> Result<Vec<Position>> {
-        // Fetch account info and extract non-zero balances as pseudo-positions.
+        // Extract non-zero balances as pseudo-positions.
         // For a more accurate implementation, query open orders or use futures API.
+        let positions = self
+            .balances()
+            .await?
+            .into_iter()
+            .filter(|b| b.asset != "USDT" && b.asset != "BNB")
+            .map(|b| Position {
+                id: uuid::Uuid::new_v4().to_string(),
+                pair: format!("{}USDT", b.asset),
+                side: OrderSide::Buy,
+                entry_price: Decimal::ZERO, // unknown without trade history
+                quantity: b.total(),
+                mode: TradingMode::Live,
+                opened_at: Utc::now(),
+            })
+            .collect();
+
+        Ok(positions)
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
         let body = self.signed_get("/api/v3/account", "").await?;
         let account: AccountResponse =
             serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
 
-        let positions = account
+        let balances = account
             .balances
             .into_iter()
-            .filter(|b| {
-                b.free.parse::<f64>().unwrap_or(0.0) > 0.0
-                    || b.locked.parse::<f64>().unwrap_or(0.0) > 0.0
-            })
-            .filter(|b| b.asset != "USDT" && b.asset != "BNB")
-            .map(|b| {
-                let qty =
-                    b.free.parse::<f64>().unwrap_or(0.0) + b.locked.parse::<f64>().unwrap_or(0.0);
-                Position {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    pair: format!("{}USDT", b.asset),
-                    side: OrderSide::Buy,
-                    entry_price: 0.0, // unknown without trade history
-                    quantity: qty,
-                    mode: TradingMode::Live,
-                    opened_at: Utc::now(),
-                }
+            .map(|b| Balance {
+                asset: b.asset,
+                free: b.free.parse().unwrap_or_default(),
+                locked: b.locked.parse().unwrap_or_default(),
             })
+            .filter(|b| b.total() > Decimal::ZERO)
             .collect();
 
-        Ok(positions)
+        Ok(balances)
+    }
+
+    async fn order_status(&self, pair: &str, order_id: &str) -> Result<OrderStatusReport> {
+        let params = format!("symbol={pair}&origClientOrderId={order_id}");
+        let body = self.signed_get("/api/v3/order", &params).await?;
+        let resp: OrderQueryResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+
+        let status = match resp.status.as_str() {
+            "NEW" | "PENDING_NEW" => OrderStatus::New,
+            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
+            "FILLED" => OrderStatus::Filled,
+            "CANCELED" | "PENDING_CANCEL" => OrderStatus::Canceled,
+            "EXPIRED" | "EXPIRED_IN_MATCH" => OrderStatus::Expired,
+            "REJECTED" => OrderStatus::Rejected,
+            other => return Err(Error::Exchange(format!("unknown order status '{other}'"))),
+        };
+        let executed_quantity = resp.executed_qty.parse::<Decimal>().unwrap_or_default();
+        let quote = resp
+            .cummulative_quote_qty
+            .parse::<Decimal>()
+            .unwrap_or_default();
+        let average_price = (executed_quantity > Decimal::ZERO).then(|| quote / executed_quantity);
+
+        Ok(OrderStatusReport {
+            status,
+            executed_quantity,
+            average_price,
+            exchange_order_id: resp.order_id.map(|id| id.to_string()),
+        })
     }
 
-    async fn current_price(&self, pair: &str) -> Result<f64> {
+    async fn cancel_order(&self, pair: &str, order_id: &str) -> Result<()> {
+        let params = format!("symbol={pair}&origClientOrderId={order_id}");
+        debug!(pair = %pair, order_id = %order_id, "Cancelling order on Binance");
+        self.signed_delete("/api/v3/order", &params).await?;
+        Ok(())
+    }
+
+    async fn place_bracket(&self, bracket: &BracketOrder) -> Result<BracketLegs> {
+        // Each leg gets its own client order ID so it can be polled like any
+        // other order
+        let take_profit_order_id = uuid::Uuid::new_v4().to_string();
+        let stop_order_id = uuid::Uuid::new_v4().to_string();
+        // Without stopLimitPrice the stop leg is a STOP_LOSS (market) order
+        let params = format!(
+            "symbol={}&side={}&quantity={}&price={}&stopPrice={}&limitClientOrderId={}&stopClientOrderId={}",
+            bracket.pair,
+            bracket.side,
+            bracket.quantity,
+            bracket.take_profit_price,
+            bracket.stop_price,
+            take_profit_order_id,
+            stop_order_id
+        );
+        debug!(pair = %bracket.pair, "Placing OCO bracket on Binance");
+        let body = self.signed_post("/api/v3/order/oco", &params).await?;
+        let resp: OcoResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+        Ok(BracketLegs {
+            id: resp.order_list_id.to_string(),
+            take_profit_order_id,
+            stop_order_id,
+        })
+    }
+
+    async fn cancel_bracket(&self, pair: &str, bracket_id: &str) -> Result<()> {
+        let params = format!("symbol={pair}&orderListId={bracket_id}");
+        self.signed_delete("/api/v3/orderList", &params).await?;
+        Ok(())
+    }
+
+    async fn current_price(&self, pair: &str) -> Result<Decimal> {
         let url = format!("{BASE_URL}/api/v3/ticker/price?symbol={pair}");
-        let resp = self
-            .http
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::Http(e.to_string()))?;
+        self.throttle().await;
+        let body = self.dispatch(self.http.get(&url)).await?;
 
-        let ticker: PriceTicker = resp.json().await.map_err(|e| Error::Http(e.to_string()))?;
+        let ticker: PriceTicker =
+            serde_json::from_str(&body).map_err(|e| Error::Http(e.to_string()))?;
 
         ticker
             .price
-            .parse::<f64>()
+            .parse::<Decimal>()
             .map_err(|e| Error::Exchange(e.to_string()))
     }
 }
 
+/// Binance order type for `order`, from its trigger and whether it has a limit.
+fn order_type(order: &Order) -> &'static str {
+    match (order.trigger, order.price.is_some()) {
+        (None, false) => "MARKET",
+        (None, true) => "LIMIT",
+        (Some(OrderTrigger::StopLoss { .. }), false) => "STOP_LOSS",
+        (Some(OrderTrigger::StopLoss { .. }), true) => "STOP_LOSS_LIMIT",
+        (Some(OrderTrigger::TakeProfit { .. }), false) => "TAKE_PROFIT",
+        (Some(OrderTrigger::TakeProfit { .. }), true) => "TAKE_PROFIT_LIMIT",
+    }
+}
+
 // ─── Response types ───────────────────────────────────────────────────────────
 
 #[derive(Deserialize)]
@@ -194,21 +500,102 @@ impl ExchangeClient for BinanceClient {
 struct OrderResponse {
     client_order_id: String,
     #[serde(default)]
+    order_id: Option<i64>,
+    #[serde(default)]
+    executed_qty: Option<String>,
+    #[serde(default)]
+    cummulative_quote_qty: Option<String>,
+    #[serde(default)]
     fills: Vec<FillDetail>,
 }
 
 #[derive(Deserialize)]
+struct ExchangeInfoResponse {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Deserialize)]
+struct SymbolInfo {
+    symbol: String,
+    filters: Vec<RawFilter>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFilter {
+    filter_type: String,
+    min_qty: Option<String>,
+    step_size: Option<String>,
+    tick_size: Option<String>,
+    min_notional: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderQueryResponse {
+    #[serde(default)]
+    order_id: Option<i64>,
+    status: String,
+    executed_qty: String,
+    cummulative_quote_qty: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OcoResponse {
+    order_list_id: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct FillDetail {
     price: String,
+    #[serde(default)]
+    commission: String,
+    #[serde(default)]
+    commission_asset: String,
+}
+
+/// Total commission of `fills` in the quote asset of `pair`. Commission
+/// taken in the base asset is converted at its fill price; other assets
+/// (BNB discounts) can't be priced here and count as zero.
+fn quote_fee(pair: &str, fills: &[FillDetail]) -> Decimal {
+    let Some(symbol) = Symbol::parse(pair) else {
+        return Decimal::ZERO;
+    };
+    fills
+        .iter()
+        .filter_map(|f| {
+            let commission = f.commission.parse::<Decimal>().ok()?;
+            if f.commission_asset == symbol.quote {
+                Some(commission)
+            } else if f.commission_asset == symbol.base {
+                Some(commission * f.price.parse::<Decimal>().ok()?)
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+/// Permissions granted to a Binance API key.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiPermissions {
+    pub enable_reading: bool,
+    pub enable_spot_and_margin_trading: bool,
+    pub enable_withdrawals: bool,
+    /// Whether the key only works from whitelisted IPs.
+    pub ip_restrict: bool,
 }
 
 #[derive(Deserialize)]
 struct AccountResponse {
-    balances: Vec<Balance>,
+    balances: Vec<AccountBalance>,
 }
 
 #[derive(Deserialize)]
-struct Balance {
+struct AccountBalance {
     asset: String,
     free: String,
     locked: String,
@@ -218,3 +605,39 @@ struct Balance {
 struct PriceTicker {
     price: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn order_type_follows_trigger_and_limit() {
+        let mut order = Order::market("BTCUSDT", OrderSide::Sell, dec!(1));
+        assert_eq!(order_type(&order), "MARKET");
+        order.price = Some(dec!(95));
+        assert_eq!(order_type(&order), "LIMIT");
+
+        let stop_loss = OrderTrigger::StopLoss {
+            stop_price: dec!(96),
+        };
+        let take_profit = OrderTrigger::TakeProfit {
+            stop_price: dec!(110),
+        };
+        let cases = [
+            (stop_loss, None, "STOP_LOSS"),
+            (stop_loss, Some(dec!(95)), "STOP_LOSS_LIMIT"),
+            (take_profit, None, "TAKE_PROFIT"),
+            (take_profit, Some(dec!(109)), "TAKE_PROFIT_LIMIT"),
+        ];
+        for (trigger, price, expected) in cases {
+            let order = Order {
+                trigger: Some(trigger),
+                price,
+                ..order.clone()
+            };
+            assert_eq!(order_type(&order), expected);
+        }
+    }
+}
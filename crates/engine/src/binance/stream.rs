@@ -1,50 +1,103 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::time::Duration;
 
 use chrono::{DateTime, TimeZone, Utc};
-use futures_util::StreamExt;
+use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
 use tracing::{info, warn};
 use url::Url;
 
-use common::{MarketEvent, Result};
+use common::{KlineInterval, MarketEvent, Result, TradeEvent};
 
-/// Binance kline/candlestick WebSocket stream for a single pair.
+use crate::feed::{ConnectionEvent, StreamControl, StreamHandle};
+
+/// Binance combined kline/candlestick WebSocket stream for all pairs.
 ///
-/// Connects to Binance's 1-minute kline stream, parses events into
-/// `MarketEvent`, and publishes them on a broadcast channel.
+/// Holds a single connection to Binance's combined-stream endpoint with one
+/// kline stream per pair at that pair's interval, demultiplexes messages by
+/// stream name into `MarketEvent`s, and publishes them on a broadcast
+/// channel. Every pair also gets a `bookTicker` stream whose latest best
+/// bid/ask is attached to the pair's `MarketEvent`s. Pairs enabled with
+/// `set_trades` also get an `aggTrade` stream published as `TradeEvent`s.
+/// Pairs can be added or removed at runtime through a `StreamHandle`.
 /// Reconnects automatically with exponential backoff.
 pub struct BinanceStream {
-    pair: String,
+    /// Uppercase symbols currently subscribed and their intervals (kept
+    /// across reconnects).
+    pairs: BTreeMap<String, KlineInterval>,
     market_tx: broadcast::Sender<MarketEvent>,
+    /// Uppercase symbols that also stream aggregated trades.
+    trade_pairs: BTreeSet<String>,
+    trade_tx: Option<broadcast::Sender<TradeEvent>>,
+    /// Latest best (bid, ask) per uppercase symbol.
+    quotes: HashMap<String, (f64, f64)>,
+    /// Notified of every connect, reconnect and disconnect.
+    event_tx: Option<mpsc::UnboundedSender<ConnectionEvent>>,
+    connected_once: bool,
+    control_rx: mpsc::UnboundedReceiver<StreamControl>,
+    next_request_id: u64,
 }
 
 impl BinanceStream {
-    pub fn new(pair: impl Into<String>, market_tx: broadcast::Sender<MarketEvent>) -> Self {
-        Self {
-            pair: pair.into(),
+    pub fn new(
+        pairs: impl IntoIterator<Item = (String, KlineInterval)>,
+        market_tx: broadcast::Sender<MarketEvent>,
+    ) -> (Self, StreamHandle) {
+        let (handle, control_rx) = StreamHandle::channel();
+        let stream = Self {
+            pairs: pairs
+                .into_iter()
+                .map(|(p, interval)| (p.to_uppercase(), interval))
+                .collect(),
             market_tx,
-        }
+            trade_pairs: BTreeSet::new(),
+            trade_tx: None,
+            quotes: HashMap::new(),
+            event_tx: None,
+            connected_once: false,
+            control_rx,
+            next_request_id: 1,
+        };
+        (stream, handle)
+    }
+
+    /// Also stream aggregated trades for `pairs`, publishing on `trade_tx`.
+    pub fn set_trades(
+        &mut self,
+        pairs: impl IntoIterator<Item = String>,
+        trade_tx: broadcast::Sender<TradeEvent>,
+    ) {
+        self.trade_pairs = pairs.into_iter().map(|p| p.to_uppercase()).collect();
+        self.trade_tx = Some(trade_tx);
+    }
+
+    /// Report connection changes on `event_tx`.
+    pub fn set_event_notifier(&mut self, event_tx: mpsc::UnboundedSender<ConnectionEvent>) {
+        self.event_tx = Some(event_tx);
     }
 
     /// Run the stream loop forever, reconnecting on failure.
     /// Call this inside a `tokio::spawn`.
-    pub async fn run(self) {
+    pub async fn run(mut self) {
         let mut backoff = Duration::from_secs(1);
         const MAX_BACKOFF: Duration = Duration::from_secs(60);
 
         loop {
-            info!(pair = %self.pair, "Connecting to Binance WebSocket stream");
+            info!(pairs = ?self.pairs, "Connecting to Binance combined WebSocket stream");
             match self.connect_once().await {
                 Ok(()) => {
-                    info!(pair = %self.pair, "WebSocket stream closed cleanly");
+                    info!("WebSocket stream closed cleanly");
+                    self.notify(ConnectionEvent::Disconnected("closed by server".into()));
                     // Clean close — reconnect after a short delay (e.g. 24h session end)
                     tokio::time::sleep(Duration::from_secs(1)).await;
                     backoff = Duration::from_secs(1);
                 }
                 Err(e) => {
-                    warn!(pair = %self.pair, error = %e, backoff = ?backoff, "WebSocket error, reconnecting");
+                    warn!(error = %e, backoff = ?backoff, "WebSocket error, reconnecting");
+                    self.notify(ConnectionEvent::Disconnected(e.to_string()));
                     tokio::time::sleep(backoff).await;
                     backoff = (backoff * 2).min(MAX_BACKOFF);
                 }
@@ -52,30 +105,126 @@ impl BinanceStream {
         }
     }
 
-    async fn connect_once(&self) -> Result<()> {
-        let pair_lower = self.pair.to_lowercase();
-        // Subscribe to 1-minute kline stream
-        let url_str = format!("wss://stream.binance.com:9443/ws/{}@kline_1m", pair_lower);
+    fn notify(&self, event: ConnectionEvent) {
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    async fn connect_once(&mut self) -> Result<()> {
+        // Subscribe to every pair's kline, book ticker (and optional trade)
+        // stream on one connection
+        let mut streams: Vec<String> = self
+            .pairs
+            .iter()
+            .flat_map(|(pair, interval)| [stream_name(pair, *interval), book_stream_name(pair)])
+            .collect();
+        if self.trade_tx.is_some() {
+            streams.extend(self.trade_pairs.iter().map(|pair| trade_stream_name(pair)));
+        }
+        let url_str = if streams.is_empty() {
+            "wss://stream.binance.com:9443/stream".to_string()
+        } else {
+            format!(
+                "wss://stream.binance.com:9443/stream?streams={}",
+                streams.join("/")
+            )
+        };
         let url = Url::parse(&url_str).map_err(|e| common::Error::WebSocket(e.to_string()))?;
 
         let (ws_stream, _) = connect_async(url)
             .await
             .map_err(|e| common::Error::WebSocket(e.to_string()))?;
 
-        let (_, mut read) = ws_stream.split();
+        let (mut write, mut read) = ws_stream.split();
 
-        while let Some(msg) = read.next().await {
-            let msg = msg.map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        self.notify(if self.connected_once {
+            ConnectionEvent::Reconnected
+        } else {
+            ConnectionEvent::Connected
+        });
+        self.connected_once = true;
 
-            if let tokio_tungstenite::tungstenite::Message::Text(text) = msg {
-                match parse_kline_event(&self.pair, &text) {
-                    Ok(Some(event)) => {
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    let Some(msg) = msg else { break };
+                    let msg = msg.map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                    if let Message::Text(text) = msg {
                         // Ignore send errors (no active receivers)
-                        let _ = self.market_tx.send(event);
+                        match parse_combined_event(&text) {
+                            Ok(Some(StreamEvent::Kline(mut event))) => {
+                                if let Some(&(bid, ask)) = self.quotes.get(&event.pair) {
+                                    event.best_bid = Some(bid);
+                                    event.best_ask = Some(ask);
+                                }
+                                let _ = self.market_tx.send(event);
+                            }
+                            Ok(Some(StreamEvent::Quote { pair, bid, ask })) => {
+                                self.quotes.insert(pair, (bid, ask));
+                            }
+                            Ok(Some(StreamEvent::Trade(event))) => {
+                                if let Some(tx) = &self.trade_tx {
+                                    let _ = tx.send(event);
+                                }
+                            }
+                            Ok(None) => {} // subscription ack or non-kline message, skip
+                            Err(e) => {
+                                warn!(error = %e, "Failed to parse kline event");
+                            }
+                        }
+                    }
+                }
+                Some(control) = self.control_rx.recv() => {
+                    let mut requests: Vec<(&str, String)> = Vec::new();
+                    match control {
+                        StreamControl::Subscribe(pair, interval) => {
+                            let pair = pair.to_uppercase();
+                            let previous = self.pairs.insert(pair.clone(), interval);
+                            if previous == Some(interval) {
+                                continue;
+                            }
+                            // Switching interval: drop the old stream first
+                            match previous {
+                                Some(old) => {
+                                    requests.push(("UNSUBSCRIBE", stream_name(&pair, old)));
+                                    requests.push(("SUBSCRIBE", stream_name(&pair, interval)));
+                                }
+                                None => {
+                                    requests.push(("SUBSCRIBE", stream_name(&pair, interval)));
+                                    requests.push(("SUBSCRIBE", book_stream_name(&pair)));
+                                }
+                            }
+                        }
+                        StreamControl::Unsubscribe(pair) => {
+                            let pair = pair.to_uppercase();
+                            if let Some(old) = self.pairs.remove(&pair) {
+                                requests.push(("UNSUBSCRIBE", stream_name(&pair, old)));
+                                requests.push(("UNSUBSCRIBE", book_stream_name(&pair)));
+                                self.quotes.remove(&pair);
+                            }
+                            if self.trade_pairs.remove(&pair) && self.trade_tx.is_some() {
+                                requests.push(("UNSUBSCRIBE", trade_stream_name(&pair)));
+                            }
+                        }
+                        StreamControl::Reconnect => {
+                            return Err(common::Error::WebSocket(
+                                "reconnect requested".into(),
+                            ));
+                        }
                     }
-                    Ok(None) => {} // non-kline message, skip
-                    Err(e) => {
-                        warn!(error = %e, "Failed to parse kline event");
+                    for (method, stream) in requests {
+                        info!(stream = %stream, method, "Updating stream subscription");
+                        let request = serde_json::json!({
+                            "method": method,
+                            "params": [stream],
+                            "id": self.next_request_id,
+                        });
+                        self.next_request_id += 1;
+                        write
+                            .send(Message::Text(request.to_string()))
+                            .await
+                            .map_err(|e| common::Error::WebSocket(e.to_string()))?;
                     }
                 }
             }
@@ -85,7 +234,29 @@ impl BinanceStream {
     }
 }
 
-// ─── Binance kline JSON parsing ──────────────────────────────────────────────
+/// Binance stream name for a pair's klines, e.g. `btcusdt@kline_5m`.
+fn stream_name(pair: &str, interval: KlineInterval) -> String {
+    format!("{}@kline_{}", pair.to_lowercase(), interval)
+}
+
+/// Binance best bid/ask stream name for a pair, e.g. `btcusdt@bookTicker`.
+fn book_stream_name(pair: &str) -> String {
+    format!("{}@bookTicker", pair.to_lowercase())
+}
+
+/// Binance aggregated-trade stream name for a pair, e.g. `btcusdt@aggTrade`.
+fn trade_stream_name(pair: &str) -> String {
+    format!("{}@aggTrade", pair.to_lowercase())
+}
+
+// ─── Binance stream JSON parsing ─────────────────────────────────────────────
+
+/// A demultiplexed message from the combined stream.
+enum StreamEvent {
+    Kline(MarketEvent),
+    Trade(TradeEvent),
+    Quote { pair: String, bid: f64, ask: f64 },
+}
 
 #[derive(Deserialize)]
 struct KlineWrapper {
@@ -110,14 +281,85 @@ struct KlineData {
     close_time_ms: i64,
 }
 
-fn parse_kline_event(pair: &str, text: &str) -> Result<Option<MarketEvent>> {
+#[derive(Deserialize)]
+struct BookTickerData {
+    #[serde(rename = "b")]
+    bid: String,
+    #[serde(rename = "a")]
+    ask: String,
+}
+
+#[derive(Deserialize)]
+struct AggTradeData {
+    #[serde(rename = "a")]
+    trade_id: u64,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "m")]
+    is_buyer_maker: bool,
+    #[serde(rename = "T")]
+    trade_time_ms: i64,
+}
+
+/// Unwrap a combined-stream message (`{"stream": "...", "data": {...}}`) and
+/// parse its payload, taking the pair (and interval) from the stream name.
+fn parse_combined_event(text: &str) -> Result<Option<StreamEvent>> {
+    let mut wrapper: serde_json::Value = serde_json::from_str(text)?;
+    let Some(stream) = wrapper.get("stream").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    let Some((symbol, kind)) = stream.split_once('@') else {
+        return Ok(None);
+    };
+    let pair = symbol.to_uppercase();
+
+    if kind == "aggTrade" {
+        let trade: AggTradeData = serde_json::from_value(wrapper["data"].take())?;
+        return Ok(Some(StreamEvent::Trade(TradeEvent {
+            pair,
+            trade_id: trade.trade_id,
+            price: trade.price.parse().unwrap_or(0.0),
+            quantity: trade.quantity.parse().unwrap_or(0.0),
+            is_buyer_maker: trade.is_buyer_maker,
+            timestamp: Utc
+                .timestamp_millis_opt(trade.trade_time_ms)
+                .single()
+                .unwrap_or_else(Utc::now),
+        })));
+    }
+
+    if kind == "bookTicker" {
+        let book: BookTickerData = serde_json::from_value(wrapper["data"].take())?;
+        return Ok(Some(StreamEvent::Quote {
+            pair,
+            bid: book.bid.parse().unwrap_or(0.0),
+            ask: book.ask.parse().unwrap_or(0.0),
+        }));
+    }
+
+    let Some(Ok(interval)) = kind
+        .strip_prefix("kline_")
+        .map(|i| i.parse::<KlineInterval>())
+    else {
+        return Ok(None);
+    };
+    let data = wrapper["data"].take();
+    Ok(parse_kline_event(&pair, interval, data)?.map(StreamEvent::Kline))
+}
+
+fn parse_kline_event(
+    pair: &str,
+    interval: KlineInterval,
+    payload: serde_json::Value,
+) -> Result<Option<MarketEvent>> {
     // Kline messages have an "e" field set to "kline"
-    let wrapper: serde_json::Value = serde_json::from_str(text)?;
-    if wrapper.get("e").and_then(|v| v.as_str()) != Some("kline") {
+    if payload.get("e").and_then(|v| v.as_str()) != Some("kline") {
         return Ok(None);
     }
 
-    let kline: KlineWrapper = serde_json::from_value(wrapper)?;
+    let kline: KlineWrapper = serde_json::from_value(payload)?;
     let k = kline.k;
 
     let timestamp: DateTime<Utc> = Utc
@@ -127,6 +369,7 @@ fn parse_kline_event(pair: &str, text: &str) -> Result<Option<MarketEvent>> {
 
     Ok(Some(MarketEvent {
         pair: pair.to_string(),
+        interval,
         price: k.close.parse().unwrap_or(0.0),
         open: k.open.parse().unwrap_or(0.0),
         high: k.high.parse().unwrap_or(0.0),
@@ -134,5 +377,53 @@ fn parse_kline_event(pair: &str, text: &str) -> Result<Option<MarketEvent>> {
         volume: k.volume.parse().unwrap_or(0.0),
         is_candle_closed: k.is_closed,
         timestamp,
+        best_bid: None,
+        best_ask: None,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_message_is_routed_by_stream_name() {
+        let text = r#"{"stream":"ethusdt@kline_15m","data":{"e":"kline","k":{"o":"1.0","h":"2.0","l":"0.5","c":"1.5","v":"10","T":1700000000000,"x":true}}}"#;
+        let Some(StreamEvent::Kline(event)) = parse_combined_event(text).unwrap() else {
+            panic!("expected a kline event");
+        };
+        assert_eq!(event.pair, "ETHUSDT");
+        assert_eq!(event.interval, KlineInterval::FifteenMinutes);
+        assert_eq!(event.price, 1.5);
+        assert!(event.is_candle_closed);
+
+        // Subscription acks carry no stream and are skipped
+        assert!(parse_combined_event(r#"{"result":null,"id":1}"#)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn agg_trade_message_becomes_trade_event() {
+        let text = r#"{"stream":"btcusdt@aggTrade","data":{"e":"aggTrade","E":1700000000001,"s":"BTCUSDT","a":42,"p":"30000.50","q":"0.015","f":100,"l":101,"T":1700000000000,"m":true,"M":true}}"#;
+        let Some(StreamEvent::Trade(trade)) = parse_combined_event(text).unwrap() else {
+            panic!("expected a trade event");
+        };
+        assert_eq!(trade.pair, "BTCUSDT");
+        assert_eq!(trade.trade_id, 42);
+        assert_eq!(trade.price, 30000.50);
+        assert_eq!(trade.quantity, 0.015);
+        assert!(trade.is_buyer_maker);
+    }
+
+    #[test]
+    fn book_ticker_message_becomes_quote() {
+        let text = r#"{"stream":"btcusdt@bookTicker","data":{"u":400900217,"s":"BTCUSDT","b":"29999.90","B":"1.5","a":"30000.10","A":"2.0"}}"#;
+        let Some(StreamEvent::Quote { pair, bid, ask }) = parse_combined_event(text).unwrap()
+        else {
+            panic!("expected a quote");
+        };
+        assert_eq!(pair, "BTCUSDT");
+        assert_eq!((bid, ask), (29999.90, 30000.10));
+    }
+}
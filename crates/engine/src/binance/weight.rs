@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+/// Binance's default REQUEST_WEIGHT limit per IP per minute.
+pub const DEFAULT_WEIGHT_LIMIT: u32 = 6000;
+
+/// Fraction of the limit at which new requests wait for the next window.
+const THROTTLE_AT: f64 = 0.9;
+
+/// Tracks request weight used in the current one-minute window (as reported
+/// by `X-MBX-USED-WEIGHT-1M`) and any server-imposed back-off.
+///
+/// Times are Unix milliseconds so the window lines up with Binance's, which
+/// resets on wall-clock minute boundaries.
+#[derive(Debug, Clone)]
+pub struct WeightBudget {
+    limit: u32,
+    used: u32,
+    /// Unix minute the `used` figure belongs to.
+    minute: u64,
+    /// Unix ms before which no request may be sent (429/418 Retry-After).
+    blocked_until_ms: u64,
+}
+
+impl WeightBudget {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            used: 0,
+            minute: 0,
+            blocked_until_ms: 0,
+        }
+    }
+
+    /// Record the used weight reported by a response.
+    pub fn record_used(&mut self, used: u32, now_ms: u64) {
+        self.used = used;
+        self.minute = now_ms / 60_000;
+    }
+
+    /// Record a 429/418 response asking us to back off for `retry_after`.
+    pub fn record_retry_after(&mut self, retry_after: Duration, now_ms: u64) {
+        let until = now_ms + retry_after.as_millis() as u64;
+        self.blocked_until_ms = self.blocked_until_ms.max(until);
+    }
+
+    /// How long to wait before the next request, if at all.
+    pub fn delay(&self, now_ms: u64) -> Option<Duration> {
+        if now_ms < self.blocked_until_ms {
+            return Some(Duration::from_millis(self.blocked_until_ms - now_ms));
+        }
+        let current_minute = now_ms / 60_000;
+        if current_minute == self.minute && self.used as f64 >= self.limit as f64 * THROTTLE_AT {
+            let next_window_ms = (current_minute + 1) * 60_000;
+            return Some(Duration::from_millis(next_window_ms - now_ms));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_near_limit_until_next_minute() {
+        let mut budget = WeightBudget::new(1000);
+        let now = 60_000 * 100 + 15_000; // 15s into a minute
+        budget.record_used(500, now);
+        assert_eq!(budget.delay(now), None);
+
+        budget.record_used(950, now);
+        assert_eq!(budget.delay(now), Some(Duration::from_secs(45)));
+        // A new window clears the throttle
+        assert_eq!(budget.delay(now + 45_000), None);
+    }
+
+    #[test]
+    fn retry_after_blocks_requests() {
+        let mut budget = WeightBudget::new(1000);
+        budget.record_retry_after(Duration::from_secs(30), 1_000);
+        assert_eq!(budget.delay(1_000), Some(Duration::from_secs(30)));
+        assert_eq!(budget.delay(31_000), None);
+    }
+}
@@ -0,0 +1,412 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use common::{
+    Balance, Error, ExchangeClient, Fill, Order, OrderSide, OrderStatus, OrderStatusReport,
+    OrderTrigger, Position, Result, TradingMode,
+};
+
+const BASE_URL: &str = "https://api.bybit.com";
+const RECV_WINDOW: u64 = 5000;
+
+/// Coins treated as cash rather than positions when reading balances.
+const CASH_COINS: &[&str] = &["USDT", "USDC", "USD", "EUR"];
+
+/// `retCode`s Bybit uses for rate limiting and temporary unavailability.
+const TRANSIENT_RET_CODES: &[i64] = &[10002, 10006, 10016, 10018];
+
+/// REST API client for Bybit v5 spot trading. Used for order placement and
+/// account queries.
+///
+/// Requests are signed with HMAC-SHA256 over `timestamp + key + recv_window +
+/// payload` and sent with the `X-BAPI-*` headers. Bybit uses the same symbol
+/// names as Binance (`BTCUSDT`), so pairs need no mapping.
+pub struct BybitClient {
+    api_key: String,
+    secret: String,
+    http: Client,
+}
+
+impl BybitClient {
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            secret: secret.into(),
+            http: Client::builder()
+                .use_rustls_tls()
+                .build()
+                .expect("Failed to build HTTP client"),
+        }
+    }
+
+    fn timestamp_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    /// Signature over `timestamp + api_key + recv_window + payload`, where the
+    /// payload is the query string for GET and the JSON body for POST.
+    fn sign(&self, timestamp: u64, payload: &str) -> String {
+        type HmacSha256 = Hmac<Sha256>;
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts any key length");
+        mac.update(format!("{timestamp}{}{RECV_WINDOW}{payload}", self.api_key).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Send a signed request and unwrap the `result` of Bybit's response
+    /// envelope. 429, 5xx and rate-limit `retCode`s are reported as transient.
+    async fn signed_send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &str,
+        body: Option<String>,
+    ) -> Result<T> {
+        let ts = Self::timestamp_ms();
+        let body = body.unwrap_or_default();
+        let payload = if method == Method::GET { query } else { &body };
+        let signature = self.sign(ts, payload);
+        let url = if query.is_empty() {
+            format!("{BASE_URL}{path}")
+        } else {
+            format!("{BASE_URL}{path}?{query}")
+        };
+
+        let mut request = self
+            .http
+            .request(method, &url)
+            .header("X-BAPI-API-KEY", &self.api_key)
+            .header("X-BAPI-SIGN", signature)
+            .header("X-BAPI-TIMESTAMP", ts.to_string())
+            .header("X-BAPI-RECV-WINDOW", RECV_WINDOW.to_string());
+        if !body.is_empty() {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| Error::Http(e.to_string()))?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| Error::Http(e.to_string()))?;
+        if status.as_u16() == 429 {
+            warn!("Bybit rate limit hit");
+            return Err(Error::ExchangeUnavailable(format!("HTTP {status}: {text}")));
+        }
+        if status.is_server_error() {
+            return Err(Error::ExchangeUnavailable(format!("HTTP {status}: {text}")));
+        }
+        if !status.is_success() {
+            return Err(Error::Exchange(format!("HTTP {status}: {text}")));
+        }
+
+        let envelope: Envelope<T> =
+            serde_json::from_str(&text).map_err(|e| Error::Exchange(e.to_string()))?;
+        if envelope.ret_code != 0 {
+            let message = format!("Bybit {}: {}", envelope.ret_code, envelope.ret_msg);
+            if TRANSIENT_RET_CODES.contains(&envelope.ret_code) {
+                return Err(Error::ExchangeUnavailable(message));
+            }
+            return Err(Error::Exchange(message));
+        }
+        envelope
+            .result
+            .ok_or_else(|| Error::Exchange("Bybit response without result".into()))
+    }
+
+    /// Look up an order by its client order ID (`orderLinkId`), checking
+    /// open orders first and then order history.
+    async fn fetch_order(&self, pair: &str, order_link_id: &str) -> Result<OrderStatusReport> {
+        let query = format!("category=spot&symbol={pair}&orderLinkId={order_link_id}");
+        let mut list: ListResult<OrderDetail> = self
+            .signed_send(Method::GET, "/v5/order/realtime", &query, None)
+            .await?;
+        if list.list.is_empty() {
+            list = self
+                .signed_send(Method::GET, "/v5/order/history", &query, None)
+                .await?;
+        }
+        let order = list
+            .list
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Exchange(format!("unknown order '{order_link_id}'")))?;
+
+        let executed_quantity = order.cum_exec_qty.parse::<Decimal>().unwrap_or_default();
+        let status = match order.order_status.as_str() {
+            "New" | "Untriggered" | "Triggered" => OrderStatus::New,
+            "PartiallyFilled" => OrderStatus::PartiallyFilled,
+            "Filled" => OrderStatus::Filled,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => OrderStatus::Canceled,
+            "Rejected" => OrderStatus::Rejected,
+            other => return Err(Error::Exchange(format!("unknown order status '{other}'"))),
+        };
+        let average_price = order
+            .avg_price
+            .parse::<Decimal>()
+            .ok()
+            .filter(|p| *p > Decimal::ZERO);
+
+        Ok(OrderStatusReport {
+            status,
+            executed_quantity,
+            average_price,
+            exchange_order_id: Some(order.order_id).filter(|id| !id.is_empty()),
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for BybitClient {
+    async fn submit_order(&self, order: &Order) -> Result<Fill> {
+        let body = order_request(order);
+        debug!(pair = %order.pair, side = %order.side, "Submitting order to Bybit");
+        let _: OrderAck = self
+            .signed_send(Method::POST, "/v5/order/create", "", Some(body.to_string()))
+            .await?;
+
+        // Market orders usually settle immediately; anything still working is
+        // reported with zero quantity so the executor tracks it as resting.
+        let report = self.fetch_order(&order.pair, &order.id).await?;
+        Ok(Fill {
+            order_id: order.id.clone(),
+            exchange_order_id: report.exchange_order_id,
+            pair: order.pair.clone(),
+            side: order.side,
+            fill_price: report.average_price.or(order.price).unwrap_or_default(),
+            quantity: report.executed_quantity,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn open_positions(&self) -> Result<Vec<Position>> {
+        // Non-cash coins are reported as long pseudo-positions quoted in USDT
+        let positions = self
+            .balances()
+            .await?
+            .into_iter()
+            .filter(|b| !CASH_COINS.contains(&b.asset.as_str()))
+            .map(|b| Position {
+                id: uuid::Uuid::new_v4().to_string(),
+                pair: format!("{}USDT", b.asset),
+                side: OrderSide::Buy,
+                entry_price: Decimal::ZERO, // unknown without fill history
+                quantity: b.total(),
+                mode: TradingMode::Live,
+                opened_at: Utc::now(),
+            })
+            .collect();
+
+        Ok(positions)
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let wallet: ListResult<WalletAccount> = self
+            .signed_send(
+                Method::GET,
+                "/v5/account/wallet-balance",
+                "accountType=UNIFIED",
+                None,
+            )
+            .await?;
+
+        let balances = wallet
+            .list
+            .into_iter()
+            .flat_map(|account| account.coin)
+            .map(|c| {
+                let total = c.wallet_balance.parse::<Decimal>().unwrap_or_default();
+                let locked = c.locked.parse::<Decimal>().unwrap_or_default();
+                Balance {
+                    asset: c.coin,
+                    free: total - locked,
+                    locked,
+                }
+            })
+            .filter(|b| b.total() > Decimal::ZERO)
+            .collect();
+
+        Ok(balances)
+    }
+
+    async fn order_status(&self, pair: &str, order_id: &str) -> Result<OrderStatusReport> {
+        self.fetch_order(pair, order_id).await
+    }
+
+    async fn cancel_order(&self, pair: &str, order_id: &str) -> Result<()> {
+        let body = json!({
+            "category": "spot",
+            "symbol": pair,
+            "orderLinkId": order_id,
+        });
+        debug!(pair = %pair, order_id = %order_id, "Cancelling order on Bybit");
+        let _: OrderAck = self
+            .signed_send(Method::POST, "/v5/order/cancel", "", Some(body.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    async fn current_price(&self, pair: &str) -> Result<Decimal> {
+        let url = format!("{BASE_URL}/v5/market/tickers?category=spot&symbol={pair}");
+        let resp = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| Error::Http(e.to_string()))?;
+        let envelope: Envelope<ListResult<Ticker>> =
+            resp.json().await.map_err(|e| Error::Http(e.to_string()))?;
+
+        envelope
+            .result
+            .and_then(|r| r.list.into_iter().next())
+            .ok_or_else(|| Error::Exchange(format!("no ticker for '{pair}'")))?
+            .last_price
+            .parse::<Decimal>()
+            .map_err(|e| Error::Exchange(e.to_string()))
+    }
+}
+
+/// `/v5/order/create` body for `order`. Market quantities are always in the
+/// base coin (Bybit defaults spot market buys to the quote coin), and
+/// triggered orders are placed as spot conditional orders.
+fn order_request(order: &Order) -> serde_json::Value {
+    let side = match order.side {
+        OrderSide::Buy => "Buy",
+        OrderSide::Sell => "Sell",
+    };
+    let mut body = json!({
+        "category": "spot",
+        "symbol": order.pair,
+        "side": side,
+        "qty": order.quote_quantity.unwrap_or(order.quantity).to_string(),
+        "orderLinkId": order.id,
+    });
+    match order.price {
+        Some(limit) => {
+            body["orderType"] = json!("Limit");
+            body["price"] = json!(limit.to_string());
+            body["timeInForce"] = json!("GTC");
+        }
+        None => {
+            body["orderType"] = json!("Market");
+            let unit = if order.quote_quantity.is_some() {
+                "quoteCoin"
+            } else {
+                "baseCoin"
+            };
+            body["marketUnit"] = json!(unit);
+        }
+    }
+    if let Some(trigger) = order.trigger {
+        let (OrderTrigger::StopLoss { stop_price } | OrderTrigger::TakeProfit { stop_price }) =
+            trigger;
+        body["orderFilter"] = json!("StopOrder");
+        body["triggerPrice"] = json!(stop_price.to_string());
+    }
+    body
+}
+
+// ─── Response types ───────────────────────────────────────────────────────────
+
+/// Envelope around every Bybit v5 response.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    #[serde(rename = "retCode")]
+    ret_code: i64,
+    #[serde(rename = "retMsg", default)]
+    ret_msg: String,
+    #[serde(default = "Option::default")]
+    result: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct ListResult<T> {
+    #[serde(default = "Vec::new")]
+    list: Vec<T>,
+}
+
+/// Body of order create/cancel results; only success matters.
+#[derive(Deserialize)]
+struct OrderAck {}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderDetail {
+    #[serde(default)]
+    order_id: String,
+    order_status: String,
+    #[serde(default)]
+    cum_exec_qty: String,
+    #[serde(default)]
+    avg_price: String,
+}
+
+#[derive(Deserialize)]
+struct WalletAccount {
+    #[serde(default)]
+    coin: Vec<WalletCoin>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WalletCoin {
+    coin: String,
+    #[serde(default)]
+    wallet_balance: String,
+    /// Held by open orders.
+    #[serde(default)]
+    locked: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker {
+    last_price: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn order_request_by_order_kind() {
+        let market = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
+        let body = order_request(&market);
+        assert_eq!(body["orderType"], "Market");
+        assert_eq!(body["marketUnit"], "baseCoin");
+        assert_eq!(body["side"], "Buy");
+
+        let spend = Order::market_quote("BTCUSDT", OrderSide::Buy, dec!(50));
+        let body = order_request(&spend);
+        assert_eq!(body["marketUnit"], "quoteCoin");
+        assert_eq!(body["qty"], "50");
+
+        let mut stop = Order::market("BTCUSDT", OrderSide::Sell, dec!(0.01));
+        stop.price = Some(dec!(29000));
+        stop.trigger = Some(OrderTrigger::StopLoss {
+            stop_price: dec!(29100),
+        });
+        let body = order_request(&stop);
+        assert_eq!(body["orderType"], "Limit");
+        assert_eq!(body["orderFilter"], "StopOrder");
+        assert_eq!(body["triggerPrice"], "29100");
+    }
+}
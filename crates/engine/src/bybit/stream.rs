@@ -0,0 +1,426 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+use url::Url;
+
+use common::{KlineInterval, MarketEvent, Result, TradeEvent};
+
+use crate::feed::{ConnectionEvent, StreamControl, StreamHandle};
+
+const WS_URL: &str = "wss://stream.bybit.com/v5/public/spot";
+
+/// Bybit drops connections that stay silent for longer than this.
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Most topics Bybit's spot stream accepts in one subscribe request.
+const MAX_TOPICS_PER_REQUEST: usize = 10;
+
+/// Bybit v5 public spot WebSocket stream for all pairs.
+///
+/// Holds a single connection with a kline topic per pair at that pair's
+/// interval and a level-1 order book topic whose best bid/ask is attached to
+/// the pair's `MarketEvent`s. Pairs enabled with `set_trades` also get a
+/// `publicTrade` topic published as `TradeEvent`s. Mirrors `BinanceStream`:
+/// pairs can be changed at runtime through a `StreamHandle`, and the stream
+/// reconnects automatically with exponential backoff.
+pub struct BybitStream {
+    /// Uppercase symbols currently subscribed and their intervals (kept
+    /// across reconnects).
+    pairs: BTreeMap<String, KlineInterval>,
+    market_tx: broadcast::Sender<MarketEvent>,
+    /// Uppercase symbols that also stream trades.
+    trade_pairs: BTreeSet<String>,
+    trade_tx: Option<broadcast::Sender<TradeEvent>>,
+    /// Latest best (bid, ask) per uppercase symbol.
+    quotes: HashMap<String, (f64, f64)>,
+    /// Notified of every connect, reconnect and disconnect.
+    event_tx: Option<mpsc::UnboundedSender<ConnectionEvent>>,
+    connected_once: bool,
+    control_rx: mpsc::UnboundedReceiver<StreamControl>,
+}
+
+impl BybitStream {
+    pub fn new(
+        pairs: impl IntoIterator<Item = (String, KlineInterval)>,
+        market_tx: broadcast::Sender<MarketEvent>,
+    ) -> (Self, StreamHandle) {
+        let (handle, control_rx) = StreamHandle::channel();
+        let stream = Self {
+            pairs: pairs
+                .into_iter()
+                .map(|(p, interval)| (p.to_uppercase(), interval))
+                .collect(),
+            market_tx,
+            trade_pairs: BTreeSet::new(),
+            trade_tx: None,
+            quotes: HashMap::new(),
+            event_tx: None,
+            connected_once: false,
+            control_rx,
+        };
+        (stream, handle)
+    }
+
+    /// Also stream trades for `pairs`, publishing on `trade_tx`.
+    pub fn set_trades(
+        &mut self,
+        pairs: impl IntoIterator<Item = String>,
+        trade_tx: broadcast::Sender<TradeEvent>,
+    ) {
+        self.trade_pairs = pairs.into_iter().map(|p| p.to_uppercase()).collect();
+        self.trade_tx = Some(trade_tx);
+    }
+
+    /// Report connection changes on `event_tx`.
+    pub fn set_event_notifier(&mut self, event_tx: mpsc::UnboundedSender<ConnectionEvent>) {
+        self.event_tx = Some(event_tx);
+    }
+
+    /// Run the stream loop forever, reconnecting on failure.
+    /// Call this inside a `tokio::spawn`.
+    pub async fn run(mut self) {
+        let mut backoff = Duration::from_secs(1);
+        const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+        loop {
+            info!(pairs = ?self.pairs, "Connecting to Bybit WebSocket stream");
+            match self.connect_once().await {
+                Ok(()) => {
+                    info!("WebSocket stream closed cleanly");
+                    self.notify(ConnectionEvent::Disconnected("closed by server".into()));
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    backoff = Duration::from_secs(1);
+                }
+                Err(e) => {
+                    warn!(error = %e, backoff = ?backoff, "WebSocket error, reconnecting");
+                    self.notify(ConnectionEvent::Disconnected(e.to_string()));
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    fn notify(&self, event: ConnectionEvent) {
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    async fn connect_once(&mut self) -> Result<()> {
+        let url = Url::parse(WS_URL).map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        let (ws_stream, _) = connect_async(url)
+            .await
+            .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        self.notify(if self.connected_once {
+            ConnectionEvent::Reconnected
+        } else {
+            ConnectionEvent::Connected
+        });
+        self.connected_once = true;
+
+        let mut topics: Vec<String> = self
+            .pairs
+            .iter()
+            .flat_map(|(pair, interval)| [kline_topic(pair, *interval), book_topic(pair)])
+            .collect();
+        if self.trade_tx.is_some() {
+            topics.extend(self.trade_pairs.iter().map(|pair| trade_topic(pair)));
+        }
+        for chunk in topics.chunks(MAX_TOPICS_PER_REQUEST) {
+            let request = serde_json::json!({ "op": "subscribe", "args": chunk });
+            write
+                .send(Message::Text(request.to_string()))
+                .await
+                .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        }
+
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    let Some(msg) = msg else { break };
+                    let msg = msg.map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                    if let Message::Text(text) = msg {
+                        // Ignore send errors (no active receivers)
+                        match parse_message(&text) {
+                            Ok(events) => {
+                                for event in events {
+                                    match event {
+                                        StreamEvent::Kline(mut event) => {
+                                            if let Some(&(bid, ask)) = self.quotes.get(&event.pair) {
+                                                event.best_bid = Some(bid);
+                                                event.best_ask = Some(ask);
+                                            }
+                                            let _ = self.market_tx.send(event);
+                                        }
+                                        StreamEvent::Quote { pair, bid, ask } => {
+                                            self.quotes.insert(pair, (bid, ask));
+                                        }
+                                        StreamEvent::Trade(event) => {
+                                            if let Some(tx) = &self.trade_tx {
+                                                let _ = tx.send(event);
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            Err(e) => warn!(error = %e, "Failed to parse Bybit message"),
+                        }
+                    }
+                }
+                _ = ping.tick() => {
+                    let request = serde_json::json!({ "op": "ping" });
+                    write
+                        .send(Message::Text(request.to_string()))
+                        .await
+                        .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                }
+                Some(control) = self.control_rx.recv() => {
+                    let mut requests: Vec<(&str, String)> = Vec::new();
+                    match control {
+                        StreamControl::Subscribe(pair, interval) => {
+                            let pair = pair.to_uppercase();
+                            let previous = self.pairs.insert(pair.clone(), interval);
+                            if previous == Some(interval) {
+                                continue;
+                            }
+                            // Switching interval: drop the old topic first
+                            match previous {
+                                Some(old) => {
+                                    requests.push(("unsubscribe", kline_topic(&pair, old)));
+                                    requests.push(("subscribe", kline_topic(&pair, interval)));
+                                }
+                                None => {
+                                    requests.push(("subscribe", kline_topic(&pair, interval)));
+                                    requests.push(("subscribe", book_topic(&pair)));
+                                }
+                            }
+                        }
+                        StreamControl::Unsubscribe(pair) => {
+                            let pair = pair.to_uppercase();
+                            if let Some(old) = self.pairs.remove(&pair) {
+                                requests.push(("unsubscribe", kline_topic(&pair, old)));
+                                requests.push(("unsubscribe", book_topic(&pair)));
+                                self.quotes.remove(&pair);
+                            }
+                            if self.trade_pairs.remove(&pair) && self.trade_tx.is_some() {
+                                requests.push(("unsubscribe", trade_topic(&pair)));
+                            }
+                        }
+                        StreamControl::Reconnect => {
+                            return Err(common::Error::WebSocket(
+                                "reconnect requested".into(),
+                            ));
+                        }
+                    }
+                    for (op, topic) in requests {
+                        info!(topic = %topic, op, "Updating stream subscription");
+                        let request = serde_json::json!({ "op": op, "args": [topic] });
+                        write
+                            .send(Message::Text(request.to_string()))
+                            .await
+                            .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Bybit kline interval code: minutes as a bare number.
+fn interval_code(interval: KlineInterval) -> &'static str {
+    match interval {
+        KlineInterval::OneMinute => "1",
+        KlineInterval::FiveMinutes => "5",
+        KlineInterval::FifteenMinutes => "15",
+        KlineInterval::OneHour => "60",
+    }
+}
+
+/// Bybit kline topic for a pair, e.g. `kline.5.BTCUSDT`.
+fn kline_topic(pair: &str, interval: KlineInterval) -> String {
+    format!("kline.{}.{pair}", interval_code(interval))
+}
+
+/// Bybit level-1 order book topic for a pair, e.g. `orderbook.1.BTCUSDT`.
+fn book_topic(pair: &str) -> String {
+    format!("orderbook.1.{pair}")
+}
+
+/// Bybit public trade topic for a pair, e.g. `publicTrade.BTCUSDT`.
+fn trade_topic(pair: &str) -> String {
+    format!("publicTrade.{pair}")
+}
+
+// ─── Bybit stream JSON parsing ───────────────────────────────────────────────
+
+/// A parsed update from one of the subscribed topics.
+enum StreamEvent {
+    Kline(MarketEvent),
+    Trade(TradeEvent),
+    Quote { pair: String, bid: f64, ask: f64 },
+}
+
+#[derive(Deserialize)]
+struct KlineData {
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+    /// Whether the candle is closed.
+    confirm: bool,
+    /// Candle close time, ms.
+    end: i64,
+}
+
+#[derive(Deserialize)]
+struct BookData {
+    #[serde(rename = "b")]
+    bids: Vec<(String, String)>,
+    #[serde(rename = "a")]
+    asks: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct TradeData {
+    #[serde(rename = "i")]
+    trade_id: String,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "v")]
+    quantity: String,
+    /// Taker side.
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "T")]
+    trade_time_ms: i64,
+}
+
+/// Parse a topic message (`{"topic": "...", "data": ...}`), taking the pair
+/// (and interval) from the topic. Pongs and subscription acks carry no topic
+/// and yield nothing.
+fn parse_message(text: &str) -> Result<Vec<StreamEvent>> {
+    let mut wrapper: serde_json::Value = serde_json::from_str(text)?;
+    let Some(topic) = wrapper
+        .get("topic")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+    else {
+        return Ok(Vec::new());
+    };
+    let parts: Vec<&str> = topic.split('.').collect();
+    let data = wrapper["data"].take();
+
+    match parts.as_slice() {
+        ["kline", code, pair] => {
+            let Some(interval) = [
+                KlineInterval::OneMinute,
+                KlineInterval::FiveMinutes,
+                KlineInterval::FifteenMinutes,
+                KlineInterval::OneHour,
+            ]
+            .into_iter()
+            .find(|i| interval_code(*i) == *code) else {
+                return Ok(Vec::new());
+            };
+            let klines: Vec<KlineData> = serde_json::from_value(data)?;
+            Ok(klines
+                .into_iter()
+                .map(|k| {
+                    StreamEvent::Kline(MarketEvent {
+                        pair: pair.to_string(),
+                        interval,
+                        price: k.close.parse().unwrap_or(0.0),
+                        open: k.open.parse().unwrap_or(0.0),
+                        high: k.high.parse().unwrap_or(0.0),
+                        low: k.low.parse().unwrap_or(0.0),
+                        volume: k.volume.parse().unwrap_or(0.0),
+                        is_candle_closed: k.confirm,
+                        timestamp: Utc
+                            .timestamp_millis_opt(k.end)
+                            .single()
+                            .unwrap_or_else(Utc::now),
+                        best_bid: None,
+                        best_ask: None,
+                    })
+                })
+                .collect())
+        }
+        ["orderbook", _, pair] => {
+            let book: BookData = serde_json::from_value(data)?;
+            let best = |levels: &[(String, String)]| {
+                levels
+                    .first()
+                    .and_then(|(price, _)| price.parse::<f64>().ok())
+            };
+            Ok(match (best(&book.bids), best(&book.asks)) {
+                (Some(bid), Some(ask)) => vec![StreamEvent::Quote {
+                    pair: pair.to_string(),
+                    bid,
+                    ask,
+                }],
+                // One-sided update: keep the previous quote
+                _ => Vec::new(),
+            })
+        }
+        ["publicTrade", pair] => {
+            let trades: Vec<TradeData> = serde_json::from_value(data)?;
+            Ok(trades
+                .into_iter()
+                .map(|t| {
+                    StreamEvent::Trade(TradeEvent {
+                        pair: pair.to_string(),
+                        trade_id: t.trade_id.parse().unwrap_or(0),
+                        price: t.price.parse().unwrap_or(0.0),
+                        quantity: t.quantity.parse().unwrap_or(0.0),
+                        // A taker sell means the buyer was resting
+                        is_buyer_maker: t.side == "Sell",
+                        timestamp: Utc
+                            .timestamp_millis_opt(t.trade_time_ms)
+                            .single()
+                            .unwrap_or_else(Utc::now),
+                    })
+                })
+                .collect())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kline_message_is_routed_by_topic() {
+        let text = r#"{"topic":"kline.15.ETHUSDT","data":[{"start":1699999100000,"end":1700000000000,"interval":"15","open":"1.0","close":"1.5","high":"2.0","low":"0.5","volume":"10","turnover":"15","confirm":true,"timestamp":1700000000000}],"ts":1700000000000,"type":"snapshot"}"#;
+        let events = parse_message(text).unwrap();
+        let [StreamEvent::Kline(event)] = events.as_slice() else {
+            panic!("expected one kline event");
+        };
+        assert_eq!(event.pair, "ETHUSDT");
+        assert_eq!(event.interval, KlineInterval::FifteenMinutes);
+        assert_eq!(event.price, 1.5);
+        assert!(event.is_candle_closed);
+
+        // Pongs carry no topic and are skipped
+        assert!(
+            parse_message(r#"{"success":true,"ret_msg":"pong","op":"ping"}"#)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
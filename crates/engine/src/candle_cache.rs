@@ -0,0 +1,135 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use common::MarketEvent;
+
+/// Closed candles kept per pair unless configured otherwise.
+pub const DEFAULT_CANDLE_CACHE_SIZE: usize = 500;
+
+#[derive(Debug)]
+struct Inner {
+    capacity: usize,
+    candles: HashMap<String, VecDeque<MarketEvent>>,
+}
+
+/// The last closed candles of every streamed pair, so components that start
+/// or reconnect late (strategy warm-up, dashboard charts) can catch up
+/// without waiting for fresh candles or hitting the REST API. Written by the
+/// engine, read through `EngineHandle::recent_candles`. Cheap to clone.
+#[derive(Clone)]
+pub struct CandleCache {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for CandleCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CANDLE_CACHE_SIZE)
+    }
+}
+
+impl CandleCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                capacity,
+                candles: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Change how many candles are kept per pair, trimming if needed.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.capacity = capacity;
+        for candles in inner.candles.values_mut() {
+            while candles.len() > capacity {
+                candles.pop_front();
+            }
+        }
+    }
+
+    /// Record a market event. Only closed candles are kept; a candle on a new
+    /// interval replaces the pair's history, and a repeat of the newest
+    /// candle (e.g. after a reconnect) overwrites it.
+    pub fn push(&self, event: &MarketEvent) {
+        if !event.is_candle_closed {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let capacity = inner.capacity;
+        if capacity == 0 {
+            return;
+        }
+        let candles = inner.candles.entry(event.pair.clone()).or_default();
+        if candles.back().is_some_and(|c| c.interval != event.interval) {
+            candles.clear();
+        }
+        if candles
+            .back()
+            .is_some_and(|c| c.timestamp == event.timestamp)
+        {
+            candles.pop_back();
+        }
+        candles.push_back(event.clone());
+        if candles.len() > capacity {
+            candles.pop_front();
+        }
+    }
+
+    /// Cached closed candles for `pair`, oldest first.
+    pub fn recent(&self, pair: &str) -> Vec<MarketEvent> {
+        self.inner
+            .lock()
+            .unwrap()
+            .candles
+            .get(pair)
+            .map(|c| c.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget `pair`'s candles.
+    pub fn remove(&self, pair: &str) {
+        self.inner.lock().unwrap().candles.remove(pair);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use common::KlineInterval;
+
+    fn candle(minute: i64, interval: KlineInterval, closed: bool) -> MarketEvent {
+        MarketEvent {
+            pair: "BTCUSDT".into(),
+            interval,
+            price: 100.0 + minute as f64,
+            open: 100.0,
+            high: 101.0,
+            low: 99.0,
+            volume: 1.0,
+            is_candle_closed: closed,
+            timestamp: Utc.timestamp_opt(0, 0).unwrap() + Duration::minutes(minute),
+            best_bid: None,
+            best_ask: None,
+        }
+    }
+
+    #[test]
+    fn keeps_last_closed_candles_per_interval() {
+        let cache = CandleCache::new(2);
+        cache.push(&candle(0, KlineInterval::OneMinute, true));
+        cache.push(&candle(1, KlineInterval::OneMinute, false));
+        cache.push(&candle(1, KlineInterval::OneMinute, true));
+        cache.push(&candle(1, KlineInterval::OneMinute, true));
+        cache.push(&candle(2, KlineInterval::OneMinute, true));
+        let recent = cache.recent("BTCUSDT");
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].price, 101.0);
+        assert_eq!(recent[1].price, 102.0);
+
+        cache.push(&candle(5, KlineInterval::FiveMinutes, true));
+        assert_eq!(cache.recent("BTCUSDT").len(), 1);
+        assert!(cache.recent("ETHUSDT").is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tokio::sync::broadcast;
+use tracing::{error, warn};
+
+use common::{KlineInterval, MarketEvent};
+
+/// Keeps the closed candles of every streamed pair in the `candles` table,
+/// so a restart can warm the candle cache and strategies from local data,
+/// and backtests can run against recently seen markets. Old candles are
+/// deleted by [`common::retention::DataRetention`].
+#[derive(Clone)]
+pub struct CandleStore {
+    db: SqlitePool,
+}
+
+impl CandleStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// The last `limit` stored candles of `pair` on `interval`, oldest first.
+    pub async fn recent(
+        &self,
+        pair: &str,
+        interval: KlineInterval,
+        limit: usize,
+    ) -> Result<Vec<MarketEvent>, sqlx::Error> {
+        let interval_str = interval.as_str();
+        let limit = limit as i64;
+        let rows = sqlx::query!(
+            r#"SELECT open, high, low, close, volume, closed_at FROM candles
+               WHERE pair = ?1 AND interval = ?2
+               ORDER BY closed_at DESC LIMIT ?3"#,
+            pair,
+            interval_str,
+            limit,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .rev()
+            .filter_map(|row| {
+                let timestamp = DateTime::parse_from_rfc3339(&row.closed_at).ok()?;
+                Some(MarketEvent {
+                    pair: pair.to_string(),
+                    interval,
+                    price: row.close,
+                    open: row.open,
+                    high: row.high,
+                    low: row.low,
+                    volume: row.volume,
+                    is_candle_closed: true,
+                    timestamp: timestamp.with_timezone(&Utc),
+                    best_bid: None,
+                    best_ask: None,
+                })
+            })
+            .collect())
+    }
+
+    /// Write the closed candles from `market_rx` until it closes. Call from
+    /// `tokio::spawn`.
+    pub async fn run(self, mut market_rx: broadcast::Receiver<MarketEvent>) {
+        loop {
+            match market_rx.recv().await {
+                Ok(event) if event.is_candle_closed => self.record(&event).await,
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(dropped = n, "Candle store lagged — candles not stored");
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Store one closed candle. Failures are logged, never propagated.
+    async fn record(&self, event: &MarketEvent) {
+        let interval = event.interval.as_str();
+        let closed_at = event.timestamp.to_rfc3339();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO candles (pair, interval, open, high, low, close, volume, closed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(pair, interval, closed_at) DO UPDATE SET
+                open = excluded.open, high = excluded.high, low = excluded.low,
+                close = excluded.close, volume = excluded.volume
+            "#,
+            event.pair,
+            interval,
+            event.open,
+            event.high,
+            event.low,
+            event.price,
+            event.volume,
+            closed_at,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(pair = %event.pair, error = %e, "Failed to store candle");
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod rest;
+mod stream;
+
+pub use rest::CoinbaseClient;
+pub use stream::CoinbaseStream;
@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use common::symbol::to_coinbase_product;
+use common::{
+    Balance, Error, ExchangeClient, Fill, Order, OrderSide, OrderStatus, OrderStatusReport,
+    OrderTrigger, Position, Result, TradingMode,
+};
+
+const BASE_URL: &str = "https://api.coinbase.com";
+const API_PREFIX: &str = "/api/v3/brokerage";
+
+/// Currencies treated as cash rather than positions when reading balances.
+const CASH_CURRENCIES: &[&str] = &["USD", "USDC", "USDT", "EUR", "GBP"];
+
+/// REST API client for Coinbase Advanced Trade. Used for order placement and
+/// account queries.
+///
+/// Authenticates with an API key/secret pair using the HMAC-SHA256 request
+/// signature (`CB-ACCESS-*` headers). Pairs are given in the internal form
+/// (`BTCUSD`) and mapped to product IDs (`BTC-USD`) at the edge.
+pub struct CoinbaseClient {
+    api_key: String,
+    secret: String,
+    http: Client,
+    /// Exchange order ID for each client order ID submitted by this client.
+    order_ids: Mutex<HashMap<String, String>>,
+}
+
+impl CoinbaseClient {
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            secret: secret.into(),
+            http: Client::builder()
+                .use_rustls_tls()
+                .build()
+                .expect("Failed to build HTTP client"),
+            order_ids: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn timestamp_secs() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Signature over `timestamp + method + path + body`; the path excludes
+    /// the query string.
+    fn sign(&self, timestamp: u64, method: &Method, path: &str, body: &str) -> String {
+        type HmacSha256 = Hmac<Sha256>;
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts any key length");
+        mac.update(format!("{timestamp}{method}{path}{body}").as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Send a signed request to `API_PREFIX + path` and map non-2xx
+    /// responses to errors. 429 and 5xx responses are reported as transient.
+    async fn signed_send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &str,
+        body: Option<String>,
+    ) -> Result<String> {
+        let path = format!("{API_PREFIX}{path}");
+        let body = body.unwrap_or_default();
+        let ts = Self::timestamp_secs();
+        let signature = self.sign(ts, &method, &path, &body);
+        let url = if query.is_empty() {
+            format!("{BASE_URL}{path}")
+        } else {
+            format!("{BASE_URL}{path}?{query}")
+        };
+
+        let mut request = self
+            .http
+            .request(method, &url)
+            .header("CB-ACCESS-KEY", &self.api_key)
+            .header("CB-ACCESS-SIGN", signature)
+            .header("CB-ACCESS-TIMESTAMP", ts.to_string());
+        if !body.is_empty() {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| Error::Http(e.to_string()))?;
+        let status = resp.status();
+        let text = resp.text().await.map_err(|e| Error::Http(e.to_string()))?;
+        if status.as_u16() == 429 {
+            warn!("Coinbase rate limit hit");
+            return Err(Error::ExchangeUnavailable(format!("HTTP {status}: {text}")));
+        }
+        if status.is_server_error() {
+            return Err(Error::ExchangeUnavailable(format!("HTTP {status}: {text}")));
+        }
+        if !status.is_success() {
+            return Err(Error::Exchange(format!("HTTP {status}: {text}")));
+        }
+        Ok(text)
+    }
+
+    /// Exchange order ID for a client order ID submitted by this client.
+    fn exchange_order_id(&self, order_id: &str) -> Result<String> {
+        self.order_ids
+            .lock()
+            .unwrap()
+            .get(order_id)
+            .cloned()
+            .ok_or_else(|| Error::Exchange(format!("unknown client order ID '{order_id}'")))
+    }
+
+    async fn fetch_order(&self, exchange_order_id: &str) -> Result<OrderStatusReport> {
+        let body = self
+            .signed_send(
+                Method::GET,
+                &format!("/orders/historical/{exchange_order_id}"),
+                "",
+                None,
+            )
+            .await?;
+        let resp: OrderQueryResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+
+        let executed_quantity = resp
+            .order
+            .filled_size
+            .parse::<Decimal>()
+            .unwrap_or_default();
+        let status = match resp.order.status.as_str() {
+            "PENDING" | "QUEUED" | "OPEN" if executed_quantity > Decimal::ZERO => {
+                OrderStatus::PartiallyFilled
+            }
+            "PENDING" | "QUEUED" | "OPEN" => OrderStatus::New,
+            "FILLED" => OrderStatus::Filled,
+            "CANCELLED" | "CANCEL_QUEUED" => OrderStatus::Canceled,
+            "EXPIRED" => OrderStatus::Expired,
+            "FAILED" => OrderStatus::Rejected,
+            other => return Err(Error::Exchange(format!("unknown order status '{other}'"))),
+        };
+        let average_price = resp
+            .order
+            .average_filled_price
+            .parse::<Decimal>()
+            .ok()
+            .filter(|p| *p > Decimal::ZERO);
+
+        Ok(OrderStatusReport {
+            status,
+            executed_quantity,
+            average_price,
+            exchange_order_id: Some(exchange_order_id.to_string()),
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for CoinbaseClient {
+    async fn submit_order(&self, order: &Order) -> Result<Fill> {
+        let product_id = to_coinbase_product(&order.pair);
+        // Our order ID doubles as the client order ID, making retries idempotent
+        let body = json!({
+            "client_order_id": order.id,
+            "product_id": product_id,
+            "side": order.side.to_string(),
+            "order_configuration": order_configuration(order),
+        });
+
+        debug!(pair = %order.pair, side = %order.side, "Submitting order to Coinbase");
+        let body = self
+            .signed_send(Method::POST, "/orders", "", Some(body.to_string()))
+            .await?;
+        let resp: CreateOrderResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+        let exchange_order_id = match (resp.success, resp.success_response) {
+            (true, Some(ok)) => ok.order_id,
+            _ => {
+                let reason = resp
+                    .error_response
+                    .map(|e| format!("{}: {}", e.error, e.message))
+                    .unwrap_or_else(|| "order not accepted".into());
+                return Err(Error::Exchange(reason));
+            }
+        };
+        self.order_ids
+            .lock()
+            .unwrap()
+            .insert(order.id.clone(), exchange_order_id.clone());
+
+        // Market orders usually settle immediately; anything still working is
+        // reported with zero quantity so the executor tracks it as resting.
+        let report = self.fetch_order(&exchange_order_id).await?;
+        Ok(Fill {
+            order_id: order.id.clone(),
+            exchange_order_id: Some(exchange_order_id),
+            pair: order.pair.clone(),
+            side: order.side,
+            fill_price: report.average_price.or(order.price).unwrap_or_default(),
+            quantity: report.executed_quantity,
+            fee: Decimal::ZERO,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn open_positions(&self) -> Result<Vec<Position>> {
+        // Non-cash balances are reported as long pseudo-positions quoted in USD
+        let positions = self
+            .balances()
+            .await?
+            .into_iter()
+            .filter(|b| !CASH_CURRENCIES.contains(&b.asset.as_str()))
+            .map(|b| Position {
+                id: uuid::Uuid::new_v4().to_string(),
+                pair: format!("{}USD", b.asset),
+                side: OrderSide::Buy,
+                entry_price: Decimal::ZERO, // unknown without fill history
+                quantity: b.total(),
+                mode: TradingMode::Live,
+                opened_at: Utc::now(),
+            })
+            .collect();
+
+        Ok(positions)
+    }
+
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let body = self
+            .signed_send(Method::GET, "/accounts", "limit=250", None)
+            .await?;
+        let resp: AccountsResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+
+        let balances = resp
+            .accounts
+            .into_iter()
+            .map(|a| Balance {
+                asset: a.currency,
+                free: a.available_balance.value.parse().unwrap_or_default(),
+                locked: a.hold.value.parse().unwrap_or_default(),
+            })
+            .filter(|b| b.total() > Decimal::ZERO)
+            .collect();
+
+        Ok(balances)
+    }
+
+    async fn order_status(&self, _pair: &str, order_id: &str) -> Result<OrderStatusReport> {
+        let exchange_order_id = self.exchange_order_id(order_id)?;
+        self.fetch_order(&exchange_order_id).await
+    }
+
+    async fn cancel_order(&self, pair: &str, order_id: &str) -> Result<()> {
+        let exchange_order_id = self.exchange_order_id(order_id)?;
+        let body = json!({ "order_ids": [exchange_order_id] });
+        debug!(pair = %pair, order_id = %order_id, "Cancelling order on Coinbase");
+        let body = self
+            .signed_send(
+                Method::POST,
+                "/orders/batch_cancel",
+                "",
+                Some(body.to_string()),
+            )
+            .await?;
+        let resp: CancelOrdersResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+        match resp.results.into_iter().next() {
+            Some(result) if result.success => Ok(()),
+            Some(result) => Err(Error::Exchange(format!(
+                "cancel rejected: {}",
+                result.failure_reason
+            ))),
+            None => Err(Error::Exchange("empty cancel response".into())),
+        }
+    }
+
+    async fn current_price(&self, pair: &str) -> Result<Decimal> {
+        let product_id = to_coinbase_product(pair);
+        let body = self
+            .signed_send(Method::GET, &format!("/products/{product_id}"), "", None)
+            .await?;
+        let product: ProductResponse =
+            serde_json::from_str(&body).map_err(|e| Error::Exchange(e.to_string()))?;
+
+        product
+            .price
+            .parse::<Decimal>()
+            .map_err(|e| Error::Exchange(e.to_string()))
+    }
+}
+
+/// Coinbase `order_configuration` for `order`, from its trigger and whether
+/// it has a limit. Triggered orders become stop-limit orders whose limit
+/// defaults to the stop price.
+fn order_configuration(order: &Order) -> serde_json::Value {
+    let base_size = order.quantity.to_string();
+    match (order.trigger, order.price) {
+        (None, None) => match order.quote_quantity {
+            Some(quote) => json!({ "market_market_ioc": { "quote_size": quote.to_string() } }),
+            None => json!({ "market_market_ioc": { "base_size": base_size } }),
+        },
+        (None, Some(limit)) => json!({
+            "limit_limit_gtc": {
+                "base_size": base_size,
+                "limit_price": limit.to_string(),
+                "post_only": false,
+            }
+        }),
+        (Some(trigger), limit) => {
+            let (stop_price, falling) = match trigger {
+                // A sell stop-loss fires on a fall, a buy stop-loss on a rise;
+                // take-profits the other way round
+                OrderTrigger::StopLoss { stop_price } => {
+                    (stop_price, order.side == OrderSide::Sell)
+                }
+                OrderTrigger::TakeProfit { stop_price } => {
+                    (stop_price, order.side == OrderSide::Buy)
+                }
+            };
+            let direction = if falling {
+                "STOP_DIRECTION_STOP_DOWN"
+            } else {
+                "STOP_DIRECTION_STOP_UP"
+            };
+            json!({
+                "stop_limit_stop_limit_gtc": {
+                    "base_size": base_size,
+                    "limit_price": limit.unwrap_or(stop_price).to_string(),
+                    "stop_price": stop_price.to_string(),
+                    "stop_direction": direction,
+                }
+            })
+        }
+    }
+}
+
+// ─── Response types ───────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct CreateOrderResponse {
+    success: bool,
+    #[serde(default)]
+    success_response: Option<CreateOrderSuccess>,
+    #[serde(default)]
+    error_response: Option<CreateOrderError>,
+}
+
+#[derive(Deserialize)]
+struct CreateOrderSuccess {
+    order_id: String,
+}
+
+#[derive(Deserialize)]
+struct CreateOrderError {
+    #[serde(default)]
+    error: String,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct OrderQueryResponse {
+    order: OrderDetail,
+}
+
+#[derive(Deserialize)]
+struct OrderDetail {
+    status: String,
+    #[serde(default)]
+    filled_size: String,
+    #[serde(default)]
+    average_filled_price: String,
+}
+
+#[derive(Deserialize)]
+struct CancelOrdersResponse {
+    #[serde(default)]
+    results: Vec<CancelResult>,
+}
+
+#[derive(Deserialize)]
+struct CancelResult {
+    success: bool,
+    #[serde(default)]
+    failure_reason: String,
+}
+
+#[derive(Deserialize)]
+struct AccountsResponse {
+    accounts: Vec<Account>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    currency: String,
+    available_balance: Amount,
+    hold: Amount,
+}
+
+#[derive(Deserialize)]
+struct Amount {
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct ProductResponse {
+    price: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn order_configuration_by_order_kind() {
+        let market = Order::market("BTCUSD", OrderSide::Buy, dec!(0.01));
+        assert_eq!(
+            order_configuration(&market)["market_market_ioc"]["base_size"],
+            "0.01"
+        );
+
+        let spend = Order::market_quote("BTCUSD", OrderSide::Buy, dec!(50));
+        assert_eq!(
+            order_configuration(&spend)["market_market_ioc"]["quote_size"],
+            "50"
+        );
+
+        let mut limit = Order::market("BTCUSD", OrderSide::Buy, dec!(0.01));
+        limit.price = Some(dec!(30000));
+        assert_eq!(
+            order_configuration(&limit)["limit_limit_gtc"]["limit_price"],
+            "30000"
+        );
+
+        let mut stop = Order::market("BTCUSD", OrderSide::Sell, dec!(0.01));
+        stop.trigger = Some(OrderTrigger::StopLoss {
+            stop_price: dec!(29000),
+        });
+        let config = order_configuration(&stop);
+        assert_eq!(
+            config["stop_limit_stop_limit_gtc"]["stop_direction"],
+            "STOP_DIRECTION_STOP_DOWN"
+        );
+        assert_eq!(config["stop_limit_stop_limit_gtc"]["limit_price"], "29000");
+    }
+}
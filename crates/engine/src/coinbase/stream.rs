@@ -0,0 +1,442 @@
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+use url::Url;
+
+use common::symbol::{from_coinbase_product, to_coinbase_product};
+use common::{KlineInterval, MarketEvent, Result, TradeEvent};
+
+use crate::feed::{ConnectionEvent, StreamControl, StreamHandle};
+
+const WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
+
+/// The only candle granularity Coinbase's WebSocket publishes.
+const CANDLE_INTERVAL: KlineInterval = KlineInterval::FiveMinutes;
+
+/// Coinbase Advanced Trade market data stream for all pairs.
+///
+/// Holds a single connection subscribed to the `candles` and `ticker`
+/// channels for every pair, plus `market_trades` for pairs enabled with
+/// `set_trades`, and publishes `MarketEvent`s / `TradeEvent`s like
+/// `BinanceStream`. Coinbase only streams 5-minute candles, so every pair
+/// uses that interval, and a candle is reported closed when the next one
+/// starts. Reconnects automatically with exponential backoff.
+pub struct CoinbaseStream {
+    /// Internal pair names currently subscribed (kept across reconnects).
+    pairs: BTreeSet<String>,
+    market_tx: broadcast::Sender<MarketEvent>,
+    /// Internal pair names that also stream trades.
+    trade_pairs: BTreeSet<String>,
+    trade_tx: Option<broadcast::Sender<TradeEvent>>,
+    /// Latest best (bid, ask) per pair.
+    quotes: HashMap<String, (f64, f64)>,
+    /// Latest candle per pair, published as closed once a newer one starts.
+    candles: HashMap<String, MarketEvent>,
+    /// Notified of every connect, reconnect and disconnect.
+    event_tx: Option<mpsc::UnboundedSender<ConnectionEvent>>,
+    connected_once: bool,
+    control_rx: mpsc::UnboundedReceiver<StreamControl>,
+}
+
+impl CoinbaseStream {
+    pub fn new(
+        pairs: impl IntoIterator<Item = (String, KlineInterval)>,
+        market_tx: broadcast::Sender<MarketEvent>,
+    ) -> (Self, StreamHandle) {
+        let (handle, control_rx) = StreamHandle::channel();
+        let pairs = pairs
+            .into_iter()
+            .map(|(pair, interval)| {
+                warn_unsupported_interval(&pair, interval);
+                pair.to_uppercase()
+            })
+            .collect();
+        let stream = Self {
+            pairs,
+            market_tx,
+            trade_pairs: BTreeSet::new(),
+            trade_tx: None,
+            quotes: HashMap::new(),
+            candles: HashMap::new(),
+            event_tx: None,
+            connected_once: false,
+            control_rx,
+        };
+        (stream, handle)
+    }
+
+    /// Also stream trades for `pairs`, publishing on `trade_tx`.
+    pub fn set_trades(
+        &mut self,
+        pairs: impl IntoIterator<Item = String>,
+        trade_tx: broadcast::Sender<TradeEvent>,
+    ) {
+        self.trade_pairs = pairs.into_iter().map(|p| p.to_uppercase()).collect();
+        self.trade_tx = Some(trade_tx);
+    }
+
+    /// Report connection changes on `event_tx`.
+    pub fn set_event_notifier(&mut self, event_tx: mpsc::UnboundedSender<ConnectionEvent>) {
+        self.event_tx = Some(event_tx);
+    }
+
+    /// Run the stream loop forever, reconnecting on failure.
+    /// Call this inside a `tokio::spawn`.
+    pub async fn run(mut self) {
+        let mut backoff = Duration::from_secs(1);
+        const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+        loop {
+            info!(pairs = ?self.pairs, "Connecting to Coinbase WebSocket stream");
+            match self.connect_once().await {
+                Ok(()) => {
+                    info!("WebSocket stream closed cleanly");
+                    self.notify(ConnectionEvent::Disconnected("closed by server".into()));
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    backoff = Duration::from_secs(1);
+                }
+                Err(e) => {
+                    warn!(error = %e, backoff = ?backoff, "WebSocket error, reconnecting");
+                    self.notify(ConnectionEvent::Disconnected(e.to_string()));
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    fn notify(&self, event: ConnectionEvent) {
+        if let Some(tx) = &self.event_tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    async fn connect_once(&mut self) -> Result<()> {
+        let url = Url::parse(WS_URL).map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        let (ws_stream, _) = connect_async(url)
+            .await
+            .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        let (mut write, mut read) = ws_stream.split();
+
+        self.notify(if self.connected_once {
+            ConnectionEvent::Reconnected
+        } else {
+            ConnectionEvent::Connected
+        });
+        self.connected_once = true;
+
+        // Heartbeats keep the connection open while a pair is quiet
+        let mut requests = vec![subscription("subscribe", "heartbeats", &[])];
+        let pairs: Vec<String> = self.pairs.iter().cloned().collect();
+        if !pairs.is_empty() {
+            requests.push(subscription("subscribe", "candles", &pairs));
+            requests.push(subscription("subscribe", "ticker", &pairs));
+        }
+        let trade_pairs: Vec<String> = self.trade_pairs.iter().cloned().collect();
+        if self.trade_tx.is_some() && !trade_pairs.is_empty() {
+            requests.push(subscription("subscribe", "market_trades", &trade_pairs));
+        }
+        for request in requests {
+            write
+                .send(Message::Text(request.to_string()))
+                .await
+                .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+        }
+
+        loop {
+            tokio::select! {
+                msg = read.next() => {
+                    let Some(msg) = msg else { break };
+                    let msg = msg.map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                    if let Message::Text(text) = msg {
+                        match parse_message(&text) {
+                            Ok(events) => {
+                                for event in events {
+                                    self.publish(event);
+                                }
+                            }
+                            Err(e) => warn!(error = %e, "Failed to parse Coinbase message"),
+                        }
+                    }
+                }
+                Some(control) = self.control_rx.recv() => {
+                    let mut requests = Vec::new();
+                    match control {
+                        StreamControl::Subscribe(pair, interval) => {
+                            let pair = pair.to_uppercase();
+                            warn_unsupported_interval(&pair, interval);
+                            if !self.pairs.insert(pair.clone()) {
+                                continue;
+                            }
+                            let pairs = [pair];
+                            requests.push(subscription("subscribe", "candles", &pairs));
+                            requests.push(subscription("subscribe", "ticker", &pairs));
+                        }
+                        StreamControl::Unsubscribe(pair) => {
+                            let pair = pair.to_uppercase();
+                            let pairs = [pair.clone()];
+                            if self.pairs.remove(&pair) {
+                                requests.push(subscription("unsubscribe", "candles", &pairs));
+                                requests.push(subscription("unsubscribe", "ticker", &pairs));
+                                self.quotes.remove(&pair);
+                                self.candles.remove(&pair);
+                            }
+                            if self.trade_pairs.remove(&pair) && self.trade_tx.is_some() {
+                                requests.push(subscription("unsubscribe", "market_trades", &pairs));
+                            }
+                        }
+                        StreamControl::Reconnect => {
+                            return Err(common::Error::WebSocket(
+                                "reconnect requested".into(),
+                            ));
+                        }
+                    }
+                    for request in requests {
+                        info!(request = %request, "Updating stream subscription");
+                        write
+                            .send(Message::Text(request.to_string()))
+                            .await
+                            .map_err(|e| common::Error::WebSocket(e.to_string()))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Route one parsed event to the right channel. Ignores send errors (no
+    /// active receivers).
+    fn publish(&mut self, event: StreamEvent) {
+        match event {
+            StreamEvent::Quote { pair, bid, ask } => {
+                self.quotes.insert(pair, (bid, ask));
+            }
+            StreamEvent::Trade(trade) => {
+                if let Some(tx) = &self.trade_tx {
+                    let _ = tx.send(trade);
+                }
+            }
+            StreamEvent::Candle {
+                snapshot,
+                mut event,
+            } => {
+                if let Some(&(bid, ask)) = self.quotes.get(&event.pair) {
+                    event.best_bid = Some(bid);
+                    event.best_ask = Some(ask);
+                }
+                let previous = self.candles.get(&event.pair).cloned();
+                match previous {
+                    // Older than what we already have (e.g. snapshot history)
+                    Some(prev) if event.timestamp < prev.timestamp => return,
+                    // A new candle started: the previous one is final
+                    Some(mut prev) if event.timestamp > prev.timestamp && !snapshot => {
+                        prev.is_candle_closed = true;
+                        let _ = self.market_tx.send(prev);
+                    }
+                    _ => {}
+                }
+                self.candles.insert(event.pair.clone(), event.clone());
+                let _ = self.market_tx.send(event);
+            }
+        }
+    }
+}
+
+fn warn_unsupported_interval(pair: &str, interval: KlineInterval) {
+    if interval != CANDLE_INTERVAL {
+        warn!(
+            pair = %pair,
+            requested = %interval,
+            "Coinbase only streams 5m candles — using 5m"
+        );
+    }
+}
+
+/// Channel (un)subscribe request for the given internal pairs.
+fn subscription(kind: &str, channel: &str, pairs: &[String]) -> serde_json::Value {
+    let product_ids: Vec<String> = pairs.iter().map(|p| to_coinbase_product(p)).collect();
+    serde_json::json!({
+        "type": kind,
+        "product_ids": product_ids,
+        "channel": channel,
+    })
+}
+
+// ─── Coinbase stream JSON parsing ─────────────────────────────────────────────
+
+/// A parsed update from one of the subscribed channels.
+enum StreamEvent {
+    /// Candle update; `snapshot` marks the initial backfill on subscribe.
+    Candle {
+        snapshot: bool,
+        event: MarketEvent,
+    },
+    Quote {
+        pair: String,
+        bid: f64,
+        ask: f64,
+    },
+    Trade(TradeEvent),
+}
+
+#[derive(Deserialize)]
+struct WsMessage {
+    #[serde(default)]
+    channel: String,
+    #[serde(default)]
+    events: Vec<WsEvent>,
+}
+
+#[derive(Deserialize)]
+struct WsEvent {
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    candles: Vec<CandleData>,
+    #[serde(default)]
+    tickers: Vec<TickerData>,
+    #[serde(default)]
+    trades: Vec<TradeData>,
+}
+
+#[derive(Deserialize)]
+struct CandleData {
+    product_id: String,
+    /// Candle start, Unix seconds.
+    start: String,
+    open: String,
+    high: String,
+    low: String,
+    close: String,
+    volume: String,
+}
+
+#[derive(Deserialize)]
+struct TickerData {
+    product_id: String,
+    #[serde(default)]
+    best_bid: String,
+    #[serde(default)]
+    best_ask: String,
+}
+
+#[derive(Deserialize)]
+struct TradeData {
+    trade_id: String,
+    product_id: String,
+    price: String,
+    size: String,
+    /// Taker side.
+    side: String,
+    time: String,
+}
+
+fn parse_message(text: &str) -> Result<Vec<StreamEvent>> {
+    let msg: WsMessage = serde_json::from_str(text)?;
+    let mut out = Vec::new();
+    for event in msg.events {
+        let snapshot = event.kind == "snapshot";
+        match msg.channel.as_str() {
+            "candles" => {
+                let mut candles = event.candles;
+                candles.sort_by_key(|c| c.start.parse::<i64>().unwrap_or(0));
+                out.extend(candles.into_iter().map(|c| StreamEvent::Candle {
+                    snapshot,
+                    event: candle_event(c),
+                }));
+            }
+            "ticker" => {
+                out.extend(event.tickers.into_iter().filter_map(|t| {
+                    Some(StreamEvent::Quote {
+                        pair: from_coinbase_product(&t.product_id),
+                        bid: t.best_bid.parse().ok()?,
+                        ask: t.best_ask.parse().ok()?,
+                    })
+                }));
+            }
+            // The snapshot replays recent history; only live trades matter
+            "market_trades" if !snapshot => {
+                out.extend(event.trades.into_iter().map(|t| {
+                    StreamEvent::Trade(TradeEvent {
+                        pair: from_coinbase_product(&t.product_id),
+                        trade_id: t.trade_id.parse().unwrap_or(0),
+                        price: t.price.parse().unwrap_or(0.0),
+                        quantity: t.size.parse().unwrap_or(0.0),
+                        // A taker sell means the buyer was resting
+                        is_buyer_maker: t.side == "SELL",
+                        timestamp: DateTime::parse_from_rfc3339(&t.time)
+                            .map(|t| t.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                }));
+            }
+            _ => {} // subscriptions, heartbeats
+        }
+    }
+    Ok(out)
+}
+
+fn candle_event(c: CandleData) -> MarketEvent {
+    let start = c.start.parse::<i64>().unwrap_or(0);
+    // Like Binance, stamp the candle with its close time
+    let timestamp = Utc
+        .timestamp_opt(start + 300, 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    MarketEvent {
+        pair: from_coinbase_product(&c.product_id),
+        interval: CANDLE_INTERVAL,
+        price: c.close.parse().unwrap_or(0.0),
+        open: c.open.parse().unwrap_or(0.0),
+        high: c.high.parse().unwrap_or(0.0),
+        low: c.low.parse().unwrap_or(0.0),
+        volume: c.volume.parse().unwrap_or(0.0),
+        is_candle_closed: false,
+        timestamp,
+        best_bid: None,
+        best_ask: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candle_closes_when_next_one_starts() {
+        let (market_tx, mut market_rx) = broadcast::channel(16);
+        let (mut stream, _handle) = CoinbaseStream::new(
+            [("BTCUSD".to_string(), KlineInterval::FiveMinutes)],
+            market_tx,
+        );
+
+        let candle = |start: i64, close: &str| {
+            format!(
+                r#"{{"channel":"candles","events":[{{"type":"update","candles":[{{"start":"{start}","high":"1","low":"1","open":"1","close":"{close}","volume":"1","product_id":"BTC-USD"}}]}}]}}"#
+            )
+        };
+        for text in [candle(1_700_000_100, "10"), candle(1_700_000_400, "11")] {
+            for event in parse_message(&text).unwrap() {
+                stream.publish(event);
+            }
+        }
+
+        let first = market_rx.try_recv().unwrap();
+        assert!(!first.is_candle_closed);
+        let closed = market_rx.try_recv().unwrap();
+        assert!(closed.is_candle_closed);
+        assert_eq!(closed.pair, "BTCUSD");
+        assert_eq!(closed.price, 10.0);
+        let next = market_rx.try_recv().unwrap();
+        assert!(!next.is_candle_closed);
+        assert_eq!(next.price, 11.0);
+    }
+}
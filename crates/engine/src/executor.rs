@@ -1,21 +1,89 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use chrono::Utc;
+use rust_decimal::Decimal;
 use sqlx::SqlitePool;
-use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tokio::sync::{broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
 
-use common::{ExchangeClient, Fill, Order, RiskEvent, TradingMode};
+use common::{
+    decimal, metrics, BracketLegs, BracketOrder, DashboardEvent, ExchangeClient, ExecutorCommand,
+    Fill, Order, OrderSide, OrderStatus, OrderStatusReport, OrderTrigger, Position, RetryQueue,
+    RiskCommand, RiskEvent, TradingMode,
+};
+
+use crate::order_journal::OrderJournal;
+use crate::order_tracker::OrderTracker;
+use crate::symbol_filters::SymbolFilterMap;
+use crate::trade_ledger::{Booking, TradeLedger};
+
+/// How often resting orders are polled for status changes.
+const ORDER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Submission attempts for an order before it is declared failed.
+const MAX_SUBMIT_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry; doubles on each subsequent one.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// How often the failed-order retry queue is checked for due entries.
+const RETRY_QUEUE_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Receives approved orders from the Risk Manager and submits them to the exchange.
-/// On success, persists the fill to the database.
+/// On success, books the fill against open positions and closed trades
+/// (see `TradeLedger`). Every order's lifecycle is
+/// recorded in the `orders` table.
 ///
 /// This is the ONLY component that calls `ExchangeClient::submit_order`.
 pub struct OrderExecutor {
     order_rx: mpsc::Receiver<Order>,
     risk_event_tx: mpsc::Sender<RiskEvent>,
     client: Arc<dyn ExchangeClient>,
-    db: SqlitePool,
     mode: TradingMode,
+    /// Where fill reports go for post-trade checks. Optional so the executor
+    /// can run without a risk manager (e.g. in tests).
+    risk_tx: Option<mpsc::Sender<RiskCommand>>,
+    /// Exchange-side exit brackets currently resting, keyed by position ID.
+    brackets: HashMap<String, PlacedBracket>,
+    /// Orders accepted but not yet filled, polled until they settle.
+    tracker: OrderTracker,
+    /// Exchange trading rules per pair; pairs without an entry are sent as-is.
+    symbol_filters: SymbolFilterMap,
+    journal: OrderJournal,
+    ledger: TradeLedger,
+    /// Orders whose submission failed, retried later or on operator request.
+    retry_queue: RetryQueue,
+    /// Dashboard broadcast for booked fills, if connected.
+    dashboard_tx: Option<broadcast::Sender<DashboardEvent>>,
+    /// Operator commands (e.g. the Telegram `/halt`), if connected.
+    control_rx: Option<mpsc::Receiver<ExecutorCommand>>,
+    /// Clients for the other modes a runtime switch may select.
+    standby_clients: Vec<(TradingMode, Arc<dyn ExchangeClient>)>,
+    /// Stops the loop between orders once cancelled.
+    shutdown: CancellationToken,
+}
+
+/// A bracket resting on the exchange and the order that describes it.
+struct PlacedBracket {
+    legs: BracketLegs,
+    order: BracketOrder,
+}
+
+impl PlacedBracket {
+    /// The other leg's client order ID, if `order_id` is one of this
+    /// bracket's legs.
+    fn sibling(&self, order_id: &str) -> Option<&str> {
+        if order_id == self.legs.take_profit_order_id {
+            Some(&self.legs.stop_order_id)
+        } else if order_id == self.legs.stop_order_id {
+            Some(&self.legs.take_profit_order_id)
+        } else {
+            None
+        }
+    }
 }
 
 impl OrderExecutor {
@@ -30,66 +98,981 @@ impl OrderExecutor {
             order_rx,
             risk_event_tx,
             client,
-            db,
+            journal: OrderJournal::new(db.clone(), mode),
+            ledger: TradeLedger::new(db, mode),
+            retry_queue: RetryQueue::new(),
             mode,
+            risk_tx: None,
+            brackets: HashMap::new(),
+            tracker: OrderTracker::new(),
+            symbol_filters: SymbolFilterMap::new(),
+            dashboard_tx: None,
+            control_rx: None,
+            standby_clients: Vec::new(),
+            shutdown: CancellationToken::new(),
         }
     }
 
-    /// Run the executor loop. Call from `tokio::spawn`.
-    pub async fn run(mut self) {
+    /// Stop once `shutdown` is cancelled. An order being submitted is
+    /// finished and recorded first.
+    pub fn set_shutdown(&mut self, shutdown: CancellationToken) {
+        self.shutdown = shutdown;
+    }
+
+    /// Record orders, positions, and trades under `account`.
+    pub fn set_account(&mut self, account: &str) {
+        self.journal.set_account(account);
+        self.ledger.set_account(account);
+    }
+
+    /// Round orders to the exchange's lot/tick sizes and enforce its minimum
+    /// notional before submission.
+    pub fn set_symbol_filters(&mut self, filters: SymbolFilterMap) {
+        self.symbol_filters = filters;
+    }
+
+    /// Share the failed-order retry queue with the operator surfaces.
+    pub fn set_retry_queue(&mut self, queue: RetryQueue) {
+        self.retry_queue = queue;
+    }
+
+    /// Publish booked fills to dashboard clients.
+    pub fn set_dashboard_events(&mut self, tx: broadcast::Sender<DashboardEvent>) {
+        self.dashboard_tx = Some(tx);
+    }
+
+    /// Report fills to the risk manager's control channel.
+    pub fn set_risk_control(&mut self, tx: mpsc::Sender<RiskCommand>) {
+        self.risk_tx = Some(tx);
+    }
+
+    /// Allow a runtime switch to `mode`, trading through `client` after it.
+    pub fn add_standby_client(&mut self, mode: TradingMode, client: Arc<dyn ExchangeClient>) {
+        self.standby_clients.push((mode, client));
+    }
+
+    /// Accept operator commands on `rx`.
+    pub fn set_control(&mut self, rx: mpsc::Receiver<ExecutorCommand>) {
+        self.control_rx = Some(rx);
+    }
+
+    /// Run the executor loop. Borrows rather than consumes, so a supervisor
+    /// can restart the loop with the same state after a panic.
+    pub async fn run(&mut self) {
         info!("OrderExecutor running in {:?} mode", self.mode);
-        while let Some(order) = self.order_rx.recv().await {
-            info!(pair = %order.pair, side = ?order.side, qty = order.quantity, "Executing order");
+        let mut poll = tokio::time::interval(ORDER_POLL_INTERVAL);
+        let mut retries = tokio::time::interval(RETRY_QUEUE_INTERVAL);
+        let shutdown = self.shutdown.clone();
+        loop {
+            let polling = !self.tracker.is_empty();
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!(resting = self.tracker.len(), "OrderExecutor stopped for shutdown");
+                    return;
+                }
+                maybe_order = self.order_rx.recv() => {
+                    let Some(order) = maybe_order else { break };
+                    self.execute(order, 0).await;
+                }
+                _ = poll.tick(), if polling => self.poll_open_orders().await,
+                _ = retries.tick() => self.process_retries().await,
+                Some(cmd) = async { self.control_rx.as_mut()?.recv().await } => {
+                    self.handle_command(cmd).await;
+                }
+            }
+        }
+        warn!("OrderExecutor: order channel closed");
+    }
+
+    async fn handle_command(&mut self, cmd: ExecutorCommand) {
+        match cmd {
+            ExecutorCommand::CancelResting { reply } => {
+                let _ = reply.send(self.cancel_resting().await);
+            }
+            ExecutorCommand::SwitchMode { mode, reply } => {
+                let _ = reply.send(self.switch_mode(mode).await);
+            }
+        }
+    }
+
+    /// Swap in the standby client for `mode`, reset so a paper session starts
+    /// from its initial balance rather than where the last one left off.
+    /// Orders and brackets resting on the current exchange would be
+    /// orphaned, so they block the switch.
+    async fn switch_mode(&mut self, mode: TradingMode) -> Result<(), String> {
+        if mode == self.mode {
+            return Ok(());
+        }
+        if !self.tracker.is_empty() {
+            return Err(format!(
+                "{} order(s) resting on the exchange; cancel them first",
+                self.tracker.len()
+            ));
+        }
+        if !self.brackets.is_empty() {
+            return Err(format!(
+                "{} exit bracket(s) resting on the exchange",
+                self.brackets.len()
+            ));
+        }
+        let Some(index) = self.standby_clients.iter().position(|(m, _)| *m == mode) else {
+            return Err(format!("no {mode} exchange client is configured"));
+        };
+        let (_, client) = self.standby_clients.swap_remove(index);
+        client.reset().await;
+        let previous = std::mem::replace(&mut self.client, client);
+        self.standby_clients.push((self.mode, previous));
+        warn!(from = %self.mode, to = %mode, "Trading mode switched at runtime");
+        self.mode = mode;
+        self.journal.set_mode(mode);
+        self.ledger.set_mode(mode);
+        Ok(())
+    }
 
-            match self.client.submit_order(&order).await {
+    /// Ask the exchange to cancel every tracked order except bracket legs,
+    /// which protect open positions. Cancelled orders stay tracked, so the
+    /// next poll books whatever they filled before the cancel.
+    async fn cancel_resting(&mut self) -> (usize, usize) {
+        let (mut cancelled, mut failed) = (0, 0);
+        for (pair, order_id) in self.tracker.poll_targets() {
+            if self.bracket_of(&order_id).is_some() {
+                continue;
+            }
+            match self.client.cancel_order(&pair, &order_id).await {
+                Ok(()) => {
+                    info!(pair = %pair, order_id = %order_id, "Resting order cancelled");
+                    cancelled += 1;
+                }
+                Err(e) => {
+                    warn!(pair = %pair, order_id = %order_id, error = %e, "Failed to cancel resting order");
+                    failed += 1;
+                }
+            }
+        }
+        (cancelled, failed)
+    }
+
+    /// Retry queued orders that are due, and drop those that expired.
+    async fn process_retries(&mut self) {
+        let (due, expired) = self.retry_queue.take_due(Utc::now());
+        for failed in expired {
+            warn!(
+                pair = %failed.order.pair,
+                order_id = %failed.order.id,
+                rounds = failed.rounds,
+                "Failed order expired from the retry queue"
+            );
+        }
+        for failed in due {
+            // Retries reuse the client order ID, so an attempt that looked
+            // failed may have reached the exchange after all
+            let status = self
+                .client
+                .order_status(&failed.order.pair, &failed.order.id)
+                .await;
+            if let Ok(report) = status {
+                let order = failed.order;
+                info!(
+                    pair = %order.pair,
+                    order_id = %order.id,
+                    status = %report.status,
+                    "Queued order reached the exchange after all"
+                );
+                self.journal.settled(&order, &report).await;
+                let released = match &order.position_id {
+                    Some(position_id) => self.cancel_bracket(position_id).await,
+                    None => None,
+                };
+                let status = report.status;
+                let fill = report_fill(&order, report);
+                self.on_accepted(order, fill, status, released).await;
+                continue;
+            }
+            info!(
+                pair = %failed.order.pair,
+                order_id = %failed.order.id,
+                round = failed.rounds + 1,
+                "Retrying failed order"
+            );
+            self.execute(failed.order, failed.rounds + 1).await;
+        }
+    }
+
+    /// Submit one order. `rounds` counts previous trips through the retry
+    /// queue; a failed submission goes (back) into the queue until its
+    /// rounds run out.
+    async fn execute(&mut self, mut order: Order, rounds: u32) {
+        if let Some(filters) = self.symbol_filters.get(&order.pair) {
+            if let Err(reason) = filters.normalize(&mut order) {
+                warn!(pair = %order.pair, reason = %reason, "Order violates exchange filters");
+                metrics().count_order_failure("filters");
+                self.journal.rejected(&order, &reason).await;
+                let _ = self
+                    .risk_event_tx
+                    .send(RiskEvent::OrderFailed {
+                        pair: order.pair.clone(),
+                        error: reason.clone(),
+                        attempts: 0,
+                    })
+                    .await;
+                self.report_failure(order, reason).await;
+                return;
+            }
+        }
+        info!(pair = %order.pair, side = ?order.side, qty = %order.quantity, "Executing order");
+        self.journal.submitted(&order).await;
+
+        // A bracket would fight a client-side close for the same quantity
+        let released = match &order.position_id {
+            Some(position_id) => self.cancel_bracket(position_id).await,
+            None => None,
+        };
+
+        let started = Instant::now();
+        let result = self.submit_with_retry(&order).await;
+        metrics()
+            .order_latency
+            .observe(started.elapsed().as_secs_f64());
+        match &result {
+            Ok((fill, _, attempts)) => self.journal.accepted(&order, fill, *attempts).await,
+            Err((e, attempts)) => {
+                let reason = if e.is_transient() {
+                    "transient"
+                } else {
+                    "permanent"
+                };
+                metrics().count_order_failure(reason);
+                self.journal.failed(&order, &e.to_string(), *attempts).await
+            }
+        }
+        match result.map(|(fill, status, _)| (fill, status)) {
+            Ok((fill, status)) => self.on_accepted(order, fill, status, released).await,
+            Err((e, attempts)) => {
+                error!(pair = %order.pair, error = %e, attempts, "Order submission failed");
+                // The close didn't happen: restore the position's protection
+                if let (Some(position_id), Some(bracket)) = (&order.position_id, released) {
+                    self.submit_bracket(position_id.clone(), bracket).await;
+                }
+                let _ = self
+                    .risk_event_tx
+                    .send(RiskEvent::OrderFailed {
+                        pair: order.pair.clone(),
+                        error: e.to_string(),
+                        attempts,
+                    })
+                    .await;
+                self.report_failure(order.clone(), e.to_string()).await;
+                let (pair, order_id) = (order.pair.clone(), order.id.clone());
+                if self
+                    .retry_queue
+                    .push(order, e.to_string(), rounds, e.is_transient())
+                {
+                    info!(pair = %pair, order_id = %order_id, "Failed order queued for retry");
+                } else {
+                    warn!(pair = %pair, order_id = %order_id, rounds, "Failed order out of retries, dropped");
+                }
+            }
+        }
+    }
+
+    /// Book what an order the exchange accepted has executed, and keep
+    /// tracking it while it rests.
+    async fn on_accepted(
+        &mut self,
+        order: Order,
+        fill: Fill,
+        status: OrderStatus,
+        released: Option<BracketOrder>,
+    ) {
+        let executed = fill.quantity.max(Decimal::ZERO);
+        let order_id = fill.order_id.clone();
+        if status.is_terminal() {
+            if executed > Decimal::ZERO {
+                self.on_fill(order, fill, released).await;
+                return;
+            }
+            warn!(pair = %order.pair, order_id = %order_id, %status, "Order ended without executing");
+            // The close didn't happen: restore the position's protection
+            if let (Some(position_id), Some(bracket)) = (&order.position_id, released) {
+                self.submit_bracket(position_id.clone(), bracket).await;
+            }
+            self.report_failure(order, format!("order {status}")).await;
+            return;
+        }
+        info!(
+            pair = %order.pair,
+            order_id = %order_id,
+            executed = %executed,
+            "Order accepted and resting on the exchange"
+        );
+        self.tracker.track(order.clone(), order_id, executed);
+        if executed > Decimal::ZERO {
+            self.on_fill(order, fill, released).await;
+        }
+    }
+
+    /// Submit `order`, retrying transient failures with exponential backoff.
+    ///
+    /// The order ID is sent as the exchange client order ID, so before each
+    /// retry the exchange is asked whether an earlier attempt actually landed;
+    /// if it did, that result is returned instead of submitting again.
+    /// Returns the fill and the order's status, or the last error, with the
+    /// number of attempts made.
+    async fn submit_with_retry(
+        &self,
+        order: &Order,
+    ) -> Result<(Fill, OrderStatus, u32), (common::Error, u32)> {
+        let mut attempt = 1;
+        loop {
+            let err = match self.client.submit_order(order).await {
                 Ok(fill) => {
-                    info!(
-                        pair = %fill.pair,
-                        price = fill.fill_price,
-                        qty = fill.quantity,
-                        "Order filled"
-                    );
-                    if let Err(e) = self.persist_fill(&fill).await {
-                        error!("Failed to persist fill: {e}");
-                    }
+                    let status = submitted_status(order, &fill);
+                    return Ok((fill, status, attempt));
+                }
+                Err(e) if e.is_transient() && attempt < MAX_SUBMIT_ATTEMPTS => e,
+                Err(e) => return Err((e, attempt)),
+            };
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+            warn!(
+                pair = %order.pair,
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                error = %err,
+                "Transient order failure, retrying"
+            );
+            tokio::time::sleep(delay).await;
+
+            if let Ok(report) = self.client.order_status(&order.pair, &order.id).await {
+                info!(pair = %order.pair, status = %report.status, "Earlier attempt reached the exchange");
+                let status = report.status;
+                return Ok((report_fill(order, report), status, attempt));
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Persist a fill, adjust exit brackets, and report it to the risk manager.
+    async fn on_fill(&mut self, order: Order, fill: Fill, released: Option<BracketOrder>) {
+        info!(
+            pair = %fill.pair,
+            price = %fill.fill_price,
+            qty = %fill.quantity,
+            "Order filled"
+        );
+        // From here on, `fill` is the increment booked
+        let Booking {
+            fill,
+            trades,
+            opened,
+            reduced,
+        } = match self.ledger.record_fill(&order, &fill).await {
+            Ok(Some(booking)) => booking,
+            Ok(None) => {
+                warn!(pair = %fill.pair, order_id = %fill.order_id, "Fill already booked, ignoring repeat");
+                return;
+            }
+            // Positions and PnL now disagree with the exchange: stop here
+            // rather than bracket or report a fill that isn't booked
+            Err(e) => {
+                error!(pair = %fill.pair, order_id = %fill.order_id, error = %e, "Failed to book fill");
+                let _ = self
+                    .risk_event_tx
+                    .send(RiskEvent::FillNotBooked {
+                        pair: fill.pair.clone(),
+                        order_id: fill.order_id.clone(),
+                        error: e.to_string(),
+                    })
+                    .await;
+                return;
+            }
+        };
+        let realized: Decimal = trades.iter().map(|t| decimal::from_f64(t.pnl_usd)).sum();
+        if let Some(tx) = &self.dashboard_tx {
+            let _ = tx.send(DashboardEvent::Trade {
+                fill: fill.clone(),
+                realized_pnl: realized,
+            });
+        }
+        for trade in &trades {
+            info!(
+                pair = %trade.pair,
+                pnl_usd = trade.pnl_usd,
+                fee_usd = trade.fee_usd,
+                "Position closed"
+            );
+            let _ = self
+                .risk_event_tx
+                .send(RiskEvent::TradeClosed {
+                    trade: trade.clone(),
+                })
+                .await;
+        }
+        match (&order.position_id, released) {
+            (None, _) => {
+                if let Some(position) = &opened {
+                    self.place_bracket(&order, position).await;
+                }
+            }
+            // Partial close: re-protect whatever remains
+            (Some(position_id), Some(mut bracket)) => {
+                bracket.quantity -= fill.quantity;
+                if bracket.quantity > Decimal::ZERO {
+                    self.submit_bracket(position_id.clone(), bracket).await;
                 }
+            }
+            (Some(_), None) => {}
+        }
+        // Exits that don't name a position, like strategy exits, can still
+        // close positions a bracket guards
+        for position in &reduced {
+            if order.position_id.as_ref() == Some(&position.id) {
+                continue;
+            }
+            let Some(mut bracket) = self.cancel_bracket(&position.id).await else {
+                continue;
+            };
+            if position.quantity > Decimal::ZERO {
+                bracket.quantity = position.quantity;
+                self.submit_bracket(position.id.clone(), bracket).await;
+            }
+        }
+        if let Some(tx) = &self.risk_tx {
+            let _ = tx
+                .send(RiskCommand::OrderFilled {
+                    order: Box::new(order),
+                    fill,
+                    trades,
+                    opened: opened.map(Box::new),
+                    reduced,
+                })
+                .await;
+        }
+    }
+
+    /// Tell the risk manager `order` failed, so a close it was waiting on
+    /// leaves its position open and watched again.
+    async fn report_failure(&self, order: Order, error: String) {
+        if let Some(tx) = &self.risk_tx {
+            let _ = tx
+                .send(RiskCommand::OrderFailed {
+                    order: Box::new(order),
+                    error,
+                })
+                .await;
+        }
+    }
+
+    /// Query every resting order once. Orders that reached a terminal state
+    /// are dropped from tracking, and whatever executed is booked as a fill.
+    async fn poll_open_orders(&mut self) {
+        for (pair, order_id) in self.tracker.poll_targets() {
+            let report = match self.client.order_status(&pair, &order_id).await {
+                Ok(report) => report,
                 Err(e) => {
-                    error!(pair = %order.pair, error = %e, "Order submission failed");
-                    let _ = self
-                        .risk_event_tx
-                        .send(RiskEvent::OrderFailed {
-                            pair: order.pair.clone(),
-                            error: e.to_string(),
-                        })
-                        .await;
+                    warn!(pair = %pair, order_id = %order_id, error = %e, "Order status poll failed");
+                    continue;
                 }
+            };
+            // Book what executed since the last poll, whether or not the
+            // order is done
+            if let Some(order) = self.tracker.executed(&order_id, report.executed_quantity) {
+                info!(
+                    pair = %pair,
+                    order_id = %order_id,
+                    executed = %report.executed_quantity,
+                    "Resting order executed further"
+                );
+                self.settle_bracket_leg(&order_id).await;
+                let fill = report_fill(&order, report.clone());
+                self.on_fill(order, fill, None).await;
             }
+            if !report.status.is_terminal() {
+                continue;
+            }
+            let Some(tracked) = self.tracker.remove(&order_id) else {
+                continue;
+            };
+            info!(
+                pair = %pair,
+                order_id = %order_id,
+                status = %report.status,
+                executed = %report.executed_quantity,
+                "Resting order settled"
+            );
+            self.journal.settled(&tracked.order, &report).await;
+            if tracked.executed <= Decimal::ZERO && report.status != OrderStatus::Filled {
+                let error = format!("order {}", report.status);
+                self.report_failure(tracked.order, error).await;
+            }
+            let _ = self
+                .risk_event_tx
+                .send(RiskEvent::OrderStatusChanged {
+                    pair: pair.clone(),
+                    order_id: order_id.clone(),
+                    status: report.status,
+                    filled_quantity: decimal::to_f64(report.executed_quantity),
+                })
+                .await;
         }
-        warn!("OrderExecutor: order channel closed");
     }
 
-    async fn persist_fill(&self, fill: &Fill) -> Result<(), sqlx::Error> {
-        let side = fill.side.to_string();
-        let mode = self.mode.to_string();
-        let opened_at = fill.timestamp.to_rfc3339();
-
-        sqlx::query!(
-            r#"
-            INSERT INTO positions (id, pair, side, entry_price, quantity, mode, opened_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
-            ON CONFLICT(id) DO NOTHING
-            "#,
-            fill.order_id,
-            fill.pair,
-            side,
-            fill.fill_price,
-            fill.quantity,
-            mode,
-            opened_at,
-        )
-        .execute(&self.db)
-        .await?;
+    /// Protect a freshly filled entry with an OCO bracket if the order asks
+    /// for one. Live mode only — the paper client doesn't simulate OCO brackets.
+    /// A later part of the same entry replaces the bracket with one covering
+    /// the whole position.
+    async fn place_bracket(&mut self, order: &Order, position: &Position) {
+        let Some(spec) = order.bracket else {
+            return;
+        };
+        if self.mode != TradingMode::Live {
+            return;
+        }
+        self.cancel_bracket(&position.id).await;
+        let take_profit = decimal::from_f64(spec.take_profit_pct);
+        let stop_loss = decimal::from_f64(spec.stop_loss_pct);
+        let entry = position.entry_price;
+        let (take_profit_price, stop_price) = match position.side {
+            OrderSide::Buy => (
+                entry * (Decimal::ONE + take_profit),
+                entry * (Decimal::ONE - stop_loss),
+            ),
+            OrderSide::Sell => (
+                entry * (Decimal::ONE - take_profit),
+                entry * (Decimal::ONE + stop_loss),
+            ),
+        };
+        let mut bracket = BracketOrder {
+            pair: position.pair.clone(),
+            side: position.side.opposite(),
+            quantity: position.quantity,
+            take_profit_price,
+            stop_price,
+        };
+        if let Some(filters) = self.symbol_filters.get(&bracket.pair) {
+            filters.normalize_bracket(&mut bracket);
+        }
+        self.submit_bracket(position.id.clone(), bracket).await;
+    }
 
-        Ok(())
+    /// Place `bracket` for `position_id` and track both legs, so whichever
+    /// fills is booked as a close of the position.
+    async fn submit_bracket(&mut self, position_id: String, bracket: BracketOrder) {
+        match self.client.place_bracket(&bracket).await {
+            Ok(legs) => {
+                info!(
+                    pair = %bracket.pair,
+                    bracket_id = %legs.id,
+                    stop = %bracket.stop_price,
+                    take_profit = %bracket.take_profit_price,
+                    "Exit bracket placed on exchange"
+                );
+                let close = Order {
+                    position_id: Some(position_id.clone()),
+                    ..Order::market(&bracket.pair, bracket.side, bracket.quantity)
+                };
+                let take_profit = Order {
+                    id: legs.take_profit_order_id.clone(),
+                    price: Some(bracket.take_profit_price),
+                    ..close.clone()
+                };
+                let stop = Order {
+                    id: legs.stop_order_id.clone(),
+                    trigger: Some(OrderTrigger::StopLoss {
+                        stop_price: bracket.stop_price,
+                    }),
+                    ..close
+                };
+                for leg in [take_profit, stop] {
+                    self.journal.submitted(&leg).await;
+                    let id = leg.id.clone();
+                    self.tracker.track(leg, id, Decimal::ZERO);
+                }
+                self.brackets.insert(
+                    position_id,
+                    PlacedBracket {
+                        legs,
+                        order: bracket,
+                    },
+                );
+            }
+            Err(e) => {
+                error!(pair = %bracket.pair, error = %e, "Failed to place exit bracket");
+                let _ = self
+                    .risk_event_tx
+                    .send(RiskEvent::OrderFailed {
+                        pair: bracket.pair.clone(),
+                        error: format!("bracket placement failed: {e}"),
+                        attempts: 1,
+                    })
+                    .await;
+            }
+        }
+    }
+
+    /// The position whose bracket has the leg `order_id`, if any.
+    fn bracket_of(&self, order_id: &str) -> Option<String> {
+        self.brackets
+            .iter()
+            .find(|(_, b)| b.sibling(order_id).is_some())
+            .map(|(position_id, _)| position_id.clone())
+    }
+
+    /// A bracket leg executed and closed its position: forget the bracket
+    /// and cancel the other leg. The exchange normally cancels it already;
+    /// it stays tracked either way, so anything it filled is still booked.
+    async fn settle_bracket_leg(&mut self, order_id: &str) {
+        let Some(position_id) = self.bracket_of(order_id) else {
+            return;
+        };
+        let Some(placed) = self.brackets.remove(&position_id) else {
+            return;
+        };
+        let sibling = placed.sibling(order_id).unwrap_or_default();
+        info!(
+            pair = %placed.order.pair,
+            position_id = %position_id,
+            leg = %order_id,
+            "Exit bracket leg executed"
+        );
+        if let Err(e) = self.client.cancel_order(&placed.order.pair, sibling).await {
+            debug!(
+                pair = %placed.order.pair,
+                order_id = %sibling,
+                error = %e,
+                "Bracket sibling leg not cancelled (likely already done by the exchange)"
+            );
+        }
+    }
+
+    /// Cancel the bracket protecting `position_id`, returning its order so a
+    /// partial close can re-place it for the remaining quantity. Its legs
+    /// stay tracked, so a fill that beat the cancel is still booked.
+    async fn cancel_bracket(&mut self, position_id: &str) -> Option<BracketOrder> {
+        let placed = self.brackets.remove(position_id)?;
+        if let Err(e) = self
+            .client
+            .cancel_bracket(&placed.order.pair, &placed.legs.id)
+            .await
+        {
+            warn!(
+                pair = %placed.order.pair,
+                bracket_id = %placed.legs.id,
+                error = %e,
+                "Failed to cancel exit bracket"
+            );
+        }
+        Some(placed.order)
+    }
+}
+
+/// Status of a just-submitted order, judged from its fill: a limit order
+/// short of its quantity keeps resting, market orders never do.
+fn submitted_status(order: &Order, fill: &Fill) -> OrderStatus {
+    if fill.quantity <= Decimal::ZERO {
+        OrderStatus::New
+    } else if order.price.is_some() && fill.quantity < order.quantity {
+        OrderStatus::PartiallyFilled
+    } else {
+        OrderStatus::Filled
+    }
+}
+
+/// The fill an exchange status report amounts to: everything the order has
+/// executed so far, at its average price.
+fn report_fill(order: &Order, report: OrderStatusReport) -> Fill {
+    Fill {
+        order_id: order.id.clone(),
+        exchange_order_id: report.exchange_order_id,
+        pair: order.pair.clone(),
+        side: order.side,
+        fill_price: report.average_price.or(order.price).unwrap_or_default(),
+        quantity: report.executed_quantity,
+        fee: Decimal::ZERO,
+        timestamp: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use common::{Balance, BracketSpec, Position, Result as ExchangeResult};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    /// Fills entries at 100, or as far as `statuses` reports them if it
+    /// already knows the order, and places brackets whose legs are reported
+    /// by `statuses`.
+    #[derive(Default)]
+    struct MockClient {
+        statuses: Mutex<HashMap<String, OrderStatusReport>>,
+        cancelled: Mutex<Vec<String>>,
+        /// Quantity of each bracket placed, in order.
+        brackets: Mutex<Vec<Decimal>>,
+    }
+
+    impl MockClient {
+        fn set_status(&self, order_id: &str, status: OrderStatus, executed: Decimal) {
+            self.statuses.lock().unwrap().insert(
+                order_id.to_string(),
+                OrderStatusReport {
+                    status,
+                    executed_quantity: executed,
+                    average_price: (executed > Decimal::ZERO).then_some(dec!(98)),
+                    exchange_order_id: None,
+                },
+            );
+        }
+    }
+
+    #[async_trait]
+    impl ExchangeClient for MockClient {
+        async fn submit_order(&self, order: &Order) -> ExchangeResult<Fill> {
+            let report = self.statuses.lock().unwrap().get(&order.id).cloned();
+            Ok(Fill {
+                order_id: order.id.clone(),
+                exchange_order_id: None,
+                pair: order.pair.clone(),
+                side: order.side,
+                fill_price: report
+                    .as_ref()
+                    .and_then(|r| r.average_price)
+                    .unwrap_or(dec!(100)),
+                quantity: report.map_or(order.quantity, |r| r.executed_quantity),
+                fee: Decimal::ZERO,
+                timestamp: Utc::now(),
+            })
+        }
+
+        async fn open_positions(&self) -> ExchangeResult<Vec<Position>> {
+            Ok(Vec::new())
+        }
+
+        async fn balances(&self) -> ExchangeResult<Vec<Balance>> {
+            Ok(Vec::new())
+        }
+
+        async fn current_price(&self, _pair: &str) -> ExchangeResult<Decimal> {
+            Ok(dec!(100))
+        }
+
+        async fn order_status(
+            &self,
+            _pair: &str,
+            order_id: &str,
+        ) -> ExchangeResult<OrderStatusReport> {
+            self.statuses
+                .lock()
+                .unwrap()
+                .get(order_id)
+                .cloned()
+                .ok_or_else(|| common::Error::Exchange(format!("unknown order {order_id}")))
+        }
+
+        async fn cancel_order(&self, _pair: &str, order_id: &str) -> ExchangeResult<()> {
+            self.cancelled.lock().unwrap().push(order_id.to_string());
+            self.set_status(order_id, OrderStatus::Canceled, Decimal::ZERO);
+            Ok(())
+        }
+
+        async fn place_bracket(&self, bracket: &BracketOrder) -> ExchangeResult<BracketLegs> {
+            let n = {
+                let mut brackets = self.brackets.lock().unwrap();
+                brackets.push(bracket.quantity);
+                brackets.len()
+            };
+            self.set_status(&format!("tp-{n}"), OrderStatus::New, Decimal::ZERO);
+            self.set_status(&format!("sl-{n}"), OrderStatus::New, Decimal::ZERO);
+            Ok(BracketLegs {
+                id: format!("oco-{n}"),
+                take_profit_order_id: format!("tp-{n}"),
+                stop_order_id: format!("sl-{n}"),
+            })
+        }
+
+        async fn cancel_bracket(&self, _pair: &str, bracket_id: &str) -> ExchangeResult<()> {
+            let n = bracket_id.trim_start_matches("oco-");
+            self.cancelled.lock().unwrap().push(bracket_id.to_string());
+            self.set_status(&format!("tp-{n}"), OrderStatus::Canceled, Decimal::ZERO);
+            self.set_status(&format!("sl-{n}"), OrderStatus::Canceled, Decimal::ZERO);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn bracket_leg_fill_closes_the_position() {
+        let db = crate::test_db().await;
+        let client = Arc::new(MockClient::default());
+        let (_order_tx, order_rx) = mpsc::channel(1);
+        let (risk_event_tx, _risk_event_rx) = mpsc::channel(16);
+        let (risk_tx, mut risk_rx) = mpsc::channel(16);
+        let mut executor = OrderExecutor::new(
+            order_rx,
+            risk_event_tx,
+            client.clone(),
+            db.clone(),
+            TradingMode::Live,
+        );
+        executor.set_risk_control(risk_tx);
+
+        let mut entry = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        entry.bracket = Some(BracketSpec {
+            stop_loss_pct: 0.02,
+            take_profit_pct: 0.05,
+        });
+        executor.execute(entry.clone(), 0).await;
+        assert!(risk_rx.recv().await.is_some());
+        assert_eq!(executor.tracker.len(), 2);
+
+        // The stop triggers on the exchange
+        client.set_status("sl-1", OrderStatus::Filled, dec!(1));
+        executor.poll_open_orders().await;
+        let Some(RiskCommand::OrderFilled {
+            order,
+            trades,
+            reduced,
+            ..
+        }) = risk_rx.recv().await
+        else {
+            panic!("expected the stop's fill");
+        };
+        assert_eq!(order.position_id.as_deref(), Some(entry.id.as_str()));
+        assert_eq!(trades.len(), 1);
+        assert!((trades[0].pnl_usd + 2.0).abs() < 1e-9);
+        assert_eq!(reduced[0].quantity, Decimal::ZERO);
+        assert_eq!(*client.cancelled.lock().unwrap(), ["tp-1"]);
+        assert!(executor.brackets.is_empty());
+        let ledger = TradeLedger::new(db, TradingMode::Live);
+        assert!(ledger.open_positions().await.unwrap().is_empty());
+
+        // The cancelled take-profit settles on the next poll
+        executor.poll_open_orders().await;
+        assert!(executor.tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn partly_filled_limit_is_booked_as_it_fills() {
+        let db = crate::test_db().await;
+        let client = Arc::new(MockClient::default());
+        let (_order_tx, order_rx) = mpsc::channel(1);
+        let (risk_event_tx, _risk_event_rx) = mpsc::channel(16);
+        let (risk_tx, mut risk_rx) = mpsc::channel(16);
+        let mut executor = OrderExecutor::new(
+            order_rx,
+            risk_event_tx,
+            client.clone(),
+            db.clone(),
+            TradingMode::Live,
+        );
+        executor.set_risk_control(risk_tx);
+
+        let order = Order {
+            price: Some(dec!(99)),
+            ..Order::market("BTCUSDT", OrderSide::Buy, dec!(1))
+        };
+        client.set_status(&order.id, OrderStatus::PartiallyFilled, dec!(0.4));
+        executor.execute(order.clone(), 0).await;
+        let Some(RiskCommand::OrderFilled { fill, .. }) = risk_rx.recv().await else {
+            panic!("expected the first part's fill");
+        };
+        assert_eq!(fill.quantity, dec!(0.4));
+        assert_eq!(executor.tracker.len(), 1);
+
+        // Nothing more executed: nothing more to book
+        executor.poll_open_orders().await;
+        assert!(risk_rx.try_recv().is_err());
+
+        client.set_status(&order.id, OrderStatus::Filled, dec!(1));
+        executor.poll_open_orders().await;
+        let Some(RiskCommand::OrderFilled { fill, opened, .. }) = risk_rx.recv().await else {
+            panic!("expected the rest's fill");
+        };
+        assert_eq!(fill.quantity, dec!(0.6));
+        assert_eq!(opened.unwrap().quantity, dec!(1));
+        assert!(executor.tracker.is_empty());
+        let ledger = TradeLedger::new(db, TradingMode::Live);
+        let positions = ledger.open_positions().await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].quantity, dec!(1));
+    }
+
+    #[tokio::test]
+    async fn queued_order_that_landed_is_booked_not_resubmitted() {
+        let db = crate::test_db().await;
+        let client = Arc::new(MockClient::default());
+        let (_order_tx, order_rx) = mpsc::channel(1);
+        let (risk_event_tx, _risk_event_rx) = mpsc::channel(16);
+        let (risk_tx, mut risk_rx) = mpsc::channel(16);
+        let mut executor = OrderExecutor::new(
+            order_rx,
+            risk_event_tx,
+            client.clone(),
+            db,
+            TradingMode::Live,
+        );
+        executor.set_risk_control(risk_tx);
+
+        // The submission timed out, but the exchange took the order
+        let order = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        executor
+            .retry_queue
+            .push(order.clone(), "timed out".into(), 0, true);
+        executor.retry_queue.retry_now(&order.id);
+        client.set_status(&order.id, OrderStatus::Filled, dec!(1));
+        executor.process_retries().await;
+
+        // Booked at the reported price, not a second submission's 100
+        let Some(RiskCommand::OrderFilled { fill, .. }) = risk_rx.recv().await else {
+            panic!("expected the landed order's fill");
+        };
+        assert_eq!(fill.fill_price, dec!(98));
+        assert_eq!(fill.quantity, dec!(1));
+        assert!(risk_rx.try_recv().is_err());
+        assert!(executor.retry_queue.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn exit_without_position_id_shrinks_then_cancels_the_bracket() {
+        let db = crate::test_db().await;
+        let client = Arc::new(MockClient::default());
+        let (_order_tx, order_rx) = mpsc::channel(1);
+        let (risk_event_tx, _risk_event_rx) = mpsc::channel(16);
+        let mut executor = OrderExecutor::new(
+            order_rx,
+            risk_event_tx,
+            client.clone(),
+            db,
+            TradingMode::Live,
+        );
+
+        let mut entry = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        entry.bracket = Some(BracketSpec {
+            stop_loss_pct: 0.02,
+            take_profit_pct: 0.05,
+        });
+        executor.execute(entry.clone(), 0).await;
+        assert_eq!(*client.brackets.lock().unwrap(), [dec!(1)]);
+
+        // A strategy exit for part of the position
+        executor
+            .execute(Order::market("BTCUSDT", OrderSide::Sell, dec!(0.4)), 0)
+            .await;
+        assert_eq!(*client.cancelled.lock().unwrap(), ["oco-1"]);
+        assert_eq!(*client.brackets.lock().unwrap(), [dec!(1), dec!(0.6)]);
+        assert_eq!(executor.brackets[&entry.id].order.quantity, dec!(0.6));
+
+        // And for the rest
+        executor
+            .execute(Order::market("BTCUSDT", OrderSide::Sell, dec!(0.6)), 0)
+            .await;
+        assert_eq!(*client.cancelled.lock().unwrap(), ["oco-1", "oco-2"]);
+        assert_eq!(client.brackets.lock().unwrap().len(), 2);
+        assert!(executor.brackets.is_empty());
+
+        // The cancelled legs settle on the next poll
+        executor.poll_open_orders().await;
+        assert!(executor.tracker.is_empty());
     }
 }
@@ -0,0 +1,55 @@
+use tokio::sync::mpsc;
+
+use common::KlineInterval;
+
+/// Connection change reported by a market data stream.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// First connection of a freshly spawned stream.
+    Connected,
+    /// Connected again after a drop.
+    Reconnected,
+    /// The connection closed or failed, with the reason.
+    Disconnected(String),
+}
+
+/// Runtime subscription change for a running market data stream.
+#[derive(Debug, Clone)]
+pub(crate) enum StreamControl {
+    Subscribe(String, KlineInterval),
+    Unsubscribe(String),
+    Reconnect,
+}
+
+/// Cloneable handle for changing a running stream's subscriptions,
+/// whichever exchange it is connected to.
+#[derive(Clone)]
+pub struct StreamHandle {
+    control_tx: mpsc::UnboundedSender<StreamControl>,
+}
+
+impl StreamHandle {
+    /// A handle and the control receiver its stream listens on.
+    pub(crate) fn channel() -> (Self, mpsc::UnboundedReceiver<StreamControl>) {
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        (Self { control_tx }, control_rx)
+    }
+
+    /// Subscribe to `pair`, or move it to `interval` if already subscribed.
+    pub fn subscribe(&self, pair: impl Into<String>, interval: KlineInterval) {
+        let _ = self
+            .control_tx
+            .send(StreamControl::Subscribe(pair.into(), interval));
+    }
+
+    pub fn unsubscribe(&self, pair: impl Into<String>) {
+        let _ = self
+            .control_tx
+            .send(StreamControl::Unsubscribe(pair.into()));
+    }
+
+    /// Drop the current connection and reconnect (e.g. after a silent stall).
+    pub fn reconnect(&self) {
+        let _ = self.control_tx.send(StreamControl::Reconnect);
+    }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use common::{EngineState, PairStreamStatus, StreamHealth};
+
+/// Longest a heartbeat ping may take.
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Dead-man's switch: pings an external monitor (healthchecks.io, Uptime
+/// Kuma push, ...) every `interval`, but only while the bot is healthy — the
+/// database answers and, while running, every pair has had market data
+/// within `max_event_age`. The monitor alerts once pings stop, which covers
+/// what the bot cannot report itself: the process dying or hanging.
+pub struct Heartbeat {
+    url: String,
+    interval: Duration,
+    db: SqlitePool,
+    engine_state: Arc<RwLock<EngineState>>,
+    stream_health: StreamHealth,
+    max_event_age: Duration,
+    http: Client,
+}
+
+impl Heartbeat {
+    pub fn new(
+        url: impl Into<String>,
+        interval: Duration,
+        db: SqlitePool,
+        engine_state: Arc<RwLock<EngineState>>,
+        stream_health: StreamHealth,
+        max_event_age: Duration,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            interval,
+            db,
+            engine_state,
+            stream_health,
+            max_event_age,
+            http: Client::builder()
+                .use_rustls_tls()
+                .timeout(PING_TIMEOUT)
+                .build()
+                .expect("Failed to build HTTP client"),
+        }
+    }
+
+    /// Ping every `interval`, starting one interval after startup so the
+    /// streams have time to deliver data. Call from `tokio::spawn`.
+    pub async fn run(self) {
+        let start = tokio::time::Instant::now() + self.interval;
+        let mut ticker = tokio::time::interval_at(start, self.interval);
+        loop {
+            ticker.tick().await;
+            if let Some(reason) = self.unhealthy().await {
+                warn!(reason = %reason, "Heartbeat skipped: bot is unhealthy");
+                continue;
+            }
+            match self.http.get(&self.url).send().await {
+                Ok(resp) if resp.status().is_success() => debug!("Heartbeat sent"),
+                Ok(resp) => warn!(status = %resp.status(), "Heartbeat ping refused"),
+                Err(e) => warn!(error = %e, "Heartbeat ping failed"),
+            }
+        }
+    }
+
+    /// Why the bot shouldn't ping, if it shouldn't.
+    async fn unhealthy(&self) -> Option<String> {
+        if let Err(e) = sqlx::query("SELECT 1").execute(&self.db).await {
+            return Some(format!("database: {e}"));
+        }
+        if *self.engine_state.read().await != EngineState::Running {
+            return None;
+        }
+        let stale = stale_pairs(
+            &self.stream_health.snapshot(),
+            Utc::now(),
+            self.max_event_age,
+        );
+        (!stale.is_empty()).then(|| format!("no recent market data for {}", stale.join(", ")))
+    }
+}
+
+/// Pairs without a market event within `max_age` of `now`, including pairs
+/// that never had one.
+fn stale_pairs(pairs: &[PairStreamStatus], now: DateTime<Utc>, max_age: Duration) -> Vec<String> {
+    pairs
+        .iter()
+        .filter(|p| {
+            p.last_event_at
+                .is_none_or(|at| (now - at).to_std().unwrap_or_default() > max_age)
+        })
+        .map(|p| p.pair.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::KlineInterval;
+
+    use super::*;
+
+    fn status(pair: &str, last_event_at: Option<DateTime<Utc>>) -> PairStreamStatus {
+        PairStreamStatus {
+            pair: pair.to_string(),
+            interval: KlineInterval::OneMinute,
+            connected: true,
+            connected_since: None,
+            uptime_secs: 0,
+            reconnects: 0,
+            stale_reconnects: 0,
+            restarts: 0,
+            last_error: None,
+            last_error_at: None,
+            last_event_at,
+        }
+    }
+
+    #[test]
+    fn quiet_pairs_are_stale() {
+        let now = Utc::now();
+        let pairs = vec![
+            status("BTCUSDT", Some(now - chrono::Duration::seconds(5))),
+            status("ETHUSDT", Some(now - chrono::Duration::seconds(90))),
+            status("SOLUSDT", None),
+        ];
+        assert_eq!(
+            stale_pairs(&pairs, now, Duration::from_secs(60)),
+            vec!["ETHUSDT", "SOLUSDT"]
+        );
+        assert_eq!(
+            stale_pairs(&pairs, now, Duration::from_secs(120)),
+            vec!["SOLUSDT"]
+        );
+    }
+}
@@ -1,7 +1,42 @@
+pub mod audit;
 pub mod binance;
+pub mod bybit;
+pub mod candle_cache;
+pub mod candle_store;
+pub mod coinbase;
 pub mod executor;
+pub mod feed;
+pub mod heartbeat;
 pub mod lifecycle;
+pub mod order_journal;
+pub mod order_tracker;
+pub mod symbol_filters;
+pub mod trade_ledger;
+pub mod watchdog;
 
+pub use audit::PositionAuditor;
 pub use binance::BinanceClient;
+pub use bybit::BybitClient;
+pub use candle_cache::{CandleCache, DEFAULT_CANDLE_CACHE_SIZE};
+pub use candle_store::CandleStore;
+pub use coinbase::CoinbaseClient;
 pub use executor::OrderExecutor;
+pub use feed::StreamHandle;
+pub use heartbeat::Heartbeat;
 pub use lifecycle::{Engine, EngineHandle};
+pub use symbol_filters::{SymbolFilterMap, SymbolFilters};
+pub use trade_ledger::{Booking, TradeLedger};
+pub use watchdog::Watchdog;
+
+/// A fresh, migrated in-memory database. One connection, so every query
+/// sees the same database.
+#[cfg(test)]
+pub(crate) async fn test_db() -> sqlx::SqlitePool {
+    let db = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("../../migrations").run(&db).await.unwrap();
+    db
+}
@@ -1,11 +1,32 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use tokio::sync::{broadcast, mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
-use common::{EngineCommand, EngineState, MarketEvent};
+use common::metrics::{metrics, PairLabels};
+use common::{
+    EngineCommand, EngineState, ExchangeKind, ExecutorCommand, HaltOutcome, KlineInterval,
+    MarketEvent, RiskCommand, RiskEvent, StopOutcome, StreamHealth, TradeEvent,
+};
 
 use crate::binance::BinanceStream;
+use crate::bybit::BybitStream;
+use crate::candle_cache::CandleCache;
+use crate::coinbase::CoinbaseStream;
+use crate::feed::{ConnectionEvent, StreamHandle};
+
+/// How long `Stop` waits for position closes to fill before stopping anyway.
+const STOP_CLOSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long `Halt` waits for the order executor to cancel resting orders.
+const HALT_CANCEL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the watchdog checks each pair for staleness and the stream task
+/// for liveness.
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Cloneable handle passed to other crates (Telegram, API).
 #[derive(Clone)]
@@ -13,6 +34,9 @@ pub struct EngineHandle {
     command_tx: mpsc::Sender<EngineCommand>,
     state: Arc<RwLock<EngineState>>,
     market_tx: broadcast::Sender<MarketEvent>,
+    trade_tx: broadcast::Sender<TradeEvent>,
+    health: StreamHealth,
+    candles: CandleCache,
 }
 
 impl EngineHandle {
@@ -33,57 +57,379 @@ impl EngineHandle {
     pub fn subscribe_market(&self) -> broadcast::Receiver<MarketEvent> {
         self.market_tx.subscribe()
     }
+
+    /// Subscribe to the trade event broadcast (pairs with trade streaming only).
+    pub fn subscribe_trades(&self) -> broadcast::Receiver<TradeEvent> {
+        self.trade_tx.subscribe()
+    }
+
+    /// Per-pair market data stream health, for the health endpoint.
+    pub fn stream_health(&self) -> StreamHealth {
+        self.health.clone()
+    }
+
+    /// The most recent closed candles for `pair`, oldest first.
+    pub fn recent_candles(&self, pair: &str) -> Vec<MarketEvent> {
+        self.candles.recent(&pair.to_uppercase())
+    }
 }
 
 /// The main engine: manages WebSocket stream lifecycle and command processing.
 pub struct Engine {
-    pairs: Vec<String>,
+    /// Exchange the market data stream connects to.
+    exchange: ExchangeKind,
+    /// Streamed pairs and their kline intervals.
+    pairs: Vec<(String, KlineInterval)>,
+    /// Interval for pairs added at runtime with `AddPair`.
+    default_interval: KlineInterval,
+    /// Pairs that also stream aggregated trades.
+    trade_pairs: Vec<String>,
     state: Arc<RwLock<EngineState>>,
     market_tx: broadcast::Sender<MarketEvent>,
+    trade_tx: broadcast::Sender<TradeEvent>,
     command_rx: mpsc::Receiver<EngineCommand>,
     #[allow(dead_code)] // kept to prevent channel close
     command_tx: mpsc::Sender<EngineCommand>,
     /// Hook called after every reconnect to trigger a position audit.
     on_reconnect: Option<Box<dyn Fn() + Send + Sync>>,
+    /// Control channel into the Risk Manager, used by `Flatten` and `ResetDrawdown`.
+    risk_tx: Option<mpsc::Sender<RiskCommand>>,
+    /// Control channel into the order executor, used by `Halt`.
+    executor_tx: Option<mpsc::Sender<ExecutorCommand>>,
+    /// Silence after which a pair's market data counts as stale, and where
+    /// to report it. `None` disables the watchdog.
+    stale_after: Option<Duration>,
+    risk_event_tx: Option<mpsc::Sender<RiskEvent>>,
+    /// Connection and event health per streamed pair.
+    health: StreamHealth,
+    /// Last closed candles per pair, for late subscribers.
+    candles: CandleCache,
+    /// Ends `run` once cancelled.
+    shutdown: CancellationToken,
 }
 
 impl Engine {
-    pub fn new(pairs: Vec<String>) -> (Self, EngineHandle) {
+    pub fn new(pairs: Vec<(String, KlineInterval)>) -> (Self, EngineHandle) {
         let (command_tx, command_rx) = mpsc::channel(32);
         let (market_tx, _) = broadcast::channel(1024);
+        let (trade_tx, _) = broadcast::channel(4096);
         let state = Arc::new(RwLock::new(EngineState::Stopped));
+        let health = StreamHealth::new();
+        for (pair, interval) in &pairs {
+            health.track(pair, *interval);
+        }
+        let candles = CandleCache::default();
 
         let handle = EngineHandle {
             command_tx: command_tx.clone(),
             state: state.clone(),
             market_tx: market_tx.clone(),
+            trade_tx: trade_tx.clone(),
+            health: health.clone(),
+            candles: candles.clone(),
         };
 
         let engine = Engine {
+            exchange: ExchangeKind::Binance,
             pairs,
+            default_interval: KlineInterval::default(),
+            trade_pairs: Vec::new(),
             state,
             market_tx,
+            trade_tx,
             command_rx,
             command_tx,
             on_reconnect: None,
+            risk_tx: None,
+            executor_tx: None,
+            stale_after: None,
+            risk_event_tx: None,
+            health,
+            candles,
+            shutdown: CancellationToken::new(),
         };
 
         (engine, handle)
     }
 
+    /// Connect the Risk Manager's control channel so `Flatten` can close
+    /// positions and `ResetDrawdown` can reset peak tracking.
+    pub fn set_risk_control(&mut self, risk_tx: mpsc::Sender<RiskCommand>) {
+        self.risk_tx = Some(risk_tx);
+    }
+
+    /// Connect the order executor's control channel so `Halt` can cancel
+    /// resting orders.
+    pub fn set_executor_control(&mut self, executor_tx: mpsc::Sender<ExecutorCommand>) {
+        self.executor_tx = Some(executor_tx);
+    }
+
+    /// Abort the market stream and return from `run` once `shutdown` is
+    /// cancelled. Unlike `Stop`, open positions are left alone.
+    pub fn set_shutdown(&mut self, shutdown: CancellationToken) {
+        self.shutdown = shutdown;
+    }
+
+    /// Stream market data from `exchange` (Binance by default).
+    pub fn set_exchange(&mut self, exchange: ExchangeKind) {
+        self.exchange = exchange;
+    }
+
+    /// Kline interval used for pairs added at runtime with `AddPair`.
+    pub fn set_default_interval(&mut self, interval: KlineInterval) {
+        self.default_interval = interval;
+    }
+
+    /// Enable the aggregated-trade stream for `pairs`.
+    pub fn set_trade_pairs(&mut self, pairs: Vec<String>) {
+        self.trade_pairs = pairs;
+    }
+
+    /// Closed candles kept per pair for `EngineHandle::recent_candles`
+    /// (`DEFAULT_CANDLE_CACHE_SIZE` by default; 0 disables the cache).
+    pub fn set_candle_cache_size(&mut self, size: usize) {
+        self.candles.set_capacity(size);
+    }
+
+    /// Seed the candle cache with `candles` from before a restart, oldest
+    /// first.
+    pub fn preload_candles(&mut self, candles: &[MarketEvent]) {
+        for candle in candles {
+            self.candles.push(candle);
+        }
+    }
+
+    /// Reconnect the market stream and raise `MarketDataStale` when a pair
+    /// goes `stale_after` without an event while the engine is running.
+    pub fn set_staleness_watchdog(
+        &mut self,
+        stale_after: Duration,
+        risk_event_tx: mpsc::Sender<RiskEvent>,
+    ) {
+        self.stale_after = Some(stale_after);
+        self.risk_event_tx = Some(risk_event_tx);
+    }
+
     pub fn on_reconnect<F: Fn() + Send + Sync + 'static>(&mut self, f: F) {
         self.on_reconnect = Some(Box::new(f));
     }
 
+    /// Ask the Risk Manager to close every open position and wait (bounded)
+    /// for the fills, so streams keep feeding prices until the book is flat.
+    async fn close_positions_for_stop(&self) -> StopOutcome {
+        let Some(tx) = &self.risk_tx else {
+            warn!("Stop requested but no risk control channel is set — positions left open");
+            return StopOutcome::LeftOpen;
+        };
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if tx
+            .send(RiskCommand::CloseAll { reply: reply_tx })
+            .await
+            .is_err()
+        {
+            warn!("Risk manager unavailable — positions left open");
+            return StopOutcome::LeftOpen;
+        }
+        match tokio::time::timeout(STOP_CLOSE_TIMEOUT, reply_rx).await {
+            Ok(Ok(Ok(count))) => {
+                info!(count, "Open positions closed before stop");
+                StopOutcome::Closed(count)
+            }
+            Ok(Ok(Err(e))) => {
+                warn!(error = %e, "A position close failed — stopping with it open");
+                StopOutcome::CloseFailed(e)
+            }
+            Ok(Err(_)) => {
+                warn!("Risk manager dropped the close request");
+                StopOutcome::LeftOpen
+            }
+            Err(_) => {
+                warn!(
+                    timeout_secs = STOP_CLOSE_TIMEOUT.as_secs(),
+                    "Timed out waiting for position closes to fill — stopping anyway"
+                );
+                StopOutcome::TimedOut
+            }
+        }
+    }
+
+    /// Have the order executor cancel every resting order. Returns the number
+    /// cancelled and failed, or `None` if the executor did not answer.
+    async fn cancel_resting_for_halt(&self) -> Option<(usize, usize)> {
+        let Some(tx) = &self.executor_tx else {
+            warn!("Halt requested but no executor control channel is set — resting orders left");
+            return None;
+        };
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let cancel = async {
+            tx.send(ExecutorCommand::CancelResting { reply: reply_tx })
+                .await
+                .ok()?;
+            reply_rx.await.ok()
+        };
+        let orders = tokio::time::timeout(HALT_CANCEL_TIMEOUT, cancel)
+            .await
+            .ok()
+            .flatten();
+        match orders {
+            Some((cancelled, failed)) => {
+                info!(cancelled, failed, "Resting orders cancelled for halt")
+            }
+            None => warn!("Order executor unresponsive — resting orders not cancelled"),
+        }
+        orders
+    }
+
+    /// Force a stream reconnect if any running pair has gone quiet for longer
+    /// than the staleness window. Pairs get a fresh grace period afterwards.
+    async fn check_market_staleness(
+        &self,
+        last_seen: &mut HashMap<String, Instant>,
+        stream: Option<&StreamHandle>,
+    ) {
+        let (Some(stale_after), Some(stream)) = (self.stale_after, stream) else {
+            return;
+        };
+        if *self.state.read().await != EngineState::Running {
+            return;
+        }
+
+        let now = Instant::now();
+        let stale: Vec<(String, Duration)> = self
+            .pairs
+            .iter()
+            .filter_map(|(pair, _)| {
+                let silent = now - *last_seen.entry(pair.clone()).or_insert(now);
+                (silent >= stale_after).then(|| (pair.clone(), silent))
+            })
+            .collect();
+        if stale.is_empty() {
+            return;
+        }
+
+        for (pair, silent) in &stale {
+            warn!(pair = %pair, silent_secs = silent.as_secs(), "Market data stale — reconnecting stream");
+            self.health.stale(pair);
+            if let Some(tx) = &self.risk_event_tx {
+                let _ = tx
+                    .send(RiskEvent::MarketDataStale {
+                        pair: pair.clone(),
+                        silent_secs: silent.as_secs(),
+                    })
+                    .await;
+            }
+        }
+        stream.reconnect();
+        for seen in last_seen.values_mut() {
+            *seen = now;
+        }
+    }
+
+    /// Spawn the market data stream for every pair on the configured exchange.
+    fn spawn_stream(
+        &self,
+        event_tx: &mpsc::UnboundedSender<ConnectionEvent>,
+    ) -> (tokio::task::JoinHandle<()>, StreamHandle) {
+        match self.exchange {
+            ExchangeKind::Binance => {
+                let (mut stream, handle) =
+                    BinanceStream::new(self.pairs.clone(), self.market_tx.clone());
+                if !self.trade_pairs.is_empty() {
+                    stream.set_trades(self.trade_pairs.clone(), self.trade_tx.clone());
+                }
+                stream.set_event_notifier(event_tx.clone());
+                (tokio::spawn(stream.run()), handle)
+            }
+            ExchangeKind::Coinbase => {
+                let (mut stream, handle) =
+                    CoinbaseStream::new(self.pairs.clone(), self.market_tx.clone());
+                if !self.trade_pairs.is_empty() {
+                    stream.set_trades(self.trade_pairs.clone(), self.trade_tx.clone());
+                }
+                stream.set_event_notifier(event_tx.clone());
+                (tokio::spawn(stream.run()), handle)
+            }
+            ExchangeKind::Bybit => {
+                let (mut stream, handle) =
+                    BybitStream::new(self.pairs.clone(), self.market_tx.clone());
+                if !self.trade_pairs.is_empty() {
+                    stream.set_trades(self.trade_pairs.clone(), self.trade_tx.clone());
+                }
+                stream.set_event_notifier(event_tx.clone());
+                (tokio::spawn(stream.run()), handle)
+            }
+        }
+    }
+
     /// Run the engine. This task drives stream spawning and command processing.
     /// Call from `tokio::spawn`.
     pub async fn run(mut self) {
         info!("Engine initialized in Stopped state. Waiting for Start command.");
 
-        let mut stream_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
+        let mut stream_task: Option<tokio::task::JoinHandle<()>> = None;
+        let mut stream_handle: Option<StreamHandle> = None;
+        // Staleness watchdog: last market event per pair
+        let mut market_rx = self.market_tx.subscribe();
+        let mut last_seen: HashMap<String, Instant> = HashMap::new();
+        let mut watchdog = tokio::time::interval(WATCHDOG_INTERVAL);
+        let (stream_event_tx, mut stream_event_rx) = mpsc::unbounded_channel();
+        let shutdown = self.shutdown.clone();
 
         loop {
-            match self.command_rx.recv().await {
+            let command = tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!("Engine shutting down — aborting stream tasks");
+                    if let Some(task) = stream_task.take() {
+                        task.abort();
+                    }
+                    self.health.stopped();
+                    return;
+                }
+                command = self.command_rx.recv() => command,
+                event = market_rx.recv() => {
+                    if let Ok(event) = event {
+                        metrics()
+                            .market_events
+                            .get_or_create(&PairLabels { pair: event.pair.clone() })
+                            .inc();
+                        self.health.event(&event.pair);
+                        self.candles.push(&event);
+                        last_seen.insert(event.pair, Instant::now());
+                    }
+                    continue;
+                }
+                Some(event) = stream_event_rx.recv() => {
+                    match event {
+                        ConnectionEvent::Connected => self.health.connected(false),
+                        ConnectionEvent::Reconnected => {
+                            info!("Market stream reconnected");
+                            metrics().stream_reconnects.inc();
+                            self.health.connected(true);
+                            if let Some(hook) = &self.on_reconnect {
+                                hook();
+                            }
+                        }
+                        ConnectionEvent::Disconnected(error) => self.health.disconnected(error),
+                    }
+                    continue;
+                }
+                _ = watchdog.tick() => {
+                    // A stream task only exits by panicking: bring it back
+                    if stream_task.as_ref().is_some_and(|t| t.is_finished()) {
+                        warn!("Market stream task died — restarting");
+                        self.health.restarted();
+                        let (task, handle) = self.spawn_stream(&stream_event_tx);
+                        stream_task = Some(task);
+                        stream_handle = Some(handle);
+                        last_seen.clear();
+                    }
+                    self.check_market_staleness(&mut last_seen, stream_handle.as_ref())
+                        .await;
+                    continue;
+                }
+            };
+
+            match command {
                 Some(EngineCommand::Start) => {
                     let current = *self.state.read().await;
                     if current == EngineState::Running {
@@ -92,22 +438,33 @@ impl Engine {
                     }
 
                     info!(pairs = ?self.pairs, "Starting market data streams");
-                    *self.state.write().await = EngineState::Running;
+                    if current == EngineState::Halted {
+                        // Restored from a persisted halt: stream prices but keep trading blocked
+                        warn!("Starting while halted — use ResetDrawdown to resume trading");
+                    } else {
+                        *self.state.write().await = EngineState::Running;
+                    }
 
-                    // Spawn one WebSocket stream per pair
-                    for pair in &self.pairs {
-                        let stream = BinanceStream::new(pair.clone(), self.market_tx.clone());
-                        let handle = tokio::spawn(stream.run());
-                        stream_handles.push(handle);
+                    // One combined WebSocket stream carries every pair
+                    if let Some(task) = stream_task.take() {
+                        task.abort();
                     }
+                    let (task, handle) = self.spawn_stream(&stream_event_tx);
+                    stream_task = Some(task);
+                    stream_handle = Some(handle);
+                    last_seen.clear();
                 }
 
-                Some(EngineCommand::Stop) => {
+                Some(EngineCommand::Stop { reply }) => {
+                    let outcome = self.close_positions_for_stop().await;
                     info!("Engine stopping — aborting stream tasks");
                     *self.state.write().await = EngineState::Stopped;
-                    for h in stream_handles.drain(..) {
-                        h.abort();
+                    if let Some(task) = stream_task.take() {
+                        task.abort();
                     }
+                    stream_handle = None;
+                    self.health.stopped();
+                    let _ = reply.send(outcome);
                 }
 
                 Some(EngineCommand::Pause) => {
@@ -118,6 +475,19 @@ impl Engine {
                     }
                 }
 
+                Some(EngineCommand::Halt { reply }) => {
+                    let state = {
+                        let mut state = self.state.write().await;
+                        if *state == EngineState::Running {
+                            warn!("Engine halted — streams continue, entries blocked");
+                            *state = EngineState::Paused;
+                        }
+                        *state
+                    };
+                    let orders = self.cancel_resting_for_halt().await;
+                    let _ = reply.send(HaltOutcome { state, orders });
+                }
+
                 Some(EngineCommand::Resume) => {
                     let current = *self.state.read().await;
                     if current == EngineState::Paused {
@@ -131,11 +501,70 @@ impl Engine {
                     if current == EngineState::Halted {
                         info!("Drawdown reset — engine resuming");
                         *self.state.write().await = EngineState::Running;
+                        if let Some(tx) = &self.risk_tx {
+                            let _ = tx.send(RiskCommand::ResetDrawdown).await;
+                        }
                     } else {
                         warn!("ResetDrawdown received but engine is not halted");
                     }
                 }
 
+                Some(EngineCommand::Flatten) => {
+                    warn!("Flatten requested — closing all positions and pausing entries");
+                    match &self.risk_tx {
+                        Some(tx) => {
+                            let _ = tx.send(RiskCommand::Flatten).await;
+                        }
+                        None => warn!("Flatten requested but no risk control channel is set"),
+                    }
+                    let current = *self.state.read().await;
+                    if current == EngineState::Running {
+                        *self.state.write().await = EngineState::Paused;
+                    }
+                }
+
+                Some(EngineCommand::AddPair(pair)) => {
+                    let pair = pair.to_uppercase();
+                    if self.pairs.iter().any(|(p, _)| p == &pair) {
+                        info!(pair = %pair, "Pair already streamed");
+                        continue;
+                    }
+                    let interval = self.default_interval;
+                    info!(pair = %pair, interval = %interval, "Adding pair");
+                    self.pairs.push((pair.clone(), interval));
+                    self.health.track(&pair, interval);
+                    if let Some(handle) = &stream_handle {
+                        handle.subscribe(pair, interval);
+                    }
+                }
+
+                Some(EngineCommand::SubscribePair(pair, interval)) => {
+                    let pair = pair.to_uppercase();
+                    match self.pairs.iter_mut().find(|(p, _)| p == &pair) {
+                        Some((_, current)) if *current == interval => continue,
+                        Some((_, current)) => *current = interval,
+                        None => self.pairs.push((pair.clone(), interval)),
+                    }
+                    info!(pair = %pair, interval = %interval, "Subscribing to pair");
+                    self.health.track(&pair, interval);
+                    if let Some(handle) = &stream_handle {
+                        handle.subscribe(pair, interval);
+                    }
+                }
+
+                Some(EngineCommand::RemovePair(pair)) => {
+                    let pair = pair.to_uppercase();
+                    info!(pair = %pair, "Removing pair");
+                    self.pairs.retain(|(p, _)| p != &pair);
+                    self.trade_pairs.retain(|p| p != &pair);
+                    last_seen.remove(&pair);
+                    self.health.untrack(&pair);
+                    self.candles.remove(&pair);
+                    if let Some(handle) = &stream_handle {
+                        handle.unsubscribe(pair);
+                    }
+                }
+
                 None => {
                     warn!("Engine command channel closed — shutting down");
                     break;
@@ -144,3 +573,71 @@ impl Engine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stop an engine whose risk manager answers the close request with
+    /// `closes`, or drops it when `None`, and return the engine's reply.
+    async fn stop_with(closes: Option<Result<usize, String>>) -> StopOutcome {
+        let (mut engine, handle) = Engine::new(Vec::new());
+        let (risk_tx, mut risk_rx) = mpsc::channel(1);
+        engine.set_risk_control(risk_tx);
+        tokio::spawn(engine.run());
+        tokio::spawn(async move {
+            while let Some(cmd) = risk_rx.recv().await {
+                if let (RiskCommand::CloseAll { reply }, Some(closes)) = (cmd, closes.clone()) {
+                    let _ = reply.send(closes);
+                }
+            }
+        });
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        handle.send(EngineCommand::Stop { reply: reply_tx }).await;
+        let outcome = reply_rx.await.unwrap();
+        assert_eq!(handle.state().await, EngineState::Stopped);
+        outcome
+    }
+
+    #[tokio::test]
+    async fn stop_reports_the_positions_it_closed() {
+        assert_eq!(stop_with(Some(Ok(2))).await, StopOutcome::Closed(2));
+    }
+
+    #[tokio::test]
+    async fn stop_reports_a_failed_close() {
+        assert_eq!(
+            stop_with(Some(Err("insufficient balance".into()))).await,
+            StopOutcome::CloseFailed("insufficient balance".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn stop_reports_a_dropped_close_request() {
+        assert_eq!(stop_with(None).await, StopOutcome::LeftOpen);
+    }
+
+    #[tokio::test]
+    async fn halt_pauses_and_cancels_resting_orders() {
+        let (mut engine, handle) = Engine::new(Vec::new());
+        let (executor_tx, mut executor_rx) = mpsc::channel(1);
+        engine.set_executor_control(executor_tx);
+        *handle.state_handle().write().await = EngineState::Running;
+        tokio::spawn(engine.run());
+        tokio::spawn(async move {
+            while let Some(cmd) = executor_rx.recv().await {
+                if let ExecutorCommand::CancelResting { reply } = cmd {
+                    let _ = reply.send((2, 1));
+                }
+            }
+        });
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        handle.send(EngineCommand::Halt { reply: reply_tx }).await;
+        let outcome = reply_rx.await.unwrap();
+        assert_eq!(outcome.state, EngineState::Paused);
+        assert_eq!(outcome.orders, Some((2, 1)));
+        assert_eq!(handle.state().await, EngineState::Paused);
+    }
+}
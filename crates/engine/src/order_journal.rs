@@ -0,0 +1,270 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+use tracing::error;
+
+use common::{
+    decimal, Fill, Order, OrderStatus, OrderStatusReport, OrderTrigger, TradingMode,
+    DEFAULT_ACCOUNT,
+};
+
+/// Lifecycle state of an order as stored in the `orders` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    /// Handed to the exchange client; no answer yet.
+    Submitted,
+    /// Accepted by the exchange and waiting to fill.
+    Resting,
+    PartiallyFilled,
+    Filled,
+    /// Submission failed, after retries for transient errors.
+    Failed,
+    /// Refused before or by the exchange (filters, exchange rejection).
+    Rejected,
+    Canceled,
+    Expired,
+}
+
+impl OrderState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderState::Submitted => "submitted",
+            OrderState::Resting => "resting",
+            OrderState::PartiallyFilled => "partially_filled",
+            OrderState::Filled => "filled",
+            OrderState::Failed => "failed",
+            OrderState::Rejected => "rejected",
+            OrderState::Canceled => "canceled",
+            OrderState::Expired => "expired",
+        }
+    }
+
+    /// State for an order whose execution report shows `filled` of `quantity`.
+    fn from_fill(filled: Decimal, quantity: Decimal) -> Self {
+        if filled <= Decimal::ZERO {
+            OrderState::Resting
+        } else if filled < quantity {
+            OrderState::PartiallyFilled
+        } else {
+            OrderState::Filled
+        }
+    }
+}
+
+impl From<OrderStatus> for OrderState {
+    fn from(status: OrderStatus) -> Self {
+        match status {
+            OrderStatus::New => OrderState::Resting,
+            OrderStatus::PartiallyFilled => OrderState::PartiallyFilled,
+            OrderStatus::Filled => OrderState::Filled,
+            OrderStatus::Canceled => OrderState::Canceled,
+            OrderStatus::Expired => OrderState::Expired,
+            OrderStatus::Rejected => OrderState::Rejected,
+        }
+    }
+}
+
+/// Writes every order the executor handles to the `orders` table, from
+/// submission through fill, failure, or rejection, so order history and
+/// errors survive beyond the log stream.
+#[derive(Clone)]
+pub struct OrderJournal {
+    db: SqlitePool,
+    mode: TradingMode,
+    account: String,
+}
+
+impl OrderJournal {
+    pub fn new(db: SqlitePool, mode: TradingMode) -> Self {
+        Self {
+            db,
+            mode,
+            account: DEFAULT_ACCOUNT.to_string(),
+        }
+    }
+
+    /// Record later orders under `mode`, after a runtime mode switch.
+    pub fn set_mode(&mut self, mode: TradingMode) {
+        self.mode = mode;
+    }
+
+    /// Record orders under `account`.
+    pub fn set_account(&mut self, account: &str) {
+        self.account = account.to_string();
+    }
+
+    /// Record `order` as handed to the exchange.
+    pub async fn submitted(&self, order: &Order) {
+        self.record(order, OrderState::Submitted, None).await;
+    }
+
+    /// Record `order` as refused before reaching the exchange.
+    pub async fn rejected(&self, order: &Order, reason: &str) {
+        self.record(order, OrderState::Rejected, Some(reason)).await;
+    }
+
+    /// Record the exchange's answer to a submission.
+    pub async fn accepted(&self, order: &Order, fill: &Fill, attempts: u32) {
+        let state = OrderState::from_fill(fill.quantity, order.quantity);
+        let average_price = (fill.quantity > Decimal::ZERO).then_some(fill.fill_price);
+        self.update(
+            &order.id,
+            state,
+            fill.exchange_order_id.as_deref(),
+            decimal::to_f64(fill.quantity),
+            average_price.map(decimal::to_f64),
+            attempts,
+        )
+        .await;
+    }
+
+    /// Record a submission that failed after `attempts` tries.
+    pub async fn failed(&self, order: &Order, error: &str, attempts: u32) {
+        let status = OrderState::Failed.as_str();
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            "UPDATE orders SET status = ?1, error = ?2, attempts = ?3, updated_at = ?4 WHERE id = ?5",
+            status,
+            error,
+            attempts,
+            now,
+            order.id,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(order_id = %order.id, error = %e, "Failed to persist order failure");
+        }
+    }
+
+    /// Record the final state of a resting order.
+    pub async fn settled(&self, order: &Order, report: &OrderStatusReport) {
+        let status = OrderState::from(report.status).as_str();
+        let filled_quantity = decimal::to_f64(report.executed_quantity);
+        let average_price = report.average_price.map(decimal::to_f64);
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            r#"
+            UPDATE orders
+            SET status = ?1, filled_quantity = ?2, average_price = ?3, updated_at = ?4,
+                exchange_order_id = COALESCE(?5, exchange_order_id)
+            WHERE id = ?6
+            "#,
+            status,
+            filled_quantity,
+            average_price,
+            now,
+            report.exchange_order_id,
+            order.id,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(order_id = %order.id, error = %e, "Failed to persist order status");
+        }
+    }
+
+    async fn update(
+        &self,
+        order_id: &str,
+        state: OrderState,
+        exchange_order_id: Option<&str>,
+        filled_quantity: f64,
+        average_price: Option<f64>,
+        attempts: u32,
+    ) {
+        let status = state.as_str();
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            r#"
+            UPDATE orders
+            SET status = ?1, exchange_order_id = ?2, filled_quantity = ?3, average_price = ?4,
+                attempts = ?5, updated_at = ?6
+            WHERE id = ?7
+            "#,
+            status,
+            exchange_order_id,
+            filled_quantity,
+            average_price,
+            attempts,
+            now,
+            order_id,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(order_id = %order_id, error = %e, "Failed to persist order update");
+        }
+    }
+
+    /// Insert (or reset) the row for `order`. Failures are logged, never
+    /// propagated — losing an audit row must not stall order flow.
+    async fn record(&self, order: &Order, state: OrderState, error: Option<&str>) {
+        let side = order.side.to_string();
+        let order_type = order_type(order);
+        let quantity = decimal::to_f64(order.quantity);
+        let price = order.price.map(decimal::to_f64);
+        let mode = self.mode.to_string();
+        let status = state.as_str();
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO orders (id, pair, side, order_type, quantity, price, position_id,
+                                status, error, mode, created_at, updated_at, account_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, ?12)
+            ON CONFLICT(id) DO UPDATE SET
+                quantity = excluded.quantity, price = excluded.price,
+                status = excluded.status, error = excluded.error,
+                updated_at = excluded.updated_at
+            "#,
+            order.id,
+            order.pair,
+            side,
+            order_type,
+            quantity,
+            price,
+            order.position_id,
+            status,
+            error,
+            mode,
+            now,
+            self.account,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(order_id = %order.id, error = %e, "Failed to persist order");
+        }
+    }
+}
+
+fn order_type(order: &Order) -> &'static str {
+    match (order.trigger, order.price) {
+        (Some(OrderTrigger::StopLoss { .. }), _) => "stop_loss",
+        (Some(OrderTrigger::TakeProfit { .. }), _) => "take_profit",
+        (None, Some(_)) => "limit",
+        (None, None) => "market",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_from_executed_quantity() {
+        let quantity = Decimal::ONE;
+        assert_eq!(
+            OrderState::from_fill(Decimal::ZERO, quantity),
+            OrderState::Resting
+        );
+        assert_eq!(
+            OrderState::from_fill(Decimal::new(4, 1), quantity),
+            OrderState::PartiallyFilled
+        );
+        assert_eq!(
+            OrderState::from_fill(quantity, quantity),
+            OrderState::Filled
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use common::Order;
+
+/// An order accepted by the exchange that has not reached a terminal state.
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    pub order: Order,
+    /// The exchange's identifier for the order (as returned in the `Fill`).
+    pub exchange_order_id: String,
+    pub submitted_at: DateTime<Utc>,
+    /// Quantity executed and booked so far; polls book what exceeds it.
+    pub executed: Decimal,
+}
+
+/// Records orders that did not fill completely on submission (resting or
+/// partly filled limits, untriggered stops) so the executor can poll them
+/// until they fill, cancel, or expire.
+#[derive(Debug, Default)]
+pub struct OrderTracker {
+    open: HashMap<String, TrackedOrder>,
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track an order of which `executed` has already been booked.
+    pub fn track(&mut self, order: Order, exchange_order_id: String, executed: Decimal) {
+        self.open.insert(
+            exchange_order_id.clone(),
+            TrackedOrder {
+                order,
+                exchange_order_id,
+                submitted_at: Utc::now(),
+                executed,
+            },
+        );
+    }
+
+    /// Record that `executed` of a tracked order has been booked. Returns
+    /// the order if that is more than before.
+    pub fn executed(&mut self, exchange_order_id: &str, executed: Decimal) -> Option<Order> {
+        let tracked = self.open.get_mut(exchange_order_id)?;
+        if executed <= tracked.executed {
+            return None;
+        }
+        tracked.executed = executed;
+        Some(tracked.order.clone())
+    }
+
+    /// Stop tracking an order, returning it if it was being tracked.
+    pub fn remove(&mut self, exchange_order_id: &str) -> Option<TrackedOrder> {
+        self.open.remove(exchange_order_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.open.len()
+    }
+
+    /// `(pair, exchange order ID)` for every tracked order, for polling.
+    pub fn poll_targets(&self) -> Vec<(String, String)> {
+        self.open
+            .values()
+            .map(|t| (t.order.pair.clone(), t.exchange_order_id.clone()))
+            .collect()
+    }
+}
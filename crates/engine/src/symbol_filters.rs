@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use common::{BracketOrder, Order, OrderSide, OrderTrigger};
+use rust_decimal::Decimal;
+
+/// Trading rules for one symbol (Binance LOT_SIZE, PRICE_FILTER, and
+/// MIN_NOTIONAL / NOTIONAL filters). A zero field means "no constraint".
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SymbolFilters {
+    pub step_size: Decimal,
+    pub min_qty: Decimal,
+    pub tick_size: Decimal,
+    pub min_notional: Decimal,
+}
+
+/// Filters for every traded symbol, keyed by pair.
+pub type SymbolFilterMap = HashMap<String, SymbolFilters>;
+
+impl SymbolFilters {
+    /// Round an order's quantity down to the lot step and its prices to the
+    /// tick size (limits conservatively: buys down, sells up). Returns an
+    /// error describing the violated rule if the order cannot be sent.
+    pub fn normalize(&self, order: &mut Order) -> Result<(), String> {
+        // Quote-quantity orders are sized by the exchange, so LOT_SIZE
+        // doesn't apply; only the amount spent has a floor
+        if let Some(quote) = order.quote_quantity {
+            if order.price.is_some() || order.trigger.is_some() {
+                return Err(format!(
+                    "quote quantity is only supported on market orders ({})",
+                    order.pair
+                ));
+            }
+            if quote <= Decimal::ZERO || quote < self.min_notional {
+                return Err(format!(
+                    "quote quantity {quote} below MIN_NOTIONAL {} for {}",
+                    self.min_notional, order.pair
+                ));
+            }
+            return Ok(());
+        }
+
+        order.quantity = floor_to_step(order.quantity, self.step_size);
+        if order.quantity <= Decimal::ZERO || order.quantity < self.min_qty {
+            return Err(format!(
+                "quantity below LOT_SIZE minimum {} for {}",
+                self.min_qty, order.pair
+            ));
+        }
+
+        if let Some(price) = order.price {
+            order.price = Some(match order.side {
+                OrderSide::Buy => floor_to_step(price, self.tick_size),
+                OrderSide::Sell => ceil_to_step(price, self.tick_size),
+            });
+        }
+        order.trigger = order.trigger.map(|t| match t {
+            OrderTrigger::StopLoss { stop_price } => OrderTrigger::StopLoss {
+                stop_price: round_to_step(stop_price, self.tick_size),
+            },
+            OrderTrigger::TakeProfit { stop_price } => OrderTrigger::TakeProfit {
+                stop_price: round_to_step(stop_price, self.tick_size),
+            },
+        });
+
+        // Closes are never blocked on notional: a dust remainder must still exit
+        if order.position_id.is_none() {
+            if let Some(price) = order.price.or(order.reference_price) {
+                let notional = order.quantity * price;
+                if notional < self.min_notional {
+                    return Err(format!(
+                        "notional {notional:.2} below MIN_NOTIONAL {} for {}",
+                        self.min_notional, order.pair
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Round a bracket's quantity and prices to valid steps.
+    pub fn normalize_bracket(&self, bracket: &mut BracketOrder) {
+        bracket.quantity = floor_to_step(bracket.quantity, self.step_size);
+        bracket.take_profit_price = round_to_step(bracket.take_profit_price, self.tick_size);
+        bracket.stop_price = round_to_step(bracket.stop_price, self.tick_size);
+    }
+}
+
+// Decimal division is exact for step sizes like 0.001, so multiples of the
+// step survive untouched and the result carries the step's scale.
+
+fn floor_to_step(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).floor() * step).normalize()
+}
+
+fn ceil_to_step(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).ceil() * step).normalize()
+}
+
+fn round_to_step(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    ((value / step).round() * step).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn filters() -> SymbolFilters {
+        SymbolFilters {
+            step_size: dec!(0.001),
+            min_qty: dec!(0.001),
+            tick_size: dec!(0.01),
+            min_notional: dec!(10),
+        }
+    }
+
+    #[test]
+    fn quantity_and_limit_price_snap_to_steps() {
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.012345));
+        order.price = Some(dec!(30_000.129));
+        filters().normalize(&mut order).unwrap();
+        assert_eq!(order.quantity.to_string(), "0.012");
+        assert_eq!(order.price, Some(dec!(30_000.12)));
+    }
+
+    #[test]
+    fn rejects_below_lot_size_and_min_notional() {
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.0004));
+        assert!(filters().normalize(&mut order).is_err());
+
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.002));
+        order.reference_price = Some(dec!(1_000));
+        assert!(filters().normalize(&mut order).is_err());
+    }
+
+    #[test]
+    fn quote_quantity_skips_lot_size_but_not_min_notional() {
+        let mut order = Order::market_quote("BTCUSDT", OrderSide::Buy, dec!(50));
+        filters().normalize(&mut order).unwrap();
+        assert_eq!(order.quote_quantity, Some(dec!(50)));
+
+        let mut order = Order::market_quote("BTCUSDT", OrderSide::Buy, dec!(5));
+        assert!(filters().normalize(&mut order).is_err());
+
+        let mut order = Order::market_quote("BTCUSDT", OrderSide::Buy, dec!(50));
+        order.price = Some(dec!(30_000));
+        assert!(filters().normalize(&mut order).is_err());
+    }
+}
@@ -0,0 +1,581 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::SqlitePool;
+
+use common::{
+    decimal, ClosedTrade, Fill, Order, OrderSide, Position, TradingMode, DEFAULT_ACCOUNT,
+};
+
+/// Books fills against the `positions` and `trades` tables.
+///
+/// A fill first closes open positions on the opposite side: the one named by
+/// `order.position_id`, or otherwise the pair's oldest positions first. Each
+/// closed slice is written to `trades` with its realized PnL and its share
+/// of the position's entry fees and the fill's fee, and shrinks (or deletes)
+/// its position row. Whatever is left of an order without a
+/// `position_id` opens a new position, attributed to `order.strategy`; the
+/// trades that close it carry the same attribution. Everything one fill
+/// books is written in a single transaction.
+///
+/// A fill reports an order's execution so far, as exchanges do: its
+/// cumulative quantity, average price, and total fee. Only the increment over
+/// what earlier fills of the order booked is booked, and it is first written
+/// to `fills`, keyed by the order ID and cumulative quantity, so a fill
+/// reported again books nothing.
+#[derive(Clone)]
+pub struct TradeLedger {
+    db: SqlitePool,
+    mode: TradingMode,
+    account: String,
+}
+
+impl TradeLedger {
+    pub fn new(db: SqlitePool, mode: TradingMode) -> Self {
+        Self {
+            db,
+            mode,
+            account: DEFAULT_ACCOUNT.to_string(),
+        }
+    }
+
+    /// Book later fills under `mode`, after a runtime mode switch.
+    pub fn set_mode(&mut self, mode: TradingMode) {
+        self.mode = mode;
+    }
+
+    /// Book fills to `account`'s positions and trades.
+    pub fn set_account(&mut self, account: &str) {
+        self.account = account.to_string();
+    }
+
+    /// Record one fill. Returns what it booked, for the executor to publish,
+    /// or `None` if earlier fills of the order already booked as much.
+    pub async fn record_fill(
+        &self,
+        order: &Order,
+        fill: &Fill,
+    ) -> Result<Option<Booking>, sqlx::Error> {
+        let mode = self.mode.to_string();
+        let opposite = fill.side.opposite().to_string();
+        let mut tx = self.db.begin().await?;
+
+        let earlier = sqlx::query!(
+            r#"SELECT MAX(executed_quantity) as "executed: f64",
+                      SUM(price * quantity) as "notional: f64",
+                      SUM(fee_usd) as "fee: f64"
+               FROM fills WHERE order_id = ?1"#,
+            fill.order_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        let booked = decimal::from_f64(earlier.executed.unwrap_or_default());
+        let quantity = fill.quantity - booked;
+        if quantity <= Decimal::ZERO {
+            return Ok(None);
+        }
+        // The increment's price is what the new average adds over the old
+        let booked_notional = decimal::from_f64(earlier.notional.unwrap_or_default());
+        let increment_price = (fill.fill_price * fill.quantity - booked_notional) / quantity;
+        let fill = &Fill {
+            fill_price: if increment_price > Decimal::ZERO {
+                increment_price
+            } else {
+                fill.fill_price
+            },
+            quantity,
+            fee: (fill.fee - decimal::from_f64(earlier.fee.unwrap_or_default())).max(Decimal::ZERO),
+            ..fill.clone()
+        };
+
+        let executed = decimal::to_f64(booked + quantity);
+        let side = fill.side.to_string();
+        let price = decimal::to_f64(fill.fill_price);
+        let filled = decimal::to_f64(fill.quantity);
+        let fee = decimal::to_f64(fill.fee);
+        let filled_at = fill.timestamp.to_rfc3339();
+        let inserted = sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO fills (order_id, executed_quantity, exchange_order_id, pair,
+                                         side, price, quantity, fee_usd, mode, filled_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            fill.order_id,
+            executed,
+            fill.exchange_order_id,
+            fill.pair,
+            side,
+            price,
+            filled,
+            fee,
+            mode,
+            filled_at,
+        )
+        .execute(&mut *tx)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let open =
+            match &order.position_id {
+                Some(position_id) => sqlx::query_as!(
+                    OpenPosition,
+                    r#"SELECT id as "id!", side, entry_price, quantity, fee_usd, opened_at, strategy
+                       FROM positions WHERE id = ?1 AND side = ?2"#,
+                    position_id,
+                    opposite,
+                )
+                .fetch_all(&mut *tx)
+                .await?,
+                None => sqlx::query_as!(
+                    OpenPosition,
+                    r#"SELECT id as "id!", side, entry_price, quantity, fee_usd, opened_at, strategy
+                       FROM positions
+                       WHERE pair = ?1 AND mode = ?2 AND side = ?3 AND account_id = ?4
+                       ORDER BY opened_at ASC"#,
+                    fill.pair,
+                    mode,
+                    opposite,
+                    self.account,
+                )
+                .fetch_all(&mut *tx)
+                .await?,
+            };
+
+        let closed_at = fill.timestamp.to_rfc3339();
+        let exit_price = decimal::to_f64(fill.fill_price);
+        let mut remaining = fill.quantity;
+        let mut trades = Vec::new();
+        let mut reduced = Vec::new();
+        for position in open {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let closed = remaining.min(decimal::from_f64(position.quantity));
+            let pnl = realized_pnl(
+                fill.side.opposite(),
+                decimal::from_f64(position.entry_price),
+                fill.fill_price,
+                closed,
+            );
+            let (quantity, pnl_usd) = (decimal::to_f64(closed), decimal::to_f64(pnl));
+            let entry_fee = pro_rata(
+                decimal::from_f64(position.fee_usd),
+                closed,
+                decimal::from_f64(position.quantity),
+            );
+            let fee_usd = decimal::to_f64(entry_fee + pro_rata(fill.fee, closed, fill.quantity));
+            let entry_fee = decimal::to_f64(entry_fee);
+            let trade_id = uuid::Uuid::new_v4().to_string();
+            sqlx::query!(
+                r#"
+                INSERT INTO trades (id, pair, side, entry_price, exit_price, quantity,
+                                              pnl_usd, mode, opened_at, closed_at, strategy,
+                                              fee_usd, order_id, exchange_order_id, position_id,
+                                              account_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+                "#,
+                trade_id,
+                fill.pair,
+                position.side,
+                position.entry_price,
+                exit_price,
+                quantity,
+                pnl_usd,
+                mode,
+                position.opened_at,
+                closed_at,
+                position.strategy,
+                fee_usd,
+                fill.order_id,
+                fill.exchange_order_id,
+                position.id,
+                self.account,
+            )
+            .execute(&mut *tx)
+            .await?;
+            remaining -= closed;
+            sqlx::query!(
+                "UPDATE positions SET quantity = quantity - ?1, fee_usd = fee_usd - ?2 WHERE id = ?3",
+                quantity,
+                entry_fee,
+                position.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!(
+                "DELETE FROM positions WHERE id = ?1 AND quantity <= 1e-9",
+                position.id,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            reduced.extend(position.to_position(
+                &fill.pair,
+                decimal::from_f64(position.quantity) - closed,
+                self.mode,
+            ));
+            trades.push(ClosedTrade {
+                id: trade_id,
+                pair: fill.pair.clone(),
+                side: fill.side.opposite(),
+                entry_price: position.entry_price,
+                exit_price,
+                quantity,
+                pnl_usd,
+                fee_usd,
+                strategy: position.strategy,
+                closed_at: fill.timestamp,
+            });
+        }
+
+        // Leftover of a close is dropped: there is nothing more to close
+        let mut opened = None;
+        if remaining > Decimal::ZERO && order.position_id.is_none() {
+            let side = fill.side.to_string();
+            let quantity = decimal::to_f64(remaining);
+            let fee_usd = decimal::to_f64(pro_rata(fill.fee, remaining, fill.quantity));
+            let opened_at = fill.timestamp.to_rfc3339();
+            // A later part of the same entry grows the position it opened
+            let position = sqlx::query!(
+                r#"
+                INSERT INTO positions (id, pair, side, entry_price, quantity, mode, opened_at,
+                                       strategy, fee_usd, account_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT(id) DO UPDATE SET
+                    entry_price = (entry_price * quantity
+                                   + excluded.entry_price * excluded.quantity)
+                                  / (quantity + excluded.quantity),
+                    quantity = quantity + excluded.quantity,
+                    fee_usd = fee_usd + excluded.fee_usd
+                RETURNING entry_price, quantity, opened_at
+                "#,
+                fill.order_id,
+                fill.pair,
+                side,
+                exit_price,
+                quantity,
+                mode,
+                opened_at,
+                order.strategy,
+                fee_usd,
+                self.account,
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            opened = Some(Position {
+                id: fill.order_id.clone(),
+                pair: fill.pair.clone(),
+                side: fill.side,
+                entry_price: decimal::from_f64(position.entry_price),
+                quantity: decimal::from_f64(position.quantity),
+                mode: self.mode,
+                opened_at: DateTime::parse_from_rfc3339(&position.opened_at)
+                    .map_or(fill.timestamp, |at| at.with_timezone(&Utc)),
+            });
+        }
+
+        tx.commit().await?;
+        Ok(Some(Booking {
+            fill: fill.clone(),
+            trades,
+            opened,
+            reduced,
+        }))
+    }
+
+    /// The account's open positions booked under the current mode, oldest
+    /// first. Rows with an unreadable side or timestamp are skipped.
+    pub async fn open_positions(&self) -> Result<Vec<Position>, sqlx::Error> {
+        let mode = self.mode.to_string();
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!", pair, side, entry_price, quantity, opened_at
+               FROM positions WHERE mode = ?1 AND account_id = ?2 ORDER BY opened_at ASC"#,
+            mode,
+            self.account,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let side = match row.side.as_str() {
+                    "BUY" => OrderSide::Buy,
+                    "SELL" => OrderSide::Sell,
+                    _ => return None,
+                };
+                let opened_at = DateTime::parse_from_rfc3339(&row.opened_at).ok()?;
+                Some(Position {
+                    id: row.id,
+                    pair: row.pair,
+                    side,
+                    entry_price: decimal::from_f64(row.entry_price),
+                    quantity: decimal::from_f64(row.quantity),
+                    mode: self.mode,
+                    opened_at: opened_at.with_timezone(&Utc),
+                })
+            })
+            .collect())
+    }
+}
+
+/// What one fill booked.
+#[derive(Debug, Clone)]
+pub struct Booking {
+    /// The increment of the order's execution that was booked: its
+    /// quantity, price, and fee.
+    pub fill: Fill,
+    /// Trades the fill closed.
+    pub trades: Vec<ClosedTrade>,
+    /// The position the fill opened or, for a later part of the same entry,
+    /// grew, as it now stands.
+    pub opened: Option<Position>,
+    /// Positions the fill closed part of, with the quantity still open
+    /// (zero once closed entirely).
+    pub reduced: Vec<Position>,
+}
+
+struct OpenPosition {
+    id: String,
+    side: String,
+    entry_price: f64,
+    quantity: f64,
+    fee_usd: f64,
+    opened_at: String,
+    strategy: Option<String>,
+}
+
+impl OpenPosition {
+    /// This row as a `Position` on `pair` with `quantity` left. `None` if
+    /// its side or timestamp is unreadable.
+    fn to_position(&self, pair: &str, quantity: Decimal, mode: TradingMode) -> Option<Position> {
+        let side = match self.side.as_str() {
+            "BUY" => OrderSide::Buy,
+            "SELL" => OrderSide::Sell,
+            _ => return None,
+        };
+        let opened_at = DateTime::parse_from_rfc3339(&self.opened_at).ok()?;
+        Some(Position {
+            id: self.id.clone(),
+            pair: pair.to_string(),
+            side,
+            entry_price: decimal::from_f64(self.entry_price),
+            quantity: quantity.max(Decimal::ZERO),
+            mode,
+            opened_at: opened_at.with_timezone(&Utc),
+        })
+    }
+}
+
+/// The share of `fee` belonging to `part` of `whole`.
+fn pro_rata(fee: Decimal, part: Decimal, whole: Decimal) -> Decimal {
+    if whole.is_zero() {
+        Decimal::ZERO
+    } else {
+        fee * part / whole
+    }
+}
+
+/// PnL of closing `quantity` of a `side` position opened at `entry` at `exit`.
+fn realized_pnl(side: OrderSide, entry: Decimal, exit: Decimal, quantity: Decimal) -> Decimal {
+    match side {
+        OrderSide::Buy => (exit - entry) * quantity,
+        OrderSide::Sell => (entry - exit) * quantity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    async fn ledger() -> TradeLedger {
+        TradeLedger::new(crate::test_db().await, TradingMode::Paper)
+    }
+
+    fn fill_for(order: &Order, price: Decimal, minute: u32) -> Fill {
+        Fill {
+            order_id: order.id.clone(),
+            exchange_order_id: None,
+            pair: order.pair.clone(),
+            side: order.side,
+            fill_price: price,
+            quantity: order.quantity,
+            fee: Decimal::ZERO,
+            timestamp: Utc.with_ymd_and_hms(2026, 10, 16, 12, minute, 0).unwrap(),
+        }
+    }
+
+    /// Book a market `side` order for `quantity` at `price`.
+    async fn book(
+        ledger: &TradeLedger,
+        side: OrderSide,
+        quantity: Decimal,
+        price: Decimal,
+        minute: u32,
+    ) -> (Order, Booking) {
+        let order = Order::market("BTCUSDT", side, quantity);
+        let booking = ledger
+            .record_fill(&order, &fill_for(&order, price, minute))
+            .await
+            .unwrap()
+            .expect("booked");
+        (order, booking)
+    }
+
+    async fn open_quantities(ledger: &TradeLedger) -> Vec<(String, Decimal)> {
+        ledger
+            .open_positions()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.id, p.quantity))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn repeated_fill_books_once() {
+        let ledger = ledger().await;
+        let order = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        let fill = fill_for(&order, dec!(100), 0);
+
+        let first = ledger.record_fill(&order, &fill).await.unwrap();
+        assert_eq!(first.unwrap().opened.unwrap().id, order.id);
+        assert!(ledger.record_fill(&order, &fill).await.unwrap().is_none());
+        assert_eq!(open_quantities(&ledger).await, [(order.id, dec!(1))]);
+    }
+
+    #[tokio::test]
+    async fn partial_fills_of_one_order_book_their_increments() {
+        let ledger = ledger().await;
+        let (entry, _) = book(&ledger, OrderSide::Buy, dec!(1), dec!(100), 0).await;
+        let position = ledger.open_positions().await.unwrap().remove(0);
+
+        // A resting close fills 0.4 at 110, then 0.6 more at 120 (average 116)
+        let close = Order::close(&position, dec!(1));
+        let mut fill = fill_for(&close, dec!(110), 1);
+        fill.quantity = dec!(0.4);
+        let first = ledger.record_fill(&close, &fill).await.unwrap().unwrap();
+        assert_eq!(first.fill.quantity, dec!(0.4));
+        assert_eq!(first.trades[0].pnl_usd, 4.0);
+        assert_eq!(
+            open_quantities(&ledger).await,
+            [(entry.id.clone(), dec!(0.6))]
+        );
+
+        // The first report again books nothing
+        assert!(ledger.record_fill(&close, &fill).await.unwrap().is_none());
+
+        fill.quantity = dec!(1);
+        fill.fill_price = dec!(116);
+        let second = ledger.record_fill(&close, &fill).await.unwrap().unwrap();
+        assert_eq!(second.fill.quantity, dec!(0.6));
+        assert_eq!(second.fill.fill_price, dec!(120));
+        assert_eq!(second.trades[0].pnl_usd, 12.0);
+        assert_eq!(second.reduced[0].quantity, Decimal::ZERO);
+        assert!(open_quantities(&ledger).await.is_empty());
+
+        // Nor does the final report, or an older one arriving late
+        assert!(ledger.record_fill(&close, &fill).await.unwrap().is_none());
+        fill.quantity = dec!(0.4);
+        assert!(ledger.record_fill(&close, &fill).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn partial_fills_of_an_entry_grow_one_position() {
+        let ledger = ledger().await;
+        let order = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        let mut fill = fill_for(&order, dec!(100), 0);
+        fill.quantity = dec!(0.5);
+        ledger.record_fill(&order, &fill).await.unwrap().unwrap();
+
+        fill.quantity = dec!(1);
+        fill.fill_price = dec!(110);
+        let booking = ledger.record_fill(&order, &fill).await.unwrap().unwrap();
+        let opened = booking.opened.unwrap();
+        assert_eq!(opened.quantity, dec!(1));
+        assert_eq!(opened.entry_price, dec!(110));
+        assert_eq!(booking.fill.fill_price, dec!(120));
+        assert_eq!(open_quantities(&ledger).await, [(order.id, dec!(1))]);
+    }
+
+    #[tokio::test]
+    async fn partial_close_leaves_the_remainder_open() {
+        let ledger = ledger().await;
+        let (entry, _) = book(&ledger, OrderSide::Buy, dec!(1), dec!(100), 0).await;
+        let position = ledger.open_positions().await.unwrap().remove(0);
+
+        let close = Order::close(&position, dec!(0.4));
+        let booking = ledger
+            .record_fill(&close, &fill_for(&close, dec!(110), 1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(booking.trades.len(), 1);
+        assert!((booking.trades[0].quantity - 0.4).abs() < 1e-9);
+        assert!((booking.trades[0].pnl_usd - 4.0).abs() < 1e-9);
+        assert!(booking.opened.is_none());
+        assert_eq!(booking.reduced.len(), 1);
+        assert_eq!(booking.reduced[0].quantity, dec!(0.6));
+        assert_eq!(open_quantities(&ledger).await, [(entry.id, dec!(0.6))]);
+    }
+
+    #[tokio::test]
+    async fn close_without_position_matches_oldest_first() {
+        let ledger = ledger().await;
+        let (older, _) = book(&ledger, OrderSide::Buy, dec!(1), dec!(100), 0).await;
+        let (newer, _) = book(&ledger, OrderSide::Buy, dec!(1), dec!(200), 1).await;
+
+        let (_, booking) = book(&ledger, OrderSide::Sell, dec!(1.5), dec!(150), 2).await;
+        let pnl: Vec<f64> = booking.trades.iter().map(|t| t.pnl_usd).collect();
+        assert_eq!(pnl.len(), 2);
+        assert!((pnl[0] - 50.0).abs() < 1e-9);
+        assert!((pnl[1] + 25.0).abs() < 1e-9);
+        let reduced: Vec<(String, Decimal)> = booking
+            .reduced
+            .into_iter()
+            .map(|p| (p.id, p.quantity))
+            .collect();
+        assert_eq!(
+            reduced,
+            [(older.id, dec!(0)), (newer.id.clone(), dec!(0.5))]
+        );
+        assert!(booking.opened.is_none());
+        assert_eq!(open_quantities(&ledger).await, [(newer.id, dec!(0.5))]);
+    }
+
+    #[tokio::test]
+    async fn over_close_leftover_opens_a_position() {
+        let ledger = ledger().await;
+        book(&ledger, OrderSide::Buy, dec!(1), dec!(100), 0).await;
+
+        let (sell, booking) = book(&ledger, OrderSide::Sell, dec!(1.5), dec!(90), 1).await;
+        assert_eq!(booking.trades.len(), 1);
+        let opened = booking.opened.expect("leftover opens a short");
+        assert_eq!(opened.id, sell.id);
+        assert_eq!(opened.side, OrderSide::Sell);
+        assert_eq!(opened.quantity, dec!(0.5));
+        assert_eq!(opened.entry_price, dec!(90));
+        assert_eq!(open_quantities(&ledger).await, [(sell.id, dec!(0.5))]);
+    }
+
+    #[test]
+    fn pnl_sign_follows_position_side() {
+        let (entry, exit) = (dec!(100), dec!(110.1));
+        assert_eq!(
+            realized_pnl(OrderSide::Buy, entry, exit, dec!(0.3)),
+            dec!(3.03)
+        );
+        assert_eq!(
+            realized_pnl(OrderSide::Sell, entry, exit, dec!(0.3)),
+            dec!(-3.03)
+        );
+    }
+
+    #[test]
+    fn fees_split_by_quantity() {
+        assert_eq!(pro_rata(dec!(0.9), dec!(1), dec!(3)), dec!(0.3));
+        assert_eq!(pro_rata(dec!(0.9), dec!(0), dec!(0)), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{error, info};
+
+use common::metrics::{metrics, WatchdogLabels};
+use common::{EngineState, ReadinessProbe, RiskEvent, StreamHealth};
+
+/// How often the watchdog looks at market data and the exchange.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Catches the bot going quiet without failing loudly: no market event on
+/// any pair for `after` while the engine is running, or the exchange REST
+/// API unreachable for `after`. Each outage raises one critical
+/// `NoMarketData`/`ExchangeUnreachable` event (alerted on Telegram), sets
+/// the `clawbot_watchdog_alarm` gauge, and ends with `WatchdogRecovered`.
+///
+/// Unlike the engine's per-pair staleness watchdog, which reconnects the
+/// stream, this one only alarms: it fires when reconnecting hasn't helped.
+pub struct Watchdog {
+    stream_health: StreamHealth,
+    engine_state: Arc<RwLock<EngineState>>,
+    after: Duration,
+    risk_event_tx: mpsc::Sender<RiskEvent>,
+    /// Exchange REST API to ping. `None` skips the check.
+    rest_probe: Option<Arc<dyn ReadinessProbe>>,
+}
+
+/// One watched condition and when it went bad.
+#[derive(Default)]
+struct Check {
+    failing_since: Option<DateTime<Utc>>,
+    alarmed: bool,
+}
+
+impl Check {
+    /// Record the check's result at `now`. Returns how long it has been
+    /// failing if that just reached `after`, i.e. when to raise the alarm.
+    fn update(&mut self, ok: bool, now: DateTime<Utc>, after: Duration) -> Option<Duration> {
+        if ok {
+            self.failing_since = None;
+            return None;
+        }
+        let since = *self.failing_since.get_or_insert(now);
+        let failing = (now - since).to_std().unwrap_or_default();
+        if failing >= after && !self.alarmed {
+            self.alarmed = true;
+            Some(failing)
+        } else {
+            None
+        }
+    }
+
+    /// Whether an alarm was raised and the check has since passed.
+    fn recovered(&mut self) -> bool {
+        let recovered = self.alarmed && self.failing_since.is_none();
+        if recovered {
+            self.alarmed = false;
+        }
+        recovered
+    }
+}
+
+impl Watchdog {
+    pub fn new(
+        stream_health: StreamHealth,
+        engine_state: Arc<RwLock<EngineState>>,
+        after: Duration,
+        risk_event_tx: mpsc::Sender<RiskEvent>,
+    ) -> Self {
+        Self {
+            stream_health,
+            engine_state,
+            after,
+            risk_event_tx,
+            rest_probe: None,
+        }
+    }
+
+    /// Also alarm when `probe` (the exchange REST API) keeps failing.
+    pub fn set_rest_probe(&mut self, probe: Arc<dyn ReadinessProbe>) {
+        self.rest_probe = Some(probe);
+    }
+
+    /// Check every [`CHECK_INTERVAL`]. Call from `tokio::spawn`.
+    pub async fn run(self) {
+        let mut market = Check::default();
+        let mut rest = Check::default();
+        // Silence is counted from when the engine (re)started running
+        let mut running_since: Option<DateTime<Utc>> = None;
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+
+            let running = *self.engine_state.read().await == EngineState::Running;
+            if !running {
+                running_since = None;
+            }
+            let started = *running_since.get_or_insert(now);
+            let last_event = self
+                .stream_health
+                .snapshot()
+                .into_iter()
+                .filter_map(|p| p.last_event_at)
+                .max()
+                .map_or(started, |at| at.max(started));
+            let fresh = !running || (now - last_event).to_std().unwrap_or_default() < self.after;
+            if let Some(silent) = market.update(fresh, now, self.after) {
+                error!(
+                    silent_secs = silent.as_secs(),
+                    "Watchdog: no market data while running"
+                );
+                self.alarm(
+                    "market_data",
+                    RiskEvent::NoMarketData {
+                        silent_secs: silent.as_secs(),
+                    },
+                )
+                .await;
+            }
+            if market.recovered() {
+                self.recover("market_data").await;
+            }
+
+            if let Some(probe) = &self.rest_probe {
+                let result = probe.check().await;
+                if let Some(down) = rest.update(result.is_ok(), now, self.after) {
+                    let error = result.err().map(|e| e.to_string()).unwrap_or_default();
+                    error!(
+                        probe = probe.name(),
+                        down_secs = down.as_secs(),
+                        error = %error,
+                        "Watchdog: exchange REST API unreachable"
+                    );
+                    self.alarm(
+                        "exchange_rest",
+                        RiskEvent::ExchangeUnreachable {
+                            error,
+                            down_secs: down.as_secs(),
+                        },
+                    )
+                    .await;
+                }
+                if rest.recovered() {
+                    self.recover("exchange_rest").await;
+                }
+            }
+        }
+    }
+
+    async fn alarm(&self, check: &str, event: RiskEvent) {
+        set_gauge(check, 1);
+        let _ = self.risk_event_tx.send(event).await;
+    }
+
+    async fn recover(&self, check: &str) {
+        info!(check, "Watchdog: recovered");
+        set_gauge(check, 0);
+        let _ = self
+            .risk_event_tx
+            .send(RiskEvent::WatchdogRecovered {
+                check: check.to_string(),
+            })
+            .await;
+    }
+}
+
+fn set_gauge(check: &str, value: i64) {
+    metrics()
+        .watchdog_alarm
+        .get_or_create(&WatchdogLabels {
+            check: check.to_string(),
+        })
+        .set(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alarms_once_per_outage() {
+        let after = Duration::from_secs(300);
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+        let mut check = Check::default();
+
+        assert_eq!(check.update(false, at(0), after), None);
+        assert_eq!(check.update(false, at(299), after), None);
+        assert_eq!(
+            check.update(false, at(300), after),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(check.update(false, at(330), after), None);
+        assert!(!check.recovered());
+
+        assert_eq!(check.update(true, at(360), after), None);
+        assert!(check.recovered());
+        assert!(!check.recovered());
+
+        // A new outage starts its own clock
+        assert_eq!(check.update(false, at(400), after), None);
+        assert!(check.update(false, at(700), after).is_some());
+    }
+}
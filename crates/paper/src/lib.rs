@@ -1,25 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::Utc;
-use tokio::sync::RwLock;
-use tracing::{debug, info};
+use rust_decimal::Decimal;
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, warn};
 
-use common::{Error, ExchangeClient, Fill, Order, OrderSide, Position, Result, TradingMode};
+use common::{
+    decimal, Balance, Error, ExchangeClient, Fill, MarketEvent, Order, OrderSide, OrderStatus,
+    OrderStatusReport, Position, Result, Symbol, TradeEvent, TradingMode,
+};
+
+/// Cash asset the simulated balance is held in.
+const CASH_ASSET: &str = "USDT";
 
 /// Simulated exchange client for paper trading.
 ///
 /// Fills are simulated at the latest known price with configurable slippage.
+/// When the pair's best bid/ask is known, market orders cross the spread
+/// (buys at the ask, sells at the bid) before slippage is applied.
+/// Limit orders fill immediately when marketable, at the slipped price. Others
+/// rest in a book and fill at their limit once the market crosses it, checked
+/// whenever the order's status is queried.
 /// No real orders are ever sent to Binance.
 pub struct PaperClient {
-    /// Simulated balance in USDT.
-    #[allow(dead_code)]
-    balance_usd: Arc<RwLock<f64>>,
+    /// Cash balance the simulation starts (and restarts) from.
+    initial_balance_usd: Decimal,
+    /// Simulated cash balance in USDT, debited by buys and credited by sells.
+    balance_usd: Arc<RwLock<Decimal>>,
     /// Open simulated positions, keyed by position ID.
     positions: Arc<RwLock<Vec<Position>>>,
     /// Latest known price per pair, updated via `update_price`.
     prices: Arc<RwLock<HashMap<String, f64>>>,
+    /// Latest best (bid, ask) per pair, updated via `update_quote`.
+    quotes: Arc<RwLock<HashMap<String, (f64, f64)>>>,
+    /// Every simulated fill, keyed by order ID, for status queries.
+    fills: Arc<RwLock<HashMap<String, Fill>>>,
+    /// Limit orders waiting for the market to reach them, keyed by order ID.
+    book: Arc<RwLock<HashMap<String, Order>>>,
+    /// IDs of resting orders cancelled before they filled.
+    cancelled: Arc<RwLock<HashSet<String>>>,
     /// Slippage in basis points applied to all fills.
     slippage_bps: f64,
 }
@@ -31,10 +52,16 @@ impl PaperClient {
             slippage_bps = slippage_bps,
             "PaperClient initialized"
         );
+        let initial_balance_usd = decimal::from_f64(initial_balance_usd);
         Self {
+            initial_balance_usd,
             balance_usd: Arc::new(RwLock::new(initial_balance_usd)),
             positions: Arc::new(RwLock::new(Vec::new())),
             prices: Arc::new(RwLock::new(HashMap::new())),
+            quotes: Arc::new(RwLock::new(HashMap::new())),
+            fills: Arc::new(RwLock::new(HashMap::new())),
+            book: Arc::new(RwLock::new(HashMap::new())),
+            cancelled: Arc::new(RwLock::new(HashSet::new())),
             slippage_bps,
         }
     }
@@ -44,48 +71,117 @@ impl PaperClient {
         self.prices.write().await.insert(pair.to_string(), price);
     }
 
+    /// Update the best bid/ask for a pair.
+    pub async fn update_quote(&self, pair: &str, bid: f64, ask: f64) {
+        if bid > 0.0 && ask >= bid {
+            self.quotes
+                .write()
+                .await
+                .insert(pair.to_string(), (bid, ask));
+        }
+    }
+
+    /// Keep prices current from the market streams. Pairs with a trade
+    /// stream are priced from their last trade; other pairs from the latest
+    /// kline close. Call from `tokio::spawn`.
+    pub async fn follow_market(
+        self: Arc<Self>,
+        mut market_rx: broadcast::Receiver<MarketEvent>,
+        mut trade_rx: broadcast::Receiver<TradeEvent>,
+    ) {
+        let mut trade_priced: HashSet<String> = HashSet::new();
+        loop {
+            tokio::select! {
+                event = market_rx.recv() => match event {
+                    Ok(event) => {
+                        if !trade_priced.contains(&event.pair) {
+                            self.update_price(&event.pair, event.price).await;
+                        }
+                        if let (Some(bid), Some(ask)) = (event.best_bid, event.best_ask) {
+                            self.update_quote(&event.pair, bid, ask).await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(dropped = n, "Paper price feed lagged on market events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+                trade = trade_rx.recv() => match trade {
+                    Ok(trade) => {
+                        trade_priced.insert(trade.pair.clone());
+                        self.update_price(&trade.pair, trade.price).await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        debug!(dropped = n, "Paper price feed lagged on trades");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+            }
+        }
+    }
+
     /// Expose open positions (for the dashboard API and auditing).
     pub fn positions_handle(&self) -> Arc<RwLock<Vec<Position>>> {
         self.positions.clone()
     }
-}
 
-#[async_trait]
-impl ExchangeClient for PaperClient {
-    async fn submit_order(&self, order: &Order) -> Result<Fill> {
-        let prices = self.prices.read().await;
-        let mid_price = prices.get(&order.pair).copied().ok_or_else(|| {
+    /// Price a market order on `side` would fill at now: the touch when
+    /// quotes are known, else the last price, with slippage applied.
+    async fn market_price(&self, pair: &str, side: OrderSide) -> Result<Decimal> {
+        let mid_price = self.prices.read().await.get(pair).copied().ok_or_else(|| {
             Error::Exchange(format!(
-                "PaperClient has no price for pair '{}'. Ensure market events are flowing.",
-                order.pair
+                "PaperClient has no price for pair '{pair}'. Ensure market events are flowing."
             ))
         })?;
-        drop(prices);
+
+        // Cross the spread when quotes are known: buys lift the ask, sells hit the bid
+        let quote = self.quotes.read().await.get(pair).copied();
+        let touch_price = decimal::from_f64(match (quote, side) {
+            (Some((_, ask)), OrderSide::Buy) => ask,
+            (Some((bid, _)), OrderSide::Sell) => bid,
+            (None, _) => mid_price,
+        });
 
         // Apply slippage: buys pay more, sells receive less
-        let fill_price = match order.side {
-            OrderSide::Buy => mid_price * (1.0 + self.slippage_bps / 10_000.0),
-            OrderSide::Sell => mid_price * (1.0 - self.slippage_bps / 10_000.0),
-        };
+        let slippage = decimal::from_f64(self.slippage_bps) / Decimal::from(10_000);
+        Ok(match side {
+            OrderSide::Buy => touch_price * (Decimal::ONE + slippage),
+            OrderSide::Sell => touch_price * (Decimal::ONE - slippage),
+        })
+    }
 
+    /// Fill `order` in full at `fill_price`, moving cash and positions.
+    async fn fill(&self, order: &Order, fill_price: Decimal) -> Fill {
+        let quantity = order.base_quantity_at(fill_price);
         debug!(
             pair = %order.pair,
             side = ?order.side,
-            mid = mid_price,
-            fill = fill_price,
-            qty = order.quantity,
+            fill = %fill_price,
+            qty = %quantity,
             "Paper fill simulated"
         );
 
         let fill = Fill {
             order_id: order.id.clone(),
+            exchange_order_id: None,
             pair: order.pair.clone(),
             side: order.side,
             fill_price,
-            quantity: order.quantity,
+            quantity,
+            fee: Decimal::ZERO,
             timestamp: Utc::now(),
         };
 
+        let notional = fill_price * quantity;
+        match order.side {
+            OrderSide::Buy => *self.balance_usd.write().await -= notional,
+            OrderSide::Sell => *self.balance_usd.write().await += notional,
+        }
+        self.fills
+            .write()
+            .await
+            .insert(order.id.clone(), fill.clone());
+
         // Update in-memory position ledger
         let mut positions = self.positions.write().await;
         match order.side {
@@ -95,32 +191,176 @@ impl ExchangeClient for PaperClient {
                     pair: order.pair.clone(),
                     side: OrderSide::Buy,
                     entry_price: fill_price,
-                    quantity: order.quantity,
+                    quantity,
                     mode: TradingMode::Paper,
                     opened_at: Utc::now(),
                 });
             }
             OrderSide::Sell => {
-                // Remove the first matching open buy position
-                if let Some(idx) = positions.iter().position(|p| p.pair == order.pair) {
-                    positions.remove(idx);
+                // Reduce the targeted position, or the first open one on the pair
+                let idx = match &order.position_id {
+                    Some(id) => positions.iter().position(|p| &p.id == id),
+                    None => positions.iter().position(|p| p.pair == order.pair),
+                };
+                if let Some(idx) = idx {
+                    positions[idx].quantity -= quantity;
+                    if positions[idx].quantity <= Decimal::ZERO {
+                        positions.remove(idx);
+                    }
                 }
             }
         }
 
-        Ok(fill)
+        fill
+    }
+}
+
+/// Whether a limit `side` order at `limit` executes against `market_price`.
+fn marketable(side: OrderSide, limit: Decimal, market_price: Decimal) -> bool {
+    match side {
+        OrderSide::Buy => market_price <= limit,
+        OrderSide::Sell => market_price >= limit,
+    }
+}
+
+#[async_trait]
+impl ExchangeClient for PaperClient {
+    async fn submit_order(&self, order: &Order) -> Result<Fill> {
+        let market_price = self.market_price(&order.pair, order.side).await?;
+
+        if order.trigger.is_some() {
+            return Err(Error::Exchange(
+                "PaperClient does not simulate conditional (stop/take-profit) orders".into(),
+            ));
+        }
+
+        match order.price {
+            Some(limit) if !marketable(order.side, limit, market_price) => {
+                debug!(
+                    pair = %order.pair,
+                    side = ?order.side,
+                    limit = %limit,
+                    market = %market_price,
+                    "Paper limit order resting"
+                );
+                self.book
+                    .write()
+                    .await
+                    .insert(order.id.clone(), order.clone());
+                Ok(Fill {
+                    order_id: order.id.clone(),
+                    exchange_order_id: None,
+                    pair: order.pair.clone(),
+                    side: order.side,
+                    fill_price: limit,
+                    quantity: Decimal::ZERO,
+                    fee: Decimal::ZERO,
+                    timestamp: Utc::now(),
+                })
+            }
+            _ => Ok(self.fill(order, market_price).await),
+        }
+    }
+
+    /// Prices are kept: they come from the live market either way.
+    async fn reset(&self) {
+        *self.balance_usd.write().await = self.initial_balance_usd;
+        self.positions.write().await.clear();
+        self.fills.write().await.clear();
+        self.book.write().await.clear();
+        self.cancelled.write().await.clear();
+        info!(balance = %self.initial_balance_usd, "PaperClient reset");
     }
 
     async fn open_positions(&self) -> Result<Vec<Position>> {
         Ok(self.positions.read().await.clone())
     }
 
-    async fn current_price(&self, pair: &str) -> Result<f64> {
+    async fn balances(&self) -> Result<Vec<Balance>> {
+        let mut holdings: HashMap<String, Decimal> = HashMap::new();
+        for position in self.positions.read().await.iter() {
+            let asset = Symbol::parse(&position.pair)
+                .map(|s| s.base)
+                .unwrap_or_else(|| position.pair.clone());
+            *holdings.entry(asset).or_default() += position.quantity;
+        }
+
+        let mut balances = vec![Balance {
+            asset: CASH_ASSET.to_string(),
+            free: *self.balance_usd.read().await,
+            locked: Decimal::ZERO,
+        }];
+        balances.extend(holdings.into_iter().map(|(asset, quantity)| Balance {
+            asset,
+            free: quantity,
+            locked: Decimal::ZERO,
+        }));
+        Ok(balances)
+    }
+
+    async fn order_status(&self, _pair: &str, order_id: &str) -> Result<OrderStatusReport> {
+        // A resting limit fills at its price once the market reaches it
+        let resting = self.book.read().await.get(order_id).cloned();
+        if let Some(
+            order @ Order {
+                price: Some(limit), ..
+            },
+        ) = resting
+        {
+            let market_price = self.market_price(&order.pair, order.side).await?;
+            if !marketable(order.side, limit, market_price) {
+                return Ok(OrderStatusReport {
+                    status: OrderStatus::New,
+                    executed_quantity: Decimal::ZERO,
+                    average_price: None,
+                    exchange_order_id: None,
+                });
+            }
+            // Another status query may have filled it meanwhile
+            if self.book.write().await.remove(order_id).is_some() {
+                self.fill(&order, limit).await;
+            }
+        }
+
+        if self.cancelled.read().await.contains(order_id) {
+            return Ok(OrderStatusReport {
+                status: OrderStatus::Canceled,
+                executed_quantity: Decimal::ZERO,
+                average_price: None,
+                exchange_order_id: None,
+            });
+        }
+        let fills = self.fills.read().await;
+        let fill = fills
+            .get(order_id)
+            .ok_or_else(|| Error::Exchange(format!("Unknown paper order '{order_id}'")))?;
+        Ok(OrderStatusReport {
+            status: OrderStatus::Filled,
+            executed_quantity: fill.quantity,
+            average_price: Some(fill.fill_price),
+            exchange_order_id: None,
+        })
+    }
+
+    async fn cancel_order(&self, _pair: &str, order_id: &str) -> Result<()> {
+        if self.book.write().await.remove(order_id).is_some() {
+            self.cancelled.write().await.insert(order_id.to_string());
+            return Ok(());
+        }
+        if self.fills.read().await.contains_key(order_id) {
+            return Err(Error::Exchange(format!(
+                "Paper order '{order_id}' is already filled"
+            )));
+        }
+        Err(Error::Exchange(format!("Unknown paper order '{order_id}'")))
+    }
+
+    async fn current_price(&self, pair: &str) -> Result<Decimal> {
         self.prices
             .read()
             .await
             .get(pair)
-            .copied()
+            .map(|&p| decimal::from_f64(p))
             .ok_or_else(|| Error::Exchange(format!("No price available for {pair}")))
     }
 }
@@ -129,22 +369,36 @@ impl ExchangeClient for PaperClient {
 mod tests {
     use super::*;
     use common::Order;
+    use rust_decimal_macros::dec;
 
     #[tokio::test]
     async fn paper_buy_fill_applies_positive_slippage() {
         let client = PaperClient::new(10_000.0, 10.0); // 10 bps
         client.update_price("BTCUSDT", 1000.0).await;
 
-        let order = Order::market("BTCUSDT", OrderSide::Buy, 0.01);
+        let order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
         let fill = client.submit_order(&order).await.unwrap();
 
-        let expected = 1000.0 * (1.0 + 10.0 / 10_000.0);
-        assert!(
-            (fill.fill_price - expected).abs() < 1e-6,
-            "Buy fill price {}, expected {}",
-            fill.fill_price,
-            expected
-        );
+        assert_eq!(fill.fill_price, dec!(1001));
+    }
+
+    #[tokio::test]
+    async fn paper_reset_restores_the_initial_balance() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("BTCUSDT", 1000.0).await;
+        let buy = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        client.submit_order(&buy).await.unwrap();
+        let mut resting = Order::market("BTCUSDT", OrderSide::Buy, dec!(1));
+        resting.price = Some(dec!(900));
+        client.submit_order(&resting).await.unwrap();
+
+        client.reset().await;
+        let balances = client.balances().await.unwrap();
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].free, dec!(10000));
+        assert!(client.open_positions().await.unwrap().is_empty());
+        assert!(client.order_status("BTCUSDT", &resting.id).await.is_err());
+        assert_eq!(client.current_price("BTCUSDT").await.unwrap(), dec!(1000));
     }
 
     #[tokio::test]
@@ -153,19 +407,88 @@ mod tests {
         client.update_price("BTCUSDT", 1000.0).await;
 
         // First buy, then sell
-        let buy = Order::market("BTCUSDT", OrderSide::Buy, 0.01);
+        let buy = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
         client.submit_order(&buy).await.unwrap();
 
-        let sell = Order::market("BTCUSDT", OrderSide::Sell, 0.01);
+        let sell = Order::market("BTCUSDT", OrderSide::Sell, dec!(0.01));
         let fill = client.submit_order(&sell).await.unwrap();
 
-        let expected = 1000.0 * (1.0 - 10.0 / 10_000.0);
-        assert!(
-            (fill.fill_price - expected).abs() < 1e-6,
-            "Sell fill price {}, expected {}",
-            fill.fill_price,
-            expected
-        );
+        assert_eq!(fill.fill_price, dec!(999));
+    }
+
+    #[tokio::test]
+    async fn paper_marketable_limit_buy_fills_at_market() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("BTCUSDT", 1000.0).await;
+
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
+        order.price = Some(dec!(1010));
+        let fill = client.submit_order(&order).await.unwrap();
+        assert_eq!(fill.fill_price, dec!(1000));
+        assert_eq!(fill.quantity, dec!(0.01));
+    }
+
+    #[tokio::test]
+    async fn paper_limit_rests_until_the_market_crosses_it() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("BTCUSDT", 1000.0).await;
+
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
+        order.price = Some(dec!(990));
+        let fill = client.submit_order(&order).await.unwrap();
+        assert_eq!(fill.quantity, Decimal::ZERO);
+        assert!(client.open_positions().await.unwrap().is_empty());
+
+        client.update_price("BTCUSDT", 995.0).await;
+        let report = client.order_status("BTCUSDT", &order.id).await.unwrap();
+        assert_eq!(report.status, OrderStatus::New);
+        assert_eq!(report.executed_quantity, Decimal::ZERO);
+
+        // Filled at its limit, not the lower market price
+        client.update_price("BTCUSDT", 985.0).await;
+        let report = client.order_status("BTCUSDT", &order.id).await.unwrap();
+        assert_eq!(report.status, OrderStatus::Filled);
+        assert_eq!(report.executed_quantity, dec!(0.01));
+        assert_eq!(report.average_price, Some(dec!(990)));
+
+        let positions = client.open_positions().await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].entry_price, dec!(990));
+        let balances = client.balances().await.unwrap();
+        assert_eq!(balances[0].free, dec!(9_990.1));
+    }
+
+    #[tokio::test]
+    async fn paper_resting_limit_can_be_cancelled() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("BTCUSDT", 1000.0).await;
+
+        let mut order = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
+        order.price = Some(dec!(990));
+        client.submit_order(&order).await.unwrap();
+        client.cancel_order("BTCUSDT", &order.id).await.unwrap();
+
+        client.update_price("BTCUSDT", 985.0).await;
+        let report = client.order_status("BTCUSDT", &order.id).await.unwrap();
+        assert_eq!(report.status, OrderStatus::Canceled);
+        assert_eq!(report.executed_quantity, Decimal::ZERO);
+        assert!(client.open_positions().await.unwrap().is_empty());
+        assert!(client.cancel_order("BTCUSDT", &order.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn paper_market_orders_cross_the_spread() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("BTCUSDT", 1000.0).await;
+        client.update_quote("BTCUSDT", 999.0, 1001.0).await;
+
+        let buy = Order::market("BTCUSDT", OrderSide::Buy, dec!(0.01));
+        let fill = client.submit_order(&buy).await.unwrap();
+        assert_eq!(fill.fill_price, dec!(1001));
+
+        let sell = Order::market("BTCUSDT", OrderSide::Sell, dec!(0.01));
+        let fill = client.submit_order(&sell).await.unwrap();
+        assert_eq!(fill.fill_price, dec!(999));
     }
 
     #[tokio::test]
@@ -173,7 +496,7 @@ mod tests {
         let client = PaperClient::new(10_000.0, 0.0);
         client.update_price("ETHUSDT", 500.0).await;
 
-        let order = Order::market("ETHUSDT", OrderSide::Buy, 1.0);
+        let order = Order::market("ETHUSDT", OrderSide::Buy, dec!(1.0));
         client.submit_order(&order).await.unwrap();
 
         let positions = client.open_positions().await.unwrap();
@@ -182,15 +505,33 @@ mod tests {
         assert_eq!(positions[0].mode, TradingMode::Paper);
     }
 
+    #[tokio::test]
+    async fn paper_fills_move_cash_and_asset_balances() {
+        let client = PaperClient::new(10_000.0, 0.0);
+        client.update_price("ETHUSDT", 500.0).await;
+
+        let order = Order::market("ETHUSDT", OrderSide::Buy, dec!(2.0));
+        client.submit_order(&order).await.unwrap();
+
+        let balances = client.balances().await.unwrap();
+        let free = |asset: &str| balances.iter().find(|b| b.asset == asset).unwrap().free;
+        assert_eq!(free("USDT"), dec!(9_000));
+        assert_eq!(free("ETH"), dec!(2));
+
+        let report = client.order_status("ETHUSDT", &order.id).await.unwrap();
+        assert_eq!(report.status, OrderStatus::Filled);
+        assert!(client.cancel_order("ETHUSDT", &order.id).await.is_err());
+    }
+
     #[tokio::test]
     async fn paper_position_removed_after_sell() {
         let client = PaperClient::new(10_000.0, 0.0);
         client.update_price("ETHUSDT", 500.0).await;
 
-        let buy = Order::market("ETHUSDT", OrderSide::Buy, 1.0);
+        let buy = Order::market("ETHUSDT", OrderSide::Buy, dec!(1.0));
         client.submit_order(&buy).await.unwrap();
 
-        let sell = Order::market("ETHUSDT", OrderSide::Sell, 1.0);
+        let sell = Order::market("ETHUSDT", OrderSide::Sell, dec!(1.0));
         client.submit_order(&sell).await.unwrap();
 
         let positions = client.open_positions().await.unwrap();
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tracing::error;
+
+use common::{EquitySnapshot, RiskCommand, TradingMode};
+
+/// Samples mark-to-market equity from the Risk Manager on a fixed interval
+/// and writes it to the `equity_snapshots` table, so the equity curve shows
+/// unrealized swings and not just realized PnL.
+pub struct EquityRecorder {
+    db: SqlitePool,
+    mode: Arc<RwLock<TradingMode>>,
+    risk_tx: mpsc::Sender<RiskCommand>,
+}
+
+impl EquityRecorder {
+    pub fn new(
+        db: SqlitePool,
+        mode: Arc<RwLock<TradingMode>>,
+        risk_tx: mpsc::Sender<RiskCommand>,
+    ) -> Self {
+        Self { db, mode, risk_tx }
+    }
+
+    /// Record a snapshot every `every` until the Risk Manager goes away.
+    /// Call from `tokio::spawn`.
+    pub async fn run(self, every: Duration) {
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            let (reply_tx, reply_rx) = oneshot::channel();
+            if self
+                .risk_tx
+                .send(RiskCommand::GetEquity { reply: reply_tx })
+                .await
+                .is_err()
+            {
+                return;
+            }
+            let Ok(snapshot) = reply_rx.await else {
+                return;
+            };
+            if let Err(e) = self.insert(&snapshot).await {
+                error!(error = %e, "Failed to persist equity snapshot");
+            }
+        }
+    }
+
+    async fn insert(&self, snapshot: &EquitySnapshot) -> common::Result<()> {
+        let mode = self.mode.read().await.to_string();
+        let taken_at = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO equity_snapshots (realized_usd, unrealized_usd, equity_usd, drawdown_pct,
+                                          mode, taken_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            snapshot.realized_usd,
+            snapshot.unrealized_usd,
+            snapshot.equity_usd,
+            snapshot.drawdown_pct,
+            mode,
+            taken_at,
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tracing::error;
+
+use common::RiskEvent;
+
+/// Writes every `RiskEvent`, with its alert severity and text, to the
+/// `risk_events` table so rejections, SL/TP triggers, halts, and order
+/// failures survive beyond the log stream.
+#[derive(Clone)]
+pub struct RiskEventJournal {
+    db: SqlitePool,
+}
+
+impl RiskEventJournal {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Persist one event. Failures are logged, never propagated — losing an
+    /// audit row must not stall the risk pipeline.
+    pub async fn record(&self, event: &RiskEvent) {
+        if let Err(e) = self.insert(event).await {
+            error!(kind = event.kind(), error = %e, "Failed to persist risk event");
+        }
+    }
+
+    async fn insert(&self, event: &RiskEvent) -> common::Result<()> {
+        let kind = event.kind();
+        let pair = event.pair();
+        let details = serde_json::to_string(event)?;
+        let severity = event.severity().as_str();
+        let message = event.message();
+        let created_at = Utc::now().to_rfc3339();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO risk_events (kind, pair, details, severity, message, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            kind,
+            pair,
+            details,
+            severity,
+            message,
+            created_at,
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
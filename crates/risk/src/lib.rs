@@ -1,3 +1,14 @@
+mod equity;
+mod journal;
 mod manager;
+mod rate_limit;
+mod signal_journal;
+mod state_store;
+mod var;
 
-pub use manager::{RiskConfig, RiskManager};
+pub use common::{ConflictPolicy, CorrelationGroup, RiskConfig, TakeProfitLevel};
+pub use equity::EquityRecorder;
+pub use journal::RiskEventJournal;
+pub use manager::RiskManager;
+pub use signal_journal::SignalJournal;
+pub use state_store::{RiskSnapshot, RiskStateStore};
@@ -0,0 +1,83 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tracing::error;
+
+use common::{decimal, Order, RejectionReason, Signal};
+
+/// Writes every signal the risk manager decides on to the `signals` table —
+/// approved (with the order it became) or rejected (with the reason) — and
+/// marks approved signals `filled` once their order fills, so users can see
+/// why the bot did or didn't trade.
+#[derive(Clone)]
+pub struct SignalJournal {
+    db: SqlitePool,
+}
+
+impl SignalJournal {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Record `signal` as approved and forwarded as `order`.
+    pub async fn approved(&self, signal: &Signal, order: &Order) {
+        self.record(signal, "approved", None, Some(&order.id)).await;
+    }
+
+    /// Record `signal` as rejected for `reason`.
+    pub async fn rejected(&self, signal: &Signal, reason: &RejectionReason) {
+        let reason = reason.to_string();
+        self.record(signal, "rejected", Some(&reason), None).await;
+    }
+
+    /// Mark the signal behind `order_id` as filled. Orders the risk manager
+    /// placed on its own (stop-loss and take-profit closes) match nothing.
+    pub async fn filled(&self, order_id: &str) {
+        let result = sqlx::query!(
+            "UPDATE signals SET outcome = 'filled' WHERE order_id = ?1",
+            order_id
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(order_id, error = %e, "Failed to persist signal fill");
+        }
+    }
+
+    /// Failures are logged, never propagated — losing a history row must
+    /// not stall the risk pipeline.
+    async fn record(
+        &self,
+        signal: &Signal,
+        outcome: &str,
+        reason: Option<&str>,
+        order_id: Option<&str>,
+    ) {
+        let side = signal.side.to_string();
+        let quantity = decimal::to_f64(signal.quantity);
+        let quote_quantity = signal.quote_quantity.map(decimal::to_f64);
+        let limit_price = signal.limit_price.map(decimal::to_f64);
+        let now = Utc::now().to_rfc3339();
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO signals (strategy, pair, side, quantity, quote_quantity, limit_price,
+                                 outcome, reason, order_id, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            signal.strategy,
+            signal.pair,
+            side,
+            quantity,
+            quote_quantity,
+            limit_price,
+            outcome,
+            reason,
+            order_id,
+            now,
+        )
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            error!(pair = %signal.pair, outcome, error = %e, "Failed to persist signal");
+        }
+    }
+}
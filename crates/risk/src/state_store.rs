@@ -0,0 +1,113 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tracing::error;
+
+use common::RiskConfig;
+
+/// Drawdown circuit-breaker state that must survive a restart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskSnapshot {
+    pub portfolio_peak_usd: f64,
+    pub portfolio_value_usd: f64,
+    pub halted: bool,
+}
+
+/// Persists the latest `RiskSnapshot` to the single-row `risk_state` table,
+/// and runtime risk config changes to the single-row `risk_config` table.
+#[derive(Clone)]
+pub struct RiskStateStore {
+    db: SqlitePool,
+}
+
+impl RiskStateStore {
+    pub fn new(db: SqlitePool) -> Self {
+        Self { db }
+    }
+
+    /// Load the last saved snapshot, if any.
+    pub async fn load(&self) -> common::Result<Option<RiskSnapshot>> {
+        let row = sqlx::query!(
+            r#"SELECT portfolio_peak_usd, portfolio_value_usd, halted AS "halted: bool"
+               FROM risk_state WHERE id = 1"#
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(|r| RiskSnapshot {
+            portfolio_peak_usd: r.portfolio_peak_usd,
+            portfolio_value_usd: r.portfolio_value_usd,
+            halted: r.halted,
+        }))
+    }
+
+    /// Overwrite the stored snapshot. Failures are logged, never propagated.
+    pub async fn save(&self, snapshot: &RiskSnapshot) {
+        if let Err(e) = self.upsert(snapshot).await {
+            error!(error = %e, "Failed to persist risk state");
+        }
+    }
+
+    /// Load the risk config saved by the last runtime update, if any.
+    pub async fn load_config(&self) -> common::Result<Option<RiskConfig>> {
+        let row = sqlx::query_scalar!("SELECT config FROM risk_config WHERE id = 1")
+            .fetch_optional(&self.db)
+            .await?;
+        match row {
+            Some(json) => {
+                let config: RiskConfig = serde_json::from_str(&json)?;
+                config.validate()?;
+                Ok(Some(config))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Overwrite the stored risk config. Failures are logged, never propagated.
+    pub async fn save_config(&self, config: &RiskConfig) {
+        if let Err(e) = self.upsert_config(config).await {
+            error!(error = %e, "Failed to persist risk config");
+        }
+    }
+
+    async fn upsert_config(&self, config: &RiskConfig) -> common::Result<()> {
+        let json = serde_json::to_string(config)?;
+        let updated_at = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO risk_config (id, config, updated_at) VALUES (1, ?1, ?2)
+            ON CONFLICT(id) DO UPDATE SET
+                config     = excluded.config,
+                updated_at = excluded.updated_at
+            "#,
+            json,
+            updated_at,
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert(&self, snapshot: &RiskSnapshot) -> common::Result<()> {
+        let updated_at = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO risk_state (id, portfolio_peak_usd, portfolio_value_usd, halted, updated_at)
+            VALUES (1, ?1, ?2, ?3, ?4)
+            ON CONFLICT(id) DO UPDATE SET
+                portfolio_peak_usd  = excluded.portfolio_peak_usd,
+                portfolio_value_usd = excluded.portfolio_value_usd,
+                halted              = excluded.halted,
+                updated_at          = excluded.updated_at
+            "#,
+            snapshot.portfolio_peak_usd,
+            snapshot.portfolio_value_usd,
+            snapshot.halted,
+            updated_at,
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Fewest aligned returns needed before a VaR estimate is produced.
+pub const MIN_VAR_SAMPLES: usize = 10;
+
+/// Rolling per-pair candle-close returns used for historical-simulation VaR.
+#[derive(Debug, Default)]
+pub struct ReturnHistory {
+    capacity: usize,
+    last_close: HashMap<String, f64>,
+    returns: HashMap<String, VecDeque<f64>>,
+}
+
+impl ReturnHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Change the window length, dropping the oldest returns if it shrank.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        for series in self.returns.values_mut() {
+            while series.len() > capacity {
+                series.pop_front();
+            }
+        }
+    }
+
+    /// Record a closed candle for `pair`.
+    pub fn record_close(&mut self, pair: &str, close: f64) {
+        if close <= 0.0 {
+            return;
+        }
+        if let Some(prev) = self.last_close.insert(pair.to_string(), close) {
+            let series = self.returns.entry(pair.to_string()).or_default();
+            series.push_back(close / prev - 1.0);
+            while series.len() > self.capacity {
+                series.pop_front();
+            }
+        }
+    }
+
+    /// One-period historical VaR in USD at `confidence` (e.g. 0.95) for the
+    /// given signed notionals (positive = long, negative = short).
+    ///
+    /// Returns are aligned from the most recent candle backwards. `None` when
+    /// any exposed pair has fewer than `MIN_VAR_SAMPLES` returns.
+    pub fn historical_var(&self, exposures: &[(String, f64)], confidence: f64) -> Option<f64> {
+        if exposures.is_empty() {
+            return Some(0.0);
+        }
+        let series: Vec<(&VecDeque<f64>, f64)> = exposures
+            .iter()
+            .map(|(pair, notional)| self.returns.get(pair).map(|s| (s, *notional)))
+            .collect::<Option<_>>()?;
+        let samples = series.iter().map(|(s, _)| s.len()).min()?;
+        if samples < MIN_VAR_SAMPLES {
+            return None;
+        }
+
+        let mut pnl: Vec<f64> = (0..samples)
+            .map(|i| {
+                series
+                    .iter()
+                    .map(|(s, notional)| notional * s[s.len() - samples + i])
+                    .sum()
+            })
+            .collect();
+        pnl.sort_by(|a, b| a.total_cmp(b));
+
+        // Loss at the edge of the (1 - confidence) tail
+        let tail = ((1.0 - confidence) * samples as f64 + 1e-9).floor() as usize;
+        let idx = tail.saturating_sub(1).min(samples - 1);
+        Some((-pnl[idx]).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history_with(pair: &str, closes: &[f64]) -> ReturnHistory {
+        let mut h = ReturnHistory::new(100);
+        for &c in closes {
+            h.record_close(pair, c);
+        }
+        h
+    }
+
+    #[test]
+    fn var_is_worst_tail_loss_of_exposure() {
+        // 20 returns: nineteen +1% moves and one -10% move
+        let mut closes = vec![100.0];
+        for _ in 0..19 {
+            closes.push(closes.last().unwrap() * 1.01);
+        }
+        closes.push(closes.last().unwrap() * 0.90);
+        let h = history_with("BTCUSDT", &closes);
+
+        let var = h
+            .historical_var(&[("BTCUSDT".into(), 1000.0)], 0.95)
+            .unwrap();
+        assert!((var - 100.0).abs() < 1e-6, "var = {var}");
+
+        // A short position profits from the drop; its tail is the +1% moves
+        let var = h
+            .historical_var(&[("BTCUSDT".into(), -1000.0)], 0.95)
+            .unwrap();
+        assert!((var - 10.0).abs() < 1e-6, "var = {var}");
+    }
+
+    #[test]
+    fn var_needs_enough_history() {
+        let h = history_with("BTCUSDT", &[100.0, 101.0, 102.0]);
+        assert!(h
+            .historical_var(&[("BTCUSDT".into(), 1000.0)], 0.95)
+            .is_none());
+        assert!(h
+            .historical_var(&[("ETHUSDT".into(), 1000.0)], 0.95)
+            .is_none());
+    }
+}
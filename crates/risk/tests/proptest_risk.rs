@@ -1,4 +1,4 @@
-use common::{EngineState, MarketEvent, OrderSide, Position, TradingMode};
+use common::{decimal, EngineState, MarketEvent, OrderSide, Position, TradingMode};
 use proptest::prelude::*;
 use risk::{RiskConfig, RiskManager};
 use std::sync::Arc;
@@ -19,8 +19,10 @@ proptest! {
                 take_profit_pct: 0.04,
                 max_exposure_per_trade_usd: 10_000.0,
                 max_drawdown_pct: 0.15,
+                ..RiskConfig::default()
             };
             let (_signal_tx, signal_rx) = mpsc::channel(1);
+            let (_control_tx, control_rx) = mpsc::channel(1);
             let (order_tx, _order_rx) = mpsc::channel(1);
             let (risk_event_tx, _risk_event_rx) = mpsc::channel(1);
             let (market_tx, market_rx) = broadcast::channel(8);
@@ -30,16 +32,17 @@ proptest! {
                     id: "p1".into(),
                     pair: "TESTUSDT".into(),
                     side: OrderSide::Buy,
-                    entry_price,
-                    quantity,
+                    entry_price: decimal::from_f64(entry_price),
+                    quantity: decimal::from_f64(quantity),
                     mode: TradingMode::Paper,
                     opened_at: chrono::Utc::now(),
                 }
             ]));
 
-            let manager = RiskManager::new(
+            let mut manager = RiskManager::new(
                 config,
                 signal_rx,
+                control_rx,
                 order_tx,
                 risk_event_tx,
                 market_rx,
@@ -48,7 +51,7 @@ proptest! {
                 10_000.0,
             );
 
-            let handle = tokio::spawn(manager.run());
+            let handle = tokio::spawn(async move { manager.run().await });
 
             // Send one market event — manager should process without panic
             let event = MarketEvent {
@@ -58,8 +61,11 @@ proptest! {
                 high: current_price,
                 low: current_price,
                 volume: 1.0,
+                interval: Default::default(),
                 is_candle_closed: true,
                 timestamp: chrono::Utc::now(),
+                best_bid: None,
+                best_ask: None,
             };
             let _ = market_tx.send(event);
             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
@@ -1,23 +1,51 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
+use common::{Error, KlineInterval, OrderSide, Result, RiskOverrides, Signal};
+
 /// Top-level strategy config file (TOML).
 ///
 /// Example `config/strategies.toml`:
 /// ```toml
+/// # Optional: candle interval for every pair (default "1m")
+/// interval = "5m"
+///
+/// # Optional: pairs that also stream individual (aggregated) trades
+/// trade_pairs = ["BTCUSDT"]
+///
+/// # Optional: per-pair interval overrides
+/// [intervals]
+/// ETHUSDT = "15m"
+///
 /// [[strategy]]
 /// type = "rsi"
 /// name = "BTC RSI 14"
 /// pair = "BTCUSDT"
-/// quantity = 0.001
+/// quantity = 0.001          # or: quote_quantity = 50.0 (USDT per signal)
 ///
 /// [strategy.params]
 /// period = 14
 /// overbought = 70.0
 /// oversold = 30.0
+///
+/// # Optional: replaces the global stop-loss / take-profit / exposure limits
+/// [strategy.risk]
+/// stop_loss_pct = 0.01
+/// take_profit_pct = 0.015
 /// ```
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StrategyFileConfig {
+    /// Kline interval streamed for pairs without an override.
+    #[serde(default)]
+    pub interval: KlineInterval,
+    /// Per-pair kline interval overrides.
+    #[serde(default)]
+    pub intervals: HashMap<String, KlineInterval>,
+    /// Pairs that also stream aggregated trades (`TradeEvent`).
+    #[serde(default)]
+    pub trade_pairs: Vec<String>,
     #[serde(rename = "strategy")]
     pub strategies: Vec<StrategyConfig>,
 }
@@ -32,10 +60,84 @@ pub struct StrategyConfig {
     /// Trading pair, e.g. "BTCUSDT".
     pub pair: String,
     /// Order quantity in base asset units.
-    pub quantity: f64,
+    #[serde(default)]
+    pub quantity: Decimal,
+    /// Spend this much quote asset per signal instead (e.g. `50.0` for
+    /// 50 USDT worth), leaving the base quantity to the exchange.
+    #[serde(default)]
+    pub quote_quantity: Option<Decimal>,
+    /// Whether the strategy emits signals. Toggled at runtime via the API.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     /// Indicator-specific parameters.
     #[serde(default)]
     pub params: HashMap<String, toml::Value>,
+    /// Risk parameter overrides for signals emitted by this strategy.
+    #[serde(default)]
+    pub risk: Option<RiskOverrides>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl StrategyConfig {
+    /// Build a signal attributed to this strategy, carrying its risk overrides.
+    pub fn signal(&self, side: OrderSide) -> Signal {
+        Signal {
+            strategy: Some(self.name.clone()),
+            risk: self.risk.clone(),
+            quote_quantity: self.quote_quantity,
+            ..Signal::new(self.pair.clone(), side, self.quantity)
+        }
+    }
+
+    /// Apply a partial update (JSON object) and return the updated config.
+    /// Only `enabled`, `quantity`, `quote_quantity`, and `params` can change;
+    /// `params` is merged key by key, and a `null` value removes a key.
+    pub fn apply_patch(&self, patch: &Value) -> Result<StrategyConfig> {
+        let Value::Object(fields) = patch else {
+            return Err(Error::Config("strategy update must be an object".into()));
+        };
+
+        let mut updated = self.clone();
+        for (key, value) in fields {
+            let invalid = |e: serde_json::Error| Error::Config(format!("invalid '{key}': {e}"));
+            match key.as_str() {
+                "enabled" => {
+                    updated.enabled = serde_json::from_value(value.clone()).map_err(invalid)?
+                }
+                "quantity" => {
+                    updated.quantity = serde_json::from_value(value.clone()).map_err(invalid)?
+                }
+                "quote_quantity" => {
+                    updated.quote_quantity =
+                        serde_json::from_value(value.clone()).map_err(invalid)?
+                }
+                "params" => {
+                    let Value::Object(params) = value else {
+                        return Err(Error::Config("'params' must be an object".into()));
+                    };
+                    for (name, param) in params {
+                        if param.is_null() {
+                            updated.params.remove(name);
+                            continue;
+                        }
+                        let param = serde_json::from_value(param.clone()).map_err(|e| {
+                            Error::Config(format!("invalid parameter '{name}': {e}"))
+                        })?;
+                        updated.params.insert(name.clone(), param);
+                    }
+                }
+                other => {
+                    return Err(Error::Config(format!(
+                        "strategy field '{other}' cannot be changed at runtime"
+                    )))
+                }
+            }
+        }
+        Ok(updated)
+    }
 }
 
 impl StrategyFileConfig {
@@ -46,4 +148,53 @@ impl StrategyFileConfig {
         toml::from_str(&content)
             .unwrap_or_else(|e| panic!("Failed to parse strategy config at '{path}': {e}"))
     }
+
+    /// Kline interval to stream for `pair`.
+    pub fn interval_for(&self, pair: &str) -> KlineInterval {
+        self.intervals.get(pair).copied().unwrap_or(self.interval)
+    }
+
+    /// Distinct traded pairs, in config order, with their stream interval.
+    pub fn pair_streams(&self) -> Vec<(String, KlineInterval)> {
+        let mut streams: Vec<(String, KlineInterval)> = Vec::new();
+        for s in &self.strategies {
+            if !streams.iter().any(|(pair, _)| pair == &s.pair) {
+                streams.push((s.pair.clone(), self.interval_for(&s.pair)));
+            }
+        }
+        streams
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn patch_merges_params_and_rejects_fixed_fields() {
+        let cfg: StrategyConfig = toml::from_str(
+            r#"
+            type = "rsi"
+            name = "BTC RSI"
+            pair = "BTCUSDT"
+            quantity = 0.001
+            params = { period = 14, overbought = 70.0 }
+            "#,
+        )
+        .unwrap();
+        assert!(cfg.enabled);
+
+        let updated = cfg
+            .apply_patch(
+                &json!({ "enabled": false, "params": { "period": 21, "overbought": null } }),
+            )
+            .unwrap();
+        assert!(!updated.enabled);
+        assert_eq!(updated.params["period"].as_integer(), Some(21));
+        assert!(!updated.params.contains_key("overbought"));
+
+        assert!(cfg.apply_patch(&json!({ "pair": "ETHUSDT" })).is_err());
+        assert!(cfg.apply_patch(&json!({ "enabled": "yes" })).is_err());
+    }
 }
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
+use chrono::{DateTime, Utc};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tracing::{info, warn};
 
-use common::{EngineState, MarketEvent, Signal};
+use common::metrics::{metrics, SignalLabels, StrategyLabels};
+use common::{EngineState, MarketEvent, OrderSide, Signal, StrategyCommand, StrategyStatus};
 
 use crate::config::{StrategyConfig, StrategyFileConfig};
 use crate::indicators::{MacdIndicator, RsiIndicator};
@@ -13,30 +16,160 @@ use crate::Strategy;
 
 /// Holds all active strategy instances and dispatches market events to them.
 pub struct StrategyRegistry {
-    strategies: Vec<Box<dyn Strategy>>,
+    strategies: Vec<Slot>,
     /// Per-pair rolling window of recent closed candles for indicator calculation.
     price_history: HashMap<String, Vec<f64>>,
     max_history: usize,
+    /// Runtime listing and editing of strategies (API). `None` until connected.
+    command_rx: Option<mpsc::Receiver<StrategyCommand>>,
+}
+
+/// A registered strategy with the config it was built from.
+struct Slot {
+    cfg: StrategyConfig,
+    strategy: Box<dyn Strategy>,
+    last_signal: Option<(OrderSide, DateTime<Utc>)>,
+}
+
+impl Slot {
+    fn status(&self) -> StrategyStatus {
+        StrategyStatus {
+            name: self.cfg.name.clone(),
+            pair: self.cfg.pair.clone(),
+            strategy_type: self.cfg.strategy_type.clone(),
+            params: serde_json::to_value(&self.cfg.params).unwrap_or_default(),
+            enabled: self.cfg.enabled,
+            quantity: self.cfg.quantity,
+            quote_quantity: self.cfg.quote_quantity,
+            last_signal: self.last_signal.map(|(side, _)| side),
+            last_signal_at: self.last_signal.map(|(_, at)| at),
+        }
+    }
 }
 
 impl StrategyRegistry {
     const DEFAULT_MAX_HISTORY: usize = 200;
 
+    /// Every problem that would stop `from_config`, one per invalid
+    /// strategy.
+    pub fn check(file_cfg: &StrategyFileConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+        for cfg in &file_cfg.strategies {
+            if let Err(e) = build_strategy(cfg) {
+                problems.push(format!("Invalid strategy '{}': {e}", cfg.name));
+            }
+            if let Some(Err(e)) = cfg.risk.as_ref().map(|o| o.validate()) {
+                problems.push(format!(
+                    "Invalid risk overrides for strategy '{}': {e}",
+                    cfg.name
+                ));
+            }
+        }
+        problems
+    }
+
     /// Build the registry from config, exiting on unknown strategy types.
     pub fn from_config(file_cfg: &StrategyFileConfig) -> Self {
-        let mut strategies: Vec<Box<dyn Strategy>> = Vec::new();
+        let mut strategies = Vec::new();
 
         for cfg in &file_cfg.strategies {
             let strategy = build_strategy(cfg)
-                .unwrap_or_else(|e| panic!("Unknown strategy type '{}': {e}", cfg.strategy_type));
-            info!(name = %strategy.name(), pair = %strategy.pair(), "Registered strategy");
-            strategies.push(strategy);
+                .unwrap_or_else(|e| panic!("Invalid strategy '{}': {e}", cfg.name));
+            if let Some(overrides) = &cfg.risk {
+                overrides.validate().unwrap_or_else(|e| {
+                    panic!("Invalid risk overrides for strategy '{}': {e}", cfg.name)
+                });
+            }
+            info!(name = %strategy.name(), pair = %strategy.pair(), enabled = cfg.enabled, "Registered strategy");
+            strategies.push(Slot {
+                cfg: cfg.clone(),
+                strategy,
+                last_signal: None,
+            });
         }
 
         Self {
             strategies,
             price_history: HashMap::new(),
             max_history: Self::DEFAULT_MAX_HISTORY,
+            command_rx: None,
+        }
+    }
+
+    /// Accept `StrategyCommand`s (list, toggle, edit) while running.
+    pub fn set_commands(&mut self, command_rx: mpsc::Receiver<StrategyCommand>) {
+        self.command_rx = Some(command_rx);
+    }
+
+    /// Status of every registered strategy, in config order.
+    pub fn statuses(&self) -> Vec<StrategyStatus> {
+        self.strategies.iter().map(Slot::status).collect()
+    }
+
+    /// Apply a partial update to the strategy called `name`, rebuilding it
+    /// from the updated config. Returns `None` if there is no such strategy.
+    /// Changes last until restart.
+    pub fn update(
+        &mut self,
+        name: &str,
+        patch: &serde_json::Value,
+    ) -> Result<Option<StrategyStatus>, String> {
+        let Some(slot) = self.strategies.iter_mut().find(|s| s.cfg.name == name) else {
+            return Ok(None);
+        };
+        let cfg = slot.cfg.apply_patch(patch).map_err(|e| e.to_string())?;
+        slot.strategy = build_strategy(&cfg)?;
+        slot.cfg = cfg;
+        Ok(Some(slot.status()))
+    }
+
+    fn handle_command(&mut self, command: StrategyCommand) {
+        match command {
+            StrategyCommand::List { reply } => {
+                let _ = reply.send(self.statuses());
+            }
+            StrategyCommand::Update {
+                name,
+                patch,
+                actor,
+                reply,
+            } => {
+                let result = self.update(&name, &patch);
+                match &result {
+                    Ok(Some(_)) => info!(
+                        target: "audit",
+                        actor = %actor,
+                        strategy = %name,
+                        changes = %patch,
+                        "Strategy updated at runtime"
+                    ),
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!(actor = %actor, strategy = %name, error = %e, "Rejected strategy update")
+                    }
+                }
+                let _ = reply.send(result);
+            }
+        }
+    }
+
+    /// Feed closed `candles` from before a restart, oldest first, to the
+    /// price history and the strategies' indicators. Signals they would
+    /// have produced are dropped.
+    pub fn warm_up(&mut self, candles: &[MarketEvent]) {
+        for candle in candles.iter().filter(|c| c.is_candle_closed) {
+            let history = self.price_history.entry(candle.pair.clone()).or_default();
+            history.push(candle.price);
+            if history.len() > self.max_history {
+                history.remove(0);
+            }
+            for s in self
+                .strategies
+                .iter()
+                .filter(|s| s.strategy.pair() == candle.pair)
+            {
+                let _ = s.strategy.evaluate(std::slice::from_ref(candle));
+            }
         }
     }
 
@@ -61,13 +194,31 @@ impl StrategyRegistry {
         let events_slice = std::slice::from_ref(event);
 
         self.strategies
-            .iter()
-            .filter(|s| s.pair() == event.pair)
+            .iter_mut()
+            .filter(|s| s.cfg.enabled && s.strategy.pair() == event.pair)
             .filter_map(|s| {
                 // Strategies receive the event slice; they can also use
                 // historical data if they hold internal state.
                 // Here we pass the current event as a single-element slice.
-                s.evaluate(events_slice)
+                let started = Instant::now();
+                let signal = s.strategy.evaluate(events_slice);
+                let strategy = s.cfg.name.clone();
+                metrics()
+                    .strategy_evaluation
+                    .get_or_create(&StrategyLabels {
+                        strategy: strategy.clone(),
+                    })
+                    .observe(started.elapsed().as_secs_f64());
+                let signal = signal?;
+                metrics()
+                    .signals
+                    .get_or_create(&SignalLabels {
+                        strategy,
+                        side: signal.side.to_string(),
+                    })
+                    .inc();
+                s.last_signal = Some((signal.side, Utc::now()));
+                Some(signal)
             })
             .collect()
     }
@@ -75,15 +226,28 @@ impl StrategyRegistry {
     /// Run the strategy dispatch loop.
     /// Reads from `market_rx`, pushes signals to `signal_tx`.
     /// Suppresses signals when engine is paused/halted.
+    ///
+    /// Borrows rather than consumes, so a supervisor can restart the loop
+    /// with the same strategies after a panic.
     pub async fn run(
-        mut self,
+        &mut self,
         mut market_rx: broadcast::Receiver<MarketEvent>,
         signal_tx: mpsc::Sender<Signal>,
         engine_state: Arc<tokio::sync::RwLock<EngineState>>,
     ) {
         info!("StrategyRegistry running");
         loop {
-            match market_rx.recv().await {
+            let event = tokio::select! {
+                event = market_rx.recv() => event,
+                command = next_command(&mut self.command_rx) => {
+                    match command {
+                        Some(command) => self.handle_command(command),
+                        None => self.command_rx = None,
+                    }
+                    continue;
+                }
+            };
+            match event {
                 Ok(event) => {
                     let state = *engine_state.read().await;
                     if state != EngineState::Running {
@@ -113,6 +277,14 @@ impl StrategyRegistry {
     }
 }
 
+/// Next command, or never when no command channel is connected.
+async fn next_command(rx: &mut Option<mpsc::Receiver<StrategyCommand>>) -> Option<StrategyCommand> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 // ─── Strategy builders ────────────────────────────────────────────────────────
 
 fn build_strategy(cfg: &StrategyConfig) -> Result<Box<dyn Strategy>, String> {
@@ -121,6 +293,12 @@ fn build_strategy(cfg: &StrategyConfig) -> Result<Box<dyn Strategy>, String> {
             let period = param_usize(&cfg.params, "period", 14);
             let overbought = param_f64(&cfg.params, "overbought", 70.0);
             let oversold = param_f64(&cfg.params, "oversold", 30.0);
+            if period == 0 {
+                return Err("RSI period must be positive".into());
+            }
+            if !(0.0..=100.0).contains(&oversold) || oversold >= overbought || overbought > 100.0 {
+                return Err("RSI thresholds must satisfy 0 <= oversold < overbought <= 100".into());
+            }
             Ok(Box::new(RsiStrategy::new(
                 cfg.clone(),
                 period,
@@ -132,6 +310,9 @@ fn build_strategy(cfg: &StrategyConfig) -> Result<Box<dyn Strategy>, String> {
             let fast = param_usize(&cfg.params, "fast", 12);
             let slow = param_usize(&cfg.params, "slow", 26);
             let signal = param_usize(&cfg.params, "signal", 9);
+            if fast == 0 || signal == 0 || fast >= slow {
+                return Err("MACD periods must be positive with fast < slow".into());
+            }
             Ok(Box::new(MacdStrategy::new(cfg.clone(), fast, slow, signal)))
         }
         other => Err(format!("unknown type '{other}'")),
@@ -141,7 +322,7 @@ fn build_strategy(cfg: &StrategyConfig) -> Result<Box<dyn Strategy>, String> {
 fn param_f64(params: &HashMap<String, toml::Value>, key: &str, default: f64) -> f64 {
     params
         .get(key)
-        .and_then(|v| v.as_float())
+        .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
         .unwrap_or(default)
 }
 
@@ -197,15 +378,9 @@ impl Strategy for RsiStrategy {
         let rsi = self.indicator.compute(&closed_prices)?;
 
         if rsi <= self.indicator.oversold {
-            Some(Signal::Buy {
-                pair: self.cfg.pair.clone(),
-                quantity: self.cfg.quantity,
-            })
+            Some(self.cfg.signal(OrderSide::Buy))
         } else if rsi >= self.indicator.overbought {
-            Some(Signal::Sell {
-                pair: self.cfg.pair.clone(),
-                quantity: self.cfg.quantity,
-            })
+            Some(self.cfg.signal(OrderSide::Sell))
         } else {
             None
         }
@@ -244,14 +419,8 @@ impl Strategy for MacdStrategy {
 
         use crate::indicators::macd::MacdSignal;
         match self.indicator.compute(&closes)? {
-            MacdSignal::Bullish => Some(Signal::Buy {
-                pair: self.cfg.pair.clone(),
-                quantity: self.cfg.quantity,
-            }),
-            MacdSignal::Bearish => Some(Signal::Sell {
-                pair: self.cfg.pair.clone(),
-                quantity: self.cfg.quantity,
-            }),
+            MacdSignal::Bullish => Some(self.cfg.signal(OrderSide::Buy)),
+            MacdSignal::Bearish => Some(self.cfg.signal(OrderSide::Sell)),
             MacdSignal::Neutral => None,
         }
     }
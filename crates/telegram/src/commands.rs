@@ -1,22 +1,87 @@
 use std::sync::Arc;
+use std::time::Duration;
 
-use teloxide::{dispatching::UpdateHandler, prelude::*, utils::command::BotCommands};
-use tokio::sync::{mpsc, RwLock};
-use tracing::{info, warn};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use teloxide::{
+    dispatching::UpdateHandler,
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode, User},
+    utils::command::BotCommands,
+};
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tracing::{error, info, warn};
 
-use common::{EngineCommand, EngineState, TradingMode};
+use common::{
+    AlertCategory, AuditLog, EngineCommand, EngineState, ExecutorCommand, HaltOutcome, RetryQueue,
+    RiskCommand, StopOutcome, StrategyCommand, StrategyStatus, StreamHealth, TelegramAllowlist,
+    TradingMode,
+};
+
+use crate::AlertSubscriptions;
 
 type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 
+/// Closed trades per `/trades` page when no count is given, and the most
+/// one page may show.
+const DEFAULT_TRADES_PAGE: i64 = 10;
+const MAX_TRADES_PAGE: i64 = 50;
+
+/// How long the Yes button of a confirmation prompt stays valid.
+const CONFIRM_TTL_SECS: i64 = 60;
+
+/// How long `/halt` waits for the engine to report back. The engine gives the
+/// order executor 5 seconds to cancel resting orders.
+const HALT_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest a `/mode` switch waits for open positions to close.
+const MODE_SWITCH_CLOSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Longest `/stop` waits for the engine to report back. The engine gives up
+/// on position closes after 30 seconds, so only a stuck engine hits this.
+const STOP_REPLY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Market data age at which `/health` flags a pair, when the staleness
+/// watchdog (`MARKET_STALE_SECS`) is disabled. Matches `/readyz`.
+const DEFAULT_MAX_EVENT_AGE_SECS: u64 = 60;
+
+/// Longest `/health` waits for the database.
+const DB_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Dependencies injected into every handler via `dptree`.
 #[derive(Clone)]
 pub struct BotDeps {
     pub command_tx: mpsc::Sender<EngineCommand>,
+    /// Control channel of the Risk Manager, for `/close`, `/closeall`, and
+    /// `/risk`.
+    pub risk_tx: mpsc::Sender<RiskCommand>,
+    /// Control channel of the order executor, for `/halt`.
+    pub executor_tx: mpsc::Sender<ExecutorCommand>,
+    /// Control channel of the Strategy Registry, for `/strategies`.
+    pub strategy_tx: mpsc::Sender<StrategyCommand>,
     pub engine_state: Arc<RwLock<EngineState>>,
-    pub trading_mode: TradingMode,
-    pub allowed_user_ids: Arc<Vec<i64>>,
+    /// Current trading mode; `/mode` may switch it at runtime.
+    pub trading_mode: Arc<RwLock<TradingMode>>,
+    /// Users allowed to control the bot, including those paired with `/pair`.
+    pub allowlist: TelegramAllowlist,
+    /// Users allowed to switch the trading mode; empty disables `/mode`
+    /// switching.
+    pub mode_switch_user_ids: Arc<Vec<i64>>,
+    /// Database with the trade history, for `/trades`.
+    pub db: SqlitePool,
+    /// Market data stream supervision, for `/health`.
+    pub stream_health: StreamHealth,
+    /// `MARKET_STALE_SECS`; `0` if the staleness watchdog is disabled.
+    pub market_stale_secs: u64,
     /// Channel for sending alerts back to the bot (used by Risk Manager).
     pub alert_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<String>>>,
+    /// Failed orders awaiting retry, shared with the executor.
+    pub retry_queue: RetryQueue,
+    /// Journal of operator actions, shared with the dashboard API.
+    pub audit: AuditLog,
+    /// Alert categories per chat, for `/alerts`.
+    pub subscriptions: AlertSubscriptions,
 }
 
 /// Telegram bot commands exposed to the operator.
@@ -29,8 +94,42 @@ pub enum Command {
     Stop,
     #[command(description = "Show engine status and PnL summary")]
     Status,
+    #[command(description = "Show stream, database, and order flow health")]
+    Health,
+    #[command(description = "Show the trading mode, or switch it: /mode [paper|live]")]
+    Mode(String),
+    #[command(description = "List strategies with buttons to enable or disable them")]
+    Strategies,
+    #[command(description = "Show the last closed trades: /trades [n]")]
+    Trades(String),
+    #[command(description = "Show risk parameters, or change one: /risk set <name> <value>")]
+    Risk(String),
     #[command(description = "Reset max-drawdown halt")]
     ResetDrawdown,
+    #[command(description = "EMERGENCY: close all open positions and pause entries")]
+    Flatten,
+    #[command(description = "EMERGENCY: block entries and cancel resting orders: /halt [flatten]")]
+    Halt(String),
+    #[command(description = "Close the open position on a pair: /close <PAIR>")]
+    Close(String),
+    #[command(description = "Close all open positions (entries stay enabled)")]
+    CloseAll,
+    #[command(description = "Resume entries after a pause or flatten")]
+    Resume,
+    #[command(description = "Choose this chat's alerts: /alerts [on|off <category>|all|none]")]
+    Alerts(String),
+    #[command(description = "List failed orders awaiting retry")]
+    Retries,
+    #[command(description = "Retry a failed order now: /retry <order id>")]
+    Retry(String),
+    #[command(description = "Discard a failed order: /discard <order id>")]
+    Discard(String),
+    #[command(description = "Start streaming a pair: /addpair <PAIR>")]
+    AddPair(String),
+    #[command(description = "Stop streaming a pair: /removepair <PAIR>")]
+    RemovePair(String),
+    #[command(description = "Pair a new operator with a one-time code: /pair <code>")]
+    Pair(String),
 }
 
 /// Start the Telegram bot in long-polling mode.
@@ -55,24 +154,104 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync>> {
         .branch(case![Command::Start].endpoint(handle_start))
         .branch(case![Command::Stop].endpoint(handle_stop))
         .branch(case![Command::Status].endpoint(handle_status))
-        .branch(case![Command::ResetDrawdown].endpoint(handle_reset_drawdown));
+        .branch(case![Command::Health].endpoint(handle_health))
+        .branch(case![Command::Mode(target)].endpoint(handle_mode))
+        .branch(case![Command::Trades(count)].endpoint(handle_trades))
+        .branch(case![Command::Strategies].endpoint(handle_strategies))
+        .branch(case![Command::Risk(args)].endpoint(handle_risk))
+        .branch(case![Command::ResetDrawdown].endpoint(handle_reset_drawdown))
+        .branch(case![Command::Flatten].endpoint(handle_flatten))
+        .branch(case![Command::Halt(args)].endpoint(handle_halt))
+        .branch(case![Command::Close(pair)].endpoint(handle_close))
+        .branch(case![Command::CloseAll].endpoint(handle_close_all))
+        .branch(case![Command::Resume].endpoint(handle_resume))
+        .branch(case![Command::Alerts(args)].endpoint(handle_alerts))
+        .branch(case![Command::Retries].endpoint(handle_retries))
+        .branch(case![Command::Retry(order_id)].endpoint(handle_retry))
+        .branch(case![Command::Discard(order_id)].endpoint(handle_discard))
+        .branch(case![Command::AddPair(pair)].endpoint(handle_add_pair))
+        .branch(case![Command::RemovePair(pair)].endpoint(handle_remove_pair));
+
+    // `/pair` is the one command open to users not yet allowed
+    let pair_handler = teloxide::filter_command::<Command, _>()
+        .branch(case![Command::Pair(code)].endpoint(handle_pair));
 
-    Update::filter_message()
+    let message_handler = Update::filter_message()
         .filter_map(|msg: Message| msg.from().map(|u| u.id))
+        .branch(pair_handler)
+        .filter_async(auth_filter)
+        .branch(command_handler);
+
+    // Inline keyboard buttons
+    let callback_handler = Update::filter_callback_query()
+        .map(|q: CallbackQuery| q.from.id)
         .filter_async(auth_filter)
-        .branch(command_handler)
+        .endpoint(handle_callback);
+
+    dptree::entry()
+        .branch(message_handler)
+        .branch(callback_handler)
 }
 
 /// Silently drop messages from users not in the allowed list.
 async fn auth_filter(user_id: UserId, deps: Arc<BotDeps>) -> bool {
     let uid = user_id.0 as i64;
-    let allowed = deps.allowed_user_ids.contains(&uid);
+    let allowed = deps.allowlist.contains(uid).await;
     if !allowed {
         warn!(user_id = uid, "Unauthorized Telegram access attempt");
     }
     allowed
 }
 
+/// Allow the sender from now on if `code` is the pairing code issued on the
+/// dashboard (and printed in the logs).
+async fn handle_pair(bot: Bot, msg: Message, code: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+    let uid = user.id.0 as i64;
+    let reply = if deps.allowlist.contains(uid).await {
+        "You are already allowed to control this bot.".to_string()
+    } else {
+        match deps
+            .allowlist
+            .pair(uid, user.username.as_deref(), code.trim())
+            .await
+        {
+            Ok(true) => {
+                info!(user_id = uid, username = ?user.username, "Telegram user paired");
+                let payload = json!({ "user_id": uid, "username": user.username });
+                deps.audit
+                    .record(&actor(&msg), "telegram.pair", payload)
+                    .await;
+                "Paired \u{2014} you can now control this bot.".to_string()
+            }
+            Ok(false) => {
+                warn!(user_id = uid, "Telegram pairing with an invalid code");
+                "Invalid or expired pairing code.".to_string()
+            }
+            Err(e) => {
+                error!(user_id = uid, error = %e, "Failed to save paired Telegram user");
+                "Pairing failed; try again later.".to_string()
+            }
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// Who sent `msg`, as recorded in the audit log, e.g. `telegram:123456`.
+fn actor(msg: &Message) -> String {
+    match msg.from() {
+        Some(user) => user_actor(user),
+        None => "telegram".to_string(),
+    }
+}
+
+fn user_actor(user: &User) -> String {
+    format!("telegram:{}", user.id.0)
+}
+
 async fn handle_start(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
     let state = *deps.engine_state.read().await;
     if state == EngineState::Running {
@@ -80,6 +259,9 @@ async fn handle_start(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResu
             .await?;
     } else {
         let _ = deps.command_tx.send(EngineCommand::Start).await;
+        deps.audit
+            .record(&actor(&msg), "engine.start", json!({}))
+            .await;
         // Wait briefly for the engine to process the command and update state
         tokio::time::sleep(std::time::Duration::from_millis(200)).await;
         let new_state = *deps.engine_state.read().await;
@@ -98,18 +280,52 @@ async fn handle_stop(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResul
     if state == EngineState::Stopped {
         bot.send_message(msg.chat.id, "Engine is already stopped.")
             .await?;
-    } else {
-        bot.send_message(msg.chat.id, "Closing open positions and stopping\u{2026}")
+    } else if *deps.trading_mode.read().await == TradingMode::Live {
+        bot.send_message(msg.chat.id, "Stop the engine and close all open positions?")
+            .reply_markup(confirm_keyboard("stop"))
             .await?;
-        let _ = deps.command_tx.send(EngineCommand::Stop).await;
-        bot.send_message(msg.chat.id, "Engine stopped.").await?;
+    } else {
+        stop_engine(&bot, msg.chat.id, &deps, &actor(&msg)).await?;
     }
     Ok(())
 }
 
+async fn stop_engine(bot: &Bot, chat_id: ChatId, deps: &BotDeps, actor: &str) -> HandlerResult {
+    bot.send_message(chat_id, "Closing open positions and stopping\u{2026}")
+        .await?;
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let stop = async {
+        deps.command_tx
+            .send(EngineCommand::Stop { reply: reply_tx })
+            .await
+            .ok()?;
+        reply_rx.await.ok()
+    };
+    let outcome = tokio::time::timeout(STOP_REPLY_TIMEOUT, stop).await;
+    deps.audit.record(actor, "engine.stop", json!({})).await;
+    let text = match outcome {
+        Ok(Some(StopOutcome::Closed(0))) => "Engine stopped. No positions were open.".to_string(),
+        Ok(Some(StopOutcome::Closed(n))) => format!("Engine stopped. Closed {n} position(s)."),
+        Ok(Some(StopOutcome::TimedOut)) => {
+            "Engine stopped, but not every close filled in time. Check /status.".to_string()
+        }
+        Ok(Some(StopOutcome::CloseFailed(e))) => {
+            format!("Engine stopped, but a close failed ({e}): its position is still open. Check /status.")
+        }
+        Ok(Some(StopOutcome::LeftOpen)) => {
+            "Engine stopped, but the risk manager was unavailable: positions were left open."
+                .to_string()
+        }
+        Ok(None) => "Engine unavailable; it may not have stopped.".to_string(),
+        Err(_) => "The engine has not confirmed the stop yet. Check /status.".to_string(),
+    };
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
 async fn handle_status(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
     let state = *deps.engine_state.read().await;
-    let mode = deps.trading_mode;
+    let mode = *deps.trading_mode.read().await;
     let text = format!(
         "ClawBot Status\n\
          Engine: {state}\n\
@@ -120,6 +336,518 @@ async fn handle_status(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerRes
     Ok(())
 }
 
+/// Show the trading mode, or start switching it. A switch needs the user to
+/// be in `MODE_SWITCH_USER_IDS` and to confirm twice.
+async fn handle_mode(bot: Bot, msg: Message, target: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let mode = *deps.trading_mode.read().await;
+    let target = target.trim().to_lowercase();
+    let reply = match target.as_str() {
+        "" => format!("Trading mode: {mode}"),
+        _ if !msg.from().is_some_and(|u| may_switch_mode(&deps, u)) => {
+            "You are not allowed to switch the trading mode.".to_string()
+        }
+        "paper" | "live" if target == mode.to_string() => format!("Already in {mode} mode."),
+        "paper" | "live" => {
+            let prompt = format!(
+                "Switch from {mode} to {target} trading? Entries are paused and every open position is closed at market."
+            );
+            bot.send_message(msg.chat.id, prompt)
+                .reply_markup(confirm_keyboard(&format!("mode1:{target}")))
+                .await?;
+            return Ok(());
+        }
+        _ => "Usage: /mode [paper|live]".to_string(),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+fn may_switch_mode(deps: &BotDeps, user: &User) -> bool {
+    deps.mode_switch_user_ids.contains(&(user.id.0 as i64))
+}
+
+/// Pause entries, close every open position, then have the executor trade
+/// through the client for `target`. Entries stay paused afterwards so the
+/// operator resumes deliberately in the new mode.
+async fn switch_mode(deps: &BotDeps, user: &User, target: &str) -> String {
+    if !may_switch_mode(deps, user) {
+        return "You are not allowed to switch the trading mode.".to_string();
+    }
+    let target = match target {
+        "paper" => TradingMode::Paper,
+        "live" => TradingMode::Live,
+        _ => return "Usage: /mode [paper|live]".to_string(),
+    };
+    let from = *deps.trading_mode.read().await;
+    if from == target {
+        return format!("Already in {target} mode.");
+    }
+    let actor = user_actor(user);
+    warn!(actor = %actor, %from, to = %target, "Trading mode switch requested via Telegram");
+
+    {
+        let mut state = deps.engine_state.write().await;
+        if *state == EngineState::Running {
+            *state = EngineState::Paused;
+        }
+    }
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let close = async {
+        deps.risk_tx
+            .send(RiskCommand::CloseAll { reply: reply_tx })
+            .await
+            .ok()?;
+        reply_rx.await.ok()
+    };
+    let closed = match tokio::time::timeout(MODE_SWITCH_CLOSE_TIMEOUT, close).await {
+        Ok(Some(Ok(closed))) => closed,
+        Ok(Some(Err(e))) => {
+            return format!(
+                "A position close failed ({e}); still in {from} mode with entries paused."
+            );
+        }
+        _ => {
+            return format!(
+                "Open positions did not close in time; still in {from} mode with entries paused."
+            );
+        }
+    };
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let cmd = ExecutorCommand::SwitchMode {
+        mode: target,
+        reply: reply_tx,
+    };
+    if deps.executor_tx.send(cmd).await.is_err() {
+        return "Order executor unavailable.".to_string();
+    }
+    match reply_rx.await {
+        Ok(Ok(())) => {
+            *deps.trading_mode.write().await = target;
+            let payload = json!({ "from": from, "to": target, "closed": closed });
+            deps.audit.record(&actor, "mode.switch", payload).await;
+            format!(
+                "Switched from {from} to {target} trading after closing {closed} position(s). Entries are paused \u{2014} use /resume to continue."
+            )
+        }
+        Ok(Err(e)) => format!("Switch refused: {e}. Still in {from} mode with entries paused."),
+        Err(_) => "Order executor unavailable.".to_string(),
+    }
+}
+
+/// `/readyz` for chat: engine state, market data freshness per pair, the
+/// database, and how long ago the last order went out.
+async fn handle_health(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    let state = *deps.engine_state.read().await;
+    let mode = *deps.trading_mode.read().await;
+    let max_age = match deps.market_stale_secs {
+        0 => DEFAULT_MAX_EVENT_AGE_SECS,
+        secs => secs,
+    } as i64;
+    let now = Utc::now();
+
+    let mut text = format!("ClawBot Health\nEngine: {state} ({mode})\n\nStreams:");
+    let streams = deps.stream_health.snapshot();
+    if streams.is_empty() {
+        text.push_str(" none");
+    }
+    for s in streams {
+        let age = s.last_event_at.map(|at| (now - at).num_seconds());
+        let fresh = s.connected && age.is_some_and(|age| age <= max_age);
+        let last_event = match age {
+            Some(age) => format!("last event {} ago", ago(age)),
+            None => "no events yet".to_string(),
+        };
+        text.push_str(&format!(
+            "\n{} {} {} \u{2014} {}, {last_event}",
+            if fresh {
+                "\u{2705}"
+            } else {
+                "\u{26a0}\u{fe0f}"
+            },
+            s.pair,
+            s.interval,
+            if s.connected {
+                "connected"
+            } else {
+                "disconnected"
+            },
+        ));
+    }
+
+    let started = std::time::Instant::now();
+    let ping = sqlx::query("SELECT 1").execute(&deps.db);
+    let database = match tokio::time::timeout(DB_CHECK_TIMEOUT, ping).await {
+        Ok(Ok(_)) => format!("\u{2705} ok ({} ms)", started.elapsed().as_millis()),
+        Ok(Err(e)) => format!("\u{274c} {e}"),
+        Err(_) => format!("\u{274c} timed out after {}s", DB_CHECK_TIMEOUT.as_secs()),
+    };
+    text.push_str(&format!("\n\nDatabase: {database}"));
+
+    let mode = mode.to_string();
+    let last_order = sqlx::query_scalar!(
+        r#"SELECT MAX(created_at) AS "created_at?: String" FROM orders WHERE mode = ?1"#,
+        mode,
+    )
+    .fetch_one(&deps.db)
+    .await;
+    let last_order = match last_order {
+        Ok(Some(at)) => match DateTime::parse_from_rfc3339(&at) {
+            Ok(at) => format!("{} ago", ago((now - at.with_timezone(&Utc)).num_seconds())),
+            Err(_) => at,
+        },
+        Ok(None) => "none yet".to_string(),
+        Err(_) => "unknown".to_string(),
+    };
+    text.push_str(&format!("\nLast order: {last_order}"));
+
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+/// `secs` as a short duration, e.g. `42s`, `5m`, `3h`, `2d`.
+fn ago(secs: i64) -> String {
+    match secs.max(0) {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+async fn handle_trades(bot: Bot, msg: Message, count: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let limit = match count.trim() {
+        "" => DEFAULT_TRADES_PAGE,
+        n => match n.parse::<i64>() {
+            Ok(n) if n > 0 => n.min(MAX_TRADES_PAGE),
+            _ => {
+                bot.send_message(msg.chat.id, "Usage: /trades [n]").await?;
+                return Ok(());
+            }
+        },
+    };
+    let (text, keyboard) = trades_page(&deps.db, limit, 0).await?;
+    bot.send_message(msg.chat.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// `limit` closed trades, newest first, skipping the `offset` newest, as a
+/// monospace table with buttons for the neighbouring pages.
+async fn trades_page(
+    db: &SqlitePool,
+    limit: i64,
+    offset: i64,
+) -> Result<(String, InlineKeyboardMarkup), sqlx::Error> {
+    // One extra row tells whether an older page exists
+    let fetch = limit + 1;
+    let mut rows = sqlx::query!(
+        r#"SELECT pair, side, pnl_usd, closed_at FROM trades
+           ORDER BY closed_at DESC, id DESC LIMIT ?1 OFFSET ?2"#,
+        fetch,
+        offset,
+    )
+    .fetch_all(db)
+    .await?;
+    let older = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+
+    if rows.is_empty() {
+        let text = "No closed trades.".to_string();
+        return Ok((text, InlineKeyboardMarkup::default()));
+    }
+    let mut table = format!(
+        "{:<11} {:<9} {:<4} {:>9}\n",
+        "CLOSED", "PAIR", "SIDE", "PNL"
+    );
+    for t in &rows {
+        let closed = DateTime::parse_from_rfc3339(&t.closed_at)
+            .map(|at| at.format("%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        table.push_str(&format!(
+            "{closed:<11} {:<9} {:<4} {:>+9.2}\n",
+            t.pair, t.side, t.pnl_usd
+        ));
+    }
+    let text = format!(
+        "Closed trades {}\u{2013}{} (UTC):\n<pre>{table}</pre>",
+        offset + 1,
+        offset + rows.len() as i64
+    );
+
+    let mut buttons = Vec::new();
+    if offset > 0 {
+        let newer = (offset - limit).max(0);
+        buttons.push(InlineKeyboardButton::callback(
+            "\u{00ab} Newer",
+            format!("trades:{limit}:{newer}"),
+        ));
+    }
+    if older {
+        buttons.push(InlineKeyboardButton::callback(
+            "More \u{00bb}",
+            format!("trades:{limit}:{}", offset + limit),
+        ));
+    }
+    Ok((text, InlineKeyboardMarkup::new([buttons])))
+}
+
+async fn handle_strategies(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = deps
+        .strategy_tx
+        .send(StrategyCommand::List { reply: reply_tx })
+        .await;
+    let Ok(strategies) = reply_rx.await else {
+        bot.send_message(msg.chat.id, "Strategy registry unavailable.")
+            .await?;
+        return Ok(());
+    };
+    let (text, keyboard) = strategy_list(&strategies);
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// One line per strategy, with a button per strategy that flips it.
+fn strategy_list(strategies: &[StrategyStatus]) -> (String, InlineKeyboardMarkup) {
+    if strategies.is_empty() {
+        return (
+            "No strategies configured.".to_string(),
+            InlineKeyboardMarkup::default(),
+        );
+    }
+    let mut text = "Strategies:".to_string();
+    let mut rows = Vec::with_capacity(strategies.len());
+    for s in strategies {
+        let (mark, label, switch) = if s.enabled {
+            ("\u{2705}", "Disable", "off")
+        } else {
+            ("\u{23f8}\u{fe0f}", "Enable", "on")
+        };
+        text.push_str(&format!(
+            "\n{mark} {} \u{2014} {} on {}",
+            s.name, s.strategy_type, s.pair
+        ));
+        rows.push([InlineKeyboardButton::callback(
+            format!("{label} {}", s.name),
+            format!("strategy:{switch}:{}", s.name),
+        )]);
+    }
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+/// Enable or disable strategy `name` through the same runtime update as
+/// `PATCH /api/strategies/{name}`. Returns the refreshed list, or why the
+/// change failed.
+async fn toggle_strategy(
+    deps: &BotDeps,
+    name: &str,
+    enabled: bool,
+    actor: &str,
+) -> Result<Vec<StrategyStatus>, String> {
+    let patch = json!({ "enabled": enabled });
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = deps
+        .strategy_tx
+        .send(StrategyCommand::Update {
+            name: name.to_string(),
+            patch: patch.clone(),
+            actor: actor.to_string(),
+            reply: reply_tx,
+        })
+        .await;
+    match reply_rx.await {
+        Ok(Ok(Some(_))) => {
+            let payload = json!({ "strategy": name, "changes": patch });
+            deps.audit.record(actor, "strategy.update", payload).await;
+        }
+        Ok(Ok(None)) => return Err(format!("No strategy named {name}.")),
+        Ok(Err(e)) => return Err(format!("Rejected: {e}")),
+        Err(_) => return Err("Strategy registry unavailable.".to_string()),
+    }
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = deps
+        .strategy_tx
+        .send(StrategyCommand::List { reply: reply_tx })
+        .await;
+    reply_rx
+        .await
+        .map_err(|_| "Strategy registry unavailable.".to_string())
+}
+
+/// Presses of inline keyboard buttons. `data` says what the button does:
+/// `trades:<limit>:<offset>` turns the page of a `/trades` reply,
+/// `strategy:<on|off>:<name>` enables or disables a strategy,
+/// `ok:<issued at>:<action>` confirms a destructive command (`mode1:` and
+/// `mode2:` are the two steps of a `/mode` switch), and `cancel` drops it.
+/// Answering a prompt removes its buttons, so each works once.
+async fn handle_callback(bot: Bot, q: CallbackQuery, deps: Arc<BotDeps>) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let (Some(data), Some(message)) = (q.data.as_deref(), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat.id;
+    let prompt = message.text().unwrap_or_default();
+    if let Some(confirmed) = data.strip_prefix("ok:") {
+        let Some((issued_at, action)) = confirmed.split_once(':') else {
+            return Ok(());
+        };
+        let issued_at: i64 = issued_at.parse().unwrap_or_default();
+        if Utc::now().timestamp() - issued_at > CONFIRM_TTL_SECS {
+            let text = format!("{prompt}\n\nExpired \u{2014} send the command again.");
+            bot.edit_message_text(chat_id, message.id, text).await?;
+            return Ok(());
+        }
+        bot.edit_message_text(chat_id, message.id, format!("{prompt}\n\nConfirmed."))
+            .await?;
+        let actor = user_actor(&q.from);
+        match action {
+            "stop" => stop_engine(&bot, chat_id, &deps, &actor).await?,
+            "flatten" => flatten(&bot, chat_id, &deps, &actor).await?,
+            _ => {
+                if let Some(target) = action.strip_prefix("mode1:") {
+                    let prompt =
+                        format!("Really switch to {target}? Every open position is closed first.");
+                    bot.send_message(chat_id, prompt)
+                        .reply_markup(confirm_keyboard(&format!("mode2:{target}")))
+                        .await?;
+                } else if let Some(target) = action.strip_prefix("mode2:") {
+                    let reply = switch_mode(&deps, &q.from, target).await;
+                    bot.send_message(chat_id, reply).await?;
+                } else if let Some(pair) = action.strip_prefix("close:") {
+                    let pair = (pair != "*").then(|| pair.to_string());
+                    let reply = close_positions(&deps, pair, &actor).await;
+                    bot.send_message(chat_id, reply).await?;
+                }
+            }
+        }
+    } else if let Some(toggle) = data.strip_prefix("strategy:") {
+        let Some((switch, name)) = toggle.split_once(':') else {
+            return Ok(());
+        };
+        let enabled = switch == "on";
+        match toggle_strategy(&deps, name, enabled, &user_actor(&q.from)).await {
+            Ok(strategies) => {
+                let (text, keyboard) = strategy_list(&strategies);
+                bot.edit_message_text(chat_id, message.id, text)
+                    .reply_markup(keyboard)
+                    .await?;
+            }
+            Err(reply) => {
+                bot.send_message(chat_id, reply).await?;
+            }
+        }
+    } else if data == "cancel" {
+        bot.edit_message_text(chat_id, message.id, format!("{prompt}\n\nCancelled."))
+            .await?;
+    } else if let Some(page) = data.strip_prefix("trades:") {
+        let Some((limit, offset)) = page
+            .split_once(':')
+            .and_then(|(l, o)| Some((l.parse::<i64>().ok()?, o.parse::<i64>().ok()?)))
+        else {
+            return Ok(());
+        };
+        // Callback data comes from the client: hold it to what `/trades` allows
+        let (limit, offset) = (limit.clamp(1, MAX_TRADES_PAGE), offset.max(0));
+        let (text, keyboard) = trades_page(&deps.db, limit, offset).await?;
+        bot.edit_message_text(chat_id, message.id, text)
+            .parse_mode(ParseMode::Html)
+            .reply_markup(keyboard)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn handle_risk(bot: Bot, msg: Message, args: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let args: Vec<&str> = args.split_whitespace().collect();
+    let reply = match args.as_slice() {
+        [] => {
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let _ = deps
+                .risk_tx
+                .send(RiskCommand::GetConfig { reply: reply_tx })
+                .await;
+            match reply_rx.await {
+                Ok(config) => format!("Risk parameters:\n{}", describe_risk(&json!(config))),
+                Err(_) => "Risk manager unavailable.".to_string(),
+            }
+        }
+        ["set", name, value] => set_risk(&deps, name, value, &actor(&msg)).await,
+        _ => "Usage: /risk, or /risk set <name> <value>".to_string(),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+/// One `name = value` line per risk parameter. Percentage parameters drop
+/// their `_pct` suffix and are shown in percent, as `/risk set` takes them.
+fn describe_risk(config: &Value) -> String {
+    let Some(fields) = config.as_object() else {
+        return String::new();
+    };
+    let mut lines = Vec::with_capacity(fields.len());
+    for (key, value) in fields {
+        match (key.strip_suffix("_pct"), value.as_f64()) {
+            (Some(name), Some(fraction)) => {
+                lines.push(format!("{name} = {:.2}%", fraction * 100.0))
+            }
+            _ => lines.push(format!("{key} = {value}")),
+        }
+    }
+    lines.join("\n")
+}
+
+/// Change risk parameter `name` to `value` through the same runtime update
+/// as `PATCH /api/risk`. `name` may omit a `_pct` suffix, in which case
+/// `value` is in percent (`/risk set stop_loss 1.5` sets `stop_loss_pct`
+/// to 0.015).
+async fn set_risk(deps: &BotDeps, name: &str, value: &str, actor: &str) -> String {
+    let percent = !name.ends_with("_pct");
+    let pct_name = format!("{name}_pct");
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = deps
+        .risk_tx
+        .send(RiskCommand::GetConfig { reply: reply_tx })
+        .await;
+    let Ok(config) = reply_rx.await else {
+        return "Risk manager unavailable.".to_string();
+    };
+    let current = json!(config);
+    let (key, value) = if percent && current.get(&pct_name).is_some() {
+        match value.parse::<f64>() {
+            Ok(v) => (pct_name, json!(v / 100.0)),
+            Err(_) => return format!("{name} takes a number in percent, e.g. 1.5"),
+        }
+    } else {
+        // Numbers and booleans as such, anything else as a string
+        let value = serde_json::from_str(value).unwrap_or_else(|_| json!(value));
+        (name.to_string(), value)
+    };
+
+    let patch = json!({ key.as_str(): value });
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let _ = deps
+        .risk_tx
+        .send(RiskCommand::UpdateConfig {
+            patch: patch.clone(),
+            actor: actor.to_string(),
+            reply: reply_tx,
+        })
+        .await;
+    match reply_rx.await {
+        Ok(Ok(_)) => {
+            deps.audit.record(actor, "risk.update", patch).await;
+            format!("Risk parameter {key} set to {value}.")
+        }
+        Ok(Err(e)) => format!("Rejected: {e}"),
+        Err(_) => "Risk manager unavailable.".to_string(),
+    }
+}
+
 async fn handle_reset_drawdown(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
     let state = *deps.engine_state.read().await;
     if state != EngineState::Halted {
@@ -127,12 +855,367 @@ async fn handle_reset_drawdown(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> Ha
             .await?;
     } else {
         let _ = deps.command_tx.send(EngineCommand::ResetDrawdown).await;
+        deps.audit
+            .record(&actor(&msg), "engine.reset_drawdown", json!({}))
+            .await;
         bot.send_message(msg.chat.id, "Drawdown reset. Engine resuming.")
             .await?;
     }
     Ok(())
 }
 
+async fn handle_flatten(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    if *deps.trading_mode.read().await == TradingMode::Live {
+        bot.send_message(
+            msg.chat.id,
+            "Flatten ALL open positions at market and pause new entries?",
+        )
+        .reply_markup(confirm_keyboard("flatten"))
+        .await?;
+    } else {
+        flatten(&bot, msg.chat.id, &deps, &actor(&msg)).await?;
+    }
+    Ok(())
+}
+
+async fn flatten(bot: &Bot, chat_id: ChatId, deps: &BotDeps, actor: &str) -> HandlerResult {
+    warn!(actor, "Flatten requested via Telegram");
+    let _ = deps.command_tx.send(EngineCommand::Flatten).await;
+    deps.audit.record(actor, "engine.flatten", json!({})).await;
+    bot.send_message(
+        chat_id,
+        "\u{1f6a8} Flattening all open positions. New entries are paused \u{2014} use /resume to continue.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Kill switch that, unlike `/stop`, leaves the streams up: the engine blocks
+/// new entries and has resting orders cancelled at once, with `flatten` also
+/// closing open positions (after confirmation in live mode).
+async fn handle_halt(bot: Bot, msg: Message, args: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let flatten_too = match args.trim() {
+        "" => false,
+        "flatten" => true,
+        _ => {
+            bot.send_message(msg.chat.id, "Usage: /halt [flatten]")
+                .await?;
+            return Ok(());
+        }
+    };
+    let actor = actor(&msg);
+    warn!(actor = %actor, flatten = flatten_too, "Halt requested via Telegram");
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let halt = async {
+        deps.command_tx
+            .send(EngineCommand::Halt { reply: reply_tx })
+            .await
+            .ok()?;
+        reply_rx.await.ok()
+    };
+    let (entries, orders) = match tokio::time::timeout(HALT_REPLY_TIMEOUT, halt).await {
+        Ok(Some(HaltOutcome { state, orders })) => {
+            let entries = format!("Engine {state} \u{2014} new entries blocked.");
+            let orders = match orders {
+                Some((cancelled, 0)) => format!("Cancelled {cancelled} resting order(s)."),
+                Some((cancelled, failed)) => format!(
+                    "Cancelled {cancelled} resting order(s); {failed} could not be cancelled, check the exchange!"
+                ),
+                None => {
+                    "Order executor unresponsive \u{2014} resting orders NOT cancelled!".to_string()
+                }
+            };
+            (entries, orders)
+        }
+        _ => (
+            "Engine unresponsive \u{2014} could not block entries!".to_string(),
+            "Resting orders NOT cancelled!".to_string(),
+        ),
+    };
+    deps.audit
+        .record(&actor, "engine.halt", json!({ "flatten": flatten_too }))
+        .await;
+    bot.send_message(
+        msg.chat.id,
+        format!("\u{26d4} Halted.\n{entries}\n{orders}\nUse /resume to continue."),
+    )
+    .await?;
+
+    if flatten_too {
+        if *deps.trading_mode.read().await == TradingMode::Live {
+            bot.send_message(msg.chat.id, "Flatten ALL open positions at market too?")
+                .reply_markup(confirm_keyboard("flatten"))
+                .await?;
+        } else {
+            flatten(&bot, msg.chat.id, &deps, &actor).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_close(bot: Bot, msg: Message, pair: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let pair = pair.trim().to_uppercase();
+    if pair.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /close <PAIR>")
+            .await?;
+        return Ok(());
+    }
+    if *deps.trading_mode.read().await == TradingMode::Live {
+        let prompt = format!("Close the open position on {pair} at market?");
+        bot.send_message(msg.chat.id, prompt)
+            .reply_markup(confirm_keyboard(&format!("close:{pair}")))
+            .await?;
+    } else {
+        let reply = close_positions(&deps, Some(pair), &actor(&msg)).await;
+        bot.send_message(msg.chat.id, reply).await?;
+    }
+    Ok(())
+}
+
+async fn handle_close_all(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    if *deps.trading_mode.read().await == TradingMode::Live {
+        bot.send_message(msg.chat.id, "Close ALL open positions at market?")
+            .reply_markup(confirm_keyboard("close:*"))
+            .await?;
+    } else {
+        let reply = close_positions(&deps, None, &actor(&msg)).await;
+        bot.send_message(msg.chat.id, reply).await?;
+    }
+    Ok(())
+}
+
+/// Yes/Cancel buttons for a destructive command in live mode. "Yes" runs
+/// `action` if pressed within `CONFIRM_TTL_SECS`.
+fn confirm_keyboard(action: &str) -> InlineKeyboardMarkup {
+    let confirm = format!("ok:{}:{action}", Utc::now().timestamp());
+    InlineKeyboardMarkup::new([[
+        InlineKeyboardButton::callback("Yes", confirm),
+        InlineKeyboardButton::callback("Cancel", "cancel"),
+    ]])
+}
+
+/// Have the Risk Manager close the open positions on `pair` (all pairs if
+/// `None`) and describe the outcome for the operator.
+async fn close_positions(deps: &BotDeps, pair: Option<String>, actor: &str) -> String {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let cmd = RiskCommand::ClosePositions {
+        pair: pair.clone(),
+        reply: reply_tx,
+    };
+    if deps.risk_tx.send(cmd).await.is_err() {
+        return "Risk manager unavailable.".to_string();
+    }
+    let Ok(closed) = reply_rx.await else {
+        return "Risk manager unavailable.".to_string();
+    };
+    warn!(pair = ?pair, closed, "Positions closed via Telegram");
+    let payload = json!({ "pair": pair, "closed": closed });
+    deps.audit.record(actor, "position.close", payload).await;
+    match (pair, closed) {
+        (Some(pair), 0) => format!("No open position on {pair}."),
+        (None, 0) => "No open positions.".to_string(),
+        (Some(pair), n) => format!("Closing {n} position(s) on {pair} at market."),
+        (None, n) => format!("Closing {n} open position(s) at market."),
+    }
+}
+
+async fn handle_resume(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    let state = *deps.engine_state.read().await;
+    if state != EngineState::Paused {
+        bot.send_message(msg.chat.id, format!("Engine is {state}, not paused."))
+            .await?;
+    } else {
+        let _ = deps.command_tx.send(EngineCommand::Resume).await;
+        deps.audit
+            .record(&actor(&msg), "engine.resume", json!({}))
+            .await;
+        bot.send_message(msg.chat.id, "Engine resumed.").await?;
+    }
+    Ok(())
+}
+
+/// Show or change the alert categories this chat receives. Works in group
+/// chats too, so a monitoring group can subscribe to more than a private
+/// chat does.
+async fn handle_alerts(bot: Bot, msg: Message, args: String, deps: Arc<BotDeps>) -> HandlerResult {
+    let chat_id = msg.chat.id.0;
+    let args: Vec<&str> = args.split_whitespace().collect();
+    let mut categories = deps.subscriptions.categories(chat_id).await?;
+    let updated = match args.as_slice() {
+        [] => None,
+        ["all"] => Some(AlertCategory::ALL.to_vec()),
+        ["none"] => Some(Vec::new()),
+        [switch @ ("on" | "off"), name] => {
+            let Some(category) = AlertCategory::parse(name) else {
+                let names: Vec<_> = AlertCategory::ALL.iter().map(|c| c.as_str()).collect();
+                let reply = format!("Unknown category. Choose from: {}", names.join(", "));
+                bot.send_message(msg.chat.id, reply).await?;
+                return Ok(());
+            };
+            categories.retain(|c| *c != category);
+            if *switch == "on" {
+                categories.push(category);
+            }
+            Some(categories.clone())
+        }
+        _ => {
+            bot.send_message(msg.chat.id, "Usage: /alerts [on|off <category>|all|none]")
+                .await?;
+            return Ok(());
+        }
+    };
+    if let Some(updated) = updated {
+        deps.subscriptions.set(chat_id, &updated).await?;
+        let names: Vec<_> = updated.iter().map(|c| c.as_str()).collect();
+        let payload = json!({ "chat_id": chat_id, "categories": names });
+        deps.audit
+            .record(&actor(&msg), "alerts.update", payload)
+            .await;
+        categories = updated;
+    }
+
+    let mut text = "Alerts for this chat:".to_string();
+    for category in AlertCategory::ALL {
+        let mark = if categories.contains(&category) {
+            "\u{2705}"
+        } else {
+            "\u{2796}"
+        };
+        text.push_str(&format!("\n{mark} {}", category.as_str()));
+    }
+    text.push_str("\n\nChange with /alerts on|off <category>, /alerts all or /alerts none.");
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn handle_retries(bot: Bot, msg: Message, deps: Arc<BotDeps>) -> HandlerResult {
+    let queue = deps.retry_queue.list();
+    if queue.is_empty() {
+        bot.send_message(msg.chat.id, "Retry queue is empty.")
+            .await?;
+        return Ok(());
+    }
+    let mut text = format!("{} failed order(s) queued:\n", queue.len());
+    for failed in queue {
+        let next = match failed.next_retry_at {
+            Some(at) => format!("next retry {}", at.format("%H:%M:%S")),
+            None => "awaiting /retry".to_string(),
+        };
+        text.push_str(&format!(
+            "\n{} {} {} {}\n  {} (round {}, {next})\n  id: {}\n",
+            failed.order.side,
+            failed.order.quantity,
+            failed.order.pair,
+            failed
+                .order
+                .price
+                .map(|p| format!("@ {p}"))
+                .unwrap_or_default(),
+            failed.error,
+            failed.rounds,
+            failed.order.id,
+        ));
+    }
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn handle_retry(
+    bot: Bot,
+    msg: Message,
+    order_id: String,
+    deps: Arc<BotDeps>,
+) -> HandlerResult {
+    let order_id = order_id.trim();
+    let reply = if deps.retry_queue.retry_now(order_id) {
+        warn!(order_id, "Order retry requested via Telegram");
+        let payload = json!({ "order_id": order_id });
+        deps.audit
+            .record(&actor(&msg), "order.retry", payload)
+            .await;
+        "Retry scheduled."
+    } else {
+        "No queued order with that ID. See /retries."
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+async fn handle_discard(
+    bot: Bot,
+    msg: Message,
+    order_id: String,
+    deps: Arc<BotDeps>,
+) -> HandlerResult {
+    let reply = match deps.retry_queue.discard(order_id.trim()) {
+        Some(failed) => {
+            warn!(order_id = %failed.order.id, "Queued order discarded via Telegram");
+            let payload = json!({ "order_id": failed.order.id });
+            deps.audit
+                .record(&actor(&msg), "order.discard", payload)
+                .await;
+            format!(
+                "Discarded {} {} {}.",
+                failed.order.side, failed.order.quantity, failed.order.pair
+            )
+        }
+        None => "No queued order with that ID. See /retries.".to_string(),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+async fn handle_add_pair(
+    bot: Bot,
+    msg: Message,
+    pair: String,
+    deps: Arc<BotDeps>,
+) -> HandlerResult {
+    let pair = pair.trim().to_uppercase();
+    if pair.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /addpair <PAIR>")
+            .await?;
+        return Ok(());
+    }
+    info!(pair = %pair, "Pair added via Telegram");
+    let _ = deps
+        .command_tx
+        .send(EngineCommand::AddPair(pair.clone()))
+        .await;
+    deps.audit
+        .record(&actor(&msg), "pair.add", json!({ "pair": pair }))
+        .await;
+    bot.send_message(msg.chat.id, format!("Streaming {pair}."))
+        .await?;
+    Ok(())
+}
+
+async fn handle_remove_pair(
+    bot: Bot,
+    msg: Message,
+    pair: String,
+    deps: Arc<BotDeps>,
+) -> HandlerResult {
+    let pair = pair.trim().to_uppercase();
+    if pair.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /removepair <PAIR>")
+            .await?;
+        return Ok(());
+    }
+    info!(pair = %pair, "Pair removed via Telegram");
+    let _ = deps
+        .command_tx
+        .send(EngineCommand::RemovePair(pair.clone()))
+        .await;
+    deps.audit
+        .record(&actor(&msg), "pair.remove", json!({ "pair": pair }))
+        .await;
+    bot.send_message(msg.chat.id, format!("Stopped streaming {pair}."))
+        .await?;
+    Ok(())
+}
+
 /// Send a proactive alert to all configured chat IDs.
 /// Call this from the Risk Manager event loop.
 pub async fn send_alert(bot: &Bot, chat_ids: &[ChatId], message: &str) {
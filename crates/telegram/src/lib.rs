@@ -1,3 +1,9 @@
 pub mod commands;
+mod probe;
+mod subscriptions;
+mod summary;
 
 pub use commands::{send_alert, start_bot, BotDeps};
+pub use probe::TelegramProbe;
+pub use subscriptions::AlertSubscriptions;
+pub use summary::DailySummary;
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use teloxide::prelude::*;
+
+use common::{Error, ReadinessProbe, Result};
+
+/// Checks that the bot token works and the Telegram Bot API is reachable.
+pub struct TelegramProbe {
+    bot: Bot,
+}
+
+impl TelegramProbe {
+    pub fn new(bot: Bot) -> Self {
+        Self { bot }
+    }
+}
+
+#[async_trait]
+impl ReadinessProbe for TelegramProbe {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    /// `getMe`, which needs a valid token.
+    async fn check(&self) -> Result<()> {
+        self.bot
+            .get_me()
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::Http(e.to_string()))
+    }
+}
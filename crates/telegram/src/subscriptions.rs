@@ -0,0 +1,97 @@
+use chrono::Utc;
+use sqlx::SqlitePool;
+use teloxide::types::ChatId;
+use tracing::error;
+
+use common::{AlertCategory, TelegramAllowlist};
+
+/// Which alert categories each chat receives, as set with `/alerts` and
+/// stored in `alert_subscriptions`. Chats without a row get the default:
+/// everything for the allowed users' private chats, nothing for other
+/// chats (e.g. a group).
+#[derive(Clone)]
+pub struct AlertSubscriptions {
+    db: SqlitePool,
+    allowlist: TelegramAllowlist,
+}
+
+impl AlertSubscriptions {
+    pub fn new(db: SqlitePool, allowlist: TelegramAllowlist) -> Self {
+        Self { db, allowlist }
+    }
+
+    /// The categories `chat_id` receives.
+    pub async fn categories(&self, chat_id: i64) -> Result<Vec<AlertCategory>, sqlx::Error> {
+        let row = sqlx::query_scalar!(
+            "SELECT categories FROM alert_subscriptions WHERE chat_id = ?1",
+            chat_id
+        )
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(match row {
+            Some(categories) => categories
+                .split(',')
+                .filter_map(AlertCategory::parse)
+                .collect(),
+            None => self.default_categories(chat_id).await,
+        })
+    }
+
+    /// Replace the categories `chat_id` receives.
+    pub async fn set(&self, chat_id: i64, categories: &[AlertCategory]) -> Result<(), sqlx::Error> {
+        let categories = categories
+            .iter()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let now = Utc::now().to_rfc3339();
+        sqlx::query!(
+            r#"
+            INSERT INTO alert_subscriptions (chat_id, categories, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(chat_id) DO UPDATE SET
+                categories = excluded.categories, updated_at = excluded.updated_at
+            "#,
+            chat_id,
+            categories,
+            now,
+        )
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Every chat subscribed to `category`. If the subscriptions can't be
+    /// read, the allowed users get the alert anyway.
+    pub async fn chats_for(&self, category: AlertCategory) -> Vec<ChatId> {
+        let rows = sqlx::query!("SELECT chat_id, categories FROM alert_subscriptions")
+            .fetch_all(&self.db)
+            .await;
+        let allowed = self.allowlist.user_ids().await;
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(error = %e, "Failed to read alert subscriptions");
+                return allowed.into_iter().map(ChatId).collect();
+            }
+        };
+        let mut chats: Vec<i64> = allowed
+            .into_iter()
+            .filter(|id| !rows.iter().any(|r| r.chat_id == *id))
+            .collect();
+        chats.extend(
+            rows.iter()
+                .filter(|r| r.categories.split(',').any(|c| c == category.as_str()))
+                .map(|r| r.chat_id),
+        );
+        chats.into_iter().map(ChatId).collect()
+    }
+
+    async fn default_categories(&self, chat_id: i64) -> Vec<AlertCategory> {
+        if self.allowlist.contains(chat_id).await {
+            AlertCategory::ALL.to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+}
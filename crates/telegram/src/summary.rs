@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+use sqlx::SqlitePool;
+use teloxide::prelude::*;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tracing::{error, info};
+
+use common::{AlertCategory, RiskCommand, TradingMode};
+
+use crate::commands::send_alert;
+use crate::AlertSubscriptions;
+
+/// Sends the chats subscribed to summaries an overview of the last 24 hours
+/// once a day: realized PnL, open positions, equity and drawdown, signals,
+/// and halts.
+pub struct DailySummary {
+    bot: Bot,
+    subscriptions: AlertSubscriptions,
+    db: SqlitePool,
+    mode: Arc<RwLock<TradingMode>>,
+    risk_tx: mpsc::Sender<RiskCommand>,
+}
+
+impl DailySummary {
+    pub fn new(
+        bot: Bot,
+        subscriptions: AlertSubscriptions,
+        db: SqlitePool,
+        mode: Arc<RwLock<TradingMode>>,
+        risk_tx: mpsc::Sender<RiskCommand>,
+    ) -> Self {
+        Self {
+            bot,
+            subscriptions,
+            db,
+            mode,
+            risk_tx,
+        }
+    }
+
+    /// Send the summary every day at `at` in `offset`. Call from
+    /// `tokio::spawn`.
+    pub async fn run(self, at: NaiveTime, offset: FixedOffset) {
+        loop {
+            let now = Utc::now();
+            let next = next_run(now, at, offset);
+            info!(at = %next, "Next daily summary scheduled");
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+            match self.compose().await {
+                Ok(text) => {
+                    let chat_ids = self.subscriptions.chats_for(AlertCategory::Summaries).await;
+                    send_alert(&self.bot, &chat_ids, &text).await;
+                }
+                Err(e) => error!(error = %e, "Failed to build daily summary"),
+            }
+        }
+    }
+
+    async fn compose(&self) -> Result<String, sqlx::Error> {
+        let mode = self.mode.read().await.to_string();
+        let since = (Utc::now() - Duration::hours(24)).to_rfc3339();
+
+        let trades = sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!: i64", COALESCE(SUM(pnl_usd), 0.0) AS "pnl!: f64"
+               FROM trades WHERE closed_at >= ?1 AND mode = ?2"#,
+            since,
+            mode,
+        )
+        .fetch_one(&self.db)
+        .await?;
+        let open_positions = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM positions WHERE mode = ?1"#,
+            mode,
+        )
+        .fetch_one(&self.db)
+        .await?;
+        let signals = sqlx::query!(
+            r#"SELECT outcome, COUNT(*) AS "count!: i64" FROM signals
+               WHERE created_at >= ?1 GROUP BY outcome ORDER BY outcome"#,
+            since,
+        )
+        .fetch_all(&self.db)
+        .await?;
+        let halts = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!: i64" FROM risk_events
+               WHERE created_at >= ?1
+                 AND kind IN ('drawdown_halt_entered', 'loss_streak_halt_entered')"#,
+            since,
+        )
+        .fetch_one(&self.db)
+        .await?;
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let _ = self
+            .risk_tx
+            .send(RiskCommand::GetEquity { reply: reply_tx })
+            .await;
+        let equity = match reply_rx.await {
+            Ok(snapshot) => format!(
+                "Equity: ${:.2} (unrealized {:+.2})\nDrawdown from peak: {:.2}%",
+                snapshot.equity_usd,
+                snapshot.unrealized_usd,
+                snapshot.drawdown_pct * 100.0
+            ),
+            Err(_) => "Equity: unavailable".to_string(),
+        };
+        let signals = if signals.is_empty() {
+            "none".to_string()
+        } else {
+            signals
+                .iter()
+                .map(|s| format!("{} {}", s.count, s.outcome))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        Ok(format!(
+            "\u{1f4ca} Daily summary ({mode}, last 24h)\n\
+             Realized PnL: {:+.2} USD over {} trade(s)\n\
+             Open positions: {open_positions}\n\
+             {equity}\n\
+             Signals: {signals}\n\
+             Halts: {halts}",
+            trades.pnl, trades.count,
+        ))
+    }
+}
+
+/// The first time after `now` that the clock in `offset` reads `at`.
+fn next_run(now: DateTime<Utc>, at: NaiveTime, offset: FixedOffset) -> DateTime<Utc> {
+    let local = now.with_timezone(&offset);
+    let today = offset
+        .from_local_datetime(&local.date_naive().and_time(at))
+        .single()
+        .expect("fixed offsets have no ambiguous local times");
+    let next = if today > local {
+        today
+    } else {
+        today + Duration::days(1)
+    };
+    next.with_timezone(&Utc)
+}
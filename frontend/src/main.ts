@@ -6,6 +6,7 @@ import Operations from './views/Operations.vue'
 import StrategyConfig from './views/StrategyConfig.vue'
 import Performance from './views/Performance.vue'
 import Login from './views/Login.vue'
+import { isAuthed } from './session'
 
 const router = createRouter({
   history: createWebHistory(),
@@ -20,8 +21,7 @@ const router = createRouter({
 })
 
 router.beforeEach((to) => {
-  const token = sessionStorage.getItem('dashboard_token')
-  if (to.meta.requiresAuth && !token) {
+  if (to.meta.requiresAuth && !isAuthed()) {
     return '/login'
   }
 })
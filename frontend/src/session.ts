@@ -0,0 +1,73 @@
+// Dashboard session: a short-lived access JWT plus a single-use refresh token,
+// both issued by POST /api/login.
+
+const ACCESS_KEY = 'dashboard_access_token'
+const REFRESH_KEY = 'dashboard_refresh_token'
+
+export function isAuthed(): boolean {
+  return !!sessionStorage.getItem(REFRESH_KEY)
+}
+
+export function accessToken(): string {
+  return sessionStorage.getItem(ACCESS_KEY) ?? ''
+}
+
+function store(json: { access_token: string; refresh_token: string }) {
+  sessionStorage.setItem(ACCESS_KEY, json.access_token)
+  sessionStorage.setItem(REFRESH_KEY, json.refresh_token)
+}
+
+function clear() {
+  sessionStorage.removeItem(ACCESS_KEY)
+  sessionStorage.removeItem(REFRESH_KEY)
+}
+
+/** Log in with `{ token }` or `{ username, password }`. */
+export async function login(credentials: Record<string, string>): Promise<boolean> {
+  const resp = await fetch('/api/login', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(credentials),
+  })
+  if (resp.ok) store(await resp.json())
+  return resp.ok
+}
+
+/** Swap the refresh token for a new pair. Concurrent callers share one request. */
+let refreshing: Promise<boolean> | null = null
+export function refresh(): Promise<boolean> {
+  refreshing ??= (async () => {
+    const resp = await fetch('/api/refresh', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ refresh_token: sessionStorage.getItem(REFRESH_KEY) }),
+    })
+    if (resp.ok) store(await resp.json())
+    else clear()
+    return resp.ok
+  })().finally(() => {
+    refreshing = null
+  })
+  return refreshing
+}
+
+export async function logout() {
+  await fetch('/api/logout', {
+    method: 'POST',
+    headers: { Authorization: `Bearer ${accessToken()}` },
+  }).catch(() => {})
+  clear()
+}
+
+/** `fetch` with the access token, refreshing it once if it has expired. */
+export async function apiFetch(url: string, init: RequestInit = {}): Promise<Response> {
+  const send = () =>
+    fetch(url, {
+      ...init,
+      headers: { ...init.headers, Authorization: `Bearer ${accessToken()}` },
+    })
+  const resp = await send()
+  if (resp.status === 401 && (await refresh())) return send()
+  if (resp.status === 401) location.assign('/login')
+  return resp
+}
@@ -0,0 +1,13 @@
+-- Durable record of every RiskEvent (rejections, SL/TP, halts, failures)
+
+CREATE TABLE IF NOT EXISTS risk_events (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind        TEXT    NOT NULL,
+    pair        TEXT,
+    details     TEXT    NOT NULL,  -- JSON-serialized RiskEvent
+    created_at  TEXT    NOT NULL   -- ISO-8601 datetime
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_events_created_at ON risk_events (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_risk_events_kind       ON risk_events (kind);
+CREATE INDEX IF NOT EXISTS idx_risk_events_pair       ON risk_events (pair);
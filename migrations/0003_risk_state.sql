@@ -0,0 +1,9 @@
+-- Single-row snapshot of the drawdown circuit breaker, restored at startup
+
+CREATE TABLE IF NOT EXISTS risk_state (
+    id                   INTEGER PRIMARY KEY CHECK (id = 1),
+    portfolio_peak_usd   REAL    NOT NULL,
+    portfolio_value_usd  REAL    NOT NULL,
+    halted               INTEGER NOT NULL DEFAULT 0,  -- boolean
+    updated_at           TEXT    NOT NULL             -- ISO-8601 datetime
+);
@@ -0,0 +1,35 @@
+-- Allow the 'live-dryrun' trading mode. SQLite cannot alter a CHECK
+-- constraint, so both tables are rebuilt.
+
+CREATE TABLE positions_new (
+    id          TEXT    PRIMARY KEY,
+    pair        TEXT    NOT NULL,
+    side        TEXT    NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    entry_price REAL    NOT NULL,
+    quantity    REAL    NOT NULL,
+    mode        TEXT    NOT NULL CHECK (mode IN ('live', 'paper', 'live-dryrun')),
+    opened_at   TEXT    NOT NULL   -- ISO-8601 datetime
+);
+INSERT INTO positions_new SELECT * FROM positions;
+DROP TABLE positions;
+ALTER TABLE positions_new RENAME TO positions;
+
+CREATE TABLE trades_new (
+    id          TEXT    PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    pair        TEXT    NOT NULL,
+    side        TEXT    NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    entry_price REAL    NOT NULL,
+    exit_price  REAL    NOT NULL,
+    quantity    REAL    NOT NULL,
+    pnl_usd     REAL    NOT NULL,
+    mode        TEXT    NOT NULL CHECK (mode IN ('live', 'paper', 'live-dryrun')),
+    opened_at   TEXT    NOT NULL,
+    closed_at   TEXT    NOT NULL
+);
+INSERT INTO trades_new SELECT * FROM trades;
+DROP TABLE trades;
+ALTER TABLE trades_new RENAME TO trades;
+
+CREATE INDEX IF NOT EXISTS idx_trades_pair       ON trades (pair);
+CREATE INDEX IF NOT EXISTS idx_trades_closed_at  ON trades (closed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_trades_mode       ON trades (mode);
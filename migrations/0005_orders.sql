@@ -0,0 +1,25 @@
+-- Lifecycle of every order the executor handles, including failures
+
+CREATE TABLE IF NOT EXISTS orders (
+    id                TEXT    PRIMARY KEY,   -- our order ID (sent as client order ID)
+    pair              TEXT    NOT NULL,
+    side              TEXT    NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    order_type        TEXT    NOT NULL,      -- market, limit, stop_loss, take_profit
+    quantity          REAL    NOT NULL,
+    price             REAL,                  -- limit price, if any
+    position_id       TEXT,                  -- position being closed, if any
+    status            TEXT    NOT NULL,      -- submitted, resting, partially_filled, filled,
+                                             -- failed, rejected, canceled, expired
+    exchange_order_id TEXT,
+    filled_quantity   REAL    NOT NULL DEFAULT 0,
+    average_price     REAL,
+    error             TEXT,
+    attempts          INTEGER NOT NULL DEFAULT 0,
+    mode              TEXT    NOT NULL,
+    created_at        TEXT    NOT NULL,      -- ISO-8601 datetime
+    updated_at        TEXT    NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_created_at ON orders (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_status     ON orders (status);
+CREATE INDEX IF NOT EXISTS idx_orders_pair       ON orders (pair);
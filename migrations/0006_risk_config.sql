@@ -0,0 +1,7 @@
+-- Single-row risk configuration saved by runtime updates, restored at startup
+
+CREATE TABLE IF NOT EXISTS risk_config (
+    id          INTEGER PRIMARY KEY CHECK (id = 1),
+    config      TEXT    NOT NULL,  -- JSON-encoded RiskConfig
+    updated_at  TEXT    NOT NULL   -- ISO-8601 datetime
+);
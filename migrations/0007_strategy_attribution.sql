@@ -0,0 +1,8 @@
+-- Name of the strategy whose signal opened a position, carried onto the
+-- trades that close it. NULL for positions opened outside a strategy
+-- (manual orders, exchange reconciliation).
+
+ALTER TABLE positions ADD COLUMN strategy TEXT;
+ALTER TABLE trades    ADD COLUMN strategy TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_trades_strategy ON trades (strategy);
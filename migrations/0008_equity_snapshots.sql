@@ -0,0 +1,12 @@
+-- Periodic mark-to-market portfolio equity, for the dashboard equity curve
+
+CREATE TABLE IF NOT EXISTS equity_snapshots (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    realized_usd    REAL    NOT NULL,  -- starting balance plus realized PnL
+    unrealized_usd  REAL    NOT NULL,  -- open positions at the latest prices
+    equity_usd      REAL    NOT NULL,
+    mode            TEXT    NOT NULL,
+    taken_at        TEXT    NOT NULL   -- ISO-8601 datetime
+);
+
+CREATE INDEX IF NOT EXISTS idx_equity_snapshots_taken_at ON equity_snapshots (taken_at);
@@ -0,0 +1,7 @@
+-- Dashboard sessions logged out and refresh tokens already used, until they
+-- would have expired anyway
+
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    id          TEXT PRIMARY KEY,  -- session ID or refresh token ID
+    expires_at  INTEGER NOT NULL   -- unix seconds
+);
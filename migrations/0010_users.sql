@@ -0,0 +1,9 @@
+-- Dashboard users and their roles
+
+CREATE TABLE IF NOT EXISTS users (
+    username       TEXT PRIMARY KEY,
+    password_hash  TEXT NOT NULL,  -- Argon2id PHC string
+    role           TEXT NOT NULL,  -- 'viewer' | 'operator'
+    created_at     TEXT NOT NULL,  -- ISO-8601 datetime
+    updated_at     TEXT NOT NULL
+);
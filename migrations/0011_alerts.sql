@@ -0,0 +1,20 @@
+-- Alert severity and text for each risk event, as shown in the dashboard
+
+ALTER TABLE risk_events ADD COLUMN severity TEXT NOT NULL DEFAULT 'info';
+ALTER TABLE risk_events ADD COLUMN message  TEXT NOT NULL DEFAULT '';
+
+-- Events recorded before this migration get their severity from their kind;
+-- their alert text is not recoverable, so the kind stands in for it.
+UPDATE risk_events SET
+    severity = CASE
+        WHEN kind IN ('order_failed', 'drawdown_halt_entered', 'loss_streak_halt_entered',
+                      'positions_flattened')
+            THEN 'critical'
+        WHEN kind IN ('order_rejected', 'stop_loss_triggered', 'fill_deviation_exceeded',
+                      'market_data_stale', 'position_mismatch')
+            THEN 'warning'
+        ELSE 'info'
+    END,
+    message = kind;
+
+CREATE INDEX IF NOT EXISTS idx_risk_events_severity ON risk_events (severity);
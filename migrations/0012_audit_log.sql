@@ -0,0 +1,14 @@
+-- Who changed what: every state-changing action taken through the dashboard
+-- API or Telegram
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor       TEXT    NOT NULL,  -- e.g. 'api:alice', 'telegram:123456'
+    action      TEXT    NOT NULL,  -- e.g. 'engine.flatten', 'risk.update'
+    payload     TEXT    NOT NULL,  -- JSON
+    created_at  TEXT    NOT NULL   -- ISO-8601 datetime
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor      ON audit_log (actor);
+CREATE INDEX IF NOT EXISTS idx_audit_log_action     ON audit_log (action);
@@ -0,0 +1,6 @@
+-- Commission paid, in USD (quote asset). A position carries the fees of the
+-- fills that opened it until trades close it; each trade carries its share
+-- of the entry and exit fees. Rows from before fees were tracked stay at 0.
+
+ALTER TABLE positions ADD COLUMN fee_usd REAL NOT NULL DEFAULT 0;
+ALTER TABLE trades    ADD COLUMN fee_usd REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,20 @@
+-- Every signal the risk manager decided on, and what became of it
+
+CREATE TABLE IF NOT EXISTS signals (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    strategy        TEXT,              -- NULL for manual orders
+    pair            TEXT    NOT NULL,
+    side            TEXT    NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    quantity        REAL    NOT NULL,
+    quote_quantity  REAL,
+    limit_price     REAL,
+    outcome         TEXT    NOT NULL CHECK (outcome IN ('approved', 'rejected', 'filled')),
+    reason          TEXT,              -- why a rejected signal was rejected
+    order_id        TEXT,              -- order forwarded for an approved signal
+    created_at      TEXT    NOT NULL   -- ISO-8601 datetime of the decision
+);
+
+CREATE INDEX IF NOT EXISTS idx_signals_created_at ON signals (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_signals_strategy   ON signals (strategy);
+CREATE INDEX IF NOT EXISTS idx_signals_pair       ON signals (pair);
+CREATE INDEX IF NOT EXISTS idx_signals_order_id   ON signals (order_id);
@@ -0,0 +1,9 @@
+-- Alert categories each Telegram chat receives, set with /alerts. Allowed
+-- users' private chats without a row get every category; other chats get
+-- none until they subscribe.
+
+CREATE TABLE IF NOT EXISTS alert_subscriptions (
+    chat_id     INTEGER PRIMARY KEY,
+    categories  TEXT    NOT NULL,  -- comma-separated, e.g. 'halts,errors'
+    updated_at  TEXT    NOT NULL   -- ISO-8601 datetime
+);
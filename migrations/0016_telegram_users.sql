@@ -0,0 +1,8 @@
+-- Telegram users paired at runtime with /pair and a one-time code. They are
+-- allowed in addition to TELEGRAM_ALLOWED_USER_IDS.
+
+CREATE TABLE IF NOT EXISTS telegram_users (
+    user_id    INTEGER PRIMARY KEY,
+    username   TEXT,              -- Telegram @username when paired, if any
+    paired_at  TEXT    NOT NULL   -- ISO-8601 datetime
+);
@@ -0,0 +1,15 @@
+-- Closed candles per pair and interval, written when CANDLE_RETENTION_DAYS
+-- is set and pruned to that window. Used to warm up after a restart and for
+-- offline backtests.
+
+CREATE TABLE IF NOT EXISTS candles (
+    pair      TEXT NOT NULL,
+    interval  TEXT NOT NULL,  -- '1m', '5m', '15m', '1h'
+    open      REAL NOT NULL,
+    high      REAL NOT NULL,
+    low       REAL NOT NULL,
+    close     REAL NOT NULL,
+    volume    REAL NOT NULL,
+    closed_at TEXT NOT NULL,  -- ISO-8601 datetime of the final event
+    PRIMARY KEY (pair, interval, closed_at)
+);
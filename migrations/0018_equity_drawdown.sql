@@ -0,0 +1,4 @@
+-- Drawdown of equity from the portfolio peak at each snapshot, as a
+-- fraction. Snapshots from before it was recorded stay at 0.
+
+ALTER TABLE equity_snapshots ADD COLUMN drawdown_pct REAL NOT NULL DEFAULT 0;
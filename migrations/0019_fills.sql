@@ -0,0 +1,24 @@
+-- Every fill the trade ledger booked, one per order. A fill reported again
+-- (a status poll retried after the order was booked, or the same execution
+-- reported twice) hits a unique key and is ignored, so it can't book trades
+-- twice.
+
+CREATE TABLE IF NOT EXISTS fills (
+    order_id          TEXT PRIMARY KEY,  -- client order ID
+    exchange_order_id TEXT UNIQUE,       -- the exchange's ID; NULL for paper and dry-run fills
+    pair              TEXT NOT NULL,
+    side              TEXT NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    price             REAL NOT NULL,
+    quantity          REAL NOT NULL,
+    fee_usd           REAL NOT NULL,
+    mode              TEXT NOT NULL,
+    filled_at         TEXT NOT NULL      -- ISO-8601 datetime
+);
+
+-- The order whose fill closed each trade, and the position it closed. NULL
+-- for trades from before fills were tracked.
+ALTER TABLE trades ADD COLUMN order_id          TEXT;
+ALTER TABLE trades ADD COLUMN exchange_order_id TEXT;
+ALTER TABLE trades ADD COLUMN position_id       TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_order_position ON trades (order_id, position_id);
@@ -0,0 +1,23 @@
+-- Named trading accounts (different API keys, or separate paper sandboxes).
+-- Each process trades one account, chosen with ACCOUNT, and registers it
+-- here at startup. Positions, trades, and orders are scoped by account, so
+-- several accounts can keep their history in one database.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id              TEXT PRIMARY KEY,  -- account name, e.g. 'default', 'sandbox'
+    exchange        TEXT,              -- exchange last traded on; NULL until first started
+    created_at      TEXT NOT NULL,     -- ISO-8601 datetime
+    last_started_at TEXT               -- ISO-8601 datetime of the latest startup
+);
+
+-- Everything recorded before accounts existed belongs to the default account
+INSERT OR IGNORE INTO accounts (id, created_at)
+VALUES ('default', strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now'));
+
+ALTER TABLE positions ADD COLUMN account_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE trades    ADD COLUMN account_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE orders    ADD COLUMN account_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_positions_account ON positions (account_id, mode);
+CREATE INDEX IF NOT EXISTS idx_trades_account    ON trades (account_id);
+CREATE INDEX IF NOT EXISTS idx_orders_account    ON orders (account_id);
@@ -0,0 +1,28 @@
+-- One row per increment of an order's execution instead of one per order, so
+-- a resting order that fills in parts books every part. Each row is keyed by
+-- the order's cumulative executed quantity after it: the same report seen
+-- again hits the key and is ignored.
+
+CREATE TABLE fills_new (
+    order_id           TEXT NOT NULL,  -- client order ID
+    executed_quantity  REAL NOT NULL,  -- the order's cumulative executed quantity after this fill
+    exchange_order_id  TEXT,           -- the exchange's ID; NULL for paper and dry-run fills
+    pair               TEXT NOT NULL,
+    side               TEXT NOT NULL CHECK (side IN ('BUY', 'SELL')),
+    price              REAL NOT NULL,  -- price of this increment
+    quantity           REAL NOT NULL,  -- quantity of this increment
+    fee_usd            REAL NOT NULL,
+    mode               TEXT NOT NULL,
+    filled_at          TEXT NOT NULL,  -- ISO-8601 datetime
+    PRIMARY KEY (order_id, executed_quantity)
+);
+INSERT INTO fills_new (order_id, executed_quantity, exchange_order_id, pair, side, price, quantity,
+                       fee_usd, mode, filled_at)
+SELECT order_id, quantity, exchange_order_id, pair, side, price, quantity, fee_usd, mode, filled_at
+FROM fills;
+DROP TABLE fills;
+ALTER TABLE fills_new RENAME TO fills;
+
+-- Each increment of a close books its own trade against the same position
+DROP INDEX IF EXISTS idx_trades_order_position;
+CREATE INDEX IF NOT EXISTS idx_trades_order ON trades (order_id);